@@ -1,5 +1,8 @@
-use rusqlite::{params, Connection, Result as SqliteResult};
+use crate::utils::schema::{add_column_if_missing, run_migrations, Migration};
+use crate::utils::sql::{configure_busy_timeout, retry_on_busy};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -15,6 +18,18 @@ pub struct ApiConfig {
     pub model_name: String,
     #[serde(rename = "maxTokens")]
     pub max_tokens: i32,
+    /// System Prompt 模板（为空时使用内置默认 Prompt）
+    #[serde(rename = "systemPrompt", default)]
+    pub system_prompt: String,
+    /// User Prompt 模板，支持 `{text}` 与 `{glossary}` 占位符（为空时使用内置默认模板）
+    #[serde(rename = "userPromptTemplate", default)]
+    pub user_prompt_template: String,
+    /// 输入 token 单价（每百万 token，用于估算费用，0 表示不计费）
+    #[serde(rename = "promptPrice", default)]
+    pub prompt_price: f64,
+    /// 输出 token 单价（每百万 token）
+    #[serde(rename = "completionPrice", default)]
+    pub completion_price: f64,
     #[serde(rename = "isActive")]
     pub is_active: bool,
     #[serde(rename = "createdAt")]
@@ -23,6 +38,57 @@ pub struct ApiConfig {
     pub updated_at: i64,
 }
 
+/// schema 迁移（新增列/索引时在末尾追加，版本号递增）
+const MIGRATIONS: &[Migration] = &[
+    // v1：为旧版本数据库补充 Prompt 模板列（空字符串表示使用内置默认模板）
+    Migration {
+        version: 1,
+        up: |conn| {
+            add_column_if_missing(conn, "api_configs", "system_prompt", "TEXT NOT NULL DEFAULT ''")?;
+            add_column_if_missing(conn, "api_configs", "user_prompt_template", "TEXT NOT NULL DEFAULT ''")
+        },
+    },
+    // v2：token 单价与 API 用量记录（config_id 不设外键，删除配置后仍保留历史用量）
+    Migration {
+        version: 2,
+        up: |conn| {
+            add_column_if_missing(conn, "api_configs", "prompt_price", "REAL NOT NULL DEFAULT 0")?;
+            add_column_if_missing(conn, "api_configs", "completion_price", "REAL NOT NULL DEFAULT 0")?;
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS api_usage (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    config_id INTEGER NOT NULL,
+                    timestamp INTEGER NOT NULL,
+                    prompt_tokens INTEGER NOT NULL,
+                    completion_tokens INTEGER NOT NULL,
+                    approx_cost REAL NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_api_usage_config_time
+                    ON api_usage(config_id, timestamp);",
+            )
+        },
+    },
+];
+
+/// 单个配置在时间范围内的用量汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiUsageSummary {
+    #[serde(rename = "configId")]
+    pub config_id: i64,
+    /// 配置名称（配置已删除时为 None）
+    #[serde(rename = "configName")]
+    pub config_name: Option<String>,
+    #[serde(rename = "requestCount")]
+    pub request_count: i64,
+    #[serde(rename = "promptTokens")]
+    pub prompt_tokens: i64,
+    #[serde(rename = "completionTokens")]
+    pub completion_tokens: i64,
+    /// 按记录时的单价估算的费用
+    #[serde(rename = "approxCost")]
+    pub approx_cost: f64,
+}
+
 /// API配置数据库管理器
 pub struct ApiConfigDB {
     conn: Arc<Mutex<Connection>>,
@@ -30,8 +96,9 @@ pub struct ApiConfigDB {
 
 impl ApiConfigDB {
     /// 初始化数据库连接
-    pub fn new(db_path: &str) -> SqliteResult<Self> {
+    pub fn new<P: AsRef<Path>>(db_path: P) -> SqliteResult<Self> {
         let conn = Connection::open(db_path)?;
+        configure_busy_timeout(&conn)?;
 
         // 启用WAL模式以支持并发
         conn.execute_batch(
@@ -73,6 +140,8 @@ impl ApiConfigDB {
             [],
         )?;
 
+        run_migrations(&conn, "api_meta", MIGRATIONS)?;
+
         Ok(())
     }
 
@@ -80,7 +149,8 @@ impl ApiConfigDB {
     pub fn get_all_configs(&self) -> SqliteResult<Vec<ApiConfig>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, endpoint, api_key, model_name, max_tokens, is_active, created_at, updated_at
+            "SELECT id, name, endpoint, api_key, model_name, max_tokens, is_active, created_at, updated_at,
+                    system_prompt, user_prompt_template, prompt_price, completion_price
              FROM api_configs
              ORDER BY is_active DESC, created_at DESC"
         )?;
@@ -96,6 +166,10 @@ impl ApiConfigDB {
                 is_active: row.get::<_, i32>(6)? == 1,
                 created_at: row.get(7)?,
                 updated_at: row.get(8)?,
+                system_prompt: row.get(9)?,
+                user_prompt_template: row.get(10)?,
+                prompt_price: row.get(11)?,
+                completion_price: row.get(12)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -111,18 +185,22 @@ impl ApiConfigDB {
             .unwrap()
             .as_secs() as i64;
 
-        conn.execute(
-            "INSERT INTO api_configs (name, endpoint, api_key, model_name, max_tokens, is_active, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)",
-            params![
-                name,
-                "",  // 默认空端点
-                "",  // 默认空API Key
-                "",  // 默认空模型名称
-                2000,  // 默认Max Tokens
-                now
-            ],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                "INSERT INTO api_configs
+                    (name, endpoint, api_key, model_name, max_tokens, is_active,
+                     created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)",
+                params![
+                    name,
+                    "",  // 默认空端点
+                    "",  // 默认空API Key
+                    "",  // 默认空模型名称
+                    2000,  // 默认Max Tokens
+                    now
+                ],
+            )
+        })?;
 
         Ok(conn.last_insert_rowid())
     }
@@ -135,20 +213,28 @@ impl ApiConfigDB {
             .unwrap()
             .as_secs() as i64;
 
-        conn.execute(
-            "UPDATE api_configs
-             SET name = ?1, endpoint = ?2, api_key = ?3, model_name = ?4, max_tokens = ?5, updated_at = ?6
-             WHERE id = ?7",
-            params![
-                config.name,
-                config.endpoint,
-                config.api_key,
-                config.model_name,
-                config.max_tokens,
-                now,
-                id
-            ],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                "UPDATE api_configs
+                 SET name = ?1, endpoint = ?2, api_key = ?3, model_name = ?4, max_tokens = ?5,
+                     updated_at = ?6, system_prompt = ?7, user_prompt_template = ?8,
+                     prompt_price = ?9, completion_price = ?10
+                 WHERE id = ?11",
+                params![
+                    config.name,
+                    config.endpoint,
+                    config.api_key,
+                    config.model_name,
+                    config.max_tokens,
+                    now,
+                    config.system_prompt,
+                    config.user_prompt_template,
+                    config.prompt_price,
+                    config.completion_price,
+                    id
+                ],
+            )
+        })?;
 
         Ok(())
     }
@@ -156,40 +242,67 @@ impl ApiConfigDB {
     /// 删除配置
     pub fn delete_config(&self, id: i64) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM api_configs WHERE id = ?1", params![id])?;
+        retry_on_busy(|| conn.execute("DELETE FROM api_configs WHERE id = ?1", params![id]))?;
         Ok(())
     }
 
-    /// 激活指定配置（自动取消其他配置的激活状态）
-    pub fn activate_config(&self, id: i64) -> SqliteResult<()> {
+    /// 激活指定配置（自动取消其他配置的激活状态），返回此前激活的配置ID
+    ///
+    /// 配置不存在时返回 `QueryReturnedNoRows` 并回滚，原激活状态保持不变
+    pub fn activate_config(&self, id: i64) -> SqliteResult<Option<i64>> {
         let conn = self.conn.lock().unwrap();
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
 
-        // 开启事务以确保原子性
-        conn.execute("BEGIN TRANSACTION", [])?;
+        // 事务保证原子性，中途出错时 tx 被 drop 自动回滚
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            let previous = Self::active_config_id(&tx)?;
 
-        // 首先取消所有配置的激活状态
-        conn.execute("UPDATE api_configs SET is_active = 0", [])?;
+            // 首先取消所有配置的激活状态，然后激活指定配置
+            tx.execute("UPDATE api_configs SET is_active = 0", [])?;
+            let activated = tx.execute(
+                "UPDATE api_configs SET is_active = 1, updated_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )?;
+            if activated == 0 {
+                return Err(rusqlite::Error::QueryReturnedNoRows);
+            }
 
-        // 然后激活指定配置
-        conn.execute(
-            "UPDATE api_configs SET is_active = 1, updated_at = ?1 WHERE id = ?2",
-            params![now, id],
-        )?;
+            tx.commit()?;
+            Ok(previous)
+        })
+    }
 
-        conn.execute("COMMIT", [])?;
+    /// 取消所有配置的激活状态（禁用 AI 功能），返回此前激活的配置ID
+    pub fn deactivate_all(&self) -> SqliteResult<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            let previous = Self::active_config_id(&tx)?;
+            tx.execute("UPDATE api_configs SET is_active = 0 WHERE is_active = 1", [])?;
+            tx.commit()?;
+            Ok(previous)
+        })
+    }
 
-        Ok(())
+    fn active_config_id(conn: &Connection) -> SqliteResult<Option<i64>> {
+        conn.query_row(
+            "SELECT id FROM api_configs WHERE is_active = 1 LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
     }
 
     /// 获取当前激活的配置
     pub fn get_current_config(&self) -> SqliteResult<Option<ApiConfig>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, endpoint, api_key, model_name, max_tokens, is_active, created_at, updated_at
+            "SELECT id, name, endpoint, api_key, model_name, max_tokens, is_active, created_at, updated_at,
+                    system_prompt, user_prompt_template, prompt_price, completion_price
              FROM api_configs
              WHERE is_active = 1
              LIMIT 1"
@@ -206,6 +319,10 @@ impl ApiConfigDB {
                 is_active: row.get::<_, i32>(6)? == 1,
                 created_at: row.get(7)?,
                 updated_at: row.get(8)?,
+                system_prompt: row.get(9)?,
+                user_prompt_template: row.get(10)?,
+                prompt_price: row.get(11)?,
+                completion_price: row.get(12)?,
             })
         })?;
 
@@ -214,4 +331,122 @@ impl ApiConfigDB {
             None => Ok(None),
         }
     }
+
+    /// 记录一次 API 调用的 token 用量，按配置当前单价估算费用并返回
+    pub fn record_usage(
+        &self,
+        config_id: i64,
+        prompt_tokens: i64,
+        completion_tokens: i64,
+    ) -> SqliteResult<f64> {
+        let conn = self.conn.lock().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        // 配置不存在时按 0 计费，仍记录 token 数
+        let (prompt_price, completion_price): (f64, f64) = conn
+            .query_row(
+                "SELECT prompt_price, completion_price FROM api_configs WHERE id = ?1",
+                params![config_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .unwrap_or((0.0, 0.0));
+        let approx_cost = (prompt_tokens as f64 * prompt_price
+            + completion_tokens as f64 * completion_price)
+            / 1_000_000.0;
+
+        retry_on_busy(|| {
+            conn.execute(
+                "INSERT INTO api_usage (config_id, timestamp, prompt_tokens, completion_tokens, approx_cost)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![config_id, now, prompt_tokens, completion_tokens, approx_cost],
+            )
+        })?;
+
+        Ok(approx_cost)
+    }
+
+    /// 按配置汇总时间范围内（Unix 秒，闭区间，None 表示不限）的用量，按费用倒序
+    pub fn get_usage_summary(
+        &self,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> SqliteResult<Vec<ApiUsageSummary>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT u.config_id, c.name, COUNT(*), SUM(u.prompt_tokens), SUM(u.completion_tokens),
+                    SUM(u.approx_cost)
+             FROM api_usage u
+             LEFT JOIN api_configs c ON c.id = u.config_id
+             WHERE (?1 IS NULL OR u.timestamp >= ?1)
+               AND (?2 IS NULL OR u.timestamp <= ?2)
+             GROUP BY u.config_id
+             ORDER BY SUM(u.approx_cost) DESC, u.config_id",
+        )?;
+
+        let summaries = stmt
+            .query_map(params![since, until], |row| {
+                Ok(ApiUsageSummary {
+                    config_id: row.get(0)?,
+                    config_name: row.get(1)?,
+                    request_count: row.get(2)?,
+                    prompt_tokens: row.get(3)?,
+                    completion_tokens: row.get(4)?,
+                    approx_cost: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(summaries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_summary_applies_prices_and_range() -> SqliteResult<()> {
+        let db = ApiConfigDB::new(":memory:")?;
+        let id = db.create_config("Paid".to_string())?;
+        let mut config = db.get_all_configs()?.remove(0);
+        config.prompt_price = 2.0;
+        config.completion_price = 8.0;
+        db.update_config(id, &config)?;
+
+        let cost = db.record_usage(id, 1_000_000, 500_000)?;
+        assert!((cost - 6.0).abs() < 1e-9);
+        db.record_usage(id, 1000, 0)?;
+
+        let summary = db.get_usage_summary(None, None)?;
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].config_name.as_deref(), Some("Paid"));
+        assert_eq!(summary[0].request_count, 2);
+        assert_eq!(summary[0].prompt_tokens, 1_001_000);
+        assert!(db.get_usage_summary(Some(i64::MAX), None)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn activation_reports_previous_and_rejects_missing_config() -> SqliteResult<()> {
+        let db = ApiConfigDB::new(":memory:")?;
+        let id = db.create_config("Main".to_string())?;
+
+        assert_eq!(db.activate_config(id)?, None);
+        assert_eq!(db.activate_config(id)?, Some(id));
+        // 不存在的配置不会取消当前激活的配置
+        assert!(matches!(
+            db.activate_config(id + 1),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+        assert_eq!(db.get_current_config()?.map(|c| c.id), Some(id));
+
+        assert_eq!(db.deactivate_all()?, Some(id));
+        assert!(db.get_current_config()?.is_none());
+        Ok(())
+    }
 }
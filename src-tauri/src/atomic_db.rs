@@ -1,12 +1,17 @@
-use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, AhoCorasickKind, MatchKind};
+use rayon::prelude::*;
+use crate::utils::schema::{run_migrations, Migration};
+use crate::utils::sql::{configure_busy_timeout, retry_on_busy, retry_on_busy_with};
 use rusqlite::{params, Connection, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 /// 原子翻译来源类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AtomSource {
     Base,   // 基础词典
     AI,     // AI学习
@@ -44,23 +49,279 @@ pub struct AtomTranslation {
     pub updated_at: i64,
 }
 
+/// 批量替换中命中的原子词（`hits` 为本次替换的命中次数，与持久化的 usage_count 无关）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedAtom {
+    pub original: String,
+    pub translated: String,
+    pub source: AtomSource,
+    pub hits: usize,
+}
+
+/// 批量替换结果及命中的原子词汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomReplaceReport {
+    /// 替换后的文本（顺序与输入一致）
+    pub texts: Vec<String>,
+    /// 按命中次数降序排列
+    pub applied_atoms: Vec<AppliedAtom>,
+}
+
+/// 单次替换的性能指标（用于调优匹配器配置、反馈性能问题）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtomReplaceMetrics {
+    /// 自动机报告的候选命中数（含被重叠或来源过滤剔除的）
+    pub candidate_matches: usize,
+    /// 实际替换的命中数
+    pub applied: usize,
+    /// 匹配与替换耗时（微秒，不含等待锁的时间）
+    pub elapsed_us: u64,
+}
+
+/// 单条文本替换结果及性能指标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomReplaceDetailed {
+    pub text: String,
+    pub metrics: AtomReplaceMetrics,
+}
+
+/// 待导入的原子词条目（例如来自社区术语表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomImportEntry {
+    pub original: String,
+    pub translated: String,
+}
+
+/// 导入冲突：库中已有该词且译文与导入译文不同
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomImportConflict {
+    /// 原文（小写形式）
+    pub original: String,
+    pub existing: String,
+    pub incoming: String,
+    pub existing_source: AtomSource,
+}
+
+/// 同一批次内原文重复的条目（原文不区分大小写，最后一条生效）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtomBatchDuplicate {
+    /// 原文（小写形式）
+    pub original: String,
+    /// 最终写入的译文
+    pub kept: String,
+    /// 被后续条目覆盖的译文（按出现顺序）
+    pub discarded: Vec<String>,
+}
+
+/// 找出批次内原文重复的条目（按首次出现顺序）
+fn find_batch_duplicates<'a>(
+    items: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<AtomBatchDuplicate> {
+    let mut order = Vec::new();
+    let mut groups: HashMap<String, Vec<&str>> = HashMap::new();
+    for (original, translated) in items {
+        let original_lower = original.to_lowercase();
+        let group = groups.entry(original_lower.clone()).or_insert_with(|| {
+            order.push(original_lower);
+            Vec::new()
+        });
+        group.push(translated);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|original| {
+            let mut translations = groups.remove(&original)?;
+            if translations.len() < 2 {
+                return None;
+            }
+            let kept = translations.pop()?.to_string();
+            Some(AtomBatchDuplicate {
+                original,
+                kept,
+                discarded: translations.into_iter().map(str::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+/// 导入冲突的处理策略
+///
+/// - `Keep`：保留库中已有译文
+/// - `Overwrite`：使用导入译文覆盖
+/// - `Skip`：跳过该条目（不写入，单独计数以便用户稍后处理）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtomConflictStrategy {
+    #[default]
+    Keep,
+    Overwrite,
+    Skip,
+}
+
+/// 原子词导入结果统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AtomImportResult {
+    /// 新增的原子词数
+    pub inserted: usize,
+    /// 覆盖的冲突数
+    pub overwritten: usize,
+    /// 保留原译文的冲突数
+    pub kept: usize,
+    /// 跳过的冲突数
+    pub skipped: usize,
+    /// 与库中译文相同、无需写入的条目数
+    pub unchanged: usize,
+    /// 导入文件内原文重复的条目（仅最后一条参与导入）
+    #[serde(default)]
+    pub duplicates: Vec<AtomBatchDuplicate>,
+}
+
+/// 原子词匹配语义
+///
+/// - `LeftmostLongest`（默认）：同一起点优先最长词，"iron sword" 整体命中，
+///   复数形式（如 "argonians"）也能完整命中；适合大多数术语表。
+/// - `Standard`：一旦某个词结束就立即报告，较短的词优先，"iron sword" 只会命中 "iron"，
+///   复数后缀会被留在匹配之外；适合只想标注最小词根的场景。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtomMatchKind {
+    #[default]
+    LeftmostLongest,
+    Standard,
+}
+
+impl AtomMatchKind {
+    fn to_aho_corasick(self) -> MatchKind {
+        match self {
+            AtomMatchKind::LeftmostLongest => MatchKind::LeftmostLongest,
+            AtomMatchKind::Standard => MatchKind::Standard,
+        }
+    }
+}
+
+/// 原子词自动机实现
+///
+/// 内存占用与模式总长度（含复数变体）近似成正比，差别在常数因子：
+/// - `Auto`（默认）：由 aho-corasick 按模式数量自动选择，小词表用 DFA，大词表退回 NFA。
+/// - `NoncontiguousNfa`：内存最小，构建最快，匹配最慢；适合数十万条的超大词表。
+/// - `ContiguousNfa`：内存约为非连续 NFA 的一半到相当，匹配速度居中。
+/// - `Dfa`：匹配最快，但每个状态需要完整的转移表，内存可达 NFA 的数十倍，
+///   大词表下构建时间和内存都会显著增加，仅建议在词表较小且批量替换频繁时使用。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtomAutomatonKind {
+    #[default]
+    Auto,
+    NoncontiguousNfa,
+    ContiguousNfa,
+    Dfa,
+}
+
+impl AtomAutomatonKind {
+    fn to_aho_corasick(self) -> Option<AhoCorasickKind> {
+        match self {
+            AtomAutomatonKind::Auto => None,
+            AtomAutomatonKind::NoncontiguousNfa => Some(AhoCorasickKind::NoncontiguousNFA),
+            AtomAutomatonKind::ContiguousNfa => Some(AhoCorasickKind::ContiguousNFA),
+            AtomAutomatonKind::Dfa => Some(AhoCorasickKind::DFA),
+        }
+    }
+}
+
+fn default_prefilter() -> bool {
+    true
+}
+
+/// 原子词匹配器配置
+///
+/// 原子词以小写形式存储，开启大小写敏感后仅匹配文本中的小写形式
+/// （例如 "iron" 命中 "iron" 但不命中 "Iron"），可用于避开首字母大写的专有名词。
+/// `prefilter` 控制是否启用前置过滤（按首字节快速跳过不可能命中的位置），
+/// 额外内存很小，通常能加速；词表首字节分布很散时可关闭以省去这部分开销。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtomMatcherOptions {
+    #[serde(default)]
+    pub match_kind: AtomMatchKind,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub automaton_kind: AtomAutomatonKind,
+    #[serde(default = "default_prefilter")]
+    pub prefilter: bool,
+}
+
+impl Default for AtomMatcherOptions {
+    fn default() -> Self {
+        Self {
+            match_kind: AtomMatchKind::default(),
+            case_sensitive: false,
+            automaton_kind: AtomAutomatonKind::default(),
+            prefilter: default_prefilter(),
+        }
+    }
+}
+
+/// 原子词匹配器（自动机 + 模式到原词的映射表）
+struct AtomMatcher {
+    automaton: AhoCorasick,
+    /// 按模式编号索引的原词（复数变体映射回单数原词）
+    canonical: Vec<String>,
+}
+
+/// schema 迁移（新增列/索引时在末尾追加，版本号递增）
+const MIGRATIONS: &[Migration] = &[];
+
+/// 累积的待写入使用计数（不同原子词）达到该数量时自动落盘
+const USAGE_FLUSH_THRESHOLD: usize = 256;
+
+/// 使用计数写入遇到数据库锁定时的默认重试次数（不含首次写入）
+pub const DEFAULT_USAGE_WRITE_RETRIES: u32 = 5;
+
+/// 使用计数写入重试次数的上限
+pub const MAX_USAGE_WRITE_RETRIES: u32 = 20;
+
+/// 使用计数写入统计（进程启动以来，用于诊断计数丢失或持续锁定）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageWriteStats {
+    /// 成功写入的次数
+    pub flushes: u64,
+    /// 因数据库锁定而重试的次数
+    pub retries: u64,
+    /// 重试耗尽后仍失败的次数（计数放回队列等待下次写入）
+    pub failures: u64,
+    /// 最近一次失败的错误信息
+    pub last_error: Option<String>,
+}
+
 /// 原子数据库
 pub struct AtomicDB {
     conn: Arc<Mutex<Connection>>,
     memory_index: Arc<Mutex<HashMap<String, AtomTranslation>>>,
-    matcher: Arc<Mutex<Option<AhoCorasick>>>,
+    matcher: Arc<Mutex<Option<AtomMatcher>>>,
+    options: Mutex<AtomMatcherOptions>,
+    /// 尚未写入数据库的使用计数增量（原词 -> 次数）
+    pending_usage: Mutex<HashMap<String, i64>>,
+    /// 使用计数写入的最大重试次数
+    usage_write_retries: AtomicU32,
+    usage_write_stats: Mutex<UsageWriteStats>,
 }
 
 impl AtomicDB {
     /// 初始化数据库（自动从SQLite加载到内存）
-    pub fn new(db_path: &str) -> SqliteResult<Self> {
+    pub fn new<P: AsRef<Path>>(db_path: P) -> SqliteResult<Self> {
         let conn = Connection::open(db_path)?;
+        configure_busy_timeout(&conn)?;
         Self::init_schema(&conn)?;
 
         let db = AtomicDB {
             conn: Arc::new(Mutex::new(conn)),
             memory_index: Arc::new(Mutex::new(HashMap::new())),
             matcher: Arc::new(Mutex::new(None)),
+            options: Mutex::new(AtomMatcherOptions::default()),
+            pending_usage: Mutex::new(HashMap::new()),
+            usage_write_retries: AtomicU32::new(DEFAULT_USAGE_WRITE_RETRIES),
+            usage_write_stats: Mutex::new(UsageWriteStats::default()),
         };
 
         // 加载所有数据到内存
@@ -99,6 +360,8 @@ impl AtomicDB {
             [],
         )?;
 
+        run_migrations(conn, "atomic_meta", MIGRATIONS)?;
+
         Ok(())
     }
 
@@ -114,16 +377,18 @@ impl AtomicDB {
 
         // 1. 持久化到SQLite
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT INTO atomic_translations
-             (original_text, translated_text, source_type, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?4)
-             ON CONFLICT(original_text) DO UPDATE SET
-                 translated_text = ?2,
-                 source_type = ?3,
-                 updated_at = ?4",
-            params![&original_lower, translated, source.as_str(), now],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                "INSERT INTO atomic_translations
+                 (original_text, translated_text, source_type, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT(original_text) DO UPDATE SET
+                     translated_text = ?2,
+                     source_type = ?3,
+                     updated_at = ?4",
+                params![&original_lower, translated, source.as_str(), now],
+            )
+        })?;
 
         drop(conn); // 释放锁
 
@@ -142,10 +407,12 @@ impl AtomicDB {
 
         // 1. 从SQLite删除
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM atomic_translations WHERE original_text = ?1",
-            params![&original_lower],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                "DELETE FROM atomic_translations WHERE original_text = ?1",
+                params![&original_lower],
+            )
+        })?;
 
         drop(conn); // 释放锁
 
@@ -158,16 +425,74 @@ impl AtomicDB {
         Ok(())
     }
 
+    /// 按ID批量删除原子翻译（单个事务，删除后只重建一次匹配器），返回删除数量
+    pub fn delete_atoms(&self, ids: &[i64]) -> SqliteResult<usize> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            let mut deleted = 0;
+            {
+                let mut stmt = tx.prepare_cached("DELETE FROM atomic_translations WHERE id = ?1")?;
+                for id in ids {
+                    deleted += stmt.execute(params![id])?;
+                }
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })?;
+        drop(conn); // 释放锁
+
+        if deleted > 0 {
+            self.load_all_to_memory()?;
+            self.rebuild_matcher()?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// 列出长期未使用的原子翻译（用于清理术语表）
+    ///
+    /// 满足任一条件即返回：`usage_count <= max_usage`，或 `updated_at < before_updated_at`。
+    /// 两个条件都未指定时返回从未使用过的原子词。按使用次数、更新时间升序排列
+    pub fn list_stale_atoms(
+        &self,
+        max_usage: Option<i32>,
+        before_updated_at: Option<i64>,
+    ) -> SqliteResult<Vec<AtomTranslation>> {
+        // 先写入累积的使用计数，避免刚使用过的原子词被误判
+        self.flush_usage()?;
+
+        let max_usage = match (max_usage, before_updated_at) {
+            (None, None) => Some(0),
+            (max_usage, _) => max_usage,
+        };
+
+        let memory = self.memory_index.lock().unwrap();
+        let mut atoms: Vec<AtomTranslation> = memory
+            .values()
+            .filter(|atom| {
+                max_usage.is_some_and(|max| atom.usage_count <= max)
+                    || before_updated_at.is_some_and(|cutoff| atom.updated_at < cutoff)
+            })
+            .cloned()
+            .collect();
+
+        atoms.sort_by_key(|atom| (atom.usage_count, atom.updated_at));
+        Ok(atoms)
+    }
+
     /// 根据ID更新原子翻译（仅更新译文和来源）
     pub fn update_atom(&self, id: i64, translated: &str, source: AtomSource) -> SqliteResult<()> {
         let now = now_timestamp();
 
         // 1. 更新SQLite
         let conn = self.conn.lock().unwrap();
-        let affected = conn.execute(
-            "UPDATE atomic_translations SET translated_text = ?1, source_type = ?2, updated_at = ?3 WHERE id = ?4",
-            params![translated, source.as_str(), now, id],
-        )?;
+        let affected = retry_on_busy(|| {
+            conn.execute(
+                "UPDATE atomic_translations SET translated_text = ?1, source_type = ?2, updated_at = ?3 WHERE id = ?4",
+                params![translated, source.as_str(), now, id],
+            )
+        })?;
 
         drop(conn); // 释放锁
 
@@ -184,6 +509,27 @@ impl AtomicDB {
         Ok(())
     }
 
+    /// 获取当前匹配器配置
+    pub fn matcher_options(&self) -> AtomMatcherOptions {
+        *self.options.lock().unwrap()
+    }
+
+    /// 更新匹配器配置并重建匹配器
+    pub fn set_matcher_options(&self, options: AtomMatcherOptions) -> SqliteResult<()> {
+        *self.options.lock().unwrap() = options;
+        self.rebuild_matcher()
+    }
+
+    /// 设置使用计数写入的最大重试次数
+    pub fn set_usage_write_retries(&self, retries: u32) {
+        self.usage_write_retries.store(retries, Ordering::Relaxed);
+    }
+
+    /// 获取使用计数写入统计
+    pub fn usage_write_stats(&self) -> UsageWriteStats {
+        self.usage_write_stats.lock().unwrap().clone()
+    }
+
     /// 获取所有原子翻译
     pub fn get_all_atoms(&self) -> SqliteResult<Vec<AtomTranslation>> {
         let memory = self.memory_index.lock().unwrap();
@@ -196,94 +542,229 @@ impl AtomicDB {
     }
 
     /// 🔥 核心功能：替换文本中的原子词
-    pub fn replace_with_atoms(&self, text: &str) -> String {
+    ///
+    /// 仅使用 `allowed_sources` 中来源的原子词（None 表示全部来源）。通过屏蔽命中结果实现，
+    /// 不重建匹配器：被屏蔽的长词命中不会回退为其中较短的词
+    pub fn replace_with_atoms(
+        &self,
+        text: &str,
+        allowed_sources: Option<&[AtomSource]>,
+    ) -> String {
+        self.replace_with_atoms_detailed(text, allowed_sources).text
+    }
+
+    /// 替换文本中的原子词，并返回候选命中数、实际替换数与耗时
+    pub fn replace_with_atoms_detailed(
+        &self,
+        text: &str,
+        allowed_sources: Option<&[AtomSource]>,
+    ) -> AtomReplaceDetailed {
         let matcher_guard = self.matcher.lock().unwrap();
         let matcher = match matcher_guard.as_ref() {
             Some(m) => m,
-            None => return text.to_string(), // 无原子词，直接返回
+            // 无原子词，直接返回
+            None => {
+                return AtomReplaceDetailed {
+                    text: text.to_string(),
+                    metrics: AtomReplaceMetrics {
+                        candidate_matches: 0,
+                        applied: 0,
+                        elapsed_us: 0,
+                    },
+                }
+            }
         };
 
         let memory = self.memory_index.lock().unwrap();
-        let text_lower = text.to_lowercase();
-
-        // 使用 Aho-Corasick 找到所有匹配位置
-        let mut matches: Vec<_> = matcher.find_iter(&text_lower).collect();
-
-        if matches.is_empty() {
-            return text.to_string();
+        let case_sensitive = self.matcher_options().case_sensitive;
+        let started = Instant::now();
+        let (result, used, candidate_matches) =
+            replace_with_matcher(matcher, &memory, case_sensitive, allowed_sources, text);
+        let elapsed_us = started.elapsed().as_micros().min(u64::MAX as u128) as u64;
+
+        drop(memory);
+        drop(matcher_guard);
+
+        let metrics = AtomReplaceMetrics {
+            candidate_matches,
+            applied: used.len(),
+            elapsed_us,
+        };
+        self.record_usage(used);
+        AtomReplaceDetailed {
+            text: result,
+            metrics,
         }
+    }
 
-        // 按位置倒序排列，避免替换时位置偏移
-        matches.sort_by_key(|m| std::cmp::Reverse(m.start()));
+    /// 批量替换多条文本中的原子词（共享同一匹配器，并行处理），返回顺序与输入一致
+    pub fn replace_many_with_atoms(
+        &self,
+        texts: &[String],
+        allowed_sources: Option<&[AtomSource]>,
+    ) -> Vec<String> {
+        self.replace_many_inner(texts, allowed_sources, false).texts
+    }
 
-        let mut result = text.to_string();
+    /// 批量替换并汇总本次命中的原子词（用于术语一致性检查）
+    pub fn replace_many_with_atoms_report(
+        &self,
+        texts: &[String],
+        allowed_sources: Option<&[AtomSource]>,
+    ) -> AtomReplaceReport {
+        self.replace_many_inner(texts, allowed_sources, true)
+    }
 
-        // 用于追踪已处理的位置，避免重复替换
-        let mut processed_ranges: Vec<(usize, usize)> = Vec::new();
+    fn replace_many_inner(
+        &self,
+        texts: &[String],
+        allowed_sources: Option<&[AtomSource]>,
+        with_report: bool,
+    ) -> AtomReplaceReport {
+        let matcher_guard = self.matcher.lock().unwrap();
+        let matcher = match matcher_guard.as_ref() {
+            Some(m) => m,
+            None => {
+                return AtomReplaceReport {
+                    texts: texts.to_vec(),
+                    applied_atoms: Vec::new(),
+                }
+            }
+        };
 
-        for mat in matches {
-            let start = mat.start();
-            let end = mat.end();
+        let memory = self.memory_index.lock().unwrap();
+        let case_sensitive = self.matcher_options().case_sensitive;
+        let (results, used): (Vec<String>, Vec<Vec<String>>) = texts
+            .par_iter()
+            .map(|text| {
+                let (result, used, _) =
+                    replace_with_matcher(matcher, &memory, case_sensitive, allowed_sources, text);
+                (result, used)
+            })
+            .unzip();
 
-            // 检查是否与已处理的范围重叠
-            if processed_ranges
-                .iter()
-                .any(|(ps, pe)| !(end <= *ps || start >= *pe))
-            {
-                continue; // 跳过重叠的匹配
+        let mut applied_atoms = Vec::new();
+        if with_report {
+            let mut hits: HashMap<&str, usize> = HashMap::new();
+            for original in used.iter().flatten() {
+                *hits.entry(original.as_str()).or_insert(0) += 1;
             }
+            applied_atoms = hits
+                .into_iter()
+                .filter_map(|(original, hits)| {
+                    memory.get(original).map(|atom| AppliedAtom {
+                        original: atom.original.clone(),
+                        translated: atom.translated.clone(),
+                        source: atom.source,
+                        hits,
+                    })
+                })
+                .collect();
+            applied_atoms.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.original.cmp(&b.original)));
+        }
 
-            let matched_text_lower = &text_lower[start..end];
+        drop(memory);
+        drop(matcher_guard);
 
-            // 尝试查找原子翻译（先查原文，再查复数变体）
-            let atom_opt = if let Some(a) = memory.get(matched_text_lower) {
-                Some(a.clone())
-            } else {
-                // 尝试词形还原（去除复数）
-                self.find_atom_by_normalization(matched_text_lower, &memory)
-            };
+        self.record_usage(used.into_iter().flatten());
+        AtomReplaceReport {
+            texts: results,
+            applied_atoms,
+        }
+    }
 
-            if let Some(atom) = atom_opt {
-                // 保留原文大小写形式
-                let original_case = &text[start..end];
-                let replacement = format!("{}({})", original_case, atom.translated);
+    /// 将累积的使用计数写入数据库并执行 WAL checkpoint，返回写入的原子词数量
+    ///
+    /// 数据库被锁定时按递增退避重试（次数由 `set_usage_write_retries` 设置），
+    /// 仍失败时计数会放回待写入队列，等待下次写入
+    pub fn flush_usage(&self) -> SqliteResult<usize> {
+        let pending = std::mem::take(&mut *self.pending_usage.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(0);
+        }
 
-                // 替换
-                result.replace_range(start..end, &replacement);
+        let conn = self.conn.lock().unwrap();
+        let max_retries = self.usage_write_retries.load(Ordering::Relaxed);
+        let mut retries = 0;
+        let written = retry_on_busy_with(max_retries, |_| retries += 1, || {
+            let tx = conn.unchecked_transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "UPDATE atomic_translations SET usage_count = usage_count + ?1 WHERE original_text = ?2",
+                )?;
+                for (original, count) in &pending {
+                    stmt.execute(params![count, original])?;
+                }
+            }
+            tx.commit()
+        });
 
-                // 记录已处理的范围
-                processed_ranges.push((start, end));
+        {
+            let mut stats = self.usage_write_stats.lock().unwrap();
+            stats.retries += retries;
+            match &written {
+                Ok(()) => stats.flushes += 1,
+                Err(e) => {
+                    stats.failures += 1;
+                    stats.last_error = Some(e.to_string());
+                }
+            }
+        }
 
-                // 增加使用计数（异步）
-                self.increment_usage_async(&atom.original);
+        if let Err(e) = written {
+            let mut queue = self.pending_usage.lock().unwrap();
+            for (original, count) in pending {
+                *queue.entry(original).or_insert(0) += count;
             }
+            return Err(e);
         }
 
-        result
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+        drop(conn);
+
+        // 同步内存中的计数，使按使用次数排序立即生效
+        let mut memory = self.memory_index.lock().unwrap();
+        for (original, count) in &pending {
+            if let Some(atom) = memory.get_mut(original) {
+                atom.usage_count += *count as i32;
+            }
+        }
+
+        Ok(pending.len())
     }
 
     /// 批量添加原子翻译（用于初始化或导入）
-    pub fn batch_upsert(&self, atoms: Vec<(&str, &str, AtomSource)>) -> SqliteResult<()> {
-        let conn = self.conn.lock().unwrap();
-        let tx = conn.unchecked_transaction()?;
+    ///
+    /// 同一批次内原文重复时最后一条生效，返回被合并的重复条目
+    pub fn batch_upsert(
+        &self,
+        atoms: Vec<(&str, &str, AtomSource)>,
+    ) -> SqliteResult<Vec<AtomBatchDuplicate>> {
+        let duplicates = find_batch_duplicates(atoms.iter().map(|(o, t, _)| (*o, *t)));
+        if !duplicates.is_empty() {
+            eprintln!("⚠ 批量写入的原子词中有 {} 个原文重复", duplicates.len());
+        }
 
+        let conn = self.conn.lock().unwrap();
         let now = now_timestamp();
 
-        for (original, translated, source) in atoms {
-            let original_lower = original.to_lowercase();
-            tx.execute(
-                "INSERT INTO atomic_translations
-                 (original_text, translated_text, source_type, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?4)
-                 ON CONFLICT(original_text) DO UPDATE SET
-                     translated_text = ?2,
-                     source_type = ?3,
-                     updated_at = ?4",
-                params![&original_lower, translated, source.as_str(), now],
-            )?;
-        }
-
-        tx.commit()?;
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            for (original, translated, source) in &atoms {
+                let original_lower = original.to_lowercase();
+                tx.execute(
+                    "INSERT INTO atomic_translations
+                     (original_text, translated_text, source_type, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?4)
+                     ON CONFLICT(original_text) DO UPDATE SET
+                         translated_text = ?2,
+                         source_type = ?3,
+                         updated_at = ?4",
+                    params![&original_lower, translated, source.as_str(), now],
+                )?;
+            }
+            tx.commit()
+        })?;
 
         drop(conn); // 释放锁
 
@@ -291,7 +772,96 @@ impl AtomicDB {
         self.load_all_to_memory()?;
         self.rebuild_matcher()?;
 
-        Ok(())
+        Ok(duplicates)
+    }
+
+    /// 检测导入冲突（不写入数据库）：库中已有且译文不同的条目
+    pub fn detect_import_conflicts(&self, entries: &[AtomImportEntry]) -> Vec<AtomImportConflict> {
+        let memory = self.memory_index.lock().unwrap();
+        let mut seen = std::collections::HashSet::new();
+
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let original_lower = entry.original.to_lowercase();
+                let existing = memory.get(&original_lower)?;
+                if existing.translated == entry.translated || !seen.insert(original_lower.clone()) {
+                    return None;
+                }
+                Some(AtomImportConflict {
+                    original: original_lower,
+                    existing: existing.translated.clone(),
+                    incoming: entry.translated.clone(),
+                    existing_source: existing.source,
+                })
+            })
+            .collect()
+    }
+
+    /// 导入原子词，冲突按 `resolutions`（原文小写 -> 策略）处理，
+    /// 未指定的冲突使用 `default_strategy`，避免术语表被整体静默覆盖
+    pub fn import_atoms(
+        &self,
+        entries: &[AtomImportEntry],
+        source: AtomSource,
+        resolutions: &HashMap<String, AtomConflictStrategy>,
+        default_strategy: AtomConflictStrategy,
+    ) -> SqliteResult<AtomImportResult> {
+        let mut result = AtomImportResult {
+            duplicates: find_batch_duplicates(
+                entries.iter().map(|e| (e.original.as_str(), e.translated.as_str())),
+            ),
+            ..Default::default()
+        };
+
+        // 文件内重复的原文只保留最后一条，避免重复计数
+        let last_positions: HashMap<String, usize> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.original.to_lowercase(), i))
+            .collect();
+
+        let mut to_write: Vec<(&str, &str, AtomSource)> = Vec::new();
+        {
+            let memory = self.memory_index.lock().unwrap();
+            for (i, entry) in entries.iter().enumerate() {
+                let original_lower = entry.original.to_lowercase();
+                if last_positions.get(&original_lower) != Some(&i) {
+                    continue;
+                }
+                match memory.get(&original_lower) {
+                    None => result.inserted += 1,
+                    Some(existing) if existing.translated == entry.translated => {
+                        result.unchanged += 1;
+                        continue;
+                    }
+                    Some(_) => {
+                        let strategy = resolutions
+                            .get(&original_lower)
+                            .copied()
+                            .unwrap_or(default_strategy);
+                        match strategy {
+                            AtomConflictStrategy::Overwrite => result.overwritten += 1,
+                            AtomConflictStrategy::Keep => {
+                                result.kept += 1;
+                                continue;
+                            }
+                            AtomConflictStrategy::Skip => {
+                                result.skipped += 1;
+                                continue;
+                            }
+                        }
+                    }
+                }
+                to_write.push((&entry.original, &entry.translated, source));
+            }
+        }
+
+        if !to_write.is_empty() {
+            self.batch_upsert(to_write)?;
+        }
+
+        Ok(result)
     }
 
     // ==================== 内部辅助方法 ====================
@@ -336,85 +906,155 @@ impl AtomicDB {
             return Ok(());
         }
 
-        // 构建匹配模式列表
-        let mut patterns: Vec<String> = memory.keys().cloned().collect();
-
-        // 添加复数形式匹配（为每个模式生成可能的复数形式）
-        let mut plural_patterns = Vec::new();
-        for pattern in &patterns {
-            // 简单复数规则
-            if !pattern.ends_with('s') {
-                plural_patterns.push(format!("{}s", pattern)); // cats
-            }
-            if !pattern.ends_with("es") {
-                plural_patterns.push(format!("{}es", pattern)); // boxes
+        // 构建匹配模式列表：原词 + 合理的复数变体（变体 -> 原词）
+        let mut entries: Vec<(String, String)> = memory
+            .keys()
+            .map(|original| (original.clone(), original.clone()))
+            .collect();
+        for original in memory.keys() {
+            for variant in plural_variants(original) {
+                // 变体本身也是原子词时，以该原子词为准
+                if !memory.contains_key(&variant) {
+                    entries.push((variant, original.clone()));
+                }
             }
         }
-        patterns.extend(plural_patterns);
 
         // 按长度降序排序，优先匹配长词（避免短词优先匹配）
-        patterns.sort_by(|a, b| b.len().cmp(&a.len()));
+        entries.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        entries.dedup_by(|a, b| a.0 == b.0);
+
+        let (patterns, canonical): (Vec<String>, Vec<String>) = entries.into_iter().unzip();
 
         // 构建 Aho-Corasick 自动机
-        let ac = AhoCorasickBuilder::new()
-            .ascii_case_insensitive(true) // 大小写不敏感
-            .match_kind(aho_corasick::MatchKind::LeftmostLongest) // 最左最长匹配
+        let options = self.matcher_options();
+        let automaton = AhoCorasickBuilder::new()
+            .ascii_case_insensitive(!options.case_sensitive)
+            .match_kind(options.match_kind.to_aho_corasick())
+            .kind(options.automaton_kind.to_aho_corasick())
+            .prefilter(options.prefilter)
             .build(&patterns)
             .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
-        *self.matcher.lock().unwrap() = Some(ac);
+        *self.matcher.lock().unwrap() = Some(AtomMatcher {
+            automaton,
+            canonical,
+        });
 
         Ok(())
     }
 
-    /// 词形还原匹配（尝试去除复数后缀）
-    fn find_atom_by_normalization(
-        &self,
-        word: &str,
-        memory: &HashMap<String, AtomTranslation>,
-    ) -> Option<AtomTranslation> {
-        // 规则1: 去除 's' (cats -> cat)
-        if word.ends_with('s') && word.len() > 2 {
-            let stem = &word[..word.len() - 1];
-            if let Some(atom) = memory.get(stem) {
-                return Some(atom.clone());
+    /// 累积使用计数（由 flush_usage 批量写入），待写入数量达到阈值时自动落盘
+    fn record_usage(&self, originals: impl IntoIterator<Item = String>) {
+        let pending_count = {
+            let mut pending = self.pending_usage.lock().unwrap();
+            for original in originals {
+                *pending.entry(original).or_insert(0) += 1;
             }
-        }
+            pending.len()
+        };
 
-        // 规则2: 去除 'es' (boxes -> box)
-        if word.ends_with("es") && word.len() > 3 {
-            let stem = &word[..word.len() - 2];
-            if let Some(atom) = memory.get(stem) {
-                return Some(atom.clone());
+        if pending_count >= USAGE_FLUSH_THRESHOLD {
+            if let Err(e) = self.flush_usage() {
+                eprintln!("⚠ 写入原子词使用计数失败: {}", e);
             }
         }
+    }
+}
 
-        // 规则3: 'ies' -> 'y' (berries -> berry)
-        if word.ends_with("ies") && word.len() > 4 {
-            let stem = &word[..word.len() - 3];
-            let singular = format!("{}y", stem);
-            if let Some(atom) = memory.get(&singular) {
-                return Some(atom.clone());
-            }
+/// 使用匹配器替换单条文本中的原子词，返回替换结果、命中的原词（用于使用计数）
+/// 以及自动机报告的候选命中数
+fn replace_with_matcher(
+    matcher: &AtomMatcher,
+    memory: &HashMap<String, AtomTranslation>,
+    case_sensitive: bool,
+    allowed_sources: Option<&[AtomSource]>,
+    text: &str,
+) -> (String, Vec<String>, usize) {
+    let text_lower = text.to_lowercase();
+
+    // 使用 Aho-Corasick 找到所有匹配位置（大小写敏感时直接匹配原文）
+    let haystack = if case_sensitive {
+        text
+    } else {
+        text_lower.as_str()
+    };
+    let mut matches: Vec<_> = matcher.automaton.find_iter(haystack).collect();
+    let candidates = matches.len();
+
+    if matches.is_empty() {
+        return (text.to_string(), Vec::new(), 0);
+    }
+
+    // 按位置倒序排列，避免替换时位置偏移
+    matches.sort_by_key(|m| std::cmp::Reverse(m.start()));
+
+    let mut result = text.to_string();
+    let mut used = Vec::new();
+
+    // 用于追踪已处理的位置，避免重复替换
+    let mut processed_ranges: Vec<(usize, usize)> = Vec::new();
+
+    for mat in matches {
+        let start = mat.start();
+        let end = mat.end();
+
+        // 检查是否与已处理的范围重叠
+        if processed_ranges
+            .iter()
+            .any(|(ps, pe)| !(end <= *ps || start >= *pe))
+        {
+            continue; // 跳过重叠的匹配
+        }
+
+        // 复数变体通过映射表还原为原词
+        let canonical = &matcher.canonical[mat.pattern().as_usize()];
+
+        let atom = memory
+            .get(canonical)
+            .filter(|atom| allowed_sources.is_none_or(|sources| sources.contains(&atom.source)));
+        if let Some(atom) = atom {
+            // 保留原文大小写形式
+            let original_case = &text[start..end];
+            let replacement = format!("{}({})", original_case, atom.translated);
+
+            // 替换
+            result.replace_range(start..end, &replacement);
+
+            // 记录已处理的范围
+            processed_ranges.push((start, end));
+            used.push(atom.original.clone());
         }
+    }
+
+    (result, used, candidates)
+}
 
-        None
+/// 生成英文复数变体（仅生成语法上合理的形式）
+///
+/// - 以 s/x/z/ch/sh 结尾：加 es（bus -> buses, box -> boxes）
+/// - 辅音 + y 结尾：y 变 ies（berry -> berries）
+/// - 其他：加 s（cat -> cats）
+fn plural_variants(word: &str) -> Vec<String> {
+    let Some(last) = word.chars().last() else {
+        return Vec::new();
+    };
+    if !last.is_ascii_alphabetic() {
+        return Vec::new();
     }
 
-    /// 异步增加使用计数
-    fn increment_usage_async(&self, original: &str) {
-        let conn = self.conn.clone();
-        let original = original.to_string();
+    if word.ends_with(['s', 'x', 'z']) || word.ends_with("ch") || word.ends_with("sh") {
+        return vec![format!("{}es", word)];
+    }
 
-        // 在后台线程执行，不阻塞主流程
-        std::thread::spawn(move || {
-            let conn = conn.lock().unwrap();
-            let _ = conn.execute(
-                "UPDATE atomic_translations SET usage_count = usage_count + 1 WHERE original_text = ?1",
-                params![&original],
-            );
-        });
+    if let Some(stem) = word.strip_suffix('y') {
+        let before_y = stem.chars().last();
+        if matches!(before_y, Some(c) if c.is_ascii_alphabetic() && !"aeiou".contains(c)) {
+            return vec![format!("{}ies", stem)];
+        }
     }
+
+    vec![format!("{}s", word)]
 }
 
 /// 获取当前时间戳（秒）
@@ -441,11 +1081,47 @@ mod tests {
 
         // 测试替换
         let input = "The Argonian waits in Savangard.";
-        let output = db.replace_with_atoms(input);
+        let output = db.replace_with_atoms(input, None);
         assert!(output.contains("Argonian(亚龙人)"));
         assert!(output.contains("Savangard(松加德)"));
     }
 
+    #[test]
+    fn import_atoms_reports_and_resolves_conflicts() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("whiterun", "雪漫", AtomSource::Manual).unwrap();
+        db.upsert_atom("riften", "裂谷城", AtomSource::Manual).unwrap();
+
+        let entries = vec![
+            AtomImportEntry { original: "Whiterun".into(), translated: "白漫城".into() },
+            AtomImportEntry { original: "Riften".into(), translated: "里弗顿".into() },
+            AtomImportEntry { original: "Solitude".into(), translated: "孤独城".into() },
+            AtomImportEntry { original: "solitude".into(), translated: "独孤城".into() },
+        ];
+        let conflicts = db.detect_import_conflicts(&entries);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].existing, "雪漫");
+
+        let resolutions = HashMap::from([("riften".to_string(), AtomConflictStrategy::Overwrite)]);
+        let result = db
+            .import_atoms(&entries, AtomSource::Base, &resolutions, AtomConflictStrategy::Keep)
+            .unwrap();
+        assert_eq!((result.inserted, result.overwritten, result.kept), (1, 1, 1));
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(result.duplicates[0].kept, "独孤城");
+        assert_eq!(result.duplicates[0].discarded, vec!["孤独城".to_string()]);
+
+        let atoms: HashMap<_, _> = db
+            .get_all_atoms()
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.original, a.translated))
+            .collect();
+        assert_eq!(atoms["whiterun"], "雪漫");
+        assert_eq!(atoms["riften"], "里弗顿");
+        assert_eq!(atoms["solitude"], "独孤城");
+    }
+
     #[test]
     fn test_plural_matching() {
         let db = AtomicDB::new(":memory:").unwrap();
@@ -455,7 +1131,7 @@ mod tests {
 
         // 测试复数匹配
         let input = "Many argonians live here.";
-        let output = db.replace_with_atoms(input);
+        let output = db.replace_with_atoms(input, None);
         println!("Input:  {}", input);
         println!("Output: {}", output);
         assert!(output.contains("argonians(亚龙人)"));
@@ -469,8 +1145,219 @@ mod tests {
 
         // 测试大小写不敏感
         let input = "Welcome to SKYRIM and Skyrim!";
-        let output = db.replace_with_atoms(input);
+        let output = db.replace_with_atoms(input, None);
         assert!(output.contains("SKYRIM(天际)"));
         assert!(output.contains("Skyrim(天际)"));
     }
+
+    #[test]
+    fn test_match_kind_iron_vs_iron_sword() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("iron", "铁", AtomSource::Base).unwrap();
+        db.upsert_atom("iron sword", "铁剑", AtomSource::Base).unwrap();
+
+        // 默认最左最长：整体命中 "iron sword"
+        let output = db.replace_with_atoms("An Iron Sword.", None);
+        assert_eq!(output, "An Iron Sword(铁剑).");
+
+        // Standard：较短的 "iron" 先结束，优先命中
+        db.set_matcher_options(AtomMatcherOptions {
+            match_kind: AtomMatchKind::Standard,
+            ..Default::default()
+        })
+        .unwrap();
+        let output = db.replace_with_atoms("An Iron Sword.", None);
+        assert_eq!(output, "An Iron(铁) Sword.");
+    }
+
+    #[test]
+    fn test_case_sensitive_matching() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("iron", "铁", AtomSource::Base).unwrap();
+        db.set_matcher_options(AtomMatcherOptions {
+            match_kind: AtomMatchKind::LeftmostLongest,
+            case_sensitive: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let output = db.replace_with_atoms("Iron ore and iron ingots.", None);
+        assert_eq!(output, "Iron ore and iron(铁) ingots.");
+    }
+
+    #[test]
+    fn test_automaton_kinds_match_identically() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("iron", "铁", AtomSource::Base).unwrap();
+        db.upsert_atom("iron sword", "铁剑", AtomSource::Base).unwrap();
+
+        for automaton_kind in [
+            AtomAutomatonKind::NoncontiguousNfa,
+            AtomAutomatonKind::ContiguousNfa,
+            AtomAutomatonKind::Dfa,
+        ] {
+            db.set_matcher_options(AtomMatcherOptions {
+                automaton_kind,
+                prefilter: false,
+                ..Default::default()
+            })
+            .unwrap();
+            let output = db.replace_with_atoms("An Iron Sword and iron.", None);
+            assert_eq!(output, "An Iron Sword(铁剑) and iron(铁).");
+        }
+
+        // 旧配置缺少新字段时保持默认行为（自动选择 + 前置过滤）
+        let options: AtomMatcherOptions =
+            serde_json::from_str(r#"{"match_kind":"standard"}"#).unwrap();
+        assert_eq!(options.automaton_kind, AtomAutomatonKind::Auto);
+        assert!(options.prefilter);
+    }
+
+    #[test]
+    fn test_plural_variants_are_plausible() {
+        assert_eq!(plural_variants("bus"), vec!["buses"]);
+        assert_eq!(plural_variants("box"), vec!["boxes"]);
+        assert_eq!(plural_variants("torch"), vec!["torches"]);
+        assert_eq!(plural_variants("berry"), vec!["berries"]);
+        assert_eq!(plural_variants("key"), vec!["keys"]);
+        assert_eq!(plural_variants("argonian"), vec!["argonians"]);
+        assert!(plural_variants("101").is_empty());
+    }
+
+    #[test]
+    fn test_plural_maps_back_to_canonical() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("bus", "公交车", AtomSource::Manual).unwrap();
+        db.upsert_atom("bu", "布", AtomSource::Manual).unwrap();
+        db.upsert_atom("berry", "浆果", AtomSource::Manual).unwrap();
+
+        let output = db.replace_with_atoms("Two buses carry berries.", None);
+        assert_eq!(output, "Two buses(公交车) carry berries(浆果).");
+    }
+
+    #[test]
+    fn flush_usage_persists_pending_counts() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("skyrim", "天际", AtomSource::Base).unwrap();
+
+        db.replace_with_atoms("Skyrim, skyrim!", None);
+        assert_eq!(db.get_all_atoms().unwrap()[0].usage_count, 0);
+
+        assert_eq!(db.flush_usage().unwrap(), 1);
+        assert_eq!(db.get_all_atoms().unwrap()[0].usage_count, 2);
+        // 重新加载后计数来自数据库
+        db.load_all_to_memory().unwrap();
+        assert_eq!(db.get_all_atoms().unwrap()[0].usage_count, 2);
+        assert_eq!(db.flush_usage().unwrap(), 0);
+    }
+
+    #[test]
+    fn replace_many_matches_single_replacement() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("argonian", "亚龙人", AtomSource::Base).unwrap();
+
+        let texts = vec![
+            "The Argonian waits.".to_string(),
+            "Nothing here.".to_string(),
+            "Many argonians live here.".to_string(),
+        ];
+        let batch = db.replace_many_with_atoms(&texts, None);
+        let single: Vec<String> = texts.iter().map(|t| db.replace_with_atoms(t, None)).collect();
+
+        assert_eq!(batch, single);
+        assert_eq!(db.flush_usage().unwrap(), 1);
+        assert_eq!(db.get_all_atoms().unwrap()[0].usage_count, 4);
+    }
+
+    #[test]
+    fn replace_report_counts_hits_per_call() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("skyrim", "天际", AtomSource::Base).unwrap();
+        db.upsert_atom("argonian", "亚龙人", AtomSource::Manual).unwrap();
+        db.upsert_atom("whiterun", "雪漫", AtomSource::Base).unwrap();
+
+        let texts = vec![
+            "Skyrim belongs to the Nords.".to_string(),
+            "Argonians in Skyrim.".to_string(),
+        ];
+        let report = db.replace_many_with_atoms_report(&texts, None);
+        assert_eq!(report.texts, db.replace_many_with_atoms(&texts, None));
+
+        let summary: Vec<_> = report
+            .applied_atoms
+            .iter()
+            .map(|a| (a.original.as_str(), a.translated.as_str(), a.hits))
+            .collect();
+        assert_eq!(summary, vec![("skyrim", "天际", 2), ("argonian", "亚龙人", 1)]);
+
+        // 本次命中次数不受累计 usage_count 影响
+        db.flush_usage().unwrap();
+        let again = db.replace_many_with_atoms_report(&texts[..1], None);
+        assert_eq!(again.applied_atoms[0].hits, 1);
+    }
+
+    #[test]
+    fn replace_detailed_reports_candidates_and_applied() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("iron", "铁", AtomSource::Base).unwrap();
+        db.upsert_atom("sword", "剑", AtomSource::Manual).unwrap();
+
+        let detailed =
+            db.replace_with_atoms_detailed("Iron sword, iron ore.", Some(&[AtomSource::Base]));
+        assert_eq!(detailed.text, "Iron(铁) sword, iron(铁) ore.");
+        assert_eq!(detailed.metrics.candidate_matches, 3);
+        assert_eq!(detailed.metrics.applied, 2);
+    }
+
+    #[test]
+    fn replace_respects_allowed_sources() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("skyrim", "天际", AtomSource::Base).unwrap();
+        db.upsert_atom("whiterun", "雪漫", AtomSource::AI).unwrap();
+
+        let input = "Whiterun is in Skyrim.";
+        assert_eq!(db.replace_with_atoms(input, None), "Whiterun(雪漫) is in Skyrim(天际).");
+        assert_eq!(
+            db.replace_with_atoms(input, Some(&[AtomSource::Base])),
+            "Whiterun is in Skyrim(天际)."
+        );
+    }
+
+    #[test]
+    fn list_stale_atoms_filters_unused_and_old() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("skyrim", "天际", AtomSource::Base).unwrap();
+        db.upsert_atom("whiterun", "雪漫", AtomSource::Base).unwrap();
+        db.replace_with_atoms("Skyrim", None);
+
+        let stale = db.list_stale_atoms(None, None).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].original, "whiterun");
+
+        let stale = db.list_stale_atoms(None, Some(i64::MAX)).unwrap();
+        assert_eq!(stale.len(), 2);
+    }
+
+    #[test]
+    fn delete_atoms_removes_all_in_one_pass() {
+        let db = AtomicDB::new(":memory:").unwrap();
+        db.upsert_atom("skyrim", "天际", AtomSource::Base).unwrap();
+        db.upsert_atom("whiterun", "雪漫", AtomSource::Base).unwrap();
+        db.upsert_atom("riften", "裂谷城", AtomSource::Base).unwrap();
+
+        let ids: Vec<i64> = db
+            .get_all_atoms()
+            .unwrap()
+            .into_iter()
+            .filter(|atom| atom.original != "riften")
+            .map(|atom| atom.id)
+            .collect();
+
+        assert_eq!(db.delete_atoms(&ids).unwrap(), 2);
+        assert_eq!(db.get_all_atoms().unwrap().len(), 1);
+        assert_eq!(
+            db.replace_with_atoms("Skyrim and Riften", None),
+            "Skyrim and Riften(裂谷城)"
+        );
+    }
 }
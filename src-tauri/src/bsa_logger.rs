@@ -1,48 +1,47 @@
-use std::path::Path;
+use esp_extractor::LoadedPlugin;
+use std::path::{Path, PathBuf};
 
 const OFFICIAL_MASTER_FILES: [&str; 5] = ["skyrim", "update", "dawnguard", "dragonborn", "hearthfires"];
 
+/// 本地化插件提取到的字符串少于该数量时视为可疑（STRING 文件可能未找到）
+pub const SUSPICIOUS_STRING_COUNT: usize = 10;
+
+/// 获取插件预期的 BSA 路径（官方主文件使用 Skyrim - Interface.bsa）
+fn expected_bsa_path(plugin_path: &Path) -> Option<PathBuf> {
+    let plugin_dir = plugin_path.parent()?;
+    let plugin_name = plugin_path.file_stem()?.to_string_lossy();
+
+    let plugin_name_lower = plugin_name.to_lowercase();
+    let is_official = OFFICIAL_MASTER_FILES
+        .iter()
+        .any(|name| plugin_name_lower == *name);
+
+    Some(if is_official {
+        plugin_dir.join("Skyrim - Interface.bsa")
+    } else {
+        plugin_dir.join(format!("{}.bsa", plugin_name))
+    })
+}
+
 /// 在调试模式下输出与 BSA fallback 相关的路径信息
 pub fn log_bsa_presence(plugin_path: &Path, language: Option<&str>) {
     #[cfg(debug_assertions)]
     {
         let language = language.unwrap_or("english");
 
-        let Some(plugin_dir) = plugin_path.parent() else {
-            eprintln!(
-                "[BSA] 无法获取插件目录，path = {:?}",
-                plugin_path
-            );
-            return;
-        };
-
-        let Some(plugin_name) = plugin_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-        else {
+        let Some(bsa_path) = expected_bsa_path(plugin_path) else {
             eprintln!(
-                "[BSA] 无法解析插件名称，path = {:?}",
+                "[BSA] 无法解析插件目录或名称，path = {:?}",
                 plugin_path
             );
             return;
         };
 
-        let plugin_name_lower = plugin_name.to_lowercase();
-        let is_official = OFFICIAL_MASTER_FILES
-            .iter()
-            .any(|name| plugin_name_lower == *name);
-
-        let bsa_path = if is_official {
-            plugin_dir.join("Skyrim - Interface.bsa")
-        } else {
-            plugin_dir.join(format!("{}.bsa", plugin_name))
-        };
-
         eprintln!(
             "[BSA] 插件: {} | 语言: {} | 目录: {}",
-            plugin_name,
+            plugin_path.file_stem().unwrap_or_default().to_string_lossy(),
             language,
-            plugin_dir.display()
+            bsa_path.parent().unwrap_or(Path::new("")).display()
         );
         eprintln!(
             "[BSA] 预期 BSA: {} | 存在: {}",
@@ -56,3 +55,49 @@ pub fn log_bsa_presence(plugin_path: &Path, language: Option<&str>) {
         let _ = (plugin_path, language);
     }
 }
+
+/// 检查本地化插件的字符串是否缺失或数量可疑，并返回包含 BSA 是否存在的警告信息（发布版本同样生效）
+///
+/// 非本地化插件，或字符串数量不少于 `SUSPICIOUS_STRING_COUNT` 时返回 None
+pub fn check_bsa_presence(
+    plugin_path: &Path,
+    loaded: &LoadedPlugin,
+    string_count: usize,
+) -> Option<String> {
+    if !loaded.plugin().is_localized() {
+        return None;
+    }
+
+    // STRING 文件加载失败时 esp_extractor 会降级为普通插件（字符串显示为 StringID）
+    let summary = if !loaded.is_localized() {
+        "本地化插件的 STRING 文件加载失败".to_string()
+    } else if string_count >= SUSPICIOUS_STRING_COUNT {
+        return None;
+    } else if string_count == 0 {
+        "本地化插件未提取到任何字符串".to_string()
+    } else {
+        format!("本地化插件仅提取到 {} 条字符串", string_count)
+    };
+
+    let Some(bsa_path) = expected_bsa_path(plugin_path) else {
+        return Some(format!("{}，且无法解析插件目录", summary));
+    };
+
+    let bsa_name = bsa_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    if bsa_path.exists() {
+        Some(format!(
+            "{}：预期的 BSA ({}) 存在，但其中可能不包含该插件的 STRING 文件",
+            summary, bsa_name
+        ))
+    } else {
+        Some(format!(
+            "{}：未在插件旁找到预期的 BSA ({})，请确认 BSA 或 Strings 目录与插件位于同一目录",
+            summary, bsa_name
+        ))
+    }
+}
@@ -1,14 +1,14 @@
-use crate::api_manage::{ApiConfig, ApiConfigDB};
+use crate::api_manage::{ApiConfig, ApiConfigDB, ApiUsageSummary};
+use crate::commands::error::{CommandError, ErrorContext};
 use std::sync::Mutex;
 
 /// 获取所有API配置
 #[tauri::command]
-pub fn get_api_configs(api_db: tauri::State<Mutex<ApiConfigDB>>) -> Result<Vec<ApiConfig>, String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_all_configs()
-        .map_err(|e| format!("获取API配置失败: {}", e))
+pub fn get_api_configs(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+) -> Result<Vec<ApiConfig>, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.get_all_configs().context("获取API配置失败")
 }
 
 /// 创建新的API配置
@@ -16,12 +16,9 @@ pub fn get_api_configs(api_db: tauri::State<Mutex<ApiConfigDB>>) -> Result<Vec<A
 pub fn create_api_config(
     api_db: tauri::State<Mutex<ApiConfigDB>>,
     name: String,
-) -> Result<i64, String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.create_config(name)
-        .map_err(|e| format!("创建API配置失败: {}", e))
+) -> Result<i64, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.create_config(name).context("创建API配置失败")
 }
 
 /// 更新API配置
@@ -30,40 +27,68 @@ pub fn update_api_config(
     api_db: tauri::State<Mutex<ApiConfigDB>>,
     id: i64,
     config: ApiConfig,
-) -> Result<(), String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.update_config(id, &config)
-        .map_err(|e| format!("更新API配置失败: {}", e))
+) -> Result<(), CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.update_config(id, &config).context("更新API配置失败")
 }
 
 /// 删除API配置
 #[tauri::command]
-pub fn delete_api_config(api_db: tauri::State<Mutex<ApiConfigDB>>, id: i64) -> Result<(), String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.delete_config(id)
-        .map_err(|e| format!("删除API配置失败: {}", e))
+pub fn delete_api_config(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+    id: i64,
+) -> Result<(), CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.delete_config(id).context("删除API配置失败")
 }
 
-/// 激活指定的API配置
+/// 激活指定的API配置，返回此前激活的配置ID
 #[tauri::command]
-pub fn activate_api_config(api_db: tauri::State<Mutex<ApiConfigDB>>, id: i64) -> Result<(), String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.activate_config(id)
-        .map_err(|e| format!("激活API配置失败: {}", e))
+pub fn activate_api_config(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+    id: i64,
+) -> Result<Option<i64>, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.activate_config(id).context("激活API配置失败")
+}
+
+/// 取消所有API配置的激活状态（禁用 AI 功能），返回此前激活的配置ID
+#[tauri::command]
+pub fn deactivate_all_api_configs(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+) -> Result<Option<i64>, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.deactivate_all().context("取消激活API配置失败")
 }
 
 /// 获取当前激活的API配置
 #[tauri::command]
-pub fn get_current_api(api_db: tauri::State<Mutex<ApiConfigDB>>) -> Result<Option<ApiConfig>, String> {
-    let db = api_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_current_config()
-        .map_err(|e| format!("获取当前API配置失败: {}", e))
+pub fn get_current_api(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+) -> Result<Option<ApiConfig>, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.get_current_config().context("获取当前API配置失败")
+}
+
+/// 记录一次 AI 调用的 token 用量，返回估算费用
+#[tauri::command]
+pub fn record_api_usage(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+    config_id: i64,
+    prompt_tokens: i64,
+    completion_tokens: i64,
+) -> Result<f64, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.record_usage(config_id, prompt_tokens, completion_tokens).context("记录API用量失败")
+}
+
+/// 按配置汇总 API 用量（since/until 为 Unix 秒，可选）
+#[tauri::command]
+pub fn get_api_usage(
+    api_db: tauri::State<Mutex<ApiConfigDB>>,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<ApiUsageSummary>, CommandError> {
+    let db = api_db.lock().context("数据库锁定失败")?;
+    db.get_usage_summary(since, until).context("获取API用量失败")
 }
@@ -1,18 +1,22 @@
-use crate::atomic_db::{AtomSource, AtomTranslation, AtomicDB};
+use crate::atomic_db::{
+    AtomConflictStrategy, AtomImportConflict, AtomImportEntry, AtomImportResult,
+    AtomMatcherOptions, AtomReplaceDetailed, AtomReplaceReport, AtomSource, AtomTranslation, AtomicDB,
+};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::settings::{read_settings, write_settings};
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
 
 /// 打开原子数据库管理窗口
 #[tauri::command]
-pub async fn open_atomic_db_window(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn open_atomic_db_window(app: tauri::AppHandle) -> Result<String, CommandError> {
     let window_label = "atomic-db-window";
 
     // 检查窗口是否已经打开
     if let Some(window) = app.get_webview_window(window_label) {
         // 窗口已存在，聚焦它
-        window
-            .set_focus()
-            .map_err(|e| format!("窗口聚焦失败: {}", e))?;
+        window.set_focus().context("窗口聚焦失败")?;
         return Ok(window_label.to_string());
     }
 
@@ -27,18 +31,28 @@ pub async fn open_atomic_db_window(app: tauri::AppHandle) -> Result<String, Stri
 
     match builder.build() {
         Ok(_) => Ok(window_label.to_string()),
-        Err(e) => Err(format!("创建原子数据库窗口失败: {}", e)),
+        Err(e) => Err(CommandError::from(e).with_context("创建原子数据库窗口失败")),
     }
 }
 
 /// 获取所有原子翻译
 #[tauri::command]
-pub fn get_all_atoms(atomic_db: tauri::State<Mutex<AtomicDB>>) -> Result<Vec<AtomTranslation>, String> {
-    let db = atomic_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_all_atoms()
-        .map_err(|e| format!("获取原子翻译失败: {}", e))
+pub fn get_all_atoms(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+) -> Result<Vec<AtomTranslation>, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.get_all_atoms().context("获取原子翻译失败")
+}
+
+/// 列出长期未使用的原子翻译（使用次数不超过 max_usage，或在 before_updated_at 之前未更新）
+#[tauri::command]
+pub fn list_stale_atoms(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    max_usage: Option<i32>,
+    before_updated_at: Option<i64>,
+) -> Result<Vec<AtomTranslation>, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.list_stale_atoms(max_usage, before_updated_at).context("获取未使用原子翻译失败")
 }
 
 /// 添加原子翻译
@@ -48,10 +62,8 @@ pub fn add_atom_translation(
     original: String,
     translated: String,
     source: String,
-) -> Result<(), String> {
-    let db = atomic_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
+) -> Result<(), CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
 
     let atom_source = match source.as_str() {
         "base" => AtomSource::Base,
@@ -59,8 +71,7 @@ pub fn add_atom_translation(
         _ => AtomSource::Manual,
     };
 
-    db.upsert_atom(&original, &translated, atom_source)
-        .map_err(|e| format!("添加原子翻译失败: {}", e))
+    db.upsert_atom(&original, &translated, atom_source).context("添加原子翻译失败")
 }
 
 /// 删除原子翻译
@@ -68,12 +79,19 @@ pub fn add_atom_translation(
 pub fn delete_atom_translation(
     atomic_db: tauri::State<Mutex<AtomicDB>>,
     original: String,
-) -> Result<(), String> {
-    let db = atomic_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.delete_atom(&original)
-        .map_err(|e| format!("删除原子翻译失败: {}", e))
+) -> Result<(), CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.delete_atom(&original).context("删除原子翻译失败")
+}
+
+/// 按ID批量删除原子翻译，返回删除数量
+#[tauri::command]
+pub fn delete_atoms(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    ids: Vec<i64>,
+) -> Result<usize, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.delete_atoms(&ids).context("批量删除原子翻译失败")
 }
 
 /// 更新原子翻译（根据ID更新译文和来源）
@@ -83,10 +101,8 @@ pub fn update_atom_translation(
     id: i64,
     translated: String,
     source: String,
-) -> Result<(), String> {
-    let db = atomic_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
+) -> Result<(), CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
 
     let atom_source = match source.as_str() {
         "base" | "Base" => AtomSource::Base,
@@ -94,18 +110,108 @@ pub fn update_atom_translation(
         _ => AtomSource::Manual,
     };
 
-    db.update_atom(id, &translated, atom_source)
-        .map_err(|e| format!("更新原子翻译失败: {}", e))
+    db.update_atom(id, &translated, atom_source).context("更新原子翻译失败")
 }
 
-/// 使用原子库替换文本
+/// 预检原子词导入，返回与库中已有译文冲突的条目（不写入）
+#[tauri::command]
+pub fn preview_atom_import(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    entries: Vec<AtomImportEntry>,
+) -> Result<Vec<AtomImportConflict>, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.detect_import_conflicts(&entries))
+}
+
+/// 导入原子词，冲突按 `resolutions` 逐条处理，未指定的冲突默认保留原译文
+#[tauri::command]
+pub fn import_atoms(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    entries: Vec<AtomImportEntry>,
+    source: AtomSource,
+    resolutions: Option<HashMap<String, AtomConflictStrategy>>,
+    default_strategy: Option<AtomConflictStrategy>,
+) -> Result<AtomImportResult, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.import_atoms(
+        &entries,
+        source,
+        &resolutions.unwrap_or_default(),
+        default_strategy.unwrap_or_default(),
+    )
+    .context("导入原子翻译失败")
+}
+
+/// 使用原子库替换文本（`allowed_sources` 为空时使用全部来源）
 #[tauri::command]
 pub fn replace_text_with_atoms(
     atomic_db: tauri::State<Mutex<AtomicDB>>,
     text: String,
-) -> Result<String, String> {
-    let db = atomic_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    Ok(db.replace_with_atoms(&text))
+    allowed_sources: Option<Vec<AtomSource>>,
+) -> Result<String, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.replace_with_atoms(&text, allowed_sources.as_deref()))
+}
+
+/// 使用原子库替换文本，并返回候选命中数、实际替换数与耗时（用于调优匹配器配置）
+#[tauri::command]
+pub fn replace_with_atoms_detailed(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    text: String,
+    allowed_sources: Option<Vec<AtomSource>>,
+) -> Result<AtomReplaceDetailed, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.replace_with_atoms_detailed(&text, allowed_sources.as_deref()))
+}
+
+/// 批量使用原子库替换多条文本（返回顺序与输入一致）
+#[tauri::command]
+pub fn replace_texts_with_atoms(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    texts: Vec<String>,
+    allowed_sources: Option<Vec<AtomSource>>,
+) -> Result<Vec<String>, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.replace_many_with_atoms(&texts, allowed_sources.as_deref()))
+}
+
+/// 批量替换并返回本次命中的原子词汇总（如对整个插件的原文调用，得到"本插件使用的术语"报告）
+#[tauri::command]
+pub fn replace_texts_with_atoms_report(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    texts: Vec<String>,
+    allowed_sources: Option<Vec<AtomSource>>,
+) -> Result<AtomReplaceReport, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.replace_many_with_atoms_report(&texts, allowed_sources.as_deref()))
+}
+
+/// 将累积的原子词使用计数写入数据库（关闭前调用，确保按使用次数排序持久）
+#[tauri::command]
+pub fn flush_atom_usage(atomic_db: tauri::State<Mutex<AtomicDB>>) -> Result<usize, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.flush_usage().context("写入原子词使用计数失败")
+}
+
+/// 获取原子词匹配器配置
+#[tauri::command]
+pub fn get_atom_matcher_options(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+) -> Result<AtomMatcherOptions, CommandError> {
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    Ok(db.matcher_options())
+}
+
+/// 设置原子词匹配器配置（写入 settings.json 并重建匹配器）
+#[tauri::command]
+pub fn set_atom_matcher_options(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    options: AtomMatcherOptions,
+) -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.atom_matcher = options;
+    write_settings(&settings)?;
+
+    let db = atomic_db.lock().context("数据库锁定失败")?;
+    db.set_matcher_options(options).context("重建原子匹配器失败")
 }
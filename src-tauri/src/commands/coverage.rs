@@ -1,12 +1,17 @@
+use crate::commands::error::{CommandError, ErrorContext};
 use crate::coverage_db::{CoverageDB, CoverageEntry};
-use crate::scanner::{read_loadorder, scan_plugins};
+use crate::dsd::DsdLayout;
+use crate::scanner::{read_loadorder, scan_plugins, PluginInfo, PluginOrderMode};
 use crate::settings::read_settings;
+use crate::translation_db::TypeCount;
+use crate::utils::coverage_snapshot::{export_snapshot, import_snapshot, CoverageSnapshotSummary};
 use crate::utils::load_order::{
-    extract_and_store, CoverageExtractionStats, CoverageProgressUpdate,
+    extract_and_store, update_plugin_coverage, CoverageExtractionStats, CoverageProgressUpdate,
+    PluginCoverageUpdate,
 };
 use serde::Serialize;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
 
@@ -60,14 +65,12 @@ pub struct CoverageCompletePayload {
 
 /// 打开覆盖数据库管理窗口
 #[tauri::command]
-pub async fn open_coverage_window(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn open_coverage_window(app: tauri::AppHandle) -> Result<String, CommandError> {
     let window_label = "coverage-window";
 
     // 检查窗口是否已经打开
     if let Some(window) = app.get_webview_window(window_label) {
-        window
-            .set_focus()
-            .map_err(|e| format!("窗口聚焦失败: {}", e))?;
+        window.set_focus().context("窗口聚焦失败")?;
         return Ok(window_label.to_string());
     }
 
@@ -82,7 +85,7 @@ pub async fn open_coverage_window(app: tauri::AppHandle) -> Result<String, Strin
 
     match builder.build() {
         Ok(_) => Ok(window_label.to_string()),
-        Err(e) => Err(format!("创建覆盖数据库窗口失败: {}", e)),
+        Err(e) => Err(CommandError::from(e).with_context("创建覆盖数据库窗口失败")),
     }
 }
 
@@ -90,24 +93,19 @@ pub async fn open_coverage_window(app: tauri::AppHandle) -> Result<String, Strin
 #[tauri::command]
 pub fn get_coverage_status(
     coverage_db: tauri::State<Arc<Mutex<CoverageDB>>>,
-) -> Result<CoverageStatus, String> {
+) -> Result<CoverageStatus, CommandError> {
     let settings = read_settings()?;
+    let data_dir = settings.data_dir_override();
     let game_path = settings
         .game
-        .ok_or_else(|| "请先在设置中指定游戏路径".to_string())?;
+        .ok_or_else(|| CommandError::NotConfigured("请先在设置中指定游戏路径".to_string()))?;
     let load_order_available = read_loadorder(Path::new(&game_path), false).is_some();
-    let current_plugins = scan_plugins(&game_path)?;
+    let current_plugins = scan_plugins(&game_path, data_dir.as_deref(), PluginOrderMode::LoadOrder)?;
 
-    let db = coverage_db
-        .lock()
-        .map_err(|e| format!("覆盖数据库锁定失败: {}", e))?;
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
 
-    let snapshot = db
-        .get_load_order_snapshot()
-        .map_err(|e| format!("读取覆盖数据库快照失败: {}", e))?;
-    let snapshot_timestamp = db
-        .get_last_snapshot_timestamp()
-        .map_err(|e| format!("读取快照时间失败: {}", e))?;
+    let snapshot = db.get_load_order_snapshot().context("读取覆盖数据库快照失败")?;
+    let snapshot_timestamp = db.get_last_snapshot_timestamp().context("读取快照时间失败")?;
 
     let mut snapshot_map = HashMap::new();
     for entry in &snapshot {
@@ -165,26 +163,32 @@ pub fn get_coverage_status(
 }
 
 /// 启动覆盖提取 (异步后台任务，通过事件通知进度)
+///
+/// `record_type_filter` 仅提取匹配的记录（如 `["INFO NAM1"]`），未指定时提取全部
 #[tauri::command]
 pub async fn run_coverage_extraction(
     app: tauri::AppHandle,
     coverage_db: tauri::State<'_, Arc<Mutex<CoverageDB>>>,
-) -> Result<(), String> {
+    record_type_filter: Option<Vec<String>>,
+) -> Result<(), CommandError> {
     // 预检查
     let settings = read_settings()?;
+    let data_dir = settings.data_dir_override();
+    let dsd_template = DsdLayout::from_settings(&settings).template().to_string();
+    let plugin_filter = settings.coverage_filter.clone();
     let game_path = settings
         .game
-        .ok_or_else(|| "请先在设置中指定游戏路径".to_string())?;
+        .ok_or_else(|| CommandError::NotConfigured("请先在设置中指定游戏路径".to_string()))?;
 
     if read_loadorder(Path::new(&game_path), false).is_none() {
-        return Err(
-            "未检测到 loadorder.txt，无法按加载顺序提取。\n请在 Mod 管理器中生成 loadorder.txt 后重试。".to_string(),
-        );
+        return Err(CommandError::NotConfigured(
+            "未检测到 loadorder.txt 或 plugins.txt，无法按加载顺序提取。\n请在 Mod 管理器中生成 loadorder.txt 后重试。".to_string(),
+        ));
     }
 
-    let plugins = scan_plugins(&game_path)?;
+    let plugins = scan_plugins(&game_path, data_dir.as_deref(), PluginOrderMode::LoadOrder)?;
     if plugins.is_empty() {
-        return Err("未检测到任何插件，无法执行覆盖提取".to_string());
+        return Err(CommandError::NotFound("未检测到任何插件，无法执行覆盖提取".to_string()));
     }
 
     // 克隆 Arc 以便在后台任务中使用
@@ -217,7 +221,14 @@ pub async fn run_coverage_extraction(
             };
 
             // 执行提取
-            let stats_result = extract_and_store(&db, &plugins_for_task, callback);
+            let stats_result = extract_and_store(
+                &db,
+                &plugins_for_task,
+                record_type_filter.as_deref(),
+                &plugin_filter,
+                &dsd_template,
+                callback,
+            );
 
             // 发送完成事件
             match stats_result {
@@ -259,6 +270,43 @@ pub async fn run_coverage_extraction(
     Ok(())
 }
 
+/// 仅重新提取单个插件并替换其覆盖记录（需已完成一次完整提取）
+///
+/// `record_type_filter` 应与上次完整提取一致，否则该插件的记录范围会与其他插件不同
+#[tauri::command]
+pub async fn update_coverage_for_plugin(
+    coverage_db: tauri::State<'_, Arc<Mutex<CoverageDB>>>,
+    plugin_path: String,
+    record_type_filter: Option<Vec<String>>,
+) -> Result<PluginCoverageUpdate, CommandError> {
+    let fs_path = PathBuf::from(&plugin_path);
+    if !fs_path.is_file() {
+        return Err(CommandError::NotFound(format!("插件文件不存在: {}", plugin_path)));
+    }
+    let name = fs_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| CommandError::InvalidInput("无效的插件路径".to_string()))?;
+    let plugin = PluginInfo {
+        name,
+        path: plugin_path,
+        fs_path,
+    };
+
+    let settings = read_settings()?;
+    let dsd_template = DsdLayout::from_settings(&settings).template().to_string();
+    let db_arc = coverage_db.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = db_arc.lock().context("覆盖数据库锁定失败")?;
+        update_plugin_coverage(&db, &plugin, record_type_filter.as_deref(), &dsd_template)
+            .map_err(CommandError::from)
+    })
+    .await
+    .context("覆盖更新任务异常")
+    .and_then(|result| result)
+}
+
 /// 搜索覆盖记录
 #[tauri::command]
 pub fn search_coverage_entries(
@@ -266,12 +314,39 @@ pub fn search_coverage_entries(
     form_id_query: Option<String>,
     text_query: Option<String>,
     limit: Option<u32>,
-) -> Result<Vec<CoverageEntry>, String> {
-    let db = coverage_db
-        .lock()
-        .map_err(|e| format!("覆盖数据库锁定失败: {}", e))?;
+) -> Result<Vec<CoverageEntry>, CommandError> {
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
     let limit = limit.unwrap_or(200).max(1);
 
     db.search_entries(form_id_query.as_deref(), text_query.as_deref(), limit)
-        .map_err(|e| format!("搜索覆盖数据库失败: {}", e))
+        .context("搜索覆盖数据库失败")
+}
+
+/// 获取覆盖数据库中出现的记录类型/子记录类型组合及条目数
+#[tauri::command]
+pub fn get_coverage_distinct_types(
+    coverage_db: tauri::State<Arc<Mutex<CoverageDB>>>,
+) -> Result<Vec<TypeCount>, CommandError> {
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
+    db.get_distinct_types().context("获取记录类型失败")
+}
+
+/// 导出覆盖数据库快照（覆盖记录、load order 与元数据），用于迁移到其他设备
+#[tauri::command]
+pub fn export_coverage_snapshot(
+    coverage_db: tauri::State<Arc<Mutex<CoverageDB>>>,
+    path: String,
+) -> Result<CoverageSnapshotSummary, CommandError> {
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
+    export_snapshot(&db, Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 导入覆盖数据库快照（替换现有数据，无需重新提取）
+#[tauri::command]
+pub fn import_coverage_snapshot(
+    coverage_db: tauri::State<Arc<Mutex<CoverageDB>>>,
+    path: String,
+) -> Result<CoverageSnapshotSummary, CommandError> {
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
+    import_snapshot(&db, Path::new(&path)).map_err(CommandError::from)
 }
@@ -0,0 +1,181 @@
+use crate::atomic_db::{AtomicDB, UsageWriteStats};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::settings::get_settings_path;
+use crate::utils::db_integrity::{
+    backup_database, integrity_check, list_database_backups, stage_restore,
+};
+use crate::utils::diagnostics::{database_diagnostics, DatabaseDiagnostics};
+use crate::utils::paths::{
+    get_api_db_path, get_atomic_db_path, get_coverage_db_path, get_db_backup_dir, get_db_path,
+    get_search_history_db_path, get_startup_log_path, get_userdata_dir,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri_plugin_opener::OpenerExt;
+
+/// 应用管理的数据库（标识, 显示名称, 路径, 主表）
+fn managed_databases() -> Vec<(&'static str, &'static str, PathBuf, &'static str)> {
+    vec![
+        ("translations", "翻译数据库", get_db_path(), "translations"),
+        ("atomic", "原子数据库", get_atomic_db_path(), "atomic_translations"),
+        ("api", "API 配置数据库", get_api_db_path(), "api_configs"),
+        ("search_history", "搜索历史数据库", get_search_history_db_path(), "search_history"),
+        ("coverage", "覆盖关系数据库", get_coverage_db_path(), "coverage_entries"),
+    ]
+}
+
+/// 安装状态诊断汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub userdata_dir: String,
+    pub databases: Vec<DatabaseDiagnostics>,
+    /// 原子词使用计数写入统计（重试与失败次数）
+    pub atom_usage_writes: UsageWriteStats,
+}
+
+/// 获取各数据库的路径、大小、主表行数、WAL 大小与修改时间
+#[tauri::command]
+pub fn get_diagnostics(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+) -> Result<Diagnostics, CommandError> {
+    let databases = managed_databases()
+        .into_iter()
+        .map(|(_, name, path, table)| database_diagnostics(name, &path, table))
+        .collect();
+
+    Ok(Diagnostics {
+        userdata_dir: get_userdata_dir().to_string_lossy().to_string(),
+        databases,
+        atom_usage_writes: atomic_db.lock().context("数据库锁定失败")?.usage_write_stats(),
+    })
+}
+
+/// 单个数据库的完整性检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    /// 数据库标识（用于 `rebuild_from_backup`）
+    pub database: String,
+    pub name: String,
+    pub path: String,
+    pub ok: bool,
+    /// `PRAGMA integrity_check` 发现的问题
+    pub problems: Vec<String>,
+    /// 可用备份文件名（新的在前）
+    pub backups: Vec<String>,
+    /// 损坏时给用户的处理建议
+    pub suggestion: Option<String>,
+}
+
+/// 对每个数据库执行 `PRAGMA integrity_check`
+#[tauri::command]
+pub fn check_integrity() -> Result<Vec<IntegrityReport>, CommandError> {
+    let backup_dir = get_db_backup_dir();
+
+    let reports = managed_databases()
+        .into_iter()
+        .filter(|(_, _, path, _)| path.exists())
+        .map(|(database, name, path, _)| {
+            let backups = list_database_backups(&backup_dir, &path);
+            let problems = integrity_check(&path)
+                .unwrap_or_else(|e| vec![format!("无法打开数据库: {}", e)]);
+            let ok = problems.is_empty();
+
+            let suggestion = match (ok, backups.first()) {
+                (true, _) => None,
+                (false, Some(latest)) => Some(format!(
+                    "{}已损坏，可从备份 {} 恢复（重启应用后生效）",
+                    name, latest
+                )),
+                (false, None) => Some(format!(
+                    "{}已损坏且没有可用备份，请关闭应用后将 {} 移出 userdata 目录，应用会在下次启动时重建空数据库",
+                    name,
+                    path.display()
+                )),
+            };
+
+            IntegrityReport {
+                database: database.to_string(),
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+                ok,
+                problems,
+                backups,
+                suggestion,
+            }
+        })
+        .collect();
+
+    Ok(reports)
+}
+
+/// 为所有数据库生成备份，返回备份文件路径
+#[tauri::command]
+pub fn backup_databases() -> Result<Vec<String>, CommandError> {
+    let backup_dir = get_db_backup_dir();
+    managed_databases()
+        .into_iter()
+        .filter(|(_, _, path, _)| path.exists())
+        .map(|(_, name, path, _)| {
+            backup_database(&path, &backup_dir)
+                .map(|p| p.to_string_lossy().to_string())
+                .context(name)
+        })
+        .collect()
+}
+
+/// 从指定备份恢复数据库（重启应用后生效）
+///
+/// # 参数
+/// * `database` - 数据库标识（见 `IntegrityReport.database`）
+/// * `backup_name` - 备份文件名（见 `IntegrityReport.backups`）
+#[tauri::command]
+pub fn rebuild_from_backup(database: String, backup_name: String) -> Result<(), CommandError> {
+    let (_, name, path, _) = managed_databases()
+        .into_iter()
+        .find(|(key, _, _, _)| *key == database)
+        .ok_or_else(|| CommandError::NotFound(format!("未知的数据库: {}", database)))?;
+
+    if !list_database_backups(&get_db_backup_dir(), &path).contains(&backup_name) {
+        return Err(CommandError::NotFound(format!("{}没有名为 {} 的备份", name, backup_name)));
+    }
+
+    stage_restore(&path, &get_db_backup_dir().join(&backup_name))?;
+    println!("✓ 已登记{}恢复: {}（重启后生效）", name, backup_name);
+    Ok(())
+}
+
+/// 在系统文件管理器中打开 userdata 目录（数据库、备份所在位置，便于用户发送数据库排查问题）
+#[tauri::command]
+pub fn reveal_userdata_dir(app: tauri::AppHandle) -> Result<(), CommandError> {
+    open_dir(&app, &get_userdata_dir())
+}
+
+/// 在系统文件管理器中定位 settings.json（尚未保存过设置时打开其所在目录）
+#[tauri::command]
+pub fn reveal_settings_file(app: tauri::AppHandle) -> Result<(), CommandError> {
+    let path = get_settings_path()?;
+    if path.exists() {
+        return app.opener().reveal_item_in_dir(&path).context("打开设置文件位置失败");
+    }
+    let dir = path
+        .parent()
+        .ok_or_else(|| CommandError::NotFound("无法定位设置文件目录".to_string()))?;
+    open_dir(&app, dir)
+}
+
+/// 在系统文件管理器中定位启动日志文件
+#[tauri::command]
+pub fn reveal_log_file(app: tauri::AppHandle) -> Result<(), CommandError> {
+    let path = get_startup_log_path();
+    if !path.exists() {
+        return Err(CommandError::NotFound(format!("日志文件不存在: {}", path.display())));
+    }
+    app.opener().reveal_item_in_dir(&path).context("打开日志文件位置失败")
+}
+
+fn open_dir(app: &tauri::AppHandle, dir: &Path) -> Result<(), CommandError> {
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .context(&format!("打开目录 {} 失败", dir.display()))
+}
@@ -1,3 +1,4 @@
+use crate::commands::error::CommandError;
 use crate::plugin_session::StringRecord;
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -10,7 +11,7 @@ pub async fn open_editor_window(
     app: tauri::AppHandle,
     editor_data_store: tauri::State<'_, Mutex<HashMap<String, StringRecord>>>,
     record: StringRecord,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     // 生成唯一的窗口标签（使用时间戳）
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -30,7 +31,7 @@ pub async fn open_editor_window(
             }
             Err(e) => {
                 println!("  ❌ 锁定数据存储失败: {}", e);
-                return Err(format!("锁定数据存储失败: {}", e));
+                return Err(CommandError::from(e).with_context("锁定数据存储失败"));
             }
         }
     }
@@ -58,7 +59,7 @@ pub async fn open_editor_window(
                 store.remove(&window_label);
             }
 
-            Err(format!("创建编辑窗口失败: {}", e))
+            Err(CommandError::from(e).with_context("创建编辑窗口失败"))
         }
     }
 }
@@ -68,7 +69,7 @@ pub async fn open_editor_window(
 pub fn get_editor_data(
     window_label: String,
     editor_data_store: tauri::State<Mutex<HashMap<String, StringRecord>>>,
-) -> Result<StringRecord, String> {
+) -> Result<StringRecord, CommandError> {
     println!("→ 前端请求编辑数据: {}", window_label);
 
     let record = {
@@ -0,0 +1,224 @@
+use crate::esp_service::PluginExtractionError;
+use crate::plugin_session::SessionError;
+use crate::translation_db::BatchSaveError;
+use crate::utils::sql::is_busy_error;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::sync::PoisonError;
+
+/// 命令错误类型
+///
+/// 序列化为 `{ code, message }`：`code` 为稳定的错误类别（供前端分支处理/本地化），
+/// `message` 为可直接展示的中文说明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// 缺少必要配置（如未设置游戏路径）
+    NotConfigured(String),
+    /// 目标不存在（文件、Session、记录等）
+    NotFound(String),
+    /// 参数不合法
+    InvalidInput(String),
+    /// 资源繁忙（数据库被锁定、任务正在进行中），可稍后重试
+    Busy(String),
+    /// 数据库错误
+    DbError(String),
+    /// 文件读写错误
+    IoError(String),
+    /// 数据解析失败
+    Parse(String),
+    /// 其他错误
+    Internal(String),
+}
+
+impl CommandError {
+    /// 稳定的错误类别代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::NotConfigured(_) => "not_configured",
+            CommandError::NotFound(_) => "not_found",
+            CommandError::InvalidInput(_) => "invalid_input",
+            CommandError::Busy(_) => "busy",
+            CommandError::DbError(_) => "db_error",
+            CommandError::IoError(_) => "io_error",
+            CommandError::Parse(_) => "parse",
+            CommandError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            CommandError::NotConfigured(msg)
+            | CommandError::NotFound(msg)
+            | CommandError::InvalidInput(msg)
+            | CommandError::Busy(msg)
+            | CommandError::DbError(msg)
+            | CommandError::IoError(msg)
+            | CommandError::Parse(msg)
+            | CommandError::Internal(msg) => msg,
+        }
+    }
+
+    /// 在消息前追加上下文（如 "保存翻译失败: ..."），保持错误类别不变
+    pub fn with_context(self, context: &str) -> Self {
+        let wrap = |msg: String| format!("{}: {}", context, msg);
+        match self {
+            CommandError::NotConfigured(msg) => CommandError::NotConfigured(wrap(msg)),
+            CommandError::NotFound(msg) => CommandError::NotFound(wrap(msg)),
+            CommandError::InvalidInput(msg) => CommandError::InvalidInput(wrap(msg)),
+            CommandError::Busy(msg) => CommandError::Busy(wrap(msg)),
+            CommandError::DbError(msg) => CommandError::DbError(wrap(msg)),
+            CommandError::IoError(msg) => CommandError::IoError(wrap(msg)),
+            CommandError::Parse(msg) => CommandError::Parse(wrap(msg)),
+            CommandError::Internal(msg) => CommandError::Internal(wrap(msg)),
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+/// Session 管理器错误（Session/记录不存在时保留 not_found 类别）
+impl From<SessionError> for CommandError {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::NotFound(msg) => CommandError::NotFound(msg),
+            SessionError::Other(msg) => CommandError::Internal(msg),
+        }
+    }
+}
+
+/// 尚未细分类别的底层错误（如 settings 模块返回的 String）
+impl From<String> for CommandError {
+    fn from(msg: String) -> Self {
+        CommandError::Internal(msg)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(msg: &str) -> Self {
+        CommandError::Internal(msg.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(e: rusqlite::Error) -> Self {
+        if is_busy_error(&e) {
+            CommandError::Busy(e.to_string())
+        } else if matches!(e, rusqlite::Error::QueryReturnedNoRows) {
+            CommandError::NotFound(e.to_string())
+        } else {
+            CommandError::DbError(e.to_string())
+        }
+    }
+}
+
+/// 批量保存失败（保留已提交条数的说明，类别与底层数据库错误一致）
+impl From<BatchSaveError> for CommandError {
+    fn from(e: BatchSaveError) -> Self {
+        let message = e.to_string();
+        match CommandError::from(e.source) {
+            CommandError::Busy(_) => CommandError::Busy(message),
+            _ => CommandError::DbError(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => CommandError::NotFound(e.to_string()),
+            _ => CommandError::IoError(e.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        CommandError::Parse(e.to_string())
+    }
+}
+
+impl From<PluginExtractionError> for CommandError {
+    fn from(e: PluginExtractionError) -> Self {
+        match e {
+            PluginExtractionError::FileMissing => CommandError::NotFound(e.to_string()),
+            PluginExtractionError::BsaMissing => CommandError::NotFound(e.to_string()),
+            PluginExtractionError::ParseError(msg) => CommandError::Parse(msg),
+        }
+    }
+}
+
+impl From<std::time::SystemTimeError> for CommandError {
+    fn from(e: std::time::SystemTimeError) -> Self {
+        CommandError::Internal(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for CommandError {
+    fn from(e: tauri::Error) -> Self {
+        CommandError::Internal(e.to_string())
+    }
+}
+
+/// 无法在系统文件管理器中打开路径
+impl From<tauri_plugin_opener::Error> for CommandError {
+    fn from(e: tauri_plugin_opener::Error) -> Self {
+        CommandError::IoError(e.to_string())
+    }
+}
+
+/// 互斥锁中毒（持锁线程 panic）
+impl<T> From<PoisonError<T>> for CommandError {
+    fn from(e: PoisonError<T>) -> Self {
+        CommandError::Internal(e.to_string())
+    }
+}
+
+/// 为底层错误附加上下文并转换为 [`CommandError`]
+pub trait ErrorContext<T> {
+    fn context(self, context: &str) -> Result<T, CommandError>;
+}
+
+impl<T, E: Into<CommandError>> ErrorContext<T> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, CommandError> {
+        self.map_err(|e| e.into().with_context(context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_error_serializes_code_and_contextual_message() {
+        let result: Result<(), rusqlite::Error> = Err(rusqlite::Error::QueryReturnedNoRows);
+        let err = result.context("获取翻译失败").unwrap_err();
+        assert_eq!(err.code(), "not_found");
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert!(json["message"].as_str().unwrap().starts_with("获取翻译失败: "));
+    }
+
+    #[test]
+    fn missing_session_maps_to_not_found() {
+        let mut manager = crate::plugin_session::PluginSessionManager::new();
+        let err = CommandError::from(manager.close("missing").unwrap_err());
+        assert_eq!(err.code(), "not_found");
+        assert_eq!(CommandError::from(SessionError::from("x")).code(), "internal");
+    }
+}
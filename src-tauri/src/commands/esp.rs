@@ -1,7 +1,17 @@
-use crate::esp_service::{extract_base_dictionary, get_base_plugins, ExtractionStats};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::esp_service::{extract_base_dictionary, get_base_plugins, ExtractionProgress, ExtractionStats};
 use crate::translation_db::TranslationDB;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+/// 基础字典提取的运行状态（防止重复启动，并支持取消）
+#[derive(Default)]
+pub struct DictionaryExtractionControl {
+    running: AtomicBool,
+    cancel: AtomicBool,
+}
 
 /// 获取基础插件列表
 #[tauri::command]
@@ -10,20 +20,54 @@ pub fn get_base_plugins_list() -> Vec<String> {
 }
 
 /// 从游戏 Data 目录提取基础字典
+///
+/// 在后台线程执行，每个插件开始时发送 `dictionary_extraction_progress` 事件；
+/// 缺失的插件记入 `skipped_files`，取消后保存已完成插件的记录并标记 `cancelled`。
+/// `record_type_filter` 仅提取匹配的记录（如 `["INFO NAM1"]`），未指定时提取全部
 #[tauri::command]
-pub fn extract_dictionary(
-    db: tauri::State<Mutex<TranslationDB>>,
+pub async fn extract_dictionary(
+    app: tauri::AppHandle,
+    control: tauri::State<'_, DictionaryExtractionControl>,
     data_dir: String,
-) -> Result<ExtractionStats, String> {
-    // 提取字符串
-    let (translations, stats) = extract_base_dictionary(Path::new(&data_dir))?;
-
-    // 批量保存到数据库
-    if !translations.is_empty() {
-        let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-        db.batch_save_translations(translations)
-            .map_err(|e| format!("保存到数据库失败: {}", e))?;
+    record_type_filter: Option<Vec<String>>,
+) -> Result<ExtractionStats, CommandError> {
+    if control.running.swap(true, Ordering::SeqCst) {
+        return Err(CommandError::Busy("基础字典提取正在进行中".to_string()));
     }
+    control.cancel.store(false, Ordering::SeqCst);
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let control = app.state::<DictionaryExtractionControl>();
+
+        // 提取字符串
+        let (translations, stats) = extract_base_dictionary(
+            Path::new(&data_dir),
+            record_type_filter.as_deref(),
+            &control.cancel,
+            |progress: ExtractionProgress| {
+                let _ = app.emit("dictionary_extraction_progress", progress);
+            },
+        )?;
+
+        // 批量保存到数据库
+        if !translations.is_empty() {
+            let db = app.state::<Mutex<TranslationDB>>();
+            let db = db.lock().context("数据库锁定失败")?;
+            db.batch_save_translations(translations, false).context("保存到数据库失败")?;
+        }
 
-    Ok(stats)
+        Ok(stats)
+    })
+    .await
+    .context("字典提取任务异常")
+    .and_then(|result| result);
+
+    control.running.store(false, Ordering::SeqCst);
+    result
+}
+
+/// 请求取消正在进行的基础字典提取（在当前插件处理完后停止）
+#[tauri::command]
+pub fn cancel_dictionary_extraction(control: tauri::State<DictionaryExtractionControl>) {
+    control.cancel.store(true, Ordering::SeqCst);
 }
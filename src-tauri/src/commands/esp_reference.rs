@@ -1,5 +1,7 @@
 use crate::bsa_logger::log_bsa_presence;
+use crate::commands::error::{CommandError, ErrorContext};
 use crate::plugin_session::PluginSessionManager;
+use crate::utils::pairing::pair_english_chinese;
 use esp_extractor::LoadedPlugin;
 use serde::Serialize;
 use std::path::PathBuf;
@@ -49,27 +51,25 @@ pub async fn load_esp_reference(
     session_manager: tauri::State<'_, Mutex<PluginSessionManager>>,
     reference_path: String,
     session_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     println!(
         "⏳ 开始加载 ESP 对照: {} -> {}",
         reference_path, session_id
     );
 
-    // 1. 获取当前 Session 中的原文映射
-    let english_map = {
-        let manager = session_manager
-            .lock()
-            .map_err(|e| format!("获取 Session 管理器锁失败: {}", e))?;
+    // 1. 获取当前 Session 中的原文记录
+    let english_strings = {
+        let manager = session_manager.lock().context("获取 Session 管理器锁失败")?;
 
-        match manager.get_original_string_map(&session_id) {
-            Some(map) => map,
+        match manager.get_original_strings(&session_id) {
+            Some(strings) => strings,
             None => {
                 let error_payload = EspReferenceErrorPayload {
                     session_id: session_id.clone(),
                     error: format!("Session {} 不存在", session_id),
                 };
                 let _ = app.emit("esp-reference-error", error_payload);
-                return Err(format!("Session {} 不存在", session_id));
+                return Err(CommandError::NotFound(format!("Session {} 不存在", session_id)));
             }
         }
     };
@@ -80,9 +80,8 @@ pub async fn load_esp_reference(
     // 获取参考文件名
     let source_plugin_name = ref_path
         .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
 
     // 加载译文
     log_bsa_presence(&ref_path, Some("chinese"));
@@ -94,36 +93,29 @@ pub async fn load_esp_reference(
                 error: format!("加载中文版失败: {}", e),
             };
             let _ = app.emit("esp-reference-error", error_payload);
-            return Err(format!("加载中文版失败: {}", e));
+            return Err(CommandError::Parse(format!("加载中文版失败: {}", e)));
         }
     };
     let chinese_strings = loaded_zh.extract_strings();
     println!("✓ 译文提取 {} 条字符串", chinese_strings.len());
 
-    // 3. 根据 session 原文映射筛选译文
-    let mut records: Vec<ReferenceRecord> = Vec::new();
-    for s in chinese_strings {
-        let key = format!(
-            "{}|{}|{}|{}",
-            s.form_id, s.record_type, s.subrecord_type, s.index
-        );
-
-        let Some(original_text) = english_map.get(&key).cloned() else {
-            continue;
-        };
-
-        // 只保留有实际翻译的记录（中英文不同）
-        if !original_text.is_empty() && s.text != original_text {
-            records.push(ReferenceRecord {
+    // 3. 按 key 与 session 原文对齐，只保留有实际翻译的记录（中英文不同）
+    let records: Vec<ReferenceRecord> = pair_english_chinese(english_strings, chinese_strings)
+        .into_iter()
+        .filter(|pair| pair.is_translated())
+        .filter_map(|pair| {
+            let translated_text = pair.chinese?;
+            let s = pair.english;
+            Some(ReferenceRecord {
                 form_id: s.form_id,
                 record_type: s.record_type,
                 subrecord_type: s.subrecord_type,
                 index: s.index,
-                original_text,
-                translated_text: s.text,
-            });
-        }
-    }
+                original_text: s.text,
+                translated_text,
+            })
+        })
+        .collect();
 
     let total_count = records.len();
     let matched_count = records.len(); // 暂时全部返回，前端根据 session 筛选
@@ -142,8 +134,7 @@ pub async fn load_esp_reference(
         records,
     };
 
-    app.emit("esp-reference-loaded", payload)
-        .map_err(|e| format!("发送事件失败: {}", e))?;
+    app.emit("esp-reference-loaded", payload).context("发送事件失败")?;
 
     Ok(())
 }
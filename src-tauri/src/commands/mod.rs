@@ -1,7 +1,9 @@
 pub mod api_config;
 pub mod atomic;
 pub mod coverage;
+pub mod diagnostics;
 pub mod editor;
+pub mod error;
 pub mod esp;
 pub mod esp_reference;
 pub mod scanner;
@@ -14,6 +16,7 @@ pub mod translation;
 pub use api_config::*;
 pub use atomic::*;
 pub use coverage::*;
+pub use diagnostics::*;
 pub use editor::*;
 pub use esp::*;
 pub use esp_reference::*;
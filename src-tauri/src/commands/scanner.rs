@@ -1,19 +1,114 @@
-use crate::scanner::{scan_plugins, validate_game_path, PluginInfo};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::scanner::{
+    read_loadorder_with_source, scan_plugins, validate_game_path, LoadOrderFile, PluginInfo,
+    PluginOrderMode,
+};
 use crate::settings::read_settings;
+use crate::utils::checksum::{self, PluginChecksum};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 加载顺序来源
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadOrderSource {
+    /// 来自 loadorder.txt
+    LoadOrderFile,
+    /// 未找到 loadorder.txt，来自 plugins.txt（仅含已启用的插件）
+    PluginsFile,
+    /// 两者均未找到，按字母顺序排列
+    Alphabetical,
+}
+
+/// 已解析加载顺序中的单个插件
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedPlugin {
+    pub position: usize,
+    pub name: String,
+    pub path: String,
+    /// 是否列在加载顺序文件中（否则为追加在末尾的字母序插件）
+    pub in_load_order_file: bool,
+}
+
+/// 当前解析出的加载顺序
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedLoadOrder {
+    pub source: LoadOrderSource,
+    pub plugins: Vec<ResolvedPlugin>,
+}
 
 /// 验证游戏目录是否有效
 #[tauri::command]
-pub fn validate_game_directory(path: String) -> Result<bool, String> {
-    validate_game_path(&path)
+pub fn validate_game_directory(path: String) -> Result<bool, CommandError> {
+    validate_game_path(&path).map_err(CommandError::from)
 }
 
 /// 获取插件列表
+///
+/// `order_mode` 未指定时按 loadorder.txt 顺序排列
 #[tauri::command]
-pub fn get_plugin_list() -> Result<Vec<PluginInfo>, String> {
+pub fn get_plugin_list(
+    order_mode: Option<PluginOrderMode>,
+) -> Result<Vec<PluginInfo>, CommandError> {
     let settings = read_settings()?;
 
+    let data_dir = settings.data_dir_override();
     match settings.game {
-        Some(game_path) => scan_plugins(&game_path),
-        None => Err("未设置游戏路径".to_string()),
+        Some(game_path) => {
+            scan_plugins(&game_path, data_dir.as_deref(), order_mode.unwrap_or_default())
+                .map_err(CommandError::from)
+        }
+        None => Err(CommandError::NotConfigured("未设置游戏路径".to_string())),
     }
 }
+
+/// 获取当前解析出的加载顺序（含序号与来源）
+#[tauri::command]
+pub fn get_resolved_load_order() -> Result<ResolvedLoadOrder, CommandError> {
+    let settings = read_settings()?;
+    let data_dir = settings.data_dir_override();
+    let game_path = settings
+        .game
+        .ok_or_else(|| CommandError::NotConfigured("未设置游戏路径".to_string()))?;
+
+    let loadorder = read_loadorder_with_source(Path::new(&game_path), false);
+    let plugins = scan_plugins(&game_path, data_dir.as_deref(), PluginOrderMode::LoadOrder)?;
+
+    // 列表中的插件名大小写可能与磁盘上的文件不同
+    let listed: HashSet<String> = loadorder
+        .iter()
+        .flat_map(|(_, order)| order)
+        .map(|name| name.to_lowercase())
+        .collect();
+    let source = match loadorder {
+        Some((LoadOrderFile::LoadOrder, _)) => LoadOrderSource::LoadOrderFile,
+        Some((LoadOrderFile::Plugins, _)) => LoadOrderSource::PluginsFile,
+        None => LoadOrderSource::Alphabetical,
+    };
+
+    let plugins = plugins
+        .into_iter()
+        .enumerate()
+        .map(|(position, plugin)| ResolvedPlugin {
+            position,
+            in_load_order_file: listed.contains(&plugin.name.to_lowercase()),
+            name: plugin.name,
+            path: plugin.path,
+        })
+        .collect();
+
+    Ok(ResolvedLoadOrder { source, plugins })
+}
+
+/// 计算插件文件的校验信息（用于判断 Session、覆盖数据等是否因插件更新而过期）
+///
+/// 始终返回基于大小与修改时间的快速校验值；`full_hash` 为 true 时额外计算整个文件的内容哈希
+#[tauri::command]
+pub fn compute_plugin_checksum(
+    plugin_path: String,
+    full_hash: Option<bool>,
+) -> Result<PluginChecksum, CommandError> {
+    checksum::compute_plugin_checksum(Path::new(&plugin_path), full_hash.unwrap_or(false))
+        .context("计算插件校验值失败")
+}
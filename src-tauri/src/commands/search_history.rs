@@ -1,3 +1,4 @@
+use crate::commands::error::{CommandError, ErrorContext};
 use crate::search_history::{SearchHistoryDB, SearchHistoryEntry};
 use std::sync::Mutex;
 
@@ -6,24 +7,18 @@ use std::sync::Mutex;
 pub fn save_search_history(
     search_history_db: tauri::State<Mutex<SearchHistoryDB>>,
     entries: Vec<SearchHistoryEntry>,
-) -> Result<(), String> {
-    let db = search_history_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.batch_upsert(entries)
-        .map_err(|e| format!("保存搜索历史失败: {}", e))
+) -> Result<(), CommandError> {
+    let db = search_history_db.lock().context("数据库锁定失败")?;
+    db.batch_upsert(entries).context("保存搜索历史失败")
 }
 
 /// 获取所有搜索历史
 #[tauri::command]
 pub fn get_search_history(
     search_history_db: tauri::State<Mutex<SearchHistoryDB>>,
-) -> Result<Vec<SearchHistoryEntry>, String> {
-    let db = search_history_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_all()
-        .map_err(|e| format!("获取搜索历史失败: {}", e))
+) -> Result<Vec<SearchHistoryEntry>, CommandError> {
+    let db = search_history_db.lock().context("数据库锁定失败")?;
+    db.get_all().context("获取搜索历史失败")
 }
 
 /// 删除单条搜索历史记录
@@ -31,10 +26,7 @@ pub fn get_search_history(
 pub fn delete_search_history_entry(
     search_history_db: tauri::State<Mutex<SearchHistoryDB>>,
     term: String,
-) -> Result<(), String> {
-    let db = search_history_db
-        .lock()
-        .map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.delete_entry(&term)
-        .map_err(|e| format!("删除搜索历史失败: {}", e))
+) -> Result<(), CommandError> {
+    let db = search_history_db.lock().context("数据库锁定失败")?;
+    db.delete_entry(&term).context("删除搜索历史失败")
 }
@@ -1,19 +1,69 @@
-use crate::plugin_session::{PluginSessionManager, PluginStringsResponse, SessionInfo, StringRecord};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::coverage_db::CoverageDB;
+use crate::dsd::{DsdExportResult, DsdLayout, DsdPreview, DsdStatus};
+use crate::plugin_session::{
+    coverage_fill_candidates, default_length_limits, default_source_priority, diff_records_against_tm,
+    length_violations, match_orphans_by_editor_id,
+    records_to_form_identifiers, session_progress, text_fill_candidates, whitespace_only_translations,
+    ApplyOptions,
+    ApplyResult, CombinedApplyEntry, CombinedStringsResponse, CoverageFillCandidate, EditorIdRemapReport,
+    ExchangeImportResult, LengthViolation, PluginBackup, PluginSessionManager, PluginStringsResponse, RecordContext, SessionInfo,
+    SessionProgress, StringRecord, TextFillCandidate, TmDiffEntry,
+};
 use crate::settings::read_settings;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use crate::translation_db::{is_untranslated_text, FormIdentifier, Translation, TranslationDB};
+use crate::utils::string_exchange::{ExchangeEncoding, ExchangeFormat};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+/// 关闭全部 Session 后发送的 Payload
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionsClosedPayload {
+    pub session_ids: Vec<String>,
+    /// 关闭时仍存在未保存修改的 Session
+    pub dirty_session_ids: Vec<String>,
+}
 
 /// 加载插件 Session（自动缓存复用）
 #[tauri::command]
 pub fn load_plugin_session(
     session_manager: tauri::State<Mutex<PluginSessionManager>>,
     plugin_path: String,
-) -> Result<PluginStringsResponse, String> {
-    let mut manager = session_manager
-        .lock()
-        .map_err(|e| format!("Session 管理器锁定失败: {}", e))?;
+) -> Result<PluginStringsResponse, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    let layout = dsd_layout();
+
+    manager
+        .get_or_load(PathBuf::from(plugin_path), layout.template())
+        .map_err(CommandError::from)
+}
+
+/// 将多个插件加载为一个合并 Session（记录按插件顺序拼接并标记 `source_plugin`）
+#[tauri::command]
+pub fn load_combined_session(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    plugin_paths: Vec<String>,
+) -> Result<CombinedStringsResponse, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    let layout = dsd_layout();
+
+    manager
+        .load_combined(plugin_paths.into_iter().map(PathBuf::from).collect(), layout.template())
+        .map_err(CommandError::from)
+}
+
+/// 获取插件依赖的主文件列表（按 TES4 中的声明顺序）
+#[tauri::command]
+pub fn get_plugin_masters(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    plugin_path: String,
+) -> Result<Vec<String>, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
 
-    manager.get_or_load(PathBuf::from(plugin_path))
+    manager.get_masters(&PathBuf::from(plugin_path)).map_err(CommandError::from)
 }
 
 /// 关闭插件 Session
@@ -21,56 +71,543 @@ pub fn load_plugin_session(
 pub fn close_plugin_session(
     session_manager: tauri::State<Mutex<PluginSessionManager>>,
     session_id: String,
-) -> Result<(), String> {
-    let mut manager = session_manager
-        .lock()
-        .map_err(|e| format!("Session 管理器锁定失败: {}", e))?;
+) -> Result<(), CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
 
-    manager.close(&session_id)
+    manager.close(&session_id).map_err(CommandError::from)
+}
+
+/// 关闭所有 Session，返回关闭数量，并发送 sessions-closed 事件
+#[tauri::command]
+pub fn close_all_sessions(
+    app: tauri::AppHandle,
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+) -> Result<usize, CommandError> {
+    let closed = {
+        let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        manager.close_all()
+    };
+
+    let payload = SessionsClosedPayload {
+        dirty_session_ids: closed
+            .iter()
+            .filter(|info| info.dirty)
+            .map(|info| info.session_id.clone())
+            .collect(),
+        session_ids: closed.into_iter().map(|info| info.session_id).collect(),
+    };
+    let count = payload.session_ids.len();
+
+    // 发送事件（忽略发送失败）
+    let _ = app.emit("sessions-closed", payload);
+
+    Ok(count)
 }
 
 /// 列出所有活跃的 Session
 #[tauri::command]
 pub fn list_plugin_sessions(
     session_manager: tauri::State<Mutex<PluginSessionManager>>,
-) -> Result<Vec<SessionInfo>, String> {
-    let manager = session_manager
-        .lock()
-        .map_err(|e| format!("Session 管理器锁定失败: {}", e))?;
+) -> Result<Vec<SessionInfo>, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
 
     Ok(manager.list_sessions())
 }
 
-/// 应用翻译到插件文件
+/// 标记 Session 是否存在未保存的修改（用于退出前提示）
+#[tauri::command]
+pub fn mark_session_dirty(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    dirty: bool,
+) -> Result<(), CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.mark_dirty(&session_id, dirty).map_err(CommandError::from)
+}
+
+/// 将选中的未翻译记录的译文设为原文（状态改为 manual），返回修改后的记录
+#[tauri::command]
+pub fn session_copy_source_to_target(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    keys: Vec<FormIdentifier>,
+) -> Result<Vec<StringRecord>, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.copy_source_to_target(&session_id, &keys).map_err(CommandError::from)
+}
+
+/// 清除选中记录的译文（恢复为原文，状态改为 untranslated），返回修改后的记录
+#[tauri::command]
+pub fn session_clear_target(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    keys: Vec<FormIdentifier>,
+) -> Result<Vec<StringRecord>, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.clear_target(&session_id, &keys).map_err(CommandError::from)
+}
+
+/// 对话记录默认返回的前后记录数
+const DEFAULT_CONTEXT_RADIUS: usize = 3;
+
+/// 获取对话记录（INFO）在同一话题下的前后记录，非对话记录返回 None
+#[tauri::command]
+pub fn get_record_context(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    key: FormIdentifier,
+    radius: Option<usize>,
+) -> Result<Option<RecordContext>, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    manager
+        .get_record_context(&session_id, &key, radius.unwrap_or(DEFAULT_CONTEXT_RADIUS))
+        .map_err(CommandError::from)
+}
+
+/// 批量设置 Session 记录的翻译状态，返回更新后的记录
+#[tauri::command]
+pub fn set_records_status(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    keys: Vec<FormIdentifier>,
+    status: String,
+) -> Result<Vec<StringRecord>, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.set_records_status(&session_id, &keys, &status).map_err(CommandError::from)
+}
+
+/// 应用翻译到插件文件（可选回读校验、仅写入有变更的记录）
 #[tauri::command]
 pub fn apply_translations(
     session_manager: tauri::State<Mutex<PluginSessionManager>>,
     session_id: String,
     translations: Vec<StringRecord>,
     save_as: Option<String>,
-) -> Result<String, String> {
-    let mut manager = session_manager
-        .lock()
-        .map_err(|e| format!("Session 管理器锁定失败: {}", e))?;
+    verify: Option<bool>,
+    only_changed: Option<bool>,
+) -> Result<ApplyResult, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    let options = apply_options(verify, only_changed);
+
+    manager.apply_translations(&session_id, translations, save_as, &options)
+        .map_err(CommandError::from)
+}
+
+/// 将合并 Session 的翻译按来源插件分别写回（各插件均覆盖原文件并备份）
+#[tauri::command]
+pub fn apply_combined_translations(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    translations: Vec<StringRecord>,
+    verify: Option<bool>,
+    only_changed: Option<bool>,
+) -> Result<Vec<CombinedApplyEntry>, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    let options = apply_options(verify, only_changed);
+
+    manager
+        .apply_combined_translations(&session_id, translations, &options)
+        .map_err(CommandError::from)
+}
+
+/// 读取设置获取自定义临时目录、转换线程数与只读模式保护目录
+fn apply_options(verify: Option<bool>, only_changed: Option<bool>) -> ApplyOptions {
+    let settings = read_settings().ok();
+    ApplyOptions {
+        temp_dir: settings
+            .as_ref()
+            .and_then(|s| s.temp_dir.as_ref())
+            .map(PathBuf::from),
+        verify: verify.unwrap_or(false),
+        only_changed: only_changed.unwrap_or(false),
+        threads: settings.as_ref().and_then(|s| s.apply_threads),
+        protected_dirs: settings.map(|s| s.protected_dirs()).unwrap_or_default(),
+    }
+}
+
+/// 撤销最近一次写回（从最新备份恢复插件文件），返回恢复的插件路径
+#[tauri::command]
+pub fn undo_last_apply(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+) -> Result<String, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    let protected_dirs = read_settings()
+        .map(|s| s.protected_dirs())
+        .unwrap_or_default();
 
-    manager.apply_translations(&session_id, translations, save_as)
+    manager.undo_last_apply(&session_id, &protected_dirs).map_err(CommandError::from)
 }
 
-/// 导出 DSD (Dynamic String Distributor) 格式
+/// 列出 Session 对应插件写回时生成的全部备份（按时间从新到旧）
+#[tauri::command]
+pub fn list_backups(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+) -> Result<Vec<PluginBackup>, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    manager.list_backups(&session_id).map_err(CommandError::from)
+}
+
+/// 从指定备份恢复插件文件（须为 `list_backups` 返回的备份之一），返回恢复的插件路径
+#[tauri::command]
+pub fn restore_backup(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    backup_path: String,
+) -> Result<String, CommandError> {
+    let mut manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    let protected_dirs = read_settings()
+        .map(|s| s.protected_dirs())
+        .unwrap_or_default();
+
+    manager
+        .restore_backup(&session_id, Path::new(&backup_path), &protected_dirs)
+        .map_err(CommandError::from)
+}
+
+/// 将 Session 翻译写回翻译数据库（原文不会被覆盖），返回实际新增或更新的记录数
+#[tauri::command]
+pub fn commit_session_to_tm(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+    records: Vec<StringRecord>,
+) -> Result<usize, CommandError> {
+    let translations = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        manager.build_tm_translations(&session_id, records)?
+    };
+
+    if translations.is_empty() {
+        return Ok(0);
+    }
+
+    let db = db.lock().context("数据库锁定失败")?;
+    db.batch_save_translations(translations, false)
+        .map(|outcome| outcome.affected)
+        .context("写回翻译数据库失败")
+}
+
+/// 导出 DSD (Dynamic String Distributor) 格式（可选与已有文件合并）
+///
+/// `record_type_filter` 仅导出匹配的记录（如 `["INFO"]`），未指定时导出全部
 #[tauri::command]
 pub fn export_dsd(
     session_manager: tauri::State<Mutex<PluginSessionManager>>,
     session_id: String,
     records: Vec<StringRecord>,
-) -> Result<String, String> {
-    let manager = session_manager
-        .lock()
-        .map_err(|e| format!("Session 管理器锁定失败: {}", e))?;
+    merge: Option<bool>,
+    write_metadata: Option<bool>,
+    record_type_filter: Option<Vec<String>>,
+) -> Result<DsdExportResult, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager
+        .export_dsd(
+            &session_id,
+            records,
+            &dsd_layout(),
+            merge.unwrap_or(false),
+            write_metadata.unwrap_or(false),
+            record_type_filter.as_deref(),
+        )
+        .map_err(CommandError::from)
+}
+
+/// 预览 DSD 导出的 JSON 与目标路径（不写入文件，返回现有文件内容供对比）
+///
+/// `record_type_filter` 同 `export_dsd`，未指定时预览全部
+#[tauri::command]
+pub fn preview_dsd(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    records: Vec<StringRecord>,
+    merge: Option<bool>,
+    record_type_filter: Option<Vec<String>>,
+) -> Result<DsdPreview, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager
+        .preview_dsd(
+            &session_id,
+            records,
+            &dsd_layout(),
+            merge.unwrap_or(false),
+            record_type_filter.as_deref(),
+        )
+        .map_err(CommandError::from)
+}
+
+/// 查询 Session 插件是否已有 DSD 导出文件（路径、大小与条目数）
+#[tauri::command]
+pub fn dsd_status(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+) -> Result<DsdStatus, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.dsd_status(&session_id, &dsd_layout()).map_err(CommandError::from)
+}
+
+/// 读取设置中的 DSD 导出目录与路径模板（设置读取失败时使用默认约定）
+fn dsd_layout() -> DsdLayout {
+    read_settings()
+        .map(|settings| DsdLayout::from_settings(&settings))
+        .unwrap_or_default()
+}
+
+/// 导出仍需翻译的记录供外部翻译（默认 CSV），返回导出条数
+#[tauri::command]
+pub fn export_untranslated(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    records: Vec<StringRecord>,
+    path: String,
+    format: Option<ExchangeFormat>,
+    encoding: Option<ExchangeEncoding>,
+) -> Result<usize, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    let format = format.unwrap_or_default();
+    let encoding = encoding.unwrap_or_else(|| ExchangeEncoding::default_for(format));
+    manager.export_untranslated(&session_id, &records, Path::new(&path), format, encoding)
+        .map_err(CommandError::from)
+}
+
+/// 导入外部翻译好的交换文件，按 key 返回填入译文的记录
+#[tauri::command]
+pub fn import_untranslated(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    path: String,
+    format: Option<ExchangeFormat>,
+    encoding: Option<ExchangeEncoding>,
+) -> Result<ExchangeImportResult, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    manager.import_untranslated(&session_id, Path::new(&path), format.unwrap_or_default(), encoding)
+        .map_err(CommandError::from)
+}
+
+/// 导出为松散 STRINGS 文件（默认中文），返回写入的文件路径
+#[tauri::command]
+pub fn export_strings_files(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    records: Vec<StringRecord>,
+    language: Option<String>,
+) -> Result<Vec<String>, CommandError> {
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+
+    let language = language.unwrap_or_else(|| "chinese".to_string());
+    manager.export_strings_files(&session_id, records, &language).map_err(CommandError::from)
+}
+
+/// 比对 Session 编辑结果与翻译数据库（new / unchanged / conflict）
+#[tauri::command]
+pub fn diff_session_against_tm(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+    records: Vec<StringRecord>,
+) -> Result<Vec<TmDiffEntry>, CommandError> {
+    {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        if !manager.contains(&session_id) {
+            return Err(CommandError::NotFound(format!("Session {} 不存在", session_id)));
+        }
+    }
+
+    let db = db.lock().context("数据库锁定失败")?;
+    let existing = db
+        .batch_query_translations(records_to_form_identifiers(&records))
+        .context("批量查询翻译失败")?;
+
+    Ok(diff_records_against_tm(&records, existing))
+}
 
-    // 读取设置获取自定义导出目录
-    let output_base_dir = read_settings()
+/// 查找 Session 记录在翻译数据库中仅首尾空白与原文不同的译文（进度统计中视为未翻译，便于清理）
+#[tauri::command]
+pub fn find_whitespace_only_translations(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+) -> Result<Vec<Translation>, CommandError> {
+    let records = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        manager
+            .get_strings(&session_id)
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} 不存在", session_id)))?
+    };
+
+    let db = db.lock().context("数据库锁定失败")?;
+    let existing = db
+        .batch_query_translations(records_to_form_identifiers(&records))
+        .context("批量查询翻译失败")?;
+
+    Ok(whitespace_only_translations(existing))
+}
+
+/// 检查译文是否超出设置中的字节长度上限（未配置时使用内置默认值），写回前用于发现会被截断的译文
+///
+/// `records` 为前端当前编辑中的记录，未提供时检查 Session 中的记录
+#[tauri::command]
+pub fn validate_lengths(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    session_id: String,
+    records: Option<Vec<StringRecord>>,
+) -> Result<Vec<LengthViolation>, CommandError> {
+    let limits = read_settings()
         .ok()
-        .and_then(|s| s.dsd_output_dir);
+        .and_then(|s| s.length_limits)
+        .unwrap_or_else(default_length_limits);
+
+    let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+    let strings = manager
+        .get_strings(&session_id)
+        .ok_or_else(|| CommandError::NotFound(format!("Session {} 不存在", session_id)))?;
+    let records = records.as_deref().unwrap_or(&strings);
+
+    Ok(length_violations(records, &limits))
+}
+
+/// 插件更新导致 FormID 重新编号时，按 EditorID + 原文 将孤立译文匹配到 Session 中的新记录
+///
+/// `apply` 为 true 时将唯一匹配的译文改写为新的 form_id，否则仅返回报告供确认
+#[tauri::command]
+pub fn remap_by_editor_id(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+    apply: Option<bool>,
+) -> Result<EditorIdRemapReport, CommandError> {
+    let (plugin_name, records) = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        let not_found = || CommandError::NotFound(format!("Session {} 不存在", session_id));
+        let plugin_name = manager.get_plugin_name(&session_id).ok_or_else(not_found)?;
+        let records = manager.get_strings(&session_id).ok_or_else(not_found)?;
+        (plugin_name, records)
+    };
+
+    let db = db.lock().context("数据库锁定失败")?;
+    let mut tm_rows = Vec::new();
+    db.for_each_translation(Some(&plugin_name), None, |t| {
+        tm_rows.push(t);
+        Ok(())
+    })
+    .context("读取插件译文失败")?;
+
+    let mut report = match_orphans_by_editor_id(&records, tm_rows);
+    if apply.unwrap_or(false) && !report.matched.is_empty() {
+        let remaps: Vec<(FormIdentifier, String)> = report
+            .matched
+            .iter()
+            .map(|m| {
+                let old = FormIdentifier {
+                    form_id: m.old_form_id.clone(),
+                    record_type: m.record_type.clone(),
+                    subrecord_type: m.subrecord_type.clone(),
+                    index: m.index,
+                };
+                (old, m.new_form_id.clone())
+            })
+            .collect();
+        report.applied = db.remap_form_ids(&remaps).context("改写 form_id 失败")?;
+    }
+
+    Ok(report)
+}
+
+/// 按覆盖数据库中每条记录的最终文本生成填充候选（考虑整个 load order 的覆盖关系）
+///
+/// 需先完成覆盖提取；仅返回最终文本来自其他插件且与当前译文不同的记录
+#[tauri::command]
+pub fn get_coverage_fill_candidates(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    coverage_db: tauri::State<Arc<Mutex<CoverageDB>>>,
+    session_id: String,
+) -> Result<Vec<CoverageFillCandidate>, CommandError> {
+    let (plugin_name, records) = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        let not_found = || CommandError::NotFound(format!("Session {} 不存在", session_id));
+        let plugin_name = manager.get_plugin_name(&session_id).ok_or_else(not_found)?;
+        let records = manager.get_strings(&session_id).ok_or_else(not_found)?;
+        (plugin_name, records)
+    };
+
+    let db = coverage_db.lock().context("覆盖数据库锁定失败")?;
+    let entries = db
+        .get_entries_by_keys(&records_to_form_identifiers(&records))
+        .context("查询覆盖记录失败")?;
+
+    Ok(coverage_fill_candidates(&plugin_name, &records, entries))
+}
+
+/// 按原文从翻译数据库为未翻译的记录生成填充候选
+///
+/// 同一原文有多个插件的译文时按设置中的来源优先级选择（默认 同插件 > 基础字典 > 任意插件）
+#[tauri::command]
+pub fn get_text_fill_candidates(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+) -> Result<Vec<TextFillCandidate>, CommandError> {
+    let priority = read_settings()
+        .ok()
+        .and_then(|s| s.translation_source_priority)
+        .unwrap_or_else(default_source_priority);
+
+    let (plugin_name, records) = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        let not_found = || CommandError::NotFound(format!("Session {} 不存在", session_id));
+        let plugin_name = manager.get_plugin_name(&session_id).ok_or_else(not_found)?;
+        let records = manager.get_strings(&session_id).ok_or_else(not_found)?;
+        (plugin_name, records)
+    };
+
+    let texts: Vec<String> = records
+        .iter()
+        .filter(|r| is_untranslated_text(&r.original_text, &r.translated_text))
+        .map(|r| r.original_text.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let db = db.lock().context("数据库锁定失败")?;
+    let translations = db.query_by_original_texts(&texts).context("按原文查询翻译失败")?;
+
+    Ok(text_fill_candidates(&plugin_name, &records, translations, &priority))
+}
+
+/// 获取单个 Session 的翻译进度（记录总数、翻译数据库命中数、仍与原文相同的记录数）
+///
+/// `records` 为前端当前编辑中的记录，未提供时统计 Session 中的记录
+#[tauri::command]
+pub fn get_session_translation_progress(
+    session_manager: tauri::State<Mutex<PluginSessionManager>>,
+    db: tauri::State<Mutex<TranslationDB>>,
+    session_id: String,
+    records: Option<Vec<StringRecord>>,
+) -> Result<SessionProgress, CommandError> {
+    let strings = {
+        let manager = session_manager.lock().context("Session 管理器锁定失败")?;
+        manager
+            .get_strings(&session_id)
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} 不存在", session_id)))?
+    };
+    let records = records.as_deref().unwrap_or(&strings);
+
+    let db = db.lock().context("数据库锁定失败")?;
+    let existing = db
+        .batch_query_translations(records_to_form_identifiers(records))
+        .context("批量查询翻译失败")?;
 
-    manager.export_dsd(&session_id, records, output_base_dir)
+    Ok(session_progress(records, &existing))
 }
@@ -1,14 +1,52 @@
+use crate::atomic_db::{AtomicDB, DEFAULT_USAGE_WRITE_RETRIES, MAX_USAGE_WRITE_RETRIES};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::dsd::{build_dsd_json_path, validate_dsd_path_template, DsdLayout};
+use crate::plugin_session::{LengthLimit, TranslationSource, MAX_APPLY_THREADS};
+use crate::scanner::validate_data_dir;
 use crate::settings::{read_settings, write_settings, Settings};
+use crate::utils::load_order::CoverageFilter;
+use crate::utils::paths::ensure_dir_writable;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::Emitter;
 
 /// 获取应用配置
 #[tauri::command]
-pub fn get_settings() -> Result<Settings, String> {
-    read_settings()
+pub fn get_settings() -> Result<Settings, CommandError> {
+    read_settings().map_err(CommandError::from)
+}
+
+/// 重新读取 settings.json 并应用到启动时缓存的运行时状态，无需重启
+///
+/// 大部分设置在每次操作时读取，无需处理；这里重新配置原子词匹配器（配置有变化时才重建）
+/// 与使用计数写入重试次数，完成后发送携带新设置的 `settings-changed` 事件
+#[tauri::command]
+pub fn reload_settings(
+    app: tauri::AppHandle,
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+) -> Result<Settings, CommandError> {
+    let settings = read_settings()?;
+
+    {
+        let db = atomic_db.lock().context("数据库锁定失败")?;
+        if db.matcher_options() != settings.atom_matcher {
+            db.set_matcher_options(settings.atom_matcher)
+                .context("重建原子匹配器失败")?;
+        }
+        let retries = settings
+            .usage_write_retries
+            .map_or(DEFAULT_USAGE_WRITE_RETRIES, |r| r.min(MAX_USAGE_WRITE_RETRIES));
+        db.set_usage_write_retries(retries);
+    }
+
+    // 发送事件（忽略发送失败）
+    let _ = app.emit("settings-changed", settings.clone());
+    Ok(settings)
 }
 
 /// 设置游戏路径
 #[tauri::command]
-pub fn set_game_path(path: String) -> Result<(), String> {
+pub fn set_game_path(path: String) -> Result<(), CommandError> {
     let mut settings = read_settings()?;
     settings.game = Some(path);
     write_settings(&settings)?;
@@ -17,7 +55,7 @@ pub fn set_game_path(path: String) -> Result<(), String> {
 
 /// 清除游戏路径
 #[tauri::command]
-pub fn clear_game_path() -> Result<(), String> {
+pub fn clear_game_path() -> Result<(), CommandError> {
     let mut settings = read_settings()?;
     settings.game = None;
     write_settings(&settings)?;
@@ -26,7 +64,7 @@ pub fn clear_game_path() -> Result<(), String> {
 
 /// 设置 DSD 导出目录
 #[tauri::command]
-pub fn set_dsd_output_dir(path: String) -> Result<(), String> {
+pub fn set_dsd_output_dir(path: String) -> Result<(), CommandError> {
     let mut settings = read_settings()?;
     settings.dsd_output_dir = Some(path);
     write_settings(&settings)?;
@@ -35,9 +73,254 @@ pub fn set_dsd_output_dir(path: String) -> Result<(), String> {
 
 /// 清除 DSD 导出目录
 #[tauri::command]
-pub fn clear_dsd_output_dir() -> Result<(), String> {
+pub fn clear_dsd_output_dir() -> Result<(), CommandError> {
     let mut settings = read_settings()?;
     settings.dsd_output_dir = None;
     write_settings(&settings)?;
     Ok(())
 }
+
+/// 设置 DSD 路径模板（占位符 {base}/{plugin}/{stem}）
+///
+/// 除校验模板格式外，在能确定展开位置时（已设置导出目录或模板为绝对路径）
+/// 确认展开后最近的已存在目录可写
+#[tauri::command]
+pub fn set_dsd_path_template(template: String) -> Result<(), CommandError> {
+    validate_dsd_path_template(&template).map_err(CommandError::InvalidInput)?;
+    let mut settings = read_settings()?;
+    settings.dsd_path_template = Some(template.trim().to_string());
+
+    let layout = DsdLayout::from_settings(&settings);
+    if layout.base_dir.is_some() || Path::new(layout.template()).is_absolute() {
+        let sample = build_dsd_json_path(Path::new("Example.esp"), &layout)?;
+        let existing = sample
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.is_dir())
+            .ok_or_else(|| {
+                CommandError::InvalidInput(format!("DSD 路径不可用: {}", sample.display()))
+            })?;
+        ensure_dir_writable(existing).map_err(CommandError::InvalidInput)?;
+    }
+
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除 DSD 路径模板（恢复 DSD 默认目录约定）
+#[tauri::command]
+pub fn clear_dsd_path_template() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.dsd_path_template = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置 Data 目录（需包含 Skyrim.esm）
+#[tauri::command]
+pub fn set_data_dir(path: String) -> Result<(), CommandError> {
+    validate_data_dir(Path::new(&path))?;
+    let mut settings = read_settings()?;
+    settings.data_dir = Some(path);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除 Data 目录（恢复自动检测）
+#[tauri::command]
+pub fn clear_data_dir() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.data_dir = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置写回临时文件目录
+#[tauri::command]
+pub fn set_temp_dir(path: String) -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.temp_dir = Some(path);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除写回临时文件目录
+#[tauri::command]
+pub fn clear_temp_dir() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.temp_dir = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置 userdata 目录（保存前校验可写，重启后生效）
+#[tauri::command]
+pub fn set_userdata_dir(path: String) -> Result<(), CommandError> {
+    ensure_dir_writable(Path::new(&path)).map_err(CommandError::InvalidInput)?;
+    let mut settings = read_settings()?;
+    settings.userdata_dir = Some(path);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除 userdata 目录设置（重启后恢复默认位置）
+#[tauri::command]
+pub fn clear_userdata_dir() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.userdata_dir = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 开启或关闭只读模式（禁止写回游戏目录与 Data 目录内的文件）
+#[tauri::command]
+pub fn set_read_only_data(enabled: bool) -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.read_only_data = enabled;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置写回转换线程数（1 表示不并行）
+#[tauri::command]
+pub fn set_apply_threads(threads: usize) -> Result<(), CommandError> {
+    if threads == 0 || threads > MAX_APPLY_THREADS {
+        return Err(CommandError::InvalidInput(format!(
+            "线程数必须在 1 到 {} 之间",
+            MAX_APPLY_THREADS
+        )));
+    }
+    let mut settings = read_settings()?;
+    settings.apply_threads = Some(threads);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置覆盖提取的插件过滤（排除列表或仅包含列表），下次完整提取时生效
+#[tauri::command]
+pub fn set_coverage_filter(filter: CoverageFilter) -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.coverage_filter = CoverageFilter {
+        patterns: filter
+            .patterns
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect(),
+        ..filter
+    };
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除覆盖提取的插件过滤（恢复提取全部插件）
+#[tauri::command]
+pub fn clear_coverage_filter() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.coverage_filter = CoverageFilter::default();
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置按原文自动填充时的译文来源优先级（按顺序尝试，不在列表中的来源不会被采用）
+#[tauri::command]
+pub fn set_translation_source_priority(
+    priority: Vec<TranslationSource>,
+) -> Result<(), CommandError> {
+    if priority.is_empty() {
+        return Err(CommandError::InvalidInput("来源优先级不能为空".to_string()));
+    }
+    let mut deduped = Vec::with_capacity(priority.len());
+    for source in priority {
+        if !deduped.contains(&source) {
+            deduped.push(source);
+        }
+    }
+    let mut settings = read_settings()?;
+    settings.translation_source_priority = Some(deduped);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除译文来源优先级（恢复默认顺序）
+#[tauri::command]
+pub fn clear_translation_source_priority() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.translation_source_priority = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置译文字节长度上限（覆盖内置默认值）
+#[tauri::command]
+pub fn set_length_limits(limits: Vec<LengthLimit>) -> Result<(), CommandError> {
+    if let Some(invalid) = limits
+        .iter()
+        .find(|l| l.record_type.trim().is_empty() || l.max_bytes == 0)
+    {
+        return Err(CommandError::InvalidInput(format!(
+            "无效的长度上限: {} {} {}",
+            invalid.record_type,
+            invalid.subrecord_type.as_deref().unwrap_or("*"),
+            invalid.max_bytes
+        )));
+    }
+    let mut settings = read_settings()?;
+    settings.length_limits = Some(limits);
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除译文字节长度上限（恢复内置默认值）
+#[tauri::command]
+pub fn clear_length_limits() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.length_limits = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 清除写回转换线程数（恢复使用全局线程池）
+#[tauri::command]
+pub fn clear_apply_threads() -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.apply_threads = None;
+    write_settings(&settings)?;
+    Ok(())
+}
+
+/// 设置原子词使用计数写入遇到数据库锁定时的重试次数（立即生效）
+#[tauri::command]
+pub fn set_usage_write_retries(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+    retries: u32,
+) -> Result<(), CommandError> {
+    if retries > MAX_USAGE_WRITE_RETRIES {
+        return Err(CommandError::InvalidInput(format!(
+            "重试次数必须在 0 到 {} 之间",
+            MAX_USAGE_WRITE_RETRIES
+        )));
+    }
+    let mut settings = read_settings()?;
+    settings.usage_write_retries = Some(retries);
+    write_settings(&settings)?;
+
+    atomic_db.lock().context("数据库锁定失败")?.set_usage_write_retries(retries);
+    Ok(())
+}
+
+/// 清除使用计数写入重试次数（恢复默认值）
+#[tauri::command]
+pub fn clear_usage_write_retries(
+    atomic_db: tauri::State<Mutex<AtomicDB>>,
+) -> Result<(), CommandError> {
+    let mut settings = read_settings()?;
+    settings.usage_write_retries = None;
+    write_settings(&settings)?;
+
+    atomic_db
+        .lock()
+        .context("数据库锁定失败")?
+        .set_usage_write_retries(DEFAULT_USAGE_WRITE_RETRIES);
+    Ok(())
+}
@@ -1,37 +1,88 @@
-use crate::translation_db::{FormIdentifier, Translation, TranslationDB, TranslationStats};
+use crate::commands::error::{CommandError, ErrorContext};
+use crate::esp_service::extract_english_keys;
+use crate::plugin_session::StringRecord;
+use crate::translation_db::{
+    is_untranslated_text, FormIdentifier, PluginRenameResult, SearchField, SearchHit,
+    StatisticsFormat, StatisticsReport, Translation, TranslationDB, TranslationReplaceResult,
+    TranslationStats, TypeCount, WordLookupResult, DEFAULT_QUERY_CHUNK_SIZE,
+};
+use crate::utils::strings_table::StringsTable;
+use crate::utils::translation_ndjson::{
+    export_ndjson, export_translations as export_translations_file, import_ndjson,
+    TranslationExportFormat, TranslationExportSummary,
+};
 use serde::Serialize;
+use std::path::Path;
 use std::sync::Mutex;
 use tauri::Emitter;
 
-/// 翻译进度通知 Payload
+/// 插件翻译完成度估算结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageEstimate {
+    pub plugin_path: String,
+    /// 插件中的字符串记录数（按 key 去重）
+    pub total: usize,
+    /// 翻译数据库中已有译文的记录数
+    pub matched: usize,
+    /// matched / total 的百分比（total 为 0 时为 0）
+    pub percentage: f64,
+}
+
+/// 翻译进度通知 Payload（通过 session_id 区分同时打开的多个插件）
 #[derive(Debug, Clone, Serialize)]
 pub struct TranslationProgressPayload {
     pub session_id: String,
     pub current: usize,
     pub total: usize,
     pub percentage: f64,
+    /// 目前已在翻译数据库中匹配到的记录数
+    pub matched: usize,
+}
+
+impl TranslationProgressPayload {
+    fn new(session_id: String, current: usize, total: usize, matched: usize) -> Self {
+        let percentage = if total > 0 {
+            (current as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            session_id,
+            current,
+            total,
+            percentage,
+            matched,
+        }
+    }
 }
 
 /// 保存单条翻译
+///
+/// `force_update_original` 为 true 时同时覆盖已有记录的原文（用于修正错误提取，默认 false）
 #[tauri::command]
 pub fn save_translation(
     db: tauri::State<Mutex<TranslationDB>>,
     translation: Translation,
-) -> Result<(), String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.save_translation(translation)
-        .map_err(|e| format!("保存翻译失败: {}", e))
+    force_update_original: Option<bool>,
+) -> Result<(), CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.save_translation(translation, force_update_original.unwrap_or(false)).context("保存翻译失败")
 }
 
-/// 批量保存翻译
+/// 批量保存翻译（分块提交，返回已提交的记录数）
+///
+/// `force_update_original` 含义同 [`save_translation`]
 #[tauri::command]
 pub fn batch_save_translations(
     db: tauri::State<Mutex<TranslationDB>>,
     translations: Vec<Translation>,
-) -> Result<(), String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.batch_save_translations(translations)
-        .map_err(|e| format!("批量保存翻译失败: {}", e))
+    force_update_original: Option<bool>,
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.batch_save_translations(translations, force_update_original.unwrap_or(false))
+        .map(|outcome| outcome.committed)
+        .context("批量保存翻译失败")
 }
 
 /// 查询单条翻译
@@ -42,10 +93,128 @@ pub fn get_translation(
     record_type: String,
     subrecord_type: String,
     index: u32,
-) -> Result<Option<Translation>, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_translation(&form_id, &record_type, &subrecord_type, index)
-        .map_err(|e| format!("查询翻译失败: {}", e))
+) -> Result<Option<Translation>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.get_translation(&form_id, &record_type, &subrecord_type, index).context("查询翻译失败")
+}
+
+/// 按 StringRecord 的完整 key（含 index）查询翻译
+#[tauri::command]
+pub fn get_translation_for_record(
+    db: tauri::State<Mutex<TranslationDB>>,
+    record: StringRecord,
+) -> Result<Option<Translation>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.get_translation(
+        &record.form_id,
+        &record.record_type,
+        &record.subrecord_type,
+        record.index,
+    )
+    .context("查询翻译失败")
+}
+
+/// 设置翻译备注（note 为空时清除），返回记录是否存在
+#[tauri::command]
+pub fn set_translation_note(
+    db: tauri::State<Mutex<TranslationDB>>,
+    key: FormIdentifier,
+    note: Option<String>,
+) -> Result<bool, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.set_translation_note(&key, note.as_deref()).context("设置翻译备注失败")
+}
+
+/// 规范化标签（去除首尾空白，禁止空标签）
+fn normalize_tag(tag: &str) -> Result<&str, CommandError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(CommandError::InvalidInput("标签不能为空".to_string()));
+    }
+    Ok(tag)
+}
+
+/// 为翻译记录添加标签，返回是否新增
+#[tauri::command]
+pub fn add_translation_tag(
+    db: tauri::State<Mutex<TranslationDB>>,
+    key: FormIdentifier,
+    tag: String,
+) -> Result<bool, CommandError> {
+    let tag = normalize_tag(&tag)?;
+    let db = db.lock().context("数据库锁定失败")?;
+    db.add_translation_tag(&key, tag).context("添加标签失败")
+}
+
+/// 移除翻译记录的标签，返回是否删除
+#[tauri::command]
+pub fn remove_translation_tag(
+    db: tauri::State<Mutex<TranslationDB>>,
+    key: FormIdentifier,
+    tag: String,
+) -> Result<bool, CommandError> {
+    let tag = normalize_tag(&tag)?;
+    let db = db.lock().context("数据库锁定失败")?;
+    db.remove_translation_tag(&key, tag).context("移除标签失败")
+}
+
+/// 列出翻译记录（可按插件、标签与 STRINGS 表筛选，按更新时间倒序）
+#[tauri::command]
+pub fn list_translations(
+    db: tauri::State<Mutex<TranslationDB>>,
+    plugin_name: Option<String>,
+    tag: Option<String>,
+    strings_table: Option<StringsTable>,
+    limit: usize,
+) -> Result<Vec<Translation>, CommandError> {
+    let tag = tag.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let db = db.lock().context("数据库锁定失败")?;
+    db.list_translations(plugin_name.as_deref(), tag, strings_table, limit)
+        .context("查询翻译列表失败")
+}
+
+/// 将翻译数据库流式导出为 NDJSON（每行一条，可按插件过滤），返回导出条数
+#[tauri::command]
+pub fn export_translations_ndjson(
+    db: tauri::State<Mutex<TranslationDB>>,
+    path: String,
+    plugin_name: Option<String>,
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    export_ndjson(&db, Path::new(&path), plugin_name.as_deref()).map_err(CommandError::from)
+}
+
+/// 流式导出翻译（NDJSON 或 JSON 数组），用于增量备份
+///
+/// `since_ts` 存在时仅导出 `updated_at >= since_ts` 的记录；返回导出条数与最大 updated_at，
+/// 调用方可将后者保存为下次导出的 `since_ts`（边界记录会重复导出一次，导入时按 UPSERT 合并）
+#[tauri::command]
+pub fn export_translations(
+    db: tauri::State<Mutex<TranslationDB>>,
+    path: String,
+    format: Option<TranslationExportFormat>,
+    since_ts: Option<i64>,
+    plugin_name: Option<String>,
+) -> Result<TranslationExportSummary, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    export_translations_file(
+        &db,
+        Path::new(&path),
+        format.unwrap_or_default(),
+        plugin_name.as_deref(),
+        since_ts,
+    )
+    .map_err(CommandError::from)
+}
+
+/// 从 NDJSON 文件流式导入翻译（UPSERT），返回导入条数
+#[tauri::command]
+pub fn import_translations_ndjson(
+    db: tauri::State<Mutex<TranslationDB>>,
+    path: String,
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    import_ndjson(&db, Path::new(&path)).map_err(CommandError::from)
 }
 
 /// 批量查询翻译
@@ -53,53 +222,125 @@ pub fn get_translation(
 pub fn batch_query_translations(
     db: tauri::State<Mutex<TranslationDB>>,
     forms: Vec<FormIdentifier>,
-) -> Result<Vec<Translation>, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.batch_query_translations(forms)
-        .map_err(|e| format!("批量查询翻译失败: {}", e))
+) -> Result<Vec<Translation>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.batch_query_translations(forms).context("批量查询翻译失败")
 }
 
-/// 批量查询翻译（带进度通知）
+/// 批量查询翻译（带进度通知，`chunk_size` 默认为 DEFAULT_QUERY_CHUNK_SIZE）
 #[tauri::command]
 pub fn batch_query_translations_with_progress(
     app: tauri::AppHandle,
     db: tauri::State<Mutex<TranslationDB>>,
     session_id: String,
     forms: Vec<FormIdentifier>,
-) -> Result<Vec<Translation>, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
+    chunk_size: Option<usize>,
+) -> Result<Vec<Translation>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_QUERY_CHUNK_SIZE);
+
+    // 先发送 0 进度，前端可立即显示进度条
+    let _ = app.emit(
+        "translation_progress",
+        TranslationProgressPayload::new(session_id.clone(), 0, forms.len(), 0),
+    );
 
     // 使用闭包捕获 app 和 session_id 来发送进度事件
-    let session_id_clone = session_id.clone();
-    let result = db.batch_query_translations_with_progress(forms, move |current, total| {
-        let percentage = if total > 0 {
-            (current as f64 / total as f64) * 100.0
-        } else {
-            0.0
-        };
+    let result = db.batch_query_translations_with_progress(
+        forms,
+        chunk_size,
+        move |current, total, matched| {
+            let payload =
+                TranslationProgressPayload::new(session_id.clone(), current, total, matched);
 
-        let payload = TranslationProgressPayload {
-            session_id: session_id_clone.clone(),
-            current,
-            total,
-            percentage,
-        };
+            // 发送进度事件（忽略发送失败）
+            let _ = app.emit("translation_progress", payload);
+        },
+    );
+
+    result.context("批量查询翻译失败")
+}
+
+/// 估算磁盘上插件的翻译完成度（仅加载英文字符串并批量查询翻译数据库，不创建 Session）
+#[tauri::command]
+pub fn estimate_coverage(
+    db: tauri::State<Mutex<TranslationDB>>,
+    plugin_path: String,
+) -> Result<CoverageEstimate, CommandError> {
+    let keys = extract_english_keys(Path::new(&plugin_path)).context("加载插件失败")?;
+    let total = keys.len();
 
-        // 发送进度事件（忽略发送失败）
-        let _ = app.emit("translation_progress", payload);
-    });
+    // 仅首尾空白与原文不同的译文不计入
+    let matched = {
+        let db = db.lock().context("数据库锁定失败")?;
+        db.batch_query_translations(keys)
+            .context("批量查询翻译失败")?
+            .iter()
+            .filter(|t| !is_untranslated_text(&t.original_text, &t.translated_text))
+            .count()
+    };
 
-    result.map_err(|e| format!("批量查询翻译失败: {}", e))
+    let percentage = if total > 0 {
+        (matched as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(CoverageEstimate {
+        plugin_path,
+        total,
+        matched,
+        percentage,
+    })
 }
 
 /// 获取翻译统计信息
 #[tauri::command]
 pub fn get_translation_statistics(
     db: tauri::State<Mutex<TranslationDB>>,
-) -> Result<TranslationStats, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.get_statistics()
-        .map_err(|e| format!("获取统计信息失败: {}", e))
+) -> Result<TranslationStats, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.get_statistics().context("获取统计信息失败")
+}
+
+/// 获取翻译数据库中出现的记录类型/子记录类型组合及条目数
+#[tauri::command]
+pub fn get_distinct_types(
+    db: tauri::State<Mutex<TranslationDB>>,
+) -> Result<Vec<TypeCount>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.get_distinct_types().context("获取记录类型失败")
+}
+
+/// 导出翻译统计报告（JSON 或 Markdown）
+#[tauri::command]
+pub fn export_statistics(
+    db: tauri::State<Mutex<TranslationDB>>,
+    path: String,
+    format: StatisticsFormat,
+) -> Result<String, CommandError> {
+    let stats = {
+        let db = db.lock().context("数据库锁定失败")?;
+        db.get_statistics().context("获取统计信息失败")?
+    };
+
+    let generated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("获取时间失败")?
+        .as_secs() as i64;
+    let report = StatisticsReport {
+        generated_at,
+        stats,
+    };
+
+    let content = match format {
+        StatisticsFormat::Json => serde_json::to_string_pretty(&report)
+            .context("序列化统计报告失败")?,
+        StatisticsFormat::Markdown => report.to_markdown(),
+    };
+
+    std::fs::write(&path, content).context("写入统计报告失败")?;
+    Ok(path)
 }
 
 /// 清除指定插件的翻译
@@ -107,36 +348,109 @@ pub fn get_translation_statistics(
 pub fn clear_plugin_translations(
     db: tauri::State<Mutex<TranslationDB>>,
     plugin_name: String,
-) -> Result<usize, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.clear_plugin_translations(&plugin_name)
-        .map_err(|e| format!("清除插件翻译失败: {}", e))
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.clear_plugin_translations(&plugin_name).context("清除插件翻译失败")
+}
+
+/// 将翻译记录中的插件名整体重命名
+///
+/// `rewrite_form_ids` 为 true 时同时改写 form_id 中的插件部分（插件改名后重新提取的记录会使用新后缀）
+#[tauri::command]
+pub fn rename_plugin_translations(
+    db: tauri::State<Mutex<TranslationDB>>,
+    old_name: String,
+    new_name: String,
+    rewrite_form_ids: Option<bool>,
+) -> Result<PluginRenameResult, CommandError> {
+    let old_name = old_name.trim();
+    let new_name = new_name.trim();
+    if old_name.is_empty() || new_name.is_empty() {
+        return Err(CommandError::InvalidInput("插件名不能为空".to_string()));
+    }
+    if old_name == new_name {
+        return Err(CommandError::InvalidInput("新旧插件名相同".to_string()));
+    }
+
+    let db = db.lock().context("数据库锁定失败")?;
+    db.rename_plugin(old_name, new_name, rewrite_form_ids.unwrap_or(false))
+        .context("重命名插件翻译失败")
+}
+
+/// 在翻译数据库的译文中查找替换（字面量或正则，可限定插件）
+///
+/// `dry_run` 为 true 时只返回受影响记录的替换前后内容（用于预览），确认后以 false 再次调用写入。
+/// 正则模式下 `replacement` 可使用 `$1`/`${name}` 引用捕获组
+#[tauri::command]
+pub fn replace_in_translations(
+    db: tauri::State<Mutex<TranslationDB>>,
+    pattern: String,
+    replacement: String,
+    use_regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    plugin_name: Option<String>,
+    dry_run: bool,
+) -> Result<TranslationReplaceResult, CommandError> {
+    if pattern.is_empty() {
+        return Err(CommandError::InvalidInput("查找内容不能为空".to_string()));
+    }
+
+    let use_regex = use_regex.unwrap_or(false);
+    let source = if use_regex { pattern } else { regex::escape(&pattern) };
+    let regex = regex::RegexBuilder::new(&source)
+        .case_insensitive(!case_sensitive.unwrap_or(true))
+        .build()
+        .map_err(|e| CommandError::InvalidInput(format!("正则表达式无效: {}", e)))?;
+    // 字面量模式下替换文本中的 `$` 不作为捕获组引用
+    let replacement = if use_regex { replacement } else { replacement.replace('$', "$$") };
+    let plugin_name = plugin_name.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+    let db = db.lock().context("数据库锁定失败")?;
+    db.replace_in_translations(&regex, &replacement, plugin_name, dry_run)
+        .context("替换译文失败")
 }
 
 /// 清除所有翻译（慎用）
 #[tauri::command]
-pub fn clear_all_translations(db: tauri::State<Mutex<TranslationDB>>) -> Result<usize, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.clear_all_translations()
-        .map_err(|e| format!("清除所有翻译失败: {}", e))
+pub fn clear_all_translations(
+    db: tauri::State<Mutex<TranslationDB>>,
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.clear_all_translations().context("清除所有翻译失败")
 }
 
 /// 清除基础词典数据（9个官方插件）
 #[tauri::command]
-pub fn clear_base_dictionary(db: tauri::State<Mutex<TranslationDB>>) -> Result<usize, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.clear_base_dictionary()
-        .map_err(|e| format!("清除基础词典失败: {}", e))
+pub fn clear_base_dictionary(
+    db: tauri::State<Mutex<TranslationDB>>,
+) -> Result<usize, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.clear_base_dictionary().context("清除基础词典失败")
 }
 
 /// 查询单词翻译（用于编辑器参考）
+///
+/// 输入过长时只查询其中最长的单词（见 [`crate::translation_db::lookup_term`]），
+/// 并在结果中标记 `truncated`
 #[tauri::command]
 pub fn query_word_translations(
     db: tauri::State<Mutex<TranslationDB>>,
     text: String,
     limit: usize,
-) -> Result<Vec<Translation>, String> {
-    let db = db.lock().map_err(|e| format!("数据库锁定失败: {}", e))?;
-    db.query_by_text(&text, limit)
-        .map_err(|e| format!("查询单词翻译失败: {}", e))
+) -> Result<WordLookupResult, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.lookup_word_translations(&text, limit).context("查询单词翻译失败")
+}
+
+/// 在原文与译文中全局搜索（返回命中字段与上下文片段）
+#[tauri::command]
+pub fn search_all(
+    db: tauri::State<Mutex<TranslationDB>>,
+    query: String,
+    fields: Option<Vec<SearchField>>,
+    limit: usize,
+) -> Result<Vec<SearchHit>, CommandError> {
+    let db = db.lock().context("数据库锁定失败")?;
+    db.search_all(&query, fields.as_deref().unwrap_or_default(), limit)
+        .context("全局搜索失败")
 }
@@ -1,6 +1,12 @@
 use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Result};
 use rusqlite::types::Value;
+use crate::utils::schema::{
+    add_column_if_missing, read_schema_version, run_migrations, Migration, SCHEMA_VERSION_KEY,
+};
+use crate::translation_db::{query_type_counts, FormIdentifier, TypeCount};
+use crate::utils::sql::{configure_busy_timeout, escape_like, retry_on_busy};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
@@ -15,6 +21,9 @@ pub struct CoverageEntry {
     pub source_mod: String,
     pub load_order_pos: i64,
     pub extracted_at: i64,
+    /// load_order_pos 对应的插件名称（仅搜索结果填充，load order 快照中不存在时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_order_plugin: Option<String>,
 }
 
 /// load order 快照记录
@@ -23,10 +32,43 @@ pub struct LoadOrderEntry {
     pub position: i64,
     pub plugin_name: String,
     pub plugin_path: Option<String>,
+    /// 提取时插件文件的大小与修改时间校验值（文件无法读取时为 None）
     pub checksum: Option<String>,
     pub extracted_at: i64,
+    /// 是否因插件过滤设置而跳过提取（仍保留在快照中以维持 load order 位置）
+    #[serde(default)]
+    pub skipped: bool,
 }
 
+/// 单插件增量更新的写入统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginEntriesReplaced {
+    /// 写入（该插件为最终覆盖者）的记录数
+    pub written: usize,
+    /// 该插件原先覆盖、新版本中已不存在的记录数
+    pub removed: usize,
+}
+
+/// 按 key 批量查询时每条 SQL 包含的 key 数量（每个 key 占 4 个参数）
+const KEY_QUERY_CHUNK_SIZE: usize = 200;
+
+/// 批量写入时每个事务包含的最大记录数
+pub const UPSERT_CHUNK_SIZE: usize = 5000;
+
+/// schema 迁移（新增列/索引时在末尾追加，版本号递增，版本号存储在 coverage_meta）
+const MIGRATIONS: &[Migration] = &[
+    // v1：文本子串搜索使用 FTS5 trigram 索引，并根据现有数据重建
+    Migration {
+        version: 1,
+        up: CoverageDB::create_fts_index,
+    },
+    // v2：load order 快照记录插件是否被过滤设置跳过
+    Migration {
+        version: 2,
+        up: CoverageDB::add_load_order_skipped_column,
+    },
+];
+
 /// 覆盖关系数据库
 pub struct CoverageDB {
     conn: Arc<Mutex<Connection>>,
@@ -36,6 +78,7 @@ impl CoverageDB {
     /// 初始化 coverage.db
     pub fn new(path: PathBuf) -> Result<Self> {
         let conn = Connection::open(path)?;
+        configure_busy_timeout(&conn)?;
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;",
@@ -85,9 +128,61 @@ impl CoverageDB {
             [],
         )?;
 
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_coverage_source_mod ON coverage_entries(source_mod)",
+            [],
+        )?;
+
+        run_migrations(&conn, "coverage_meta", MIGRATIONS)?;
+
         Ok(())
     }
 
+    fn add_load_order_skipped_column(conn: &Connection) -> Result<()> {
+        add_column_if_missing(
+            conn,
+            "coverage_load_order",
+            "skipped",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+    }
+
+    /// 创建 FTS5 trigram 索引（LIKE '%x%' 无法利用普通索引）并根据现有数据重建
+    fn create_fts_index(conn: &Connection) -> Result<()> {
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS coverage_entries_fts USING fts5(
+                text,
+                content = 'coverage_entries',
+                content_rowid = 'rowid',
+                tokenize = 'trigram'
+            )",
+            [],
+        )?;
+        Self::create_fts_triggers(conn)?;
+        conn.execute(
+            "INSERT INTO coverage_entries_fts(coverage_entries_fts) VALUES('rebuild')",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 创建保持 FTS 索引与 coverage_entries 同步的触发器
+    fn create_fts_triggers(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TRIGGER IF NOT EXISTS coverage_entries_ai AFTER INSERT ON coverage_entries BEGIN
+                INSERT INTO coverage_entries_fts(rowid, text) VALUES (new.rowid, new.text);
+             END;
+             CREATE TRIGGER IF NOT EXISTS coverage_entries_ad AFTER DELETE ON coverage_entries BEGIN
+                INSERT INTO coverage_entries_fts(coverage_entries_fts, rowid, text) VALUES ('delete', old.rowid, old.text);
+             END;
+             CREATE TRIGGER IF NOT EXISTS coverage_entries_au AFTER UPDATE OF text ON coverage_entries BEGIN
+                INSERT INTO coverage_entries_fts(coverage_entries_fts, rowid, text) VALUES ('delete', old.rowid, old.text);
+                INSERT INTO coverage_entries_fts(rowid, text) VALUES (new.rowid, new.text);
+             END;",
+        )
+    }
+
     /// 覆盖插入/更新单条记录
     pub fn upsert_entry(&self, entry: CoverageEntry) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -116,13 +211,36 @@ impl CoverageDB {
         Ok(())
     }
 
-    /// 事务性批量写入
-    pub fn batch_upsert_entries(&self, entries: Vec<CoverageEntry>) -> Result<()> {
+    /// 分块批量写入，每 [`UPSERT_CHUNK_SIZE`] 条提交一次事务，返回写入条数
+    pub fn batch_upsert_entries<I>(&self, entries: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = CoverageEntry>,
+    {
         let conn = self.conn.lock().unwrap();
-        let tx = conn.unchecked_transaction()?;
+        let mut chunk = Vec::with_capacity(UPSERT_CHUNK_SIZE);
+        let mut written = 0;
 
         for entry in entries {
-            tx.execute(
+            chunk.push(entry);
+            if chunk.len() >= UPSERT_CHUNK_SIZE {
+                retry_on_busy(|| Self::upsert_chunk(&conn, &chunk))?;
+                written += chunk.len();
+                chunk.clear();
+            }
+        }
+        if !chunk.is_empty() {
+            retry_on_busy(|| Self::upsert_chunk(&conn, &chunk))?;
+            written += chunk.len();
+        }
+
+        Ok(written)
+    }
+
+    /// 在单个事务内写入一块记录
+    fn upsert_chunk(conn: &Connection, chunk: &[CoverageEntry]) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
                 "INSERT INTO coverage_entries
                     (form_id, record_type, subrecord_type, \"index\", text,
                      source_mod, load_order_pos, extracted_at)
@@ -133,7 +251,9 @@ impl CoverageDB {
                     source_mod = excluded.source_mod,
                     load_order_pos = excluded.load_order_pos,
                     extracted_at = excluded.extracted_at",
-                params![
+            )?;
+            for entry in chunk {
+                stmt.execute(params![
                     entry.form_id,
                     entry.record_type,
                     entry.subrecord_type,
@@ -142,19 +262,143 @@ impl CoverageDB {
                     entry.source_mod,
                     entry.load_order_pos,
                     entry.extracted_at
-                ],
-            )?;
+                ])?;
+            }
         }
+        tx.commit()
+    }
 
-        tx.commit()?;
-        Ok(())
+    /// 替换单个插件的覆盖记录（增量更新，其余插件的记录保持不变）
+    ///
+    /// 删除该插件（按 source_mod 与 load_order_pos）原先覆盖的记录后写入新记录；
+    /// 已被更后加载的插件覆盖的 key 不会被改写。被删除且新版本中不存在的 key
+    /// 不会回退到更早插件的文本（需完整重新提取）
+    pub fn replace_plugin_entries(
+        &self,
+        source_mod: &str,
+        load_order_pos: i64,
+        entries: &[CoverageEntry],
+    ) -> Result<PluginEntriesReplaced> {
+        let conn = self.conn.lock().unwrap();
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            let previous: HashSet<(String, String, String, u32)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT form_id, record_type, subrecord_type, \"index\" FROM coverage_entries
+                     WHERE source_mod = ?1 AND load_order_pos = ?2",
+                )?;
+                let keys = stmt
+                    .query_map(params![source_mod, load_order_pos], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })?
+                    .collect::<Result<_, _>>()?;
+                keys
+            };
+            tx.execute(
+                "DELETE FROM coverage_entries WHERE source_mod = ?1 AND load_order_pos = ?2",
+                params![source_mod, load_order_pos],
+            )?;
+
+            let mut written = 0;
+            let mut current = HashSet::new();
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT INTO coverage_entries
+                        (form_id, record_type, subrecord_type, \"index\", text,
+                         source_mod, load_order_pos, extracted_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT(form_id, record_type, subrecord_type, \"index\")
+                     DO UPDATE SET
+                        text = excluded.text,
+                        source_mod = excluded.source_mod,
+                        load_order_pos = excluded.load_order_pos,
+                        extracted_at = excluded.extracted_at
+                     WHERE coverage_entries.load_order_pos <= excluded.load_order_pos",
+                )?;
+                for entry in entries {
+                    written += stmt.execute(params![
+                        entry.form_id,
+                        entry.record_type,
+                        entry.subrecord_type,
+                        entry.index,
+                        entry.text,
+                        entry.source_mod,
+                        entry.load_order_pos,
+                        entry.extracted_at
+                    ])?;
+                    current.insert((
+                        entry.form_id.clone(),
+                        entry.record_type.clone(),
+                        entry.subrecord_type.clone(),
+                        entry.index,
+                    ));
+                }
+            }
+            tx.commit()?;
+
+            Ok(PluginEntriesReplaced {
+                written,
+                removed: previous.difference(&current).count(),
+            })
+        })
+    }
+
+    /// 更新 load order 快照中单个插件的路径与提取时间，返回是否存在该插件
+    pub fn touch_load_order_entry(
+        &self,
+        plugin_name: &str,
+        plugin_path: Option<&str>,
+        checksum: Option<&str>,
+        extracted_at: i64,
+    ) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE coverage_load_order
+             SET plugin_path = COALESCE(?2, plugin_path), checksum = ?4, extracted_at = ?3,
+                 skipped = 0
+             WHERE plugin_name = ?1",
+            params![plugin_name, plugin_path, extracted_at, checksum],
+        )?;
+        Ok(updated > 0)
     }
 
     /// 清空覆盖记录表，确保刷新前状态干净
     pub fn clear_entries(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM coverage_entries", [])?;
-        Ok(())
+        let tx = conn.unchecked_transaction()?;
+
+        // 整表清空时跳过逐行删除触发器，直接清空 FTS 索引
+        tx.execute("DROP TRIGGER IF EXISTS coverage_entries_ad", [])?;
+        tx.execute("DELETE FROM coverage_entries", [])?;
+        tx.execute(
+            "INSERT INTO coverage_entries_fts(coverage_entries_fts) VALUES('delete-all')",
+            [],
+        )?;
+        Self::create_fts_triggers(&tx)?;
+
+        tx.commit()
+    }
+
+    /// 删除指定记录类型的覆盖记录（`subrecord_type` 为 None 时删除该记录类型的全部子记录），
+    /// 返回删除条数
+    pub fn delete_entries_by_types(&self, types: &[(String, Option<String>)]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut deleted = 0;
+
+        for (record_type, subrecord_type) in types {
+            deleted += tx.execute(
+                "DELETE FROM coverage_entries
+                 WHERE UPPER(record_type) = ?1 AND (?2 IS NULL OR UPPER(subrecord_type) = ?2)",
+                params![
+                    record_type.to_uppercase(),
+                    subrecord_type.as_ref().map(|s| s.to_uppercase())
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(deleted)
     }
 
     /// 用新的快照替换 load order 表
@@ -166,14 +410,16 @@ impl CoverageDB {
 
         for entry in entries {
             tx.execute(
-                "INSERT INTO coverage_load_order (position, plugin_name, plugin_path, checksum, extracted_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                "INSERT INTO coverage_load_order
+                    (position, plugin_name, plugin_path, checksum, extracted_at, skipped)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
                 params![
                     entry.position,
                     entry.plugin_name,
                     entry.plugin_path,
                     entry.checksum,
-                    entry.extracted_at
+                    entry.extracted_at,
+                    entry.skipped
                 ],
             )?;
         }
@@ -186,7 +432,7 @@ impl CoverageDB {
     pub fn get_load_order_snapshot(&self) -> Result<Vec<LoadOrderEntry>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT position, plugin_name, plugin_path, checksum, extracted_at
+            "SELECT position, plugin_name, plugin_path, checksum, extracted_at, skipped
              FROM coverage_load_order
              ORDER BY position ASC",
         )?;
@@ -199,6 +445,7 @@ impl CoverageDB {
                     plugin_path: row.get(2)?,
                     checksum: row.get(3)?,
                     extracted_at: row.get(4)?,
+                    skipped: row.get(5)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -219,6 +466,121 @@ impl CoverageDB {
         Ok(ts.flatten())
     }
 
+    /// 读取全部覆盖记录（按 load order 与 key 排序）
+    pub fn get_all_entries(&self) -> Result<Vec<CoverageEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT form_id, record_type, subrecord_type, \"index\", text, source_mod, load_order_pos, extracted_at
+             FROM coverage_entries
+             ORDER BY load_order_pos ASC, form_id ASC, record_type ASC, subrecord_type ASC, \"index\" ASC",
+        )?;
+
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(CoverageEntry {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    text: row.get(4)?,
+                    source_mod: row.get(5)?,
+                    load_order_pos: row.get(6)?,
+                    extracted_at: row.get(7)?,
+                    load_order_plugin: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// 当前数据库的 schema 版本
+    pub fn schema_version(&self) -> Result<u32> {
+        let conn = self.conn.lock().unwrap();
+        read_schema_version(&conn, "coverage_meta")
+    }
+
+    /// 读取 coverage_meta 中的全部键值
+    pub fn get_meta(&self) -> Result<HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT key, value FROM coverage_meta")?;
+        let meta = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(meta)
+    }
+
+    /// 在单个事务内用快照数据替换覆盖记录、load order 与 meta（失败时保持原数据不变）
+    pub fn restore_snapshot(
+        &self,
+        load_order: &[LoadOrderEntry],
+        entries: &[CoverageEntry],
+        meta: &HashMap<String, String>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        // 与 clear_entries 相同：整表清空时跳过逐行删除触发器
+        tx.execute("DROP TRIGGER IF EXISTS coverage_entries_ad", [])?;
+        tx.execute("DELETE FROM coverage_entries", [])?;
+        tx.execute(
+            "INSERT INTO coverage_entries_fts(coverage_entries_fts) VALUES('delete-all')",
+            [],
+        )?;
+        Self::create_fts_triggers(&tx)?;
+
+        tx.execute("DELETE FROM coverage_load_order", [])?;
+        // schema_version 描述的是本地数据库结构，不随快照替换
+        tx.execute(
+            "DELETE FROM coverage_meta WHERE key != ?1",
+            params![SCHEMA_VERSION_KEY],
+        )?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO coverage_load_order
+                    (position, plugin_name, plugin_path, checksum, extracted_at, skipped)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for entry in load_order {
+                stmt.execute(params![
+                    entry.position,
+                    entry.plugin_name,
+                    entry.plugin_path,
+                    entry.checksum,
+                    entry.extracted_at,
+                    entry.skipped
+                ])?;
+            }
+
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO coverage_entries
+                    (form_id, record_type, subrecord_type, \"index\", text,
+                     source_mod, load_order_pos, extracted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for entry in entries {
+                stmt.execute(params![
+                    entry.form_id,
+                    entry.record_type,
+                    entry.subrecord_type,
+                    entry.index,
+                    entry.text,
+                    entry.source_mod,
+                    entry.load_order_pos,
+                    entry.extracted_at
+                ])?;
+            }
+
+            let mut stmt =
+                tx.prepare_cached("INSERT INTO coverage_meta (key, value) VALUES (?1, ?2)")?;
+            for (key, value) in meta.iter().filter(|(key, _)| *key != SCHEMA_VERSION_KEY) {
+                stmt.execute(params![key, value])?;
+            }
+        }
+
+        tx.commit()
+    }
+
     /// 搜索覆盖记录
     pub fn search_entries(
         &self,
@@ -232,42 +594,51 @@ impl CoverageDB {
         let mut values: Vec<Value> = Vec::new();
 
         if let Some(form_query) = form_id_query.filter(|s| !s.trim().is_empty()) {
-            conditions.push("LOWER(form_id) LIKE ?");
+            conditions.push("LOWER(e.form_id) LIKE ? ESCAPE '\\'");
             values.push(Value::Text(format!(
                 "%{}%",
-                form_query.to_lowercase()
+                escape_like(&form_query.to_lowercase())
             )));
         }
 
         let mut relevance_patterns: Option<(String, String, String)> = None;
 
         if let Some(text_query) = text_query.filter(|s| !s.trim().is_empty()) {
-            conditions.push("LOWER(text) LIKE ?");
-            values.push(Value::Text(format!(
-                "%{}%",
-                text_query.to_lowercase()
-            )));
             let lowered = text_query.to_lowercase();
+            let escaped = escape_like(&lowered);
+
+            // trigram 索引至少需要 3 个字符，先用 FTS 缩小范围，再用 LIKE 保证匹配语义不变
+            if text_query.chars().count() >= 3 {
+                conditions.push(
+                    "e.rowid IN (SELECT rowid FROM coverage_entries_fts WHERE coverage_entries_fts MATCH ?)",
+                );
+                values.push(Value::Text(fts_phrase(text_query)));
+            }
+            conditions.push("LOWER(e.text) LIKE ? ESCAPE '\\'");
+            values.push(Value::Text(format!("%{}%", escaped)));
             relevance_patterns = Some((
-                lowered.clone(),
-                format!("{}%", lowered),
-                format!("%{}%", lowered),
+                lowered,
+                format!("{}%", escaped),
+                format!("%{}%", escaped),
             ));
         }
 
-        let mut sql = "SELECT form_id, record_type, subrecord_type, \"index\", text, source_mod, load_order_pos, extracted_at FROM coverage_entries".to_string();
+        // 关联 load order 快照，使结果携带 load_order_pos 对应的插件名称
+        let mut sql = "SELECT e.form_id, e.record_type, e.subrecord_type, e.\"index\", e.text, e.source_mod, e.load_order_pos, e.extracted_at, lo.plugin_name
+                       FROM coverage_entries e
+                       LEFT JOIN coverage_load_order lo ON lo.position = e.load_order_pos".to_string();
         if !conditions.is_empty() {
             sql.push_str(" WHERE ");
             sql.push_str(&conditions.join(" AND "));
         }
 
         if let Some((exact, prefix, substring)) = relevance_patterns {
-            sql.push_str(" ORDER BY CASE WHEN LOWER(text) = ? THEN 4 WHEN LOWER(text) LIKE ? THEN 3 WHEN LOWER(text) LIKE ? THEN 2 ELSE 1 END DESC, load_order_pos DESC, extracted_at DESC LIMIT ?");
+            sql.push_str(" ORDER BY CASE WHEN LOWER(e.text) = ? THEN 4 WHEN LOWER(e.text) LIKE ? ESCAPE '\\' THEN 3 WHEN LOWER(e.text) LIKE ? ESCAPE '\\' THEN 2 ELSE 1 END DESC, e.load_order_pos DESC, e.extracted_at DESC LIMIT ?");
             values.push(Value::Text(exact));
             values.push(Value::Text(prefix));
             values.push(Value::Text(substring));
         } else {
-            sql.push_str(" ORDER BY extracted_at DESC LIMIT ?");
+            sql.push_str(" ORDER BY e.extracted_at DESC LIMIT ?");
         }
         values.push(Value::Integer(limit as i64));
 
@@ -284,10 +655,286 @@ impl CoverageDB {
                     source_mod: row.get(5)?,
                     load_order_pos: row.get(6)?,
                     extracted_at: row.get(7)?,
+                    load_order_plugin: row.get(8)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
+
+    /// 获取覆盖数据库中出现的全部记录类型/子记录类型组合（按条目数降序）
+    pub fn get_distinct_types(&self) -> Result<Vec<TypeCount>> {
+        let conn = self.conn.lock().unwrap();
+        query_type_counts(&conn, "coverage_entries")
+    }
+
+    /// 按记录 key 批量查询覆盖记录（即每条记录在整个 load order 中的最终文本）
+    pub fn get_entries_by_keys(&self, keys: &[FormIdentifier]) -> Result<Vec<CoverageEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut entries = Vec::new();
+
+        for chunk in keys.chunks(KEY_QUERY_CHUNK_SIZE) {
+            let placeholders = vec!["(?, ?, ?, ?)"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT e.form_id, e.record_type, e.subrecord_type, e.\"index\", e.text, e.source_mod,
+                        e.load_order_pos, e.extracted_at, lo.plugin_name
+                 FROM coverage_entries e
+                 LEFT JOIN coverage_load_order lo ON lo.position = e.load_order_pos
+                 WHERE (e.form_id, e.record_type, e.subrecord_type, e.\"index\") IN ({})",
+                placeholders
+            );
+            let values = chunk.iter().flat_map(|key| {
+                [
+                    Value::Text(key.form_id.clone()),
+                    Value::Text(key.record_type.clone()),
+                    Value::Text(key.subrecord_type.clone()),
+                    Value::Integer(key.index as i64),
+                ]
+            });
+
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), |row| {
+                Ok(CoverageEntry {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    text: row.get(4)?,
+                    source_mod: row.get(5)?,
+                    load_order_pos: row.get(6)?,
+                    extracted_at: row.get(7)?,
+                    load_order_plugin: row.get(8)?,
+                })
+            })?;
+            for row in rows {
+                entries.push(row?);
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// 将搜索词包装为 FTS5 短语查询（双引号转义）
+fn fts_phrase(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(i: usize, text: String) -> CoverageEntry {
+        CoverageEntry {
+            form_id: format!("{:08X}|Test.esp", i),
+            record_type: "MISC".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+            text,
+            source_mod: format!("Mod{}.esp", i % 50),
+            load_order_pos: (i % 50) as i64,
+            extracted_at: 0,
+            load_order_plugin: None,
+        }
+    }
+
+    #[test]
+    fn delete_entries_by_types_keeps_other_types() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        let typed = |i: usize, record_type: &str, subrecord_type: &str| CoverageEntry {
+            record_type: record_type.to_string(),
+            subrecord_type: subrecord_type.to_string(),
+            ..entry(i, format!("Text {}", i))
+        };
+        db.batch_upsert_entries(vec![
+            typed(1, "INFO", "NAM1"),
+            typed(2, "INFO", "RNAM"),
+            typed(3, "WEAP", "FULL"),
+            typed(4, "BOOK", "DESC"),
+        ])?;
+
+        let deleted = db.delete_entries_by_types(&[
+            ("INFO".to_string(), Some("NAM1".to_string())),
+            ("WEAP".to_string(), None),
+        ])?;
+        assert_eq!(deleted, 2);
+
+        let mut remaining: Vec<String> = db
+            .search_entries(None, Some("Text"), 10)?
+            .into_iter()
+            .map(|e| format!("{} {}", e.record_type, e.subrecord_type))
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["BOOK DESC", "INFO RNAM"]);
+        Ok(())
+    }
+
+    #[test]
+    fn search_entries_matches_substrings_through_fts() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        db.batch_upsert_entries(vec![
+            entry(1, "Iron Sword".to_string()),
+            entry(2, "Steel Sword of Fire".to_string()),
+            entry(3, "Deals 50% more damage".to_string()),
+            entry(4, "Deals 500 more damage".to_string()),
+        ])?;
+
+        let results = db.search_entries(None, Some("SWORD"), 10)?;
+        assert_eq!(results.len(), 2);
+
+        let results = db.search_entries(None, Some("50%"), 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "Deals 50% more damage");
+
+        // 少于 3 个字符时回退到 LIKE
+        let results = db.search_entries(None, Some("of"), 10)?;
+        assert_eq!(results.len(), 1);
+
+        // 更新后 FTS 索引同步
+        db.upsert_entry(entry(1, "Iron Dagger".to_string()))?;
+        assert_eq!(db.search_entries(None, Some("sword"), 10)?.len(), 1);
+        assert_eq!(db.search_entries(None, Some("dagger"), 10)?.len(), 1);
+
+        db.clear_entries()?;
+        assert!(db.search_entries(None, Some("dagger"), 10)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn replace_plugin_entries_keeps_later_overrides() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        let at = |i: usize, text: &str, pos: i64| CoverageEntry {
+            source_mod: format!("Mod{}.esp", pos),
+            load_order_pos: pos,
+            ..entry(i, text.to_string())
+        };
+        db.batch_upsert_entries(vec![
+            at(1, "Old sword", 1),
+            at(2, "Old shield", 1),
+            at(3, "Later helm", 2),
+        ])?;
+
+        let replaced = db.replace_plugin_entries(
+            "Mod1.esp",
+            1,
+            &[at(1, "New sword", 1), at(3, "Mod1 helm", 1), at(4, "New bow", 1)],
+        )?;
+
+        assert_eq!(replaced, PluginEntriesReplaced { written: 2, removed: 1 });
+        let texts: Vec<String> = db.get_all_entries()?.into_iter().map(|e| e.text).collect();
+        assert!(texts.contains(&"New sword".to_string()));
+        assert!(texts.contains(&"Later helm".to_string()));
+        assert!(!texts.contains(&"Old shield".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_entries_includes_load_order_plugin_name() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        db.replace_load_order_snapshot(&[LoadOrderEntry {
+            position: 1,
+            plugin_name: "Mod1.esp".to_string(),
+            plugin_path: None,
+            checksum: None,
+            extracted_at: 0,
+            skipped: false,
+        }])?;
+        db.batch_upsert_entries(vec![
+            entry(1, "Iron Sword".to_string()),
+            entry(2, "Steel Sword".to_string()),
+        ])?;
+
+        let results = db.search_entries(Some("00000001"), None, 10)?;
+        assert_eq!(results[0].load_order_plugin.as_deref(), Some("Mod1.esp"));
+
+        // load order 快照中不存在的位置返回 None
+        let results = db.search_entries(None, Some("steel"), 10)?;
+        assert_eq!(results[0].load_order_plugin, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_entries_by_keys_spans_query_chunks() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        let total = KEY_QUERY_CHUNK_SIZE + 10;
+        db.batch_upsert_entries((0..total).map(|i| entry(i, format!("Text {}", i))))?;
+
+        let key = |i: usize, index: u32| FormIdentifier {
+            form_id: format!("{:08X}|Test.esp", i),
+            record_type: "MISC".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index,
+        };
+        // 每隔一条查询一次，使 key 跨越分块边界；同 FormID 不同 index 与不存在的 key 均不应命中
+        let mut keys: Vec<FormIdentifier> = (0..total).step_by(2).map(|i| key(i, 0)).collect();
+        keys.extend((0..KEY_QUERY_CHUNK_SIZE).map(|i| key(i, 1)));
+        keys.push(key(total + 1, 0));
+        assert!(keys.len() > KEY_QUERY_CHUNK_SIZE);
+
+        let mut found: Vec<String> =
+            db.get_entries_by_keys(&keys)?.into_iter().map(|e| e.form_id).collect();
+        found.sort();
+        let expected: Vec<String> = (0..total).step_by(2).map(|i| key(i, 0).form_id).collect();
+        assert_eq!(found, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_snapshot_replaces_existing_data() -> Result<()> {
+        let source = CoverageDB::new(":memory:".into())?;
+        source.batch_upsert_entries(vec![entry(1, "Iron Sword".to_string())])?;
+        let entries = source.get_all_entries()?;
+        let meta = HashMap::from([
+            ("game_path".to_string(), "C:/Skyrim".to_string()),
+            (SCHEMA_VERSION_KEY.to_string(), "999".to_string()),
+        ]);
+
+        let db = CoverageDB::new(":memory:".into())?;
+        db.batch_upsert_entries(vec![entry(2, "Steel Dagger".to_string())])?;
+        db.restore_snapshot(&[], &entries, &meta)?;
+
+        assert_eq!(db.get_all_entries()?.len(), 1);
+        assert_eq!(db.search_entries(None, Some("sword"), 10)?.len(), 1);
+        assert!(db.search_entries(None, Some("dagger"), 10)?.is_empty());
+        // schema_version 保留本地值，其余 meta 被替换
+        let restored = db.get_meta()?;
+        assert_eq!(restored.get("game_path").map(String::as_str), Some("C:/Skyrim"));
+        assert_eq!(db.schema_version()?, MIGRATIONS.len() as u32);
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_entries_fts_matches_table_scan() -> Result<()> {
+        let db = CoverageDB::new(":memory:".into())?;
+        let entries: Vec<CoverageEntry> = (0..1_000)
+            .map(|i| entry(i, format!("Generic item description number {}", i)))
+            .chain(std::iter::once(entry(1_000, "The Unique Nightingale Blade".to_string())))
+            .collect();
+        db.batch_upsert_entries(entries)?;
+
+        let scanned: Vec<String> = {
+            let conn = db.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT form_id FROM coverage_entries WHERE LOWER(text) LIKE '%nightingale%'",
+            )?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<Result<_>>()?
+        };
+        let found: Vec<String> = db
+            .search_entries(None, Some("nightingale"), 10)?
+            .into_iter()
+            .map(|e| e.form_id)
+            .collect();
+
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(found, scanned);
+
+        Ok(())
+    }
 }
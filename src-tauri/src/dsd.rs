@@ -1,3 +1,4 @@
+use crate::settings::Settings;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
@@ -13,16 +14,190 @@ pub struct DsdEntry {
     pub string: String,
 }
 
-/// 按约定构建 DSD JSON 文件路径
+/// 未写入 DSD 文件的条目及原因
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DsdSkippedEntry {
+    pub form_id: String,
+    pub entry_type: String,
+    pub reason: String,
+}
+
+/// DSD 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DsdExportResult {
+    /// 写入的 DSD JSON 文件路径
+    pub path: String,
+    /// 新增的条目数（覆盖模式下为全部条目）
+    pub added: usize,
+    /// 合并模式下更新的已有条目数
+    pub updated: usize,
+    /// 文件中的总条目数
+    pub total: usize,
+    /// 本次写入的条目数（新增 + 更新）
+    pub written: usize,
+    /// 因校验失败被跳过的条目数
+    pub skipped: usize,
+    /// 被跳过的条目及原因
+    pub skipped_reasons: Vec<DsdSkippedEntry>,
+    /// 同时写入的元数据文件路径（未开启时为 None）
+    #[serde(default)]
+    pub metadata_path: Option<String>,
+    /// 参与导出的记录数（记录类型过滤后）
+    #[serde(default)]
+    pub included: usize,
+    /// 被记录类型过滤排除的记录数
+    #[serde(default)]
+    pub filtered_out: usize,
+}
+
+/// DSD 导出元数据（写入 DSD JSON 旁的 `<插件名>.meta`，用于追溯来源）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DsdMetadata {
+    pub generator: String,
+    pub version: String,
+    pub source_plugin: String,
+    /// 导出时间（RFC 3339）
+    pub exported_at: String,
+    pub entry_count: usize,
+}
+
+/// 元数据文件后缀
 ///
-/// - 当 `base_dir_override` 存在时：`<base_dir_override>/SKSE/Plugins/DynamicStringDistributor/<plugin_filename>/<plugin_stem>.json`
-/// - 否则：使用插件所在目录作为 base dir
-pub fn build_dsd_json_path(
-    plugin_path: &Path,
-    base_dir_override: Option<&Path>,
-) -> Result<PathBuf, String> {
-    let base_dir = if let Some(dir) = base_dir_override {
-        dir.to_path_buf()
+/// DSD 会加载插件目录下的所有 `.json` 文件，因此元数据不能使用 `.json` 扩展名
+const DSD_METADATA_SUFFIX: &str = ".meta";
+
+/// DSD 导出预览（不写入文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DsdPreview {
+    /// 将要写入的 DSD JSON 文件路径
+    pub path: String,
+    /// 将要写入的 JSON 内容
+    pub json: String,
+    /// 目标文件的现有内容（不存在时为 None，用于前端对比）
+    pub existing_json: Option<String>,
+    pub added: usize,
+    pub updated: usize,
+    pub total: usize,
+    pub written: usize,
+    pub skipped: usize,
+    pub skipped_reasons: Vec<DsdSkippedEntry>,
+}
+
+/// 已存在的 DSD 导出文件状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DsdStatus {
+    /// DSD JSON 文件路径（按当前设置推导，无论是否存在）
+    pub path: String,
+    pub exists: bool,
+    /// 文件大小（字节），不存在时为 None
+    pub size: Option<u64>,
+    /// 文件中的条目数，不存在或无法解析时为 None
+    pub entry_count: Option<usize>,
+}
+
+/// 默认的 DSD 路径模板（DynamicStringDistributor 读取的目录约定）
+pub const DEFAULT_DSD_PATH_TEMPLATE: &str =
+    "{base}/SKSE/Plugins/DynamicStringDistributor/{plugin}/{stem}.json";
+
+/// 路径模板支持的占位符：导出基础目录、插件文件名（含扩展名）、插件名（不含扩展名）
+const DSD_TEMPLATE_PLACEHOLDERS: &[&str] = &["{base}", "{plugin}", "{stem}"];
+
+/// DSD 文件布局（导出基础目录与路径模板）
+#[derive(Debug, Clone, Default)]
+pub struct DsdLayout {
+    /// 导出基础目录（None 时使用插件所在目录）
+    pub base_dir: Option<PathBuf>,
+    /// 路径模板（None 时使用 [`DEFAULT_DSD_PATH_TEMPLATE`]）
+    pub template: Option<String>,
+}
+
+impl DsdLayout {
+    /// 按设置中的 DSD 导出目录与路径模板构建
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            base_dir: settings.dsd_output_dir.as_ref().map(PathBuf::from),
+            template: settings.dsd_path_template.clone(),
+        }
+    }
+
+    pub fn template(&self) -> &str {
+        self.template.as_deref().unwrap_or(DEFAULT_DSD_PATH_TEMPLATE)
+    }
+}
+
+/// 校验 DSD 路径模板
+///
+/// 模板须以 `{base}` 开头或为绝对路径，以 `.json` 结尾，包含 `{plugin}` 或 `{stem}`
+/// （避免不同插件写入同一文件），且只使用已知占位符
+pub fn validate_dsd_path_template(template: &str) -> Result<(), String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("DSD 路径模板不能为空".to_string());
+    }
+    if !template.starts_with("{base}") && !Path::new(template).is_absolute() {
+        return Err("DSD 路径模板须以 {base} 开头或为绝对路径".to_string());
+    }
+    let lower = template.to_lowercase();
+    if !lower.ends_with(".json") {
+        return Err("DSD 路径模板须以 .json 结尾".to_string());
+    }
+    if !template.contains("{plugin}") && !template.contains("{stem}") {
+        return Err("DSD 路径模板须包含 {plugin} 或 {stem}".to_string());
+    }
+    if template.split(['/', '\\']).any(|segment| segment == "..") {
+        return Err("DSD 路径模板不能包含 ..".to_string());
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "DSD 路径模板的占位符缺少 }".to_string())?;
+        let placeholder = &rest[start..start + end + 1];
+        if !DSD_TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!("DSD 路径模板包含未知占位符: {}", placeholder));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// 按模板展开 DSD JSON 文件路径（`base_dir` 替换 `{base}`）
+fn render_dsd_path(template: &str, base_dir: &Path, plugin_path: &Path) -> Result<PathBuf, String> {
+    let plugin_name = plugin_path
+        .file_name()
+        .ok_or_else(|| "无法获取插件文件名".to_string())?
+        .to_string_lossy();
+    let stem = plugin_path
+        .file_stem()
+        .ok_or_else(|| "无法获取插件名称".to_string())?
+        .to_string_lossy();
+
+    let rendered = template.replace("{plugin}", &plugin_name).replace("{stem}", &stem);
+
+    // 相对于 {base} 的部分按 `/` 或 `\` 分段拼接，保证跨平台分隔符一致
+    match rendered.strip_prefix("{base}") {
+        Some(rest) => Ok(rest
+            .split(['/', '\\'])
+            .filter(|segment| !segment.is_empty())
+            .fold(base_dir.to_path_buf(), |path, segment| path.join(segment))),
+        None => Ok(PathBuf::from(rendered)),
+    }
+}
+
+/// 模板的目录部分是否按插件区分（此时该目录下的所有 JSON 都属于该插件）
+fn template_has_plugin_dir(template: &str) -> bool {
+    let dir = template.rsplit_once(['/', '\\']).map(|(dir, _)| dir).unwrap_or("");
+    dir.contains("{plugin}") || dir.contains("{stem}")
+}
+
+/// 按布局构建 DSD JSON 文件路径
+///
+/// 默认模板：`<base>/SKSE/Plugins/DynamicStringDistributor/<plugin_filename>/<plugin_stem>.json`，
+/// `base` 为设置中的导出目录，未设置时为插件所在目录
+pub fn build_dsd_json_path(plugin_path: &Path, layout: &DsdLayout) -> Result<PathBuf, String> {
+    let base_dir = if let Some(dir) = &layout.base_dir {
+        dir.clone()
     } else {
         plugin_path
             .parent()
@@ -30,51 +205,73 @@ pub fn build_dsd_json_path(
             .to_path_buf()
     };
 
-    let plugin_name_with_ext = plugin_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "无法获取插件文件名".to_string())?;
-    let plugin_name_without_ext = plugin_path
-        .file_stem()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "无法获取插件名称".to_string())?;
+    render_dsd_path(layout.template(), &base_dir, plugin_path)
+}
+
+/// 检查插件对应的 DSD 导出文件是否已存在
+pub fn dsd_status(plugin_path: &Path, layout: &DsdLayout) -> Result<DsdStatus, String> {
+    let path = build_dsd_json_path(plugin_path, layout)?;
 
-    Ok(base_dir
-        .join("SKSE")
-        .join("Plugins")
-        .join("DynamicStringDistributor")
-        .join(plugin_name_with_ext)
-        .join(format!("{}.json", plugin_name_without_ext)))
+    let Ok(metadata) = fs::metadata(&path) else {
+        return Ok(DsdStatus {
+            path: path.to_string_lossy().to_string(),
+            exists: false,
+            size: None,
+            entry_count: None,
+        });
+    };
+
+    let entry_count = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<DsdEntry>>(&content).ok())
+        .map(|entries| entries.len());
+
+    Ok(DsdStatus {
+        path: path.to_string_lossy().to_string(),
+        exists: metadata.is_file(),
+        size: Some(metadata.len()),
+        entry_count,
+    })
 }
 
 /// 载入 DSD JSON 覆盖内容（若存在）。MO2 环境下直接读取插件所在目录旁的 SKSE/Plugins/DynamicStringDistributor/<插件名>/ 下的所有 JSON。
-pub fn load_dsd_overrides(plugin_path: &Path) -> Result<Option<HashMap<String, String>>, String> {
+///
+/// `template` 为 DSD 路径模板（`{base}` 为插件所在目录）：目录部分按插件区分时读取该目录下的所有 JSON，
+/// 否则（如平铺的 `{base}/{stem}.json`）只读取模板指向的文件
+pub fn load_dsd_overrides(
+    plugin_path: &Path,
+    template: &str,
+) -> Result<Option<HashMap<String, String>>, String> {
     let plugin_dir = plugin_path
         .parent()
         .ok_or_else(|| "无法获取插件所在目录".to_string())?;
-    let plugin_name_with_ext = plugin_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "无法获取插件文件名".to_string())?;
+    let dsd_file = render_dsd_path(template, plugin_dir, plugin_path)?;
 
-    let dsd_dir = plugin_dir
-        .join("SKSE")
-        .join("Plugins")
-        .join("DynamicStringDistributor")
-        .join(plugin_name_with_ext);
-
-    if !dsd_dir.exists() || !dsd_dir.is_dir() {
+    let files: Vec<PathBuf> = if template_has_plugin_dir(template) {
+        let dsd_dir = dsd_file
+            .parent()
+            .ok_or_else(|| "无法确定 DSD 目录".to_string())?;
+        if !dsd_dir.exists() || !dsd_dir.is_dir() {
+            return Ok(None);
+        }
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dsd_dir)
+            .map_err(|e| format!("读取 DSD 目录失败 ({}): {}", dsd_dir.display(), e))?
+        {
+            let entry = entry.map_err(|e| format!("遍历 DSD 目录失败: {}", e))?;
+            files.push(entry.path());
+        }
+        files
+    } else if dsd_file.is_file() {
+        vec![dsd_file]
+    } else {
         return Ok(None);
-    }
+    };
 
     let mut overrides = HashMap::new();
     let mut processed_files = 0;
 
-    for entry in fs::read_dir(&dsd_dir)
-        .map_err(|e| format!("读取 DSD 目录失败 ({}): {}", dsd_dir.display(), e))?
-    {
-        let entry = entry.map_err(|e| format!("遍历 DSD 目录失败: {}", e))?;
-        let path = entry.path();
+    for path in files {
         if !path.is_file() {
             continue;
         }
@@ -120,13 +317,20 @@ pub fn make_record_key(form_id: &str, record_type: &str, subrecord_type: &str) -
     format!("{}|{}|{}", form_id, record_type, subrecord_type)
 }
 
-/// 将 DSD 条目导出为 JSON 文件，并返回最终文件路径
+/// 将 DSD 条目导出为 JSON 文件
+///
+/// `merge` 为 true 且文件已存在时，按 form_id + type 合并：已有条目保持原顺序，
+/// 同组内按出现序号替换为新值，多出的新条目追加到末尾；`write_metadata` 为 true 时同时写入元数据文件
 pub fn export_dsd_entries(
     plugin_path: &Path,
-    entries: &[DsdEntry],
-    base_dir_override: Option<&Path>,
-) -> Result<PathBuf, String> {
-    let output_file = build_dsd_json_path(plugin_path, base_dir_override)?;
+    entries: Vec<DsdEntry>,
+    layout: &DsdLayout,
+    merge: bool,
+    write_metadata: bool,
+) -> Result<DsdExportResult, String> {
+    let included = entries.len();
+    let preview = preview_dsd_entries(plugin_path, entries, layout, merge)?;
+    let output_file = PathBuf::from(&preview.path);
     let parent_dir = output_file
         .parent()
         .ok_or_else(|| "无法确定 DSD 输出目录".to_string())?;
@@ -134,13 +338,180 @@ pub fn export_dsd_entries(
     fs::create_dir_all(parent_dir)
         .map_err(|e| format!("创建目录失败 ({}): {}", parent_dir.display(), e))?;
 
-    let json = serde_json::to_string_pretty(entries)
+    fs::write(&output_file, preview.json)
+        .map_err(|e| format!("写入 DSD 文件失败 ({}): {}", output_file.display(), e))?;
+
+    let metadata_path = if write_metadata {
+        let path = write_dsd_metadata(&output_file, plugin_path, preview.total)?;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok(DsdExportResult {
+        path: preview.path,
+        added: preview.added,
+        updated: preview.updated,
+        total: preview.total,
+        written: preview.written,
+        skipped: preview.skipped,
+        skipped_reasons: preview.skipped_reasons,
+        metadata_path,
+        included,
+        filtered_out: 0,
+    })
+}
+
+/// 在 DSD JSON 旁写入元数据文件（`<插件名>.meta`），返回其路径
+fn write_dsd_metadata(
+    json_path: &Path,
+    plugin_path: &Path,
+    entry_count: usize,
+) -> Result<PathBuf, String> {
+    let stem = json_path
+        .file_stem()
+        .ok_or_else(|| "无法获取 DSD 文件名".to_string())?
+        .to_string_lossy();
+    let meta_path = json_path.with_file_name(format!("{}{}", stem, DSD_METADATA_SUFFIX));
+
+    let metadata = DsdMetadata {
+        generator: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        source_plugin: plugin_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+        entry_count,
+    };
+    let json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("序列化 DSD 元数据失败: {}", e))?;
+
+    fs::write(&meta_path, json)
+        .map_err(|e| format!("写入 DSD 元数据失败 ({}): {}", meta_path.display(), e))?;
+    Ok(meta_path)
+}
+
+/// 生成 DSD 导出的 JSON 内容与目标路径，但不写入任何文件（合并规则同 [`export_dsd_entries`]）
+pub fn preview_dsd_entries(
+    plugin_path: &Path,
+    entries: Vec<DsdEntry>,
+    layout: &DsdLayout,
+    merge: bool,
+) -> Result<DsdPreview, String> {
+    let output_file = build_dsd_json_path(plugin_path, layout)?;
+    let (entries, skipped_reasons) = validate_dsd_entries(entries);
+
+    let existing_json = if output_file.is_file() {
+        Some(
+            fs::read_to_string(&output_file)
+                .map_err(|e| format!("读取 DSD 文件失败 ({}): {}", output_file.display(), e))?,
+        )
+    } else {
+        None
+    };
+
+    let (entries, added, updated) = match (&existing_json, merge) {
+        (Some(content), true) => {
+            let existing: Vec<DsdEntry> = serde_json::from_str(content)
+                .map_err(|e| format!("解析 DSD 文件失败 ({}): {}", output_file.display(), e))?;
+            merge_dsd_entries(existing, entries)
+        }
+        _ => {
+            let added = entries.len();
+            (entries, added, 0)
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&entries)
         .map_err(|e| format!("序列化 JSON 失败: {}", e))?;
 
-    fs::write(&output_file, json)
-        .map_err(|e| format!("写入 DSD 文件失败 ({}): {}", output_file.display(), e))?;
+    Ok(DsdPreview {
+        path: output_file.to_string_lossy().to_string(),
+        json,
+        existing_json,
+        added,
+        updated,
+        total: entries.len(),
+        written: added + updated,
+        skipped: skipped_reasons.len(),
+        skipped_reasons,
+    })
+}
+
+/// 过滤无法被 DSD 识别的条目（form_id 为空、类型字段无法解析或文本为空）
+fn validate_dsd_entries(entries: Vec<DsdEntry>) -> (Vec<DsdEntry>, Vec<DsdSkippedEntry>) {
+    let mut valid = Vec::with_capacity(entries.len());
+    let mut skipped = Vec::new();
+
+    for entry in entries {
+        let reason = if entry.form_id.trim().is_empty() {
+            Some("form_id 为空")
+        } else if parse_entry_type(&entry.entry_type).is_none() {
+            Some("类型字段无效（应为 \"记录类型 子记录类型\"）")
+        } else if entry.string.is_empty() {
+            Some("文本为空")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => skipped.push(DsdSkippedEntry {
+                form_id: entry.form_id,
+                entry_type: entry.entry_type,
+                reason: reason.to_string(),
+            }),
+            None => valid.push(entry),
+        }
+    }
 
-    Ok(output_file)
+    (valid, skipped)
+}
+
+/// 按 form_id + type 合并 DSD 条目（新值优先），返回合并结果与新增/更新数量
+///
+/// 同一记录可能有多条相同类型的字符串（如 INFO 的多句 NAM1），
+/// 因此按条目在 (form_id, type) 组内的出现序号对应：第 n 条新值替换第 n 条旧值
+fn merge_dsd_entries(
+    existing: Vec<DsdEntry>,
+    incoming: Vec<DsdEntry>,
+) -> (Vec<DsdEntry>, usize, usize) {
+    let keyed = |entries: &[DsdEntry]| {
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        entries
+            .iter()
+            .map(|entry| {
+                let entry_type = entry.entry_type.split_whitespace().collect::<Vec<_>>().join(" ");
+                let group = format!("{}|{}", entry.form_id, entry_type);
+                let ordinal = occurrences.entry(group.clone()).or_insert(0);
+                *ordinal += 1;
+                format!("{}#{}", group, ordinal)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut positions: HashMap<String, usize> = keyed(&existing)
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| (key, i))
+        .collect();
+    let incoming_keys = keyed(&incoming);
+    let mut merged = existing;
+    let mut added = 0;
+    let mut updated = 0;
+
+    for (entry, key) in incoming.into_iter().zip(incoming_keys) {
+        if let Some(&i) = positions.get(&key) {
+            merged[i] = entry;
+            updated += 1;
+        } else {
+            positions.insert(key, merged.len());
+            merged.push(entry);
+            added += 1;
+        }
+    }
+
+    (merged, added, updated)
 }
 
 fn parse_entry_type(entry_type: &str) -> Option<(String, String)> {
@@ -149,3 +520,115 @@ fn parse_entry_type(entry_type: &str) -> Option<(String, String)> {
     let subrecord_type = parts.next()?;
     Some((record_type.to_string(), subrecord_type.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    fn entry(form_id: &str, entry_type: &str, string: &str) -> DsdEntry {
+        DsdEntry {
+            form_id: form_id.to_string(),
+            entry_type: entry_type.to_string(),
+            string: string.to_string(),
+        }
+    }
+
+    #[test]
+    fn metadata_file_is_ignored_when_loading_overrides() {
+        let tmp = TempDir::new("dsd_metadata");
+        let root = tmp.path();
+        let plugin = root.join("Test.esp");
+        let entries = vec![entry("00000800|Test.esp", "WEAP FULL", "铁剑")];
+
+        let result =
+            export_dsd_entries(&plugin, entries, &DsdLayout::default(), false, true).unwrap();
+        let meta_path = PathBuf::from(result.metadata_path.unwrap());
+        let metadata: DsdMetadata =
+            serde_json::from_str(&fs::read_to_string(&meta_path).unwrap()).unwrap();
+        assert_eq!(metadata.source_plugin, "Test.esp");
+        assert_eq!(meta_path.file_name().unwrap(), "Test.meta");
+
+        let overrides = load_dsd_overrides(&plugin, DEFAULT_DSD_PATH_TEMPLATE).unwrap().unwrap();
+        assert_eq!(overrides.len(), 1);
+    }
+
+    #[test]
+    fn dsd_status_reports_existing_export() {
+        let tmp = TempDir::new("dsd_status");
+        let plugin = tmp.join("Test.esp");
+        let layout = DsdLayout::default();
+
+        let missing = dsd_status(&plugin, &layout).unwrap();
+        assert!(!missing.exists);
+        assert_eq!((missing.size, missing.entry_count), (None, None));
+
+        let entries = vec![
+            entry("00000800|Test.esp", "WEAP FULL", "铁剑"),
+            entry("00000801|Test.esp", "WEAP DESC", "一把剑"),
+        ];
+        let result = export_dsd_entries(&plugin, entries, &layout, false, false).unwrap();
+        let status = dsd_status(&plugin, &layout).unwrap();
+        assert!(status.exists);
+        assert_eq!(status.path, missing.path);
+        assert_eq!(status.path, result.path);
+        assert_eq!(status.size, Some(fs::metadata(&status.path).unwrap().len()));
+        assert_eq!(status.entry_count, Some(2));
+    }
+
+    #[test]
+    fn flat_path_template_round_trips() {
+        let tmp = TempDir::new("dsd_template");
+        let root = tmp.path();
+        let plugin = root.join("Test.esp");
+        let layout = DsdLayout {
+            base_dir: None,
+            template: Some("{base}/DSD/{stem}_chs.json".to_string()),
+        };
+        let entries = vec![entry("00000800|Test.esp", "WEAP FULL", "铁剑")];
+
+        let result = export_dsd_entries(&plugin, entries, &layout, false, false).unwrap();
+        fs::write(root.join("DSD").join("Other_chs.json"), "not json").unwrap();
+        let overrides = load_dsd_overrides(&plugin, layout.template()).unwrap().unwrap();
+
+        assert_eq!(PathBuf::from(result.path), root.join("DSD").join("Test_chs.json"));
+        assert_eq!(overrides.len(), 1);
+        assert!(validate_dsd_path_template(DEFAULT_DSD_PATH_TEMPLATE).is_ok());
+        assert!(validate_dsd_path_template("{base}/all.json").is_err());
+        assert!(validate_dsd_path_template("{base}/{name}.json").is_err());
+    }
+
+    #[test]
+    fn merge_keeps_repeated_strings_of_same_type() {
+        let existing = vec![
+            entry("00000900|Test.esp", "INFO NAM1", "第一句"),
+            entry("00000900|Test.esp", "INFO NAM1", "第二句"),
+            entry("00000901|Test.esp", "WEAP FULL", "铁剑"),
+        ];
+        let incoming = vec![
+            entry("00000900|Test.esp", "INFO NAM1", "第一句（改）"),
+            entry("00000900|Test.esp", "INFO  NAM1", "第二句（改）"),
+            entry("00000900|Test.esp", "INFO NAM1", "第三句"),
+        ];
+
+        let (merged, added, updated) = merge_dsd_entries(existing, incoming);
+        let strings: Vec<&str> = merged.iter().map(|e| e.string.as_str()).collect();
+        assert_eq!(strings, vec!["第一句（改）", "第二句（改）", "铁剑", "第三句"]);
+        assert_eq!((added, updated), (1, 2));
+    }
+
+    #[test]
+    fn preview_reports_skipped_entries() {
+        let plugin = std::env::temp_dir().join("dsd_preview_skipped").join("Test.esp");
+        let entries = vec![
+            entry("00000800|Test.esp", "WEAP FULL", "铁剑"),
+            entry("00000801|Test.esp", "WEAP", "钢剑"),
+            entry("00000802|Test.esp", "WEAP FULL", ""),
+        ];
+
+        let preview = preview_dsd_entries(&plugin, entries, &DsdLayout::default(), false).unwrap();
+        assert_eq!((preview.written, preview.skipped, preview.total), (1, 2, 1));
+        assert_eq!(preview.skipped_reasons[0].form_id, "00000801|Test.esp");
+        assert_eq!(preview.skipped_reasons[1].reason, "文本为空");
+    }
+}
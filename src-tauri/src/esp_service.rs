@@ -1,10 +1,14 @@
 use crate::bsa_logger::log_bsa_presence;
 use crate::constants::BASE_PLUGINS;
-use crate::translation_db::Translation;
-use esp_extractor::LoadedPlugin;
+use crate::translation_db::{FormIdentifier, Translation};
+use crate::utils::encoding_check::looks_mis_encoded;
+use crate::utils::pairing::{pair_english_chinese, RecordKey};
+use crate::utils::strings_table::StringsTable;
+use esp_extractor::{ExtractedString, LoadedPlugin, Plugin};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// 提取统计信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +25,51 @@ pub struct ExtractionStats {
     pub skipped_files: Vec<String>,
     /// 错误信息列表
     pub errors: Vec<String>,
+    /// 中文版加载失败、仅提取英文的文件列表
+    #[serde(default)]
+    pub english_only_files: Vec<String>,
+    /// 疑似编码错误（乱码）的记录总数
+    #[serde(default)]
+    pub suspicious_encoding_count: usize,
+    /// 含疑似乱码记录的文件及数量（如 "Skyrim.esm: 12 条"）
+    #[serde(default)]
+    pub encoding_warnings: Vec<String>,
+    /// 用户中途取消（已完成插件的记录仍会保存）
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// 基础字典提取进度（每个插件开始处理时报告一次）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractionProgress {
+    pub current_plugin: String,
+    /// 从 1 开始的插件序号
+    pub current_progress: usize,
+    pub total: usize,
+}
+
+/// 单个插件提取失败的原因
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum PluginExtractionError {
+    /// 插件文件不存在
+    FileMissing,
+    /// 本地化插件缺少 STRINGS 文件（BSA 与松散文件均未找到）
+    BsaMissing,
+    /// 插件解析失败
+    ParseError(String),
+}
+
+impl std::fmt::Display for PluginExtractionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginExtractionError::FileMissing => write!(f, "插件文件不存在"),
+            PluginExtractionError::BsaMissing => {
+                write!(f, "本地化插件缺少 STRINGS 文件（未找到 BSA 或松散文件）")
+            }
+            PluginExtractionError::ParseError(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
 impl ExtractionStats {
@@ -32,10 +81,75 @@ impl ExtractionStats {
             total_strings: 0,
             skipped_files: Vec::new(),
             errors: Vec::new(),
+            english_only_files: Vec::new(),
+            suspicious_encoding_count: 0,
+            encoding_warnings: Vec::new(),
+            cancelled: false,
         }
     }
 }
 
+/// 单个插件的双语提取结果
+#[derive(Debug, Clone)]
+pub struct PluginStrings {
+    pub translations: Vec<Translation>,
+    /// 中文版加载失败，translated_text 全部回退为英文原文
+    pub english_only: bool,
+    /// 译文疑似编码错误（乱码）的记录数
+    pub suspicious_encoding: usize,
+}
+
+/// 流式提取的结果摘要
+#[derive(Debug, Clone, Copy)]
+pub struct PluginStringsSummary {
+    pub record_count: usize,
+    /// 中文版加载失败，translated_text 全部回退为英文原文
+    pub english_only: bool,
+    /// 译文疑似编码错误（乱码）的记录数
+    pub suspicious_encoding: usize,
+}
+
+/// 记录类型过滤条件
+///
+/// 每项为记录类型（如 `INFO`）或 "记录类型 子记录类型"（如 `INFO NAM1`），不区分大小写
+pub(crate) struct RecordTypeFilter {
+    entries: Vec<(String, Option<String>)>,
+}
+
+impl RecordTypeFilter {
+    /// 解析过滤条件，全部为空白时返回 None（不过滤）
+    pub(crate) fn parse(filter: Option<&[String]>) -> Option<Self> {
+        let entries: Vec<_> = filter?
+            .iter()
+            .filter_map(|item| {
+                let mut parts = item.split_whitespace();
+                let record_type = parts.next()?.to_uppercase();
+                Some((record_type, parts.next().map(|s| s.to_uppercase())))
+            })
+            .collect();
+        (!entries.is_empty()).then_some(Self { entries })
+    }
+
+    /// 过滤条件（记录类型与可选的子记录类型，均为大写）
+    pub(crate) fn entries(&self) -> &[(String, Option<String>)] {
+        &self.entries
+    }
+
+    fn matches(&self, s: &ExtractedString) -> bool {
+        self.matches_type(&s.record_type, &s.subrecord_type)
+    }
+
+    /// 按记录类型与子记录类型判断是否匹配
+    pub(crate) fn matches_type(&self, record_type: &str, subrecord_type: &str) -> bool {
+        self.entries.iter().any(|(filter_type, filter_sub)| {
+            record_type.eq_ignore_ascii_case(filter_type)
+                && filter_sub
+                    .as_ref()
+                    .is_none_or(|sub| subrecord_type.eq_ignore_ascii_case(sub))
+        })
+    }
+}
+
 /// 获取基础插件列表
 pub fn get_base_plugins() -> Vec<String> {
     BASE_PLUGINS.iter().map(|s| s.to_string()).collect()
@@ -43,45 +157,103 @@ pub fn get_base_plugins() -> Vec<String> {
 
 /// 从单个插件文件提取字符串（双语版本：英文 + 中文对比）
 ///
+/// 一次性返回全部记录，内部委托给 [`extract_plugin_strings_with`]。
+///
+/// # 参数
+/// * `plugin_path` - 插件文件的完整路径
+/// * `record_type_filter` - 仅保留匹配的记录类型（见 [`extract_plugin_strings_with`]）
+///
+/// # 返回
+/// * `Ok(PluginStrings)` - 成功提取的翻译记录列表
+/// * `Err(PluginExtractionError)` - 失败原因
+pub fn extract_plugin_strings(
+    plugin_path: &Path,
+    record_type_filter: Option<&[String]>,
+) -> Result<PluginStrings, PluginExtractionError> {
+    let mut translations = Vec::new();
+    let summary =
+        extract_plugin_strings_with(plugin_path, record_type_filter, |t| translations.push(t))?;
+
+    Ok(PluginStrings {
+        translations,
+        english_only: summary.english_only,
+        suspicious_encoding: summary.suspicious_encoding,
+    })
+}
+
+/// 流式提取单个插件的字符串，每条记录通过回调交出，避免一次性构建完整列表
+///
 /// # 工作原理
 /// 1. 加载英文版（Strings/XXX_English.STRINGS）提取所有字符串作为 original_text
 /// 2. 加载中文版（Strings/XXX_Chinese.STRINGS）提取所有字符串作为 translated_text
 /// 3. 根据 (form_id, record_type, subrecord_type) 进行匹配
 /// 4. 如果中文版没有对应记录，则 translated_text 使用英文（未本地化的情况）
+/// 5. 如果中文版整体加载失败，仅返回英文数据并标记 `english_only`
 ///
 /// # 参数
 /// * `plugin_path` - 插件文件的完整路径
+/// * `record_type_filter` - 仅保留匹配的记录，每项为记录类型（如 `INFO`）或
+///   "记录类型 子记录类型"（如 `INFO NAM1`），不区分大小写；None 或空列表表示不过滤
+/// * `on_record` - 每条翻译记录的回调
 ///
 /// # 返回
-/// * `Ok(Vec<Translation>)` - 成功提取的翻译记录列表
-/// * `Err(String)` - 错误信息
-pub fn extract_plugin_strings(plugin_path: &Path) -> Result<Vec<Translation>, String> {
+/// * `Ok(PluginStringsSummary)` - 记录数与是否仅英文
+/// * `Err(PluginExtractionError)` - 失败原因
+pub fn extract_plugin_strings_with<F>(
+    plugin_path: &Path,
+    record_type_filter: Option<&[String]>,
+    mut on_record: F,
+) -> Result<PluginStringsSummary, PluginExtractionError>
+where
+    F: FnMut(Translation),
+{
+    if !plugin_path.exists() {
+        return Err(PluginExtractionError::FileMissing);
+    }
+
     // 1. 加载英文版
     log_bsa_presence(plugin_path, Some("english"));
     let loaded_en = LoadedPlugin::load_auto(plugin_path.to_path_buf(), Some("english"))
-        .map_err(|e| format!("加载英文版插件失败: {}", e))?;
-    let english_strings = loaded_en.extract_strings();
+        .map_err(|e| PluginExtractionError::ParseError(format!("加载英文版插件失败: {}", e)))?;
+
+    // 本地化插件在 STRINGS 缺失时会降级为普通模式，文本只剩 StringID，视为失败
+    if loaded_en.plugin().is_localized() && !loaded_en.is_localized() {
+        return Err(PluginExtractionError::BsaMissing);
+    }
+    let filter = RecordTypeFilter::parse(record_type_filter);
+    let mut english_strings = loaded_en.extract_strings();
+    if let Some(filter) = &filter {
+        let total = english_strings.len();
+        english_strings.retain(|s| filter.matches(s));
+        println!("  🔎 按记录类型过滤：保留 {}/{} 条", english_strings.len(), total);
+    }
 
     println!("  📖 英文版提取 {} 条记录", english_strings.len());
 
-    // 2. 加载中文版
+    // 2. 加载中文版（失败时回退为仅英文，不中断整个插件）
     log_bsa_presence(plugin_path, Some("chinese"));
-    let loaded_zh = LoadedPlugin::load_auto(plugin_path.to_path_buf(), Some("chinese"))
-        .map_err(|e| format!("加载中文版插件失败: {}", e))?;
-    let chinese_strings = loaded_zh.extract_strings();
+    let chinese_strings = match LoadedPlugin::load_auto(plugin_path.to_path_buf(), Some("chinese")) {
+        Ok(loaded_zh) if loaded_zh.plugin().is_localized() && !loaded_zh.is_localized() => {
+            eprintln!("  ⚠️ 中文版 STRINGS 文件缺失，仅使用英文数据");
+            None
+        }
+        Ok(loaded_zh) => {
+            let mut strings = loaded_zh.extract_strings();
+            if let Some(filter) = &filter {
+                strings.retain(|s| filter.matches(s));
+            }
+            Some(strings)
+        }
+        Err(e) => {
+            eprintln!("  ⚠️ 加载中文版插件失败，仅使用英文数据: {}", e);
+            None
+        }
+    };
+    let english_only = chinese_strings.is_none();
+    let chinese_strings = chinese_strings.unwrap_or_default();
 
     println!("  📖 中文版提取 {} 条记录", chinese_strings.len());
 
-    // 3. 建立中文映射表 (form_id|record_type|subrecord_type -> chinese_text)
-    let mut chinese_map: HashMap<String, String> = HashMap::new();
-    for s in chinese_strings {
-        let key = format!(
-            "{}|{}|{}|{}",
-            s.form_id, s.record_type, s.subrecord_type, s.index
-        );
-        chinese_map.insert(key, s.text);
-    }
-
     // 获取当前时间戳
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -91,69 +263,139 @@ pub fn extract_plugin_strings(plugin_path: &Path) -> Result<Vec<Translation>, St
     // 提取插件名称
     let plugin_name = plugin_path
         .file_name()
-        .and_then(|n| n.to_str())
-        .map(|s| s.to_string());
+        .map(|n| n.to_string_lossy().to_string());
 
-    // 4. 遍历英文记录，查找对应的中文翻译
-    let translations: Vec<Translation> = english_strings
-        .into_iter()
-        .map(|s| {
-            let key = format!(
-                "{}|{}|{}|{}",
-                s.form_id, s.record_type, s.subrecord_type, s.index
-            );
-
-            // 查找对应的中文翻译
-            let translated_text = chinese_map
-                .get(&key)
-                .cloned()
-                .unwrap_or_else(|| s.text.clone());
-
-            Translation {
-                form_id: s.form_id,
-                record_type: s.record_type,
-                subrecord_type: s.subrecord_type,
-                index: s.index as u32,
-                editor_id: s.editor_id,
-                original_text: s.text, // 英文原文
-                translated_text,       // 中文翻译或英文回退
-                plugin_name: plugin_name.clone(),
-                created_at: now,
-                updated_at: now,
+    // 3. 按 key 对齐中英文记录，中文版缺失的记录使用英文回退
+    let mut record_count = 0;
+    let mut matched_count = 0;
+    let mut suspicious_encoding = 0;
+    for pair in pair_english_chinese(english_strings, chinese_strings) {
+        if pair.is_translated() {
+            matched_count += 1;
+            // 只检查真正来自中文版的译文（英文回退的记录与原文相同）
+            if pair.chinese.as_deref().is_some_and(looks_mis_encoded) {
+                suspicious_encoding += 1;
             }
-        })
-        .collect();
+        }
+        record_count += 1;
+
+        let s = pair.english;
+        let translated_text = pair.chinese.unwrap_or_else(|| s.text.clone());
+
+        on_record(Translation {
+            strings_table: StringsTable::classify(&s.record_type, &s.subrecord_type),
+            form_id: s.form_id,
+            record_type: s.record_type,
+            subrecord_type: s.subrecord_type,
+            index: s.index as u32,
+            editor_id: s.editor_id,
+            original_text: s.text, // 英文原文
+            translated_text,       // 中文翻译或英文回退
+            plugin_name: plugin_name.clone(),
+            created_at: now,
+            updated_at: now,
+            note: None,
+        });
+    }
 
     // 统计匹配情况
-    let matched_count = translations
-        .iter()
-        .filter(|t| t.original_text != t.translated_text)
-        .count();
-    let unmatched_count = translations.len() - matched_count;
+    let unmatched_count = record_count - matched_count;
 
     println!(
         "  ✅ 匹配成功 {} 条，未匹配 {} 条",
         matched_count, unmatched_count
     );
+    if suspicious_encoding > 0 {
+        eprintln!(
+            "  ⚠️ {} 条中文译文疑似编码错误（乱码），请检查 STRINGS 文件",
+            suspicious_encoding
+        );
+    }
 
-    Ok(translations)
+    Ok(PluginStringsSummary {
+        record_count,
+        english_only,
+        suspicious_encoding,
+    })
+}
+
+/// 仅加载插件英文版，返回所有字符串记录的 key（已去重，不创建 Session）
+pub fn extract_english_keys(plugin_path: &Path) -> Result<Vec<FormIdentifier>, PluginExtractionError> {
+    if !plugin_path.exists() {
+        return Err(PluginExtractionError::FileMissing);
+    }
+
+    log_bsa_presence(plugin_path, Some("english"));
+    let loaded = LoadedPlugin::load_auto(plugin_path.to_path_buf(), Some("english"))
+        .map_err(|e| PluginExtractionError::ParseError(format!("加载英文版插件失败: {}", e)))?;
+
+    if loaded.plugin().is_localized() && !loaded.is_localized() {
+        return Err(PluginExtractionError::BsaMissing);
+    }
+
+    let mut seen = HashSet::new();
+    let keys = loaded
+        .extract_strings()
+        .into_iter()
+        .filter(|s| seen.insert(s.record_key()))
+        .map(|s| FormIdentifier {
+            form_id: s.form_id,
+            record_type: s.record_type,
+            subrecord_type: s.subrecord_type,
+            index: s.index as u32,
+        })
+        .collect();
+
+    Ok(keys)
+}
+
+/// 读取插件 TES4 头中的 MAST 列表（按声明顺序，不加载 STRINGS 文件）
+pub fn read_plugin_masters(plugin_path: &Path) -> Result<Vec<String>, PluginExtractionError> {
+    if !plugin_path.exists() {
+        return Err(PluginExtractionError::FileMissing);
+    }
+
+    let plugin = Plugin::load(plugin_path.to_path_buf())
+        .map_err(|e| PluginExtractionError::ParseError(format!("加载插件失败: {}", e)))?;
+    Ok(plugin.masters)
 }
 
 /// 从游戏 Data 目录提取基础插件字典
 ///
 /// # 参数
 /// * `data_dir` - 游戏 Data 目录路径
+/// * `record_type_filter` - 仅提取匹配的记录类型（None 表示全部）
+/// * `cancel` - 置为 true 后在下一个插件开始前停止，返回已完成插件的记录
+/// * `on_progress` - 每个插件开始处理时的进度回调
 ///
 /// # 返回
 /// * `Ok(ExtractionStats)` - 提取统计信息
 /// * `Err(String)` - 致命错误信息
-pub fn extract_base_dictionary(
+pub fn extract_base_dictionary<F>(
     data_dir: &Path,
-) -> Result<(Vec<Translation>, ExtractionStats), String> {
+    record_type_filter: Option<&[String]>,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<(Vec<Translation>, ExtractionStats), String>
+where
+    F: FnMut(ExtractionProgress),
+{
     let mut stats = ExtractionStats::new(BASE_PLUGINS.len());
     let mut all_translations = Vec::new();
 
-    for plugin_name in BASE_PLUGINS {
+    for (index, plugin_name) in BASE_PLUGINS.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            stats.cancelled = true;
+            println!("⏹ 基础字典提取已取消，保留已完成的 {} 个插件", stats.successful_files);
+            break;
+        }
+
+        on_progress(ExtractionProgress {
+            current_plugin: plugin_name.to_string(),
+            current_progress: index + 1,
+            total: BASE_PLUGINS.len(),
+        });
+
         let plugin_path = data_dir.join(plugin_name);
 
         // 检查文件是否存在
@@ -163,9 +405,22 @@ pub fn extract_base_dictionary(
         }
 
         // 尝试提取字符串
-        match extract_plugin_strings(&plugin_path) {
-            Ok(translations) => {
+        match extract_plugin_strings(&plugin_path, record_type_filter) {
+            Ok(PluginStrings {
+                translations,
+                english_only,
+                suspicious_encoding,
+            }) => {
                 let count = translations.len();
+                if english_only {
+                    stats.english_only_files.push(plugin_name.to_string());
+                }
+                if suspicious_encoding > 0 {
+                    stats.suspicious_encoding_count += suspicious_encoding;
+                    stats
+                        .encoding_warnings
+                        .push(format!("{}: {} 条", plugin_name, suspicious_encoding));
+                }
                 stats.successful_files += 1;
                 stats.total_strings += count;
                 all_translations.extend(translations);
@@ -183,3 +438,37 @@ pub fn extract_base_dictionary(
 
     Ok((all_translations, stats))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_base_dictionary_skips_missing_and_honors_cancel() {
+        let data_dir = std::env::temp_dir().join("esp_service_missing_data_dir");
+        let cancel = AtomicBool::new(false);
+
+        let mut progress = Vec::new();
+        let (translations, stats) =
+            extract_base_dictionary(&data_dir, None, &cancel, |p| progress.push(p)).unwrap();
+        assert!(translations.is_empty());
+        assert_eq!(stats.skipped_files.len(), BASE_PLUGINS.len());
+        assert_eq!(progress.last().unwrap().current_progress, BASE_PLUGINS.len());
+        assert!(!stats.cancelled);
+
+        cancel.store(true, Ordering::Relaxed);
+        let (_, stats) = extract_base_dictionary(&data_dir, None, &cancel, |_| {}).unwrap();
+        assert!(stats.cancelled);
+        assert!(stats.skipped_files.is_empty());
+    }
+    #[test]
+    fn record_type_filter_matches_type_and_optional_subrecord() {
+        let filter = RecordTypeFilter::parse(Some(&["info".to_string(), "DIAL FULL".to_string()]))
+            .unwrap();
+        assert!(filter.matches_type("INFO", "NAM1"));
+        assert!(filter.matches_type("DIAL", "FULL"));
+        assert!(!filter.matches_type("DIAL", "NAM1"));
+        assert!(!filter.matches_type("WEAP", "FULL"));
+        assert!(RecordTypeFilter::parse(Some(&[" ".to_string()])).is_none());
+    }
+}
@@ -20,33 +20,61 @@ use plugin_session::{PluginSessionManager, StringRecord};
 use search_history::SearchHistoryDB;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::{WebviewUrl, WebviewWindowBuilder};
+use tauri::{Manager, RunEvent, WebviewUrl, WebviewWindowBuilder};
 use translation_db::TranslationDB;
+use utils::db_integrity::apply_pending_restore;
 use utils::paths::{
     get_api_db_path, get_atomic_db_path, get_coverage_db_path, get_db_path, get_search_history_db_path,
+    init_userdata_dir,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 校验 userdata 目录（环境变量/设置可指定到其他位置）
+    match init_userdata_dir() {
+        Ok(dir) => println!("✓ userdata 目录: {}", dir.display()),
+        Err(e) => panic!("{}", e),
+    }
+
+    // 应用上次运行时登记的数据库恢复（必须在打开连接之前）
+    for path in [
+        get_db_path(),
+        get_atomic_db_path(),
+        get_api_db_path(),
+        get_search_history_db_path(),
+        get_coverage_db_path(),
+    ] {
+        match apply_pending_restore(&path) {
+            Ok(true) => println!("✓ 已从备份恢复数据库: {}", path.display()),
+            Ok(false) => {}
+            Err(e) => eprintln!("⚠ 恢复数据库 {} 失败: {}", path.display(), e),
+        }
+    }
+
     // 初始化翻译数据库
     let db_path = get_db_path();
     let translation_db = TranslationDB::new(db_path).expect("无法初始化翻译数据库");
 
     // 初始化原子数据库
     let atomic_db_path = get_atomic_db_path();
-    let atomic_db = AtomicDB::new(atomic_db_path.to_str().expect("路径转换失败"))
-        .expect("无法初始化原子数据库");
+    let atomic_db = AtomicDB::new(atomic_db_path).expect("无法初始化原子数据库");
+    if let Ok(settings) = settings::read_settings() {
+        atomic_db
+            .set_matcher_options(settings.atom_matcher)
+            .expect("无法初始化原子匹配器");
+        if let Some(retries) = settings.usage_write_retries {
+            atomic_db.set_usage_write_retries(retries);
+        }
+    }
 
     // 初始化API配置数据库
     let api_db_path = get_api_db_path();
-    let api_db = ApiConfigDB::new(api_db_path.to_str().expect("路径转换失败"))
-        .expect("无法初始化API配置数据库");
+    let api_db = ApiConfigDB::new(api_db_path).expect("无法初始化API配置数据库");
 
     // 初始化搜索历史数据库
     let search_history_db_path = get_search_history_db_path();
     let search_history_db =
-        SearchHistoryDB::new(search_history_db_path.to_str().expect("路径转换失败"))
-            .expect("无法初始化搜索历史数据库");
+        SearchHistoryDB::new(search_history_db_path).expect("无法初始化搜索历史数据库");
 
     // 初始化覆盖关系数据库 (使用 Arc 以便在后台任务中共享)
     let coverage_db_path = get_coverage_db_path();
@@ -70,6 +98,7 @@ pub fn run() {
         .manage(coverage_db)
         .manage(Mutex::new(session_manager))
         .manage(editor_data_store)
+        .manage(commands::DictionaryExtractionControl::default())
         .setup(|app| {
             // 创建主窗口
             let window_builder =
@@ -86,52 +115,135 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // 配置管理
             commands::get_settings,
+            commands::reload_settings,
             commands::set_game_path,
             commands::clear_game_path,
             commands::set_dsd_output_dir,
             commands::clear_dsd_output_dir,
+            commands::set_dsd_path_template,
+            commands::clear_dsd_path_template,
+            commands::set_data_dir,
+            commands::clear_data_dir,
+            commands::set_temp_dir,
+            commands::clear_temp_dir,
+            commands::set_userdata_dir,
+            commands::clear_userdata_dir,
+            commands::set_read_only_data,
+            commands::set_apply_threads,
+            commands::clear_apply_threads,
+            commands::set_length_limits,
+            commands::clear_length_limits,
+            commands::set_coverage_filter,
+            commands::clear_coverage_filter,
+            commands::set_translation_source_priority,
+            commands::clear_translation_source_priority,
+            commands::set_usage_write_retries,
+            commands::clear_usage_write_retries,
+            // 诊断
+            commands::get_diagnostics,
+            commands::check_integrity,
+            commands::backup_databases,
+            commands::rebuild_from_backup,
+            commands::reveal_userdata_dir,
+            commands::reveal_settings_file,
+            commands::reveal_log_file,
             // 插件扫描
             commands::validate_game_directory,
             commands::get_plugin_list,
+            commands::get_resolved_load_order,
+            commands::compute_plugin_checksum,
             // 翻译数据库
             commands::save_translation,
             commands::batch_save_translations,
             commands::get_translation,
+            commands::get_translation_for_record,
+            commands::set_translation_note,
+            commands::add_translation_tag,
+            commands::remove_translation_tag,
+            commands::list_translations,
+            commands::export_translations_ndjson,
+            commands::export_translations,
+            commands::import_translations_ndjson,
             commands::batch_query_translations,
             commands::batch_query_translations_with_progress,
+            commands::estimate_coverage,
             commands::get_translation_statistics,
+            commands::get_distinct_types,
+            commands::export_statistics,
             commands::clear_plugin_translations,
+            commands::rename_plugin_translations,
+            commands::replace_in_translations,
             commands::clear_all_translations,
             commands::clear_base_dictionary,
             commands::query_word_translations,
+            commands::search_all,
             // Session 管理
             commands::load_plugin_session,
+            commands::load_combined_session,
+            commands::get_plugin_masters,
             commands::close_plugin_session,
+            commands::close_all_sessions,
             commands::list_plugin_sessions,
+            commands::mark_session_dirty,
+            commands::set_records_status,
+            commands::session_copy_source_to_target,
+            commands::session_clear_target,
+            commands::get_record_context,
             commands::apply_translations,
+            commands::apply_combined_translations,
+            commands::undo_last_apply,
+            commands::list_backups,
+            commands::restore_backup,
+            commands::commit_session_to_tm,
             commands::export_dsd,
+            commands::preview_dsd,
+            commands::dsd_status,
+            commands::export_strings_files,
+            commands::export_untranslated,
+            commands::import_untranslated,
+            commands::diff_session_against_tm,
+            commands::get_session_translation_progress,
+            commands::find_whitespace_only_translations,
+            commands::validate_lengths,
+            commands::remap_by_editor_id,
+            commands::get_coverage_fill_candidates,
+            commands::get_text_fill_candidates,
             // ESP 对照
             commands::load_esp_reference,
             // ESP 提取
             commands::get_base_plugins_list,
             commands::extract_dictionary,
+            commands::cancel_dictionary_extraction,
             // 编辑窗口
             commands::open_editor_window,
             commands::get_editor_data,
             // 原子数据库
             commands::open_atomic_db_window,
             commands::get_all_atoms,
+            commands::list_stale_atoms,
             commands::add_atom_translation,
             commands::delete_atom_translation,
+            commands::delete_atoms,
             commands::update_atom_translation,
+            commands::preview_atom_import,
+            commands::import_atoms,
             commands::replace_text_with_atoms,
+            commands::replace_with_atoms_detailed,
+            commands::replace_texts_with_atoms,
+            commands::replace_texts_with_atoms_report,
+            commands::flush_atom_usage,
+            commands::get_atom_matcher_options,
+            commands::set_atom_matcher_options,
             // API 配置
             commands::get_api_configs,
             commands::create_api_config,
             commands::update_api_config,
             commands::delete_api_config,
             commands::activate_api_config,
+            commands::deactivate_all_api_configs,
             commands::get_current_api,
+            commands::record_api_usage,
+            commands::get_api_usage,
             // 搜索历史
             commands::save_search_history,
             commands::get_search_history,
@@ -140,8 +252,22 @@ pub fn run() {
             commands::open_coverage_window,
             commands::get_coverage_status,
             commands::run_coverage_extraction,
-            commands::search_coverage_entries
+            commands::update_coverage_for_plugin,
+            commands::search_coverage_entries,
+            commands::get_coverage_distinct_types,
+            commands::export_coverage_snapshot,
+            commands::import_coverage_snapshot
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            // 退出前写入累积的原子词使用计数
+            if let RunEvent::Exit = event {
+                if let Ok(db) = app_handle.state::<Mutex<AtomicDB>>().lock() {
+                    if let Err(e) = db.flush_usage() {
+                        eprintln!("⚠ 退出时写入原子词使用计数失败: {}", e);
+                    }
+                }
+            }
+        });
 }
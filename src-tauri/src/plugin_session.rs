@@ -1,13 +1,30 @@
-use crate::bsa_logger::log_bsa_presence;
-use crate::dsd::{export_dsd_entries, load_dsd_overrides, make_record_key, DsdEntry};
-use esp_extractor::{DefaultEspWriter, ExtractedString, LoadedPlugin, PluginEditor};
+use crate::bsa_logger::{check_bsa_presence, log_bsa_presence};
+use crate::constants::BASE_PLUGINS;
+use crate::dsd::{
+    dsd_status, export_dsd_entries, load_dsd_overrides, make_record_key, preview_dsd_entries,
+    DsdEntry, DsdExportResult, DsdLayout, DsdPreview, DsdStatus,
+};
+use crate::coverage_db::CoverageEntry;
+use crate::esp_service::{read_plugin_masters, RecordTypeFilter};
+use crate::translation_db::{is_untranslated_text, FormIdentifier, Translation};
+use crate::utils::pairing::RecordKey;
+use crate::utils::string_exchange::{
+    read_exchange_file, write_exchange_file, ExchangeEncoding, ExchangeFormat,
+};
+use crate::utils::checksum::{fnv1a_hash, stat_token};
+use crate::utils::strings_table::StringsTable;
+use esp_extractor::{
+    DefaultEspWriter, ExtractedString, Group, GroupChild, LoadedPlugin, Plugin, PluginEditor,
+    StringFileType,
+};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// 字符串记录（前端显示用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,8 +38,17 @@ pub struct StringRecord {
     pub translated_text: String, // 初始复制 original_text
     #[serde(default = "default_translation_status")]
     pub translation_status: String, // 翻译状态：untranslated/manual/ai
+    /// 所在的 STRINGS 表（由 record_type/subrecord_type 推导）
+    #[serde(default)]
+    pub strings_table: StringsTable,
+    /// 记录来源插件（仅合并 Session 中设置，写回时据此路由到对应文件）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_plugin: Option<String>,
 }
 
+/// 合法的翻译状态取值
+const VALID_TRANSLATION_STATUSES: &[&str] = &["untranslated", "manual", "ai"];
+
 /// 默认翻译状态（用于向后兼容）
 fn default_translation_status() -> String {
     "untranslated".to_string()
@@ -35,11 +61,34 @@ pub struct PluginSession {
     pub strings: Arc<Vec<StringRecord>>,
     pub loaded_at: Instant,
     pub has_dsd_overrides: bool,
+    /// DSD 覆盖的套用情况（未检测到 DSD 文件时为 None）
+    pub dsd_overrides: Option<DsdOverrideSummary>,
+    pub duplicate_keys: Vec<String>,
+    /// 前端是否存在未保存的修改
+    pub dirty: bool,
+    /// 字符串缺失或数量可疑时的 BSA 诊断警告
+    pub bsa_warning: Option<String>,
+    /// 插件头信息（主文件列表、ESM/ESL 标志与记录总数）
+    pub metadata: PluginMetadata,
+    /// 连续 INFO 记录组成的对话组（`strings` 中的下标区间，按提取顺序）
+    pub dialogue_groups: Vec<Range<usize>>,
+    /// 加载时插件文件的大小与修改时间校验值（用于判断 Session 是否已过期）
+    pub stat_token: Option<String>,
     // Store the loaded plugin to avoid reloading from disk
     // Wrapped in Option because we need to take ownership when applying translations
     pub loaded_plugin: Option<LoadedPlugin>,
 }
 
+impl PluginSession {
+    /// 插件文件自加载后是否已变化（大小或修改时间不同，或文件已无法读取）
+    pub fn is_stale(&self) -> bool {
+        match &self.stat_token {
+            Some(token) => stat_token(&self.plugin_path).ok().as_ref() != Some(token),
+            None => false,
+        }
+    }
+}
+
 /// Session 信息（用于列表返回）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
@@ -47,6 +96,10 @@ pub struct SessionInfo {
     pub plugin_name: String,
     pub string_count: usize,
     pub loaded_at: u64, // 使用 u64 因为 Instant 不能序列化
+    pub dirty: bool,
+    /// 插件文件在加载后已被修改（或已无法读取）
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// 加载插件返回的完整响应
@@ -58,11 +111,1074 @@ pub struct PluginStringsResponse {
     pub strings: Vec<StringRecord>,
     pub total_count: usize,
     pub has_dsd_overrides: bool,
+    /// DSD 覆盖的套用情况（未检测到 DSD 文件时为 None）
+    pub dsd_overrides: Option<DsdOverrideSummary>,
+    /// 插件内重复出现的记录 key（不会从列表中移除）
+    pub duplicate_keys: Vec<String>,
+    /// 字符串缺失或数量可疑时的 BSA 诊断警告（如预期的 BSA 不在插件旁）
+    pub bsa_warning: Option<String>,
+    /// 插件头信息（用于编辑器标题栏展示，确认打开的是正确的文件）
+    pub metadata: PluginMetadata,
+}
+
+/// TES4 头的 ESM 标志
+const ESM_FLAG: u32 = 0x0000_0001;
+
+/// 插件头信息（加载 Session 时从 TES4 头与记录组中读取）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginMetadata {
+    /// 插件依赖的主文件（TES4 MAST，按声明顺序）
+    pub masters: Vec<String>,
+    /// 是否为主文件（.esm 扩展名或 TES4 头设置了 ESM 标志）
+    pub is_master: bool,
+    /// 是否为轻量插件（.esl 扩展名或 TES4 头设置了 Light 标志）
+    pub is_light: bool,
+    /// 是否为本地化插件（字符串存放在 STRINGS 文件中）
+    pub is_localized: bool,
+    /// 记录总数（所有记录组中的记录，不含 TES4 头，也不限于含字符串的记录）
+    pub record_count: usize,
+}
+
+impl PluginMetadata {
+    fn from_plugin(plugin: &Plugin) -> Self {
+        fn count_records(group: &Group) -> usize {
+            group
+                .children
+                .iter()
+                .map(|child| match child {
+                    GroupChild::Group(subgroup) => count_records(subgroup),
+                    GroupChild::Record(_) => 1,
+                })
+                .sum()
+        }
+
+        Self {
+            masters: plugin.masters.clone(),
+            is_master: plugin.is_master() || plugin.header.flags & ESM_FLAG != 0,
+            is_light: plugin.is_light(),
+            is_localized: plugin.is_localized(),
+            record_count: plugin.groups.iter().map(count_records).sum(),
+        }
+    }
+}
+
+/// DSD 覆盖套用统计（用于排查 DSD 未完全生效）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsdOverrideSummary {
+    /// DSD 条目总数（按 form_id + type 去重）
+    pub total: usize,
+    /// 实际改写了译文的记录数
+    pub applied: usize,
+    /// 找不到对应记录的 DSD 条目数
+    pub unmatched: usize,
+}
+
+/// 对话记录的上下文（同一对话组内的前后记录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordContext {
+    /// 对话组之前紧邻的话题（DIAL 记录文本），无则为 None
+    pub topic: Option<String>,
+    /// 对话组内的记录数
+    pub group_size: usize,
+    /// 目标记录在组内的位置（从 0 开始）
+    pub position: usize,
+    /// 之前的记录（按顺序，最接近目标的在最后）
+    pub previous: Vec<StringRecord>,
+    /// 之后的记录（按顺序）
+    pub next: Vec<StringRecord>,
+}
+
+/// Session 记录与翻译数据库的比对状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TmDiffStatus {
+    /// 翻译数据库中没有该记录
+    New,
+    /// 与翻译数据库中的译文一致
+    Unchanged,
+    /// 与翻译数据库中的译文不同
+    Conflict,
+}
+
+/// 单条记录的比对结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmDiffEntry {
+    pub form_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+    pub translated_text: String,
+    pub status: TmDiffStatus,
+    /// 翻译数据库中的译文（仅 conflict 时返回）
+    pub tm_text: Option<String>,
+}
+
+/// 单个 Session 的翻译进度（与翻译数据库比对）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SessionProgress {
+    /// Session 记录总数
+    pub total: usize,
+    /// 在翻译数据库中已有译文的记录数（仅首尾空白不同的译文不计入）
+    pub tm_hits: usize,
+    /// 译文仍与原文相同的记录数（忽略首尾空白）
+    pub same_as_source: usize,
+    /// 已有译文的百分比（0~100）
+    pub percentage: f64,
+}
+
+/// 来自覆盖数据库的填充候选：该记录在整个 load order 中最终生效的文本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageFillCandidate {
+    pub form_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+    /// Session 中的当前译文
+    pub current_text: String,
+    /// 覆盖数据库中的最终文本
+    pub coverage_text: String,
+    /// 最终覆盖该记录的插件
+    pub source_mod: String,
+    pub load_order_pos: i64,
+}
+
+/// 按原文自动填充时的译文来源（按设置中的顺序依次尝试）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationSource {
+    /// 与当前 Session 同一插件的译文
+    SamePlugin,
+    /// 基础字典（官方主文件与 CC 内容）
+    BaseDictionary,
+    /// 任意插件的译文
+    Any,
+}
+
+impl TranslationSource {
+    fn matches(self, plugin_name: &str, source_plugin: Option<&str>) -> bool {
+        match (self, source_plugin) {
+            (TranslationSource::Any, _) => true,
+            (TranslationSource::SamePlugin, Some(source)) => source.eq_ignore_ascii_case(plugin_name),
+            (TranslationSource::BaseDictionary, Some(source)) => {
+                BASE_PLUGINS.iter().any(|p| p.eq_ignore_ascii_case(source))
+            }
+            (_, None) => false,
+        }
+    }
+}
+
+/// 默认来源优先级：同插件 > 基础字典 > 任意插件
+pub fn default_source_priority() -> Vec<TranslationSource> {
+    vec![
+        TranslationSource::SamePlugin,
+        TranslationSource::BaseDictionary,
+        TranslationSource::Any,
+    ]
+}
+
+/// 按原文从翻译数据库匹配到的填充候选
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextFillCandidate {
+    pub form_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+    pub translated_text: String,
+    /// 译文所属插件
+    pub source_plugin: Option<String>,
+    /// 命中的来源规则
+    pub source: TranslationSource,
+}
+
+/// 译文字节长度上限（按 UTF-8 字节计算，中文每字 3 字节）
+///
+/// `record_type` 为 `*` 时匹配所有记录类型，`subrecord_type` 为空时匹配所有子记录；
+/// 多条规则同时匹配时取最具体的一条（记录类型精确匹配优先于子记录类型）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthLimit {
+    pub record_type: String,
+    #[serde(default)]
+    pub subrecord_type: Option<String>,
+    pub max_bytes: usize,
+}
+
+impl LengthLimit {
+    /// 匹配的具体程度（不匹配时为 None）
+    fn specificity(&self, record_type: &str, subrecord_type: &str) -> Option<u8> {
+        let record_score = match self.record_type.as_str() {
+            "*" => 0,
+            t if t.eq_ignore_ascii_case(record_type) => 2,
+            _ => return None,
+        };
+        let subrecord_score = match self.subrecord_type.as_deref() {
+            None => 0,
+            Some(t) if t.eq_ignore_ascii_case(subrecord_type) => 1,
+            Some(_) => return None,
+        };
+        Some(record_score + subrecord_score)
+    }
+}
+
+/// 子记录数据长度字段为 u16（含结尾的 \0），非本地化插件中更长的字符串无法写入
+const SUBRECORD_MAX_STRING_BYTES: usize = u16::MAX as usize - 1;
+
+/// 默认长度上限（未在设置中配置时使用）
+///
+/// 仅包含引擎层面的硬限制；游戏内界面截断因字体与控件而异，
+/// 可在设置中按需追加（如 `{"record_type": "WEAP", "subrecord_type": "FULL", "max_bytes": 96}`）
+pub fn default_length_limits() -> Vec<LengthLimit> {
+    vec![LengthLimit {
+        record_type: "*".to_string(),
+        subrecord_type: None,
+        max_bytes: SUBRECORD_MAX_STRING_BYTES,
+    }]
+}
+
+/// 超出长度上限的译文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthViolation {
+    pub form_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+    pub translated_text: String,
+    /// 译文的 UTF-8 字节数
+    pub byte_len: usize,
+    pub max_bytes: usize,
+}
+
+/// 按 EditorID 重新匹配到的记录（翻译数据库中的旧 form_id -> Session 中的新 form_id）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormIdRemap {
+    pub old_form_id: String,
+    pub new_form_id: String,
+    pub editor_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+}
+
+/// 按 EditorID 重新匹配 form_id 的报告
+///
+/// 只处理翻译数据库中 key 已不在 Session 里的记录（孤立译文）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EditorIdRemapReport {
+    /// 唯一匹配到新记录的译文
+    pub matched: Vec<FormIdRemap>,
+    /// 可匹配到多条记录（或多条译文匹配同一记录）而无法确定的译文 key
+    pub ambiguous: Vec<String>,
+    /// 找不到对应记录（或没有 EditorID）的译文 key
+    pub unmatched: Vec<String>,
+    /// 实际改写的记录数（仅预览时为 0）
+    pub applied: usize,
+}
+
+/// 插件写回前生成的备份文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginBackup {
+    pub path: String,
+    /// 文件名中的时间戳（格式 `%Y_%m_%d_%H_%M_%S`，本地时间）
+    pub timestamp: String,
+    pub size_bytes: u64,
+    /// 备份文件的修改时间（Unix 秒，无法读取时为 None）
+    pub modified_at: Option<i64>,
+}
+
+impl PluginBackup {
+    fn from_path(plugin_path: &Path, backup_path: &Path) -> Self {
+        let metadata = fs::metadata(backup_path).ok();
+        let file_name = backup_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let plugin_name = plugin_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let timestamp = file_name
+            .strip_prefix(&format!("{}.", plugin_name))
+            .and_then(|rest| rest.strip_suffix(".bak"))
+            .unwrap_or_default()
+            .to_string();
+        PluginBackup {
+            path: backup_path.to_string_lossy().to_string(),
+            timestamp,
+            size_bytes: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+            modified_at: metadata
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64),
+        }
+    }
+}
+
+/// 合并 Session：多个插件 Session 作为一个翻译单元（成员 Session 仍可单独使用）
+struct CombinedSession {
+    /// 成员插件名 -> 成员 Session ID（按加载顺序）
+    members: Vec<(String, String)>,
+}
+
+/// 合并 Session 的成员插件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedSessionMember {
+    pub session_id: String,
+    pub plugin_name: String,
+    pub plugin_path: String,
+    pub total_count: usize,
+    pub duplicate_keys: Vec<String>,
+    pub bsa_warning: Option<String>,
+    pub metadata: PluginMetadata,
+}
+
+/// 加载合并 Session 返回的响应（`strings` 按成员顺序拼接，每条记录带 `source_plugin`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedStringsResponse {
+    pub session_id: String,
+    pub members: Vec<CombinedSessionMember>,
+    pub strings: Vec<StringRecord>,
+    pub total_count: usize,
+}
+
+/// 合并 Session 中单个成员插件的写回结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedApplyEntry {
+    pub plugin_name: String,
+    pub result: ApplyResult,
+}
+
+/// 按 `source_plugin` 将合并 Session 的记录分到各成员（保持成员顺序，只返回有记录的成员）
+///
+/// 缺少来源或来源不属于合并 Session 的记录视为错误，避免写入错误的文件
+fn partition_by_source_plugin(
+    members: &[(String, String)],
+    records: Vec<StringRecord>,
+) -> Result<Vec<(String, String, Vec<StringRecord>)>, String> {
+    let mut groups: Vec<Vec<StringRecord>> = vec![Vec::new(); members.len()];
+    for record in records {
+        let source = record
+            .source_plugin
+            .as_deref()
+            .ok_or_else(|| format!("记录 {} 缺少来源插件", record.record_key()))?;
+        let position = members
+            .iter()
+            .position(|(plugin_name, _)| plugin_name == source)
+            .ok_or_else(|| {
+                format!("记录 {} 的来源插件 {} 不在合并 Session 中", record.record_key(), source)
+            })?;
+        groups[position].push(record);
+    }
+
+    Ok(members
+        .iter()
+        .zip(groups)
+        .filter(|(_, records)| !records.is_empty())
+        .map(|((plugin_name, session_id), records)| {
+            (plugin_name.clone(), session_id.clone(), records)
+        })
+        .collect())
+}
+
+/// 回读校验时最多返回的不一致记录 key 数量
+const VERIFY_MISMATCH_SAMPLE_LIMIT: usize = 50;
+
+/// 应用翻译到插件文件的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    /// 保存的路径
+    pub saved_path: String,
+    /// 原文件备份路径（另存为时为 None）
+    pub backup_path: Option<String>,
+    /// 回读校验的不一致记录数（未启用校验时为 None）
+    pub mismatch_count: Option<usize>,
+    /// 不一致记录的 key 示例（最多 VERIFY_MISMATCH_SAMPLE_LIMIT 条）
+    pub mismatched_keys: Vec<String>,
+    /// 实际写入的记录数
+    pub applied_count: usize,
+}
+
+/// 筛选需要写回的记录：翻译状态不是 untranslated，或译文与 Session 原文不同
+///
+/// 原文不为空而译文为空的记录不会写回（避免清空插件中的文本）
+fn filter_changed_records(originals: &[StringRecord], records: Vec<StringRecord>) -> Vec<StringRecord> {
+    let original_texts: HashMap<(&str, &str, &str, u32), &str> = originals
+        .iter()
+        .map(|r| {
+            (
+                (r.form_id.as_str(), r.record_type.as_str(), r.subrecord_type.as_str(), r.index),
+                r.original_text.as_str(),
+            )
+        })
+        .collect();
+
+    records
+        .into_iter()
+        .filter(|r| !r.translated_text.is_empty() || r.original_text.is_empty())
+        .filter(|r| {
+            r.translation_status != "untranslated"
+                || original_texts
+                    .get(&(r.form_id.as_str(), r.record_type.as_str(), r.subrecord_type.as_str(), r.index))
+                    .is_none_or(|original| *original != r.translated_text)
+        })
+        .collect()
+}
+
+/// 只读模式检查：写入目标位于受保护目录内时返回错误
+fn ensure_outside_protected_dirs(target: &Path, protected_dirs: &[PathBuf]) -> Result<(), String> {
+    // 规范化后比较；目标文件可能尚不存在（另存为），此时规范化其所在目录
+    let normalized = target.canonicalize().ok().or_else(|| {
+        let parent = target.parent()?.canonicalize().ok()?;
+        Some(parent.join(target.file_name()?))
+    });
+    let target = normalized.as_deref().unwrap_or(target);
+
+    for dir in protected_dirs {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if target.starts_with(&dir) {
+            return Err(format!(
+                "只读模式已开启，禁止写入游戏目录内的文件: {}\n请另存为到 {} 之外的位置或导出 DSD；如需直接写回，请先在设置中关闭只读模式",
+                target.display(),
+                dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 校验另存为路径：扩展名必须为 .esp/.esm/.esl，父目录必须存在，且不能与源插件相同
+fn validate_save_as_path(save_as: &Path, source: &Path) -> Result<(), String> {
+    let has_plugin_ext = save_as
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ["esp", "esm", "esl"].iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        .unwrap_or(false);
+    if !has_plugin_ext || save_as.is_dir() {
+        return Err(format!(
+            "另存为路径必须是 .esp/.esm/.esl 插件文件: {}",
+            save_as.display()
+        ));
+    }
+
+    let parent = match save_as.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => return Err(format!("另存为路径缺少所在目录: {}", save_as.display())),
+    };
+    if !parent.is_dir() {
+        return Err(format!("另存为目录不存在: {}", parent.display()));
+    }
+
+    // 通过规范化父目录比较，兼容相对路径与大小写不同的写法
+    let same_as_source = match (parent.canonicalize(), source.parent().map(Path::canonicalize)) {
+        (Ok(target_dir), Some(Ok(source_dir))) => {
+            target_dir == source_dir
+                && save_as
+                    .file_name()
+                    .zip(source.file_name())
+                    .map(|(a, b)| a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase())
+                    .unwrap_or(false)
+        }
+        _ => save_as == source,
+    };
+    if same_as_source {
+        return Err("另存为路径与源插件相同，如需覆盖原文件请直接应用".to_string());
+    }
+
+    Ok(())
+}
+
+/// 回读已写入的插件，返回译文与预期不一致（或缺失）的记录 key
+fn verify_written_plugin(path: &Path, expected: &[StringRecord]) -> Result<Vec<String>, String> {
+    log_bsa_presence(path, Some("english"));
+    let loaded = LoadedPlugin::load_auto(path.to_path_buf(), Some("english"))
+        .map_err(|e| format!("回读校验加载插件失败: {}", e))?;
+
+    let written: HashMap<String, String> = loaded
+        .extract_strings()
+        .into_iter()
+        .map(|s| (s.record_key(), s.text))
+        .collect();
+
+    Ok(expected
+        .iter()
+        .filter_map(|r| {
+            let key = r.record_key();
+            match written.get(&key) {
+                Some(text) if *text == r.translated_text => None,
+                _ => Some(key),
+            }
+        })
+        .collect())
+}
+
+/// 移动文件：优先 rename，跨设备（如临时目录位于其他磁盘）时回退为复制后删除
+///
+/// 其他 rename 错误（如文件被占用、权限不足）直接返回，交由调用方重试或报告
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
+
+/// 替换插件文件时的最大重试次数（不含首次执行）
+const FILE_RETRY_ATTEMPTS: u32 = 5;
+
+/// 文件操作重试的基础退避时间（第 n 次重试等待 n 倍）
+const FILE_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// 执行文件操作，失败时按递增退避重试，重试耗尽后返回最后一次的错误
+///
+/// Windows 上杀毒软件或 Mod 管理器会短暂锁定插件文件，导致删除/重命名偶发失败；
+/// 文件不存在属于确定性错误，不重试
+fn retry_file_op<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e)
+                if e.kind() != std::io::ErrorKind::NotFound && attempt < FILE_RETRY_ATTEMPTS =>
+            {
+                attempt += 1;
+                eprintln!("⚠ 文件操作失败，第 {} 次重试: {}", attempt, e);
+                std::thread::sleep(FILE_RETRY_BACKOFF * attempt);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 记录数达到该值才并行转换（单条转换开销很小，少量记录时线程调度得不偿失）
+const PARALLEL_CONVERSION_THRESHOLD: usize = 5_000;
+
+/// 转换阶段可配置的最大线程数
+pub const MAX_APPLY_THREADS: usize = 64;
+
+/// 离线翻译文件导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ExchangeImportResult {
+    /// 填入译文后的记录
+    pub records: Vec<StringRecord>,
+    /// 文件中未匹配到 Session 记录的 key
+    pub unmatched_keys: Vec<String>,
+}
+
+/// 写回选项
+#[derive(Debug, Clone, Default)]
+pub struct ApplyOptions {
+    /// 临时文件目录（为 None 则写在目标文件旁）
+    pub temp_dir: Option<PathBuf>,
+    /// 写入后是否回读校验译文
+    pub verify: bool,
+    /// 只写入已翻译（manual/ai）或译文与原文不同的记录，其余记录保持原样
+    pub only_changed: bool,
+    /// 转换阶段的线程数（为 None 则使用 rayon 全局线程池）
+    pub threads: Option<usize>,
+    /// 只读模式下禁止写入的目录（见 `Settings::protected_dirs`）
+    pub protected_dirs: Vec<PathBuf>,
+}
+
+/// 将记录转换为写回用的 ExtractedString（译文作为 text）
+///
+/// 达到 `PARALLEL_CONVERSION_THRESHOLD` 时并行处理；指定 `threads` 时使用独立线程池，
+/// 避免占满全局线程池
+fn to_extracted_strings(
+    records: &[StringRecord],
+    threads: Option<usize>,
+) -> Result<Vec<ExtractedString>, String> {
+    let convert = |r: &StringRecord| ExtractedString {
+        form_id: r.form_id.clone(),
+        editor_id: r.editor_id.clone(),
+        text: r.translated_text.clone(), // 使用翻译后的文本
+        record_type: r.record_type.clone(),
+        subrecord_type: r.subrecord_type.clone(),
+        index: r.index as i32,
+    };
+
+    if records.len() < PARALLEL_CONVERSION_THRESHOLD || threads == Some(1) {
+        return Ok(records.iter().map(convert).collect());
+    }
+
+    match threads {
+        Some(n) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(|e| format!("创建转换线程池失败: {}", e))?;
+            Ok(pool.install(|| records.par_iter().map(convert).collect()))
+        }
+        None => Ok(records.par_iter().map(convert).collect()),
+    }
+}
+
+/// 按记录类型过滤（格式同提取时的过滤条件，未指定时保留全部）
+fn filter_records_by_type(
+    records: Vec<StringRecord>,
+    record_type_filter: Option<&[String]>,
+) -> Vec<StringRecord> {
+    match RecordTypeFilter::parse(record_type_filter) {
+        Some(filter) => records
+            .into_iter()
+            .filter(|r| filter.matches_type(&r.record_type, &r.subrecord_type))
+            .collect(),
+        None => records,
+    }
+}
+
+/// 转换为 DSD 条目（type 字段为 "记录类型 子记录类型"）
+fn records_to_dsd_entries(records: Vec<StringRecord>) -> Vec<DsdEntry> {
+    records
+        .into_iter()
+        .map(|r| DsdEntry {
+            form_id: r.form_id,
+            entry_type: format!("{} {}", r.record_type, r.subrecord_type),
+            string: r.translated_text,
+        })
+        .collect()
+}
+
+/// 列出插件写回时生成的备份（{插件文件名}.{时间戳}.bak），按时间从新到旧排列
+///
+/// 时间戳格式固定，按字典序即时间顺序
+fn find_backups(plugin_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let (Some(dir), Some(file_name)) = (plugin_path.parent(), plugin_path.file_name()) else {
+        return Ok(Vec::new());
+    };
+    let prefix = [file_name.as_encoded_bytes(), b"."].concat();
+
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取插件目录失败: {}", e))?;
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            path.is_file()
+                && path.file_name().is_some_and(|name| {
+                    let name = name.as_encoded_bytes();
+                    name.starts_with(&prefix) && name.ends_with(b".bak")
+                })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+
+    Ok(backups)
+}
+
+/// 查找插件最近一次写回时生成的备份
+fn find_latest_backup(plugin_path: &Path) -> Result<Option<PathBuf>, String> {
+    Ok(find_backups(plugin_path)?.into_iter().next())
+}
+
+/// 按提取顺序把连续的 INFO 记录划分为对话组
+///
+/// 插件中 INFO 记录按话题（DIAL）分组存放，提取顺序与文件顺序一致，
+/// 因此连续的 INFO 记录即同一话题下的对话；无文本的 DIAL 不会出现在列表中，
+/// 相邻话题此时会合并为一组
+fn group_dialogue_records(records: &[StringRecord]) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = None;
+    for (i, record) in records.iter().enumerate() {
+        match (record.record_type == "INFO", start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                groups.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        groups.push(s..records.len());
+    }
+    groups
+}
+
+/// 取目标记录所在对话组内前后各 `radius` 条记录，不属于任何对话组时返回 None
+fn record_context(
+    records: &[StringRecord],
+    groups: &[Range<usize>],
+    position: usize,
+    radius: usize,
+) -> Option<RecordContext> {
+    let group = groups
+        .get(groups.partition_point(|g| g.end <= position))
+        .filter(|g| g.contains(&position))?;
+
+    let topic = group
+        .start
+        .checked_sub(1)
+        .map(|i| &records[i])
+        .filter(|r| r.record_type == "DIAL")
+        .map(|r| r.translated_text.clone());
+    let before = position.saturating_sub(radius).max(group.start);
+    let after = (position + 1 + radius).min(group.end);
+
+    Some(RecordContext {
+        topic,
+        group_size: group.len(),
+        position: position - group.start,
+        previous: records[before..position].to_vec(),
+        next: records[position + 1..after].to_vec(),
+    })
+}
+
+/// 找出重复出现的记录 key（form_id|record_type|subrecord_type|index），按字典序返回
+fn find_duplicate_keys(records: &[StringRecord]) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for r in records {
+        let key = r.record_key();
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    let mut duplicates: Vec<String> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(key, _)| key)
+        .collect();
+    duplicates.sort();
+    duplicates
+}
+
+/// 将 Session 记录转换为批量查询用的 Form 标识符
+pub fn records_to_form_identifiers(records: &[StringRecord]) -> Vec<FormIdentifier> {
+    records
+        .iter()
+        .map(|r| FormIdentifier {
+            form_id: r.form_id.clone(),
+            record_type: r.record_type.clone(),
+            subrecord_type: r.subrecord_type.clone(),
+            index: r.index,
+        })
+        .collect()
+}
+
+/// 将 Session 记录与翻译数据库中的已有译文逐条比对
+pub fn diff_records_against_tm(records: &[StringRecord], existing: Vec<Translation>) -> Vec<TmDiffEntry> {
+    let tm_map: HashMap<String, String> = existing
+        .into_iter()
+        .map(|t| (t.record_key(), t.translated_text))
+        .collect();
+
+    records
+        .iter()
+        .map(|r| {
+            let key = r.record_key();
+            let (status, tm_text) = match tm_map.get(&key) {
+                None => (TmDiffStatus::New, None),
+                Some(tm) if *tm == r.translated_text => (TmDiffStatus::Unchanged, None),
+                Some(tm) => (TmDiffStatus::Conflict, Some(tm.clone())),
+            };
+
+            TmDiffEntry {
+                form_id: r.form_id.clone(),
+                record_type: r.record_type.clone(),
+                subrecord_type: r.subrecord_type.clone(),
+                index: r.index,
+                translated_text: r.translated_text.clone(),
+                status,
+                tm_text,
+            }
+        })
+        .collect()
+}
+
+/// 统计 Session 记录的翻译进度（`existing` 为翻译数据库中按 key 查到的译文）
+///
+/// 去除首尾空白后与原文相同的译文视为未翻译
+pub fn session_progress(records: &[StringRecord], existing: &[Translation]) -> SessionProgress {
+    let tm_keys: HashSet<String> = existing
+        .iter()
+        .filter(|t| !is_untranslated_text(&t.original_text, &t.translated_text))
+        .map(|t| t.record_key())
+        .collect();
+
+    let total = records.len();
+    let tm_hits = records
+        .iter()
+        .filter(|r| {
+            let key = r.record_key();
+            tm_keys.contains(&key)
+        })
+        .count();
+    let same_as_source = records
+        .iter()
+        .filter(|r| is_untranslated_text(&r.original_text, &r.translated_text))
+        .count();
+    let percentage = if total > 0 {
+        (tm_hits as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    SessionProgress {
+        total,
+        tm_hits,
+        same_as_source,
+        percentage,
+    }
+}
+
+/// 筛选仅首尾空白与原文不同的译文（统计中视为未翻译，通常是误输入的空格）
+pub fn whitespace_only_translations(existing: Vec<Translation>) -> Vec<Translation> {
+    existing
+        .into_iter()
+        .filter(|t| {
+            t.translated_text != t.original_text
+                && is_untranslated_text(&t.original_text, &t.translated_text)
+        })
+        .collect()
+}
+
+/// 检查译文是否超出对应记录类型的字节长度上限（按 UTF-8 字节计算），按记录顺序返回超限记录
+pub fn length_violations(records: &[StringRecord], limits: &[LengthLimit]) -> Vec<LengthViolation> {
+    records
+        .iter()
+        .filter_map(|r| {
+            let limit = limits
+                .iter()
+                .filter_map(|l| Some((l.specificity(&r.record_type, &r.subrecord_type)?, l)))
+                .max_by_key(|(score, _)| *score)?
+                .1;
+            let byte_len = r.translated_text.len();
+            (byte_len > limit.max_bytes).then(|| LengthViolation {
+                form_id: r.form_id.clone(),
+                record_type: r.record_type.clone(),
+                subrecord_type: r.subrecord_type.clone(),
+                index: r.index,
+                translated_text: r.translated_text.clone(),
+                byte_len,
+                max_bytes: limit.max_bytes,
+            })
+        })
+        .collect()
+}
+
+/// 将插件在翻译数据库中的孤立译文按 EditorID + 记录类型 + 原文 匹配到 Session 中尚无译文的记录
+///
+/// 用于插件更新后 FormID 被重新编号、EditorID 保持不变的情况；一对一匹配才计入 `matched`
+pub fn match_orphans_by_editor_id(
+    records: &[StringRecord],
+    tm_rows: Vec<Translation>,
+) -> EditorIdRemapReport {
+    // 不含 form_id 的匹配 key（EditorID|类型|子类型|序号|原文）
+    fn match_key(
+        editor_id: &str,
+        record_type: &str,
+        subrecord_type: &str,
+        index: u32,
+        text: &str,
+    ) -> String {
+        format!("{}|{}|{}|{}|{}", editor_id, record_type, subrecord_type, index, text)
+    }
+
+    let session_keys: HashSet<String> = records.iter().map(RecordKey::record_key).collect();
+    let tm_keys: HashSet<String> = tm_rows.iter().map(RecordKey::record_key).collect();
+
+    // 尚无译文的 Session 记录
+    let mut candidates: HashMap<String, Vec<&StringRecord>> = HashMap::new();
+    for r in records {
+        if let Some(editor_id) = r.editor_id.as_deref() {
+            if !tm_keys.contains(&r.record_key()) {
+                let key = match_key(
+                    editor_id,
+                    &r.record_type,
+                    &r.subrecord_type,
+                    r.index,
+                    &r.original_text,
+                );
+                candidates.entry(key).or_default().push(r);
+            }
+        }
+    }
+
+    // 孤立译文（key 已不在 Session 中）
+    let orphans: Vec<(Translation, Option<String>)> = tm_rows
+        .into_iter()
+        .filter(|t| !session_keys.contains(&t.record_key()))
+        .map(|t| {
+            let key = t.editor_id.as_deref().map(|editor_id| {
+                match_key(editor_id, &t.record_type, &t.subrecord_type, t.index, &t.original_text)
+            });
+            (t, key)
+        })
+        .collect();
+    let mut orphans_per_key: HashMap<&str, usize> = HashMap::new();
+    for key in orphans.iter().filter_map(|(_, key)| key.as_deref()) {
+        *orphans_per_key.entry(key).or_insert(0) += 1;
+    }
+
+    let mut report = EditorIdRemapReport::default();
+    for (orphan, key) in &orphans {
+        let targets = key.as_deref().and_then(|key| candidates.get(key).map(|c| (key, c)));
+        match targets {
+            Some((key, targets)) if targets.len() == 1 && orphans_per_key[key] == 1 => {
+                report.matched.push(FormIdRemap {
+                    old_form_id: orphan.form_id.clone(),
+                    new_form_id: targets[0].form_id.clone(),
+                    editor_id: orphan.editor_id.clone().unwrap_or_default(),
+                    record_type: orphan.record_type.clone(),
+                    subrecord_type: orphan.subrecord_type.clone(),
+                    index: orphan.index,
+                })
+            }
+            Some(_) => report.ambiguous.push(orphan.record_key()),
+            None => report.unmatched.push(orphan.record_key()),
+        }
+    }
+
+    report
+}
+
+/// 按 key 比对 Session 记录与覆盖数据库，返回最终文本来自其他插件且与当前译文不同的记录
+///
+/// 由插件自身决定最终文本的记录不作为候选（即该插件未被覆盖）
+pub fn coverage_fill_candidates(
+    plugin_name: &str,
+    records: &[StringRecord],
+    entries: Vec<CoverageEntry>,
+) -> Vec<CoverageFillCandidate> {
+    let winners: HashMap<String, CoverageEntry> = entries
+        .into_iter()
+        .map(|e| {
+            let key = e.record_key();
+            (key, e)
+        })
+        .collect();
+
+    records
+        .iter()
+        .filter_map(|r| {
+            let key = r.record_key();
+            let winner = winners.get(&key)?;
+            if winner.source_mod.eq_ignore_ascii_case(plugin_name)
+                || winner.text == r.translated_text
+            {
+                return None;
+            }
+            Some(CoverageFillCandidate {
+                form_id: r.form_id.clone(),
+                record_type: r.record_type.clone(),
+                subrecord_type: r.subrecord_type.clone(),
+                index: r.index,
+                current_text: r.translated_text.clone(),
+                coverage_text: winner.text.clone(),
+                source_mod: winner.source_mod.clone(),
+                load_order_pos: winner.load_order_pos,
+            })
+        })
+        .collect()
+}
+
+/// 为未翻译的记录按原文选出最合适的译文
+///
+/// 同一原文有多条译文时取 `priority` 中排名最靠前的来源，同一来源内取最近更新的一条；
+/// 不匹配任何来源规则的译文不会被采用（如优先级中不含 `any` 时忽略无关插件的译文）
+pub fn text_fill_candidates(
+    plugin_name: &str,
+    records: &[StringRecord],
+    translations: Vec<Translation>,
+    priority: &[TranslationSource],
+) -> Vec<TextFillCandidate> {
+    let mut best: HashMap<String, (usize, Translation)> = HashMap::new();
+    for t in translations {
+        if is_untranslated_text(&t.original_text, &t.translated_text) {
+            continue;
+        }
+        let Some(rank) = priority
+            .iter()
+            .position(|s| s.matches(plugin_name, t.plugin_name.as_deref()))
+        else {
+            continue;
+        };
+        match best.get(&t.original_text) {
+            Some((best_rank, current))
+                if (*best_rank, -current.updated_at) <= (rank, -t.updated_at) => {}
+            _ => {
+                best.insert(t.original_text.clone(), (rank, t));
+            }
+        }
+    }
+
+    records
+        .iter()
+        .filter(|r| is_untranslated_text(&r.original_text, &r.translated_text))
+        .filter_map(|r| {
+            let (rank, t) = best.get(&r.original_text)?;
+            Some(TextFillCandidate {
+                form_id: r.form_id.clone(),
+                record_type: r.record_type.clone(),
+                subrecord_type: r.subrecord_type.clone(),
+                index: r.index,
+                translated_text: t.translated_text.clone(),
+                source_plugin: t.plugin_name.clone(),
+                source: priority[*rank],
+            })
+        })
+        .collect()
+}
+
+/// 根据插件完整路径生成 Session ID
+///
+/// 同名插件可能来自不同目录，因此以规范化后的完整路径计算 FNV-1a 哈希，
+/// 并保留文件名前缀便于日志阅读；路径无法规范化时直接使用原路径
+pub fn session_id_for_path(plugin_path: &Path) -> String {
+    let canonical = fs::canonicalize(plugin_path).unwrap_or_else(|_| plugin_path.to_path_buf());
+    let hash = fnv1a_hash(&canonical.to_string_lossy());
+    let file_name = plugin_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}#{:016x}", file_name, hash)
+}
+
+/// 合并 Session ID 的前缀（与单插件 Session ID 区分）
+const COMBINED_SESSION_PREFIX: &str = "combined#";
+
+/// 根据成员 Session ID 生成合并 Session ID（成员及顺序相同则 ID 相同）
+fn combined_session_id(member_ids: &[String]) -> String {
+    format!("{}{:016x}", COMBINED_SESSION_PREFIX, fnv1a_hash(&member_ids.join("\n")))
+}
+
+/// Session 管理器错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// Session、合并 Session 或记录不存在
+    NotFound(String),
+    /// 其他错误（插件读写失败、参数无效等）
+    Other(String),
+}
+
+impl SessionError {
+    fn session_not_found(session_id: &str) -> Self {
+        SessionError::NotFound(format!("Session {} 不存在", session_id))
+    }
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::NotFound(msg) | SessionError::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<String> for SessionError {
+    fn from(msg: String) -> Self {
+        SessionError::Other(msg)
+    }
+}
+
+impl From<&str> for SessionError {
+    fn from(msg: &str) -> Self {
+        SessionError::Other(msg.to_string())
+    }
 }
 
 /// Session 管理器
 pub struct PluginSessionManager {
     sessions: HashMap<String, PluginSession>,
+    combined: HashMap<String, CombinedSession>,
 }
 
 impl PluginSessionManager {
@@ -70,6 +1186,7 @@ impl PluginSessionManager {
     pub fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            combined: HashMap::new(),
         }
     }
 
@@ -77,29 +1194,39 @@ impl PluginSessionManager {
     ///
     /// # 参数
     /// * `plugin_path` - 插件文件的完整路径
+    /// * `dsd_template` - DSD 路径模板（用于查找插件旁已有的 DSD 覆盖）
     ///
     /// # 返回
     /// * `Ok(PluginStringsResponse)` - Session ID 和字符串数据
-    /// * `Err(String)` - 错误信息
-    pub fn get_or_load(&mut self, plugin_path: PathBuf) -> Result<PluginStringsResponse, String> {
-        // 提取插件名称作为 session_id
+    /// * `Err(SessionError)` - 错误信息
+    pub fn get_or_load(
+        &mut self,
+        plugin_path: PathBuf,
+        dsd_template: &str,
+    ) -> Result<PluginStringsResponse, SessionError> {
+        // 插件名称仅用于显示，session_id 由完整路径生成（文件操作始终使用原始 PathBuf）
         let plugin_name = plugin_path
             .file_name()
-            .and_then(|n| n.to_str())
             .ok_or("无效的插件路径")?
+            .to_string_lossy()
             .to_string();
+        let session_id = session_id_for_path(&plugin_path);
 
         // 检查缓存
-        if let Some(session) = self.sessions.get(&plugin_name) {
-            println!("✓ 使用缓存的 Session: {}", plugin_name);
+        if let Some(session) = self.sessions.get(&session_id) {
+            println!("✓ 使用缓存的 Session: {}", session_id);
             // ✅ 只克隆 Arc 指向的数据（引用计数增加，不深度复制）
             return Ok(PluginStringsResponse {
-                session_id: plugin_name.clone(),
+                session_id,
                 plugin_name: plugin_name.clone(),
                 plugin_path: plugin_path.to_string_lossy().to_string(),
                 strings: (*session.strings).clone(), // 只在这里克隆一次
                 total_count: session.strings.len(),
                 has_dsd_overrides: session.has_dsd_overrides,
+                dsd_overrides: session.dsd_overrides,
+                duplicate_keys: session.duplicate_keys.clone(),
+                bsa_warning: session.bsa_warning.clone(),
+                metadata: session.metadata.clone(),
             });
         }
 
@@ -114,10 +1241,16 @@ impl PluginSessionManager {
         let extracted = loaded.extract_strings();
         println!("✓ 提取到 {} 条字符串", extracted.len());
 
+        let bsa_warning = check_bsa_presence(&plugin_path, &loaded, extracted.len());
+        if let Some(warning) = &bsa_warning {
+            println!("⚠️ {}", warning);
+        }
+
         // 转换为 StringRecord
         let mut strings: Vec<StringRecord> = extracted
             .into_iter()
             .map(|s| StringRecord {
+                strings_table: StringsTable::classify(&s.record_type, &s.subrecord_type),
                 form_id: s.form_id,
                 editor_id: s.editor_id,
                 record_type: s.record_type,
@@ -126,22 +1259,39 @@ impl PluginSessionManager {
                 original_text: s.text.clone(),
                 translated_text: s.text, // 初始复制 original_text
                 translation_status: "untranslated".to_string(), // 初始状态：未翻译
+                source_plugin: None,
             })
             .collect();
 
+        // 检测重复 key（重复记录会在翻译数据库与 DSD 映射中互相覆盖）
+        let duplicate_keys = find_duplicate_keys(&strings);
+        if !duplicate_keys.is_empty() {
+            println!("⚠️ 检测到 {} 个重复的记录 key", duplicate_keys.len());
+        }
+
         // 读取 DSD 覆盖（若存在）并套用（直接从插件目录旁的 SKSE/Plugins/DynamicStringDistributor/<插件名>/ 中读取）
         let mut has_dsd_overrides = false;
-        if let Some(overrides) = load_dsd_overrides(&plugin_path)? {
-            let applied = Self::apply_dsd_overrides_to_records(&mut strings, &overrides);
-            if applied > 0 {
+        let mut dsd_overrides = None;
+        if let Some(overrides) = load_dsd_overrides(&plugin_path, dsd_template)? {
+            let summary = Self::apply_dsd_overrides_to_records(&mut strings, &overrides);
+            if summary.applied > 0 {
                 has_dsd_overrides = true;
-                println!("✓ DSD 覆盖 {} 条记录", applied);
+                println!("✓ DSD 覆盖 {} 条记录", summary.applied);
             } else {
                 println!("⚠️ 检测到 DSD 目录但无匹配的条目");
             }
+            if summary.unmatched > 0 {
+                println!(
+                    "⚠️ {} / {} 条 DSD 条目找不到对应记录",
+                    summary.unmatched, summary.total
+                );
+            }
+            dsd_overrides = Some(summary);
         }
 
         let total_count = strings.len();
+        let dialogue_groups = group_dialogue_records(&strings);
+        let metadata = PluginMetadata::from_plugin(loaded.plugin());
 
         // ✅ 将 strings 包装在 Arc 中，支持共享
         let strings_arc = Arc::new(strings);
@@ -153,135 +1303,542 @@ impl PluginSessionManager {
             strings: Arc::clone(&strings_arc),
             loaded_at: Instant::now(),
             has_dsd_overrides,
+            dsd_overrides,
+            duplicate_keys: duplicate_keys.clone(),
+            dirty: false,
+            bsa_warning: bsa_warning.clone(),
+            metadata: metadata.clone(),
+            dialogue_groups,
+            stat_token: stat_token(&plugin_path).ok(),
             loaded_plugin: Some(loaded),
         };
 
         // 缓存 Session
-        self.sessions.insert(plugin_name.clone(), session);
-        println!("✓ Session 已缓存: {}", plugin_name);
+        self.sessions.insert(session_id.clone(), session);
+        println!("✓ Session 已缓存: {}", session_id);
 
         Ok(PluginStringsResponse {
-            session_id: plugin_name.clone(),
+            session_id,
             plugin_name,
             plugin_path: plugin_path.to_string_lossy().to_string(),
             strings: (*strings_arc).clone(), // 只在返回时克隆一次
             total_count,
             has_dsd_overrides,
+            dsd_overrides,
+            duplicate_keys,
+            bsa_warning,
+            metadata,
+        })
+    }
+
+    /// 将多个插件加载为一个合并 Session（用于拆分为多个插件的 Mod）
+    ///
+    /// 每个插件仍作为独立 Session 加载并缓存（可单独打开），合并 Session 只记录成员；
+    /// 返回的记录按插件顺序拼接，并以 `source_plugin` 标记来源。
+    /// 成员插件名必须互不相同（写回时按插件名路由）
+    pub fn load_combined(
+        &mut self,
+        plugin_paths: Vec<PathBuf>,
+        dsd_template: &str,
+    ) -> Result<CombinedStringsResponse, SessionError> {
+        if plugin_paths.len() < 2 {
+            return Err("合并 Session 至少需要两个插件".into());
+        }
+
+        let mut responses: Vec<PluginStringsResponse> = Vec::with_capacity(plugin_paths.len());
+        for plugin_path in plugin_paths {
+            let response = self.get_or_load(plugin_path, dsd_template)?;
+            let duplicate = responses
+                .iter()
+                .any(|r| r.plugin_name.eq_ignore_ascii_case(&response.plugin_name));
+            if duplicate {
+                return Err(format!("合并 Session 中存在同名插件: {}", response.plugin_name).into());
+            }
+            responses.push(response);
+        }
+
+        let member_ids: Vec<String> = responses.iter().map(|r| r.session_id.clone()).collect();
+        let session_id = combined_session_id(&member_ids);
+
+        let mut strings = Vec::new();
+        let mut members = Vec::with_capacity(responses.len());
+        for response in responses {
+            let source = response.plugin_name.clone();
+            strings.extend(response.strings.into_iter().map(|record| StringRecord {
+                source_plugin: Some(source.clone()),
+                ..record
+            }));
+            members.push(CombinedSessionMember {
+                session_id: response.session_id,
+                plugin_name: response.plugin_name,
+                plugin_path: response.plugin_path,
+                total_count: response.total_count,
+                duplicate_keys: response.duplicate_keys,
+                bsa_warning: response.bsa_warning,
+                metadata: response.metadata,
+            });
+        }
+
+        self.combined.insert(
+            session_id.clone(),
+            CombinedSession {
+                members: members
+                    .iter()
+                    .map(|m| (m.plugin_name.clone(), m.session_id.clone()))
+                    .collect(),
+            },
+        );
+        println!("✓ 合并 Session 已缓存: {} ({} 个插件)", session_id, members.len());
+
+        Ok(CombinedStringsResponse {
+            session_id,
+            total_count: strings.len(),
+            members,
+            strings,
         })
     }
 
+    /// 将合并 Session 的翻译按 `source_plugin` 写回各自的插件文件（不支持另存为）
+    ///
+    /// 先校验所有记录的来源再逐个写回；某个插件写回失败时，之前的插件已写入（均有备份）
+    pub fn apply_combined_translations(
+        &mut self,
+        combined_id: &str,
+        translations: Vec<StringRecord>,
+        options: &ApplyOptions,
+    ) -> Result<Vec<CombinedApplyEntry>, SessionError> {
+        let combined = self
+            .combined
+            .get(combined_id)
+            .ok_or_else(|| SessionError::NotFound(format!("合并 Session {} 不存在", combined_id)))?;
+        if let Some((plugin_name, _)) = combined
+            .members
+            .iter()
+            .find(|(_, session_id)| !self.sessions.contains_key(session_id))
+        {
+            return Err(format!("合并 Session 的成员 {} 已关闭，请重新加载", plugin_name).into());
+        }
+        let groups = partition_by_source_plugin(&combined.members, translations)?;
+
+        let mut results = Vec::with_capacity(groups.len());
+        for (plugin_name, session_id, records) in groups {
+            let result = self
+                .apply_translations(&session_id, records, None, options)
+                .map_err(|e| format!("写回 {} 失败: {}", plugin_name, e))?;
+            results.push(CombinedApplyEntry { plugin_name, result });
+        }
+        Ok(results)
+    }
+
     /// 关闭指定的 Session
     ///
+    /// 关闭合并 Session 只移除合并关系，成员 Session 保持打开
+    ///
     /// # 参数
-    /// * `session_id` - Session ID（即插件名称）
+    /// * `session_id` - Session ID（由插件完整路径生成）
     ///
     /// # 返回
     /// * `Ok(())` - 成功关闭
-    /// * `Err(String)` - Session 不存在
-    pub fn close(&mut self, session_id: &str) -> Result<(), String> {
+    /// * `Err(SessionError)` - Session 不存在
+    pub fn close(&mut self, session_id: &str) -> Result<(), SessionError> {
+        if self.combined.remove(session_id).is_some() {
+            println!("✓ 合并 Session 已关闭: {}", session_id);
+            return Ok(());
+        }
         self.sessions
             .remove(session_id)
-            .ok_or_else(|| format!("Session {} 不存在", session_id))?;
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
         println!("✓ Session 已关闭: {}", session_id);
         Ok(())
     }
 
-    /// 列出所有活跃的 Session
+    /// 关闭所有 Session，返回被关闭的 Session 信息（含未保存标记）
+    pub fn close_all(&mut self) -> Vec<SessionInfo> {
+        let closed = self.list_sessions();
+        self.sessions.clear();
+        self.combined.clear();
+        println!("✓ 已关闭全部 {} 个 Session", closed.len());
+        closed
+    }
+
+    /// 标记 Session 是否存在未保存的修改
+    pub fn mark_dirty(&mut self, session_id: &str, dirty: bool) -> Result<(), SessionError> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+        session.dirty = dirty;
+        Ok(())
+    }
+
+    /// 检查指定 Session 是否存在
+    pub fn contains(&self, session_id: &str) -> bool {
+        self.sessions.contains_key(session_id)
+    }
+
+    /// 列出所有活跃的 Session
+    ///
+    /// # 返回
+    /// * `Vec<SessionInfo>` - Session 信息列表
+    pub fn list_sessions(&self) -> Vec<SessionInfo> {
+        self.sessions
+            .iter()
+            .map(|(session_id, session)| {
+                let elapsed = session.loaded_at.elapsed().as_secs();
+                SessionInfo {
+                    session_id: session_id.clone(),
+                    plugin_name: session.plugin_name.clone(),
+                    string_count: session.strings.len(),
+                    loaded_at: elapsed,
+                    dirty: session.dirty,
+                    stale: session.is_stale(),
+                }
+            })
+            .collect()
+    }
+
+    /// 获取插件依赖的主文件列表（已打开 Session 时直接使用缓存结果）
+    pub fn get_masters(&self, plugin_path: &Path) -> Result<Vec<String>, SessionError> {
+        if let Some(session) = self.sessions.get(&session_id_for_path(plugin_path)) {
+            return Ok(session.metadata.masters.clone());
+        }
+        read_plugin_masters(plugin_path)
+            .map_err(|e| SessionError::from(format!("读取主文件列表失败: {}", e)))
+    }
+
+    /// 获取指定 Session 的插件名称
+    pub fn get_plugin_name(&self, session_id: &str) -> Option<String> {
+        self.sessions.get(session_id).map(|session| session.plugin_name.clone())
+    }
+
+    /// 获取指定 Session 的记录（加载时的内容，共享 Arc 不复制）
+    pub fn get_strings(&self, session_id: &str) -> Option<Arc<Vec<StringRecord>>> {
+        self.sessions.get(session_id).map(|session| Arc::clone(&session.strings))
+    }
+
+    /// 获取指定 Session 的原文记录（用于与参考译文按 key 对齐）
+    pub fn get_original_strings(&self, session_id: &str) -> Option<Vec<ExtractedString>> {
+        self.sessions.get(session_id).map(|session| {
+            session
+                .strings
+                .iter()
+                .map(|record| ExtractedString {
+                    form_id: record.form_id.clone(),
+                    editor_id: record.editor_id.clone(),
+                    text: record.original_text.clone(),
+                    record_type: record.record_type.clone(),
+                    subrecord_type: record.subrecord_type.clone(),
+                    index: record.index as i32,
+                })
+                .collect()
+        })
+    }
+
+    /// 批量设置 Session 记录的翻译状态
+    ///
+    /// # 参数
+    /// * `session_id` - Session ID
+    /// * `keys` - 目标记录的完整 key
+    /// * `status` - 新状态（untranslated/manual/ai）
+    ///
+    /// # 返回
+    /// * `Ok(Vec<StringRecord>)` - 更新后的记录
+    /// * `Err(SessionError)` - Session 不存在或状态值无效
+    pub fn set_records_status(
+        &mut self,
+        session_id: &str,
+        keys: &[FormIdentifier],
+        status: &str,
+    ) -> Result<Vec<StringRecord>, SessionError> {
+        if !VALID_TRANSLATION_STATUSES.contains(&status) {
+            return Err(format!("无效的翻译状态: {}", status).into());
+        }
+
+        self.update_records(session_id, keys, |record| {
+            record.translation_status = status.to_string();
+            true
+        })
+    }
+
+    /// 将选中的未翻译记录的译文设为原文（如人名、地名等无需翻译的条目），状态改为 manual
+    ///
+    /// 已翻译的记录保持不变，返回实际修改的记录
+    pub fn copy_source_to_target(
+        &mut self,
+        session_id: &str,
+        keys: &[FormIdentifier],
+    ) -> Result<Vec<StringRecord>, SessionError> {
+        self.update_records(session_id, keys, |record| {
+            if record.translation_status != "untranslated" {
+                return false;
+            }
+            record.translated_text = record.original_text.clone();
+            record.translation_status = "manual".to_string();
+            true
+        })
+    }
+
+    /// 清除选中记录的译文（恢复为原文），状态改为 untranslated，返回修改后的记录
+    ///
+    /// 不置为空字符串：写回时未翻译的记录会按原样写入，空译文会清空插件中的文本
+    pub fn clear_target(
+        &mut self,
+        session_id: &str,
+        keys: &[FormIdentifier],
+    ) -> Result<Vec<StringRecord>, SessionError> {
+        self.update_records(session_id, keys, |record| {
+            record.translated_text = record.original_text.clone();
+            record.translation_status = "untranslated".to_string();
+            true
+        })
+    }
+
+    /// 按 key 修改 Session 中的记录，`update` 返回 true 的记录计入返回结果
+    fn update_records(
+        &mut self,
+        session_id: &str,
+        keys: &[FormIdentifier],
+        mut update: impl FnMut(&mut StringRecord) -> bool,
+    ) -> Result<Vec<StringRecord>, SessionError> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let targets: HashSet<String> = keys.iter().map(RecordKey::record_key).collect();
+
+        let strings = Arc::make_mut(&mut session.strings);
+        let mut updated = Vec::new();
+        for record in strings.iter_mut() {
+            if targets.contains(&record.record_key()) && update(record) {
+                updated.push(record.clone());
+            }
+        }
+
+        Ok(updated)
+    }
+
+    /// 获取对话记录的上下文（同一话题下前后各 `radius` 条记录）
+    ///
+    /// 记录文本取自 Session（加载时的内容），前端可按 key 合并未保存的编辑；
+    /// 非对话记录返回 None
+    pub fn get_record_context(
+        &self,
+        session_id: &str,
+        key: &FormIdentifier,
+        radius: usize,
+    ) -> Result<Option<RecordContext>, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let position = session
+            .strings
+            .iter()
+            .position(|r| {
+                r.form_id == key.form_id
+                    && r.record_type == key.record_type
+                    && r.subrecord_type == key.subrecord_type
+                    && r.index == key.index
+            })
+            .ok_or_else(|| SessionError::NotFound(format!("记录 {} 不存在", key.form_id)))?;
+
+        Ok(record_context(&session.strings, &session.dialogue_groups, position, radius))
+    }
+
+    /// 导出仍需翻译的记录（状态为 untranslated 或译文与原文相同）到交换文件，返回导出条数
+    ///
+    /// # 参数
+    /// * `records` - 当前编辑中的记录（来自前端）
+    pub fn export_untranslated(
+        &self,
+        session_id: &str,
+        records: &[StringRecord],
+        path: &Path,
+        format: ExchangeFormat,
+        encoding: ExchangeEncoding,
+    ) -> Result<usize, SessionError> {
+        if !self.sessions.contains_key(session_id) {
+            return Err(SessionError::session_not_found(session_id));
+        }
+
+        let pending: Vec<&StringRecord> = records
+            .iter()
+            .filter(|r| {
+                r.translation_status == "untranslated"
+                    || is_untranslated_text(&r.original_text, &r.translated_text)
+            })
+            .collect();
+        write_exchange_file(path, &pending, format, encoding).map_err(SessionError::from)
+    }
+
+    /// 读取交换文件中的译文并按 key 匹配 Session 记录
     ///
-    /// # 返回
-    /// * `Vec<SessionInfo>` - Session 信息列表
-    pub fn list_sessions(&self) -> Vec<SessionInfo> {
-        self.sessions
+    /// 不修改 Session，返回填入译文（状态为 manual）的记录供前端应用，
+    /// 以及文件中未匹配到记录的 key
+    pub fn import_untranslated(
+        &self,
+        session_id: &str,
+        path: &Path,
+        format: ExchangeFormat,
+        encoding: Option<ExchangeEncoding>,
+    ) -> Result<ExchangeImportResult, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let mut translations: HashMap<String, String> =
+            read_exchange_file(path, format, encoding)?.into_iter().collect();
+
+        let records: Vec<StringRecord> = session
+            .strings
             .iter()
-            .map(|(name, session)| {
-                let elapsed = session.loaded_at.elapsed().as_secs();
-                SessionInfo {
-                    session_id: name.clone(),
-                    plugin_name: session.plugin_name.clone(),
-                    string_count: session.strings.len(),
-                    loaded_at: elapsed,
-                }
+            .filter_map(|r| {
+                let text = translations.remove(&r.record_key())?;
+                Some(StringRecord {
+                    translated_text: text,
+                    translation_status: "manual".to_string(),
+                    ..r.clone()
+                })
             })
-            .collect()
-    }
+            .collect();
 
-    /// 获取指定 Session 的原文映射（key -> original_text）
-    pub fn get_original_string_map(&self, session_id: &str) -> Option<HashMap<String, String>> {
-        self.sessions.get(session_id).map(|session| {
-            session
-                .strings
-                .iter()
-                .map(|record| {
-                    (
-                        format!(
-                            "{}|{}|{}|{}",
-                            record.form_id, record.record_type, record.subrecord_type, record.index
-                        ),
-                        record.original_text.clone(),
-                    )
-                })
-                .collect()
+        let mut unmatched_keys: Vec<String> = translations.into_keys().collect();
+        unmatched_keys.sort();
+
+        Ok(ExchangeImportResult {
+            records,
+            unmatched_keys,
         })
     }
 
+    /// 将 Session 中已翻译的记录转换为翻译数据库记录（plugin_name 取自 Session）
+    ///
+    /// # 参数
+    /// * `session_id` - Session ID
+    /// * `records` - 翻译记录列表（来自前端），状态为 untranslated 的记录会被忽略
+    pub fn build_tm_translations(
+        &self,
+        session_id: &str,
+        records: Vec<StringRecord>,
+    ) -> Result<Vec<Translation>, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        Ok(records
+            .into_iter()
+            .filter(|r| r.translation_status != "untranslated")
+            .map(|r| Translation {
+                strings_table: StringsTable::classify(&r.record_type, &r.subrecord_type),
+                form_id: r.form_id,
+                record_type: r.record_type,
+                subrecord_type: r.subrecord_type,
+                index: r.index,
+                editor_id: r.editor_id,
+                original_text: r.original_text,
+                translated_text: r.translated_text,
+                plugin_name: Some(session.plugin_name.clone()),
+                created_at: now,
+                updated_at: now,
+                note: None,
+            })
+            .collect())
+    }
+
     /// 应用翻译到插件文件
     ///
     /// # 参数
     /// * `session_id` - Session ID
     /// * `translations` - 翻译记录列表
     /// * `save_as` - 另存为路径（可选，如果为 None 则覆盖原文件）
+    /// * `options` - 临时目录、回读校验等写回选项
     ///
     /// # 返回
-    /// * `Ok(String)` - 保存的路径
-    /// * `Err(String)` - 错误信息
+    /// * `Ok(ApplyResult)` - 保存路径、备份路径与校验结果
+    /// * `Err(SessionError)` - 错误信息
     pub fn apply_translations(
         &mut self,
         session_id: &str,
         translations: Vec<StringRecord>,
         save_as: Option<String>,
-    ) -> Result<String, String> {
+        options: &ApplyOptions,
+    ) -> Result<ApplyResult, SessionError> {
+        let ApplyOptions {
+            temp_dir,
+            verify,
+            only_changed,
+            threads,
+            protected_dirs,
+        } = options;
+
         let session = self
             .sessions
             .get_mut(session_id)
-            .ok_or_else(|| format!("Session {} 不存在", session_id))?;
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let translations = if *only_changed {
+            let changed = filter_changed_records(&session.strings, translations);
+            println!("✓ 仅写入有变更的记录: {} 条", changed.len());
+            changed
+        } else {
+            translations
+        };
 
         let plugin_path = session.plugin_path.clone();
+        if let Some(ref path) = save_as {
+            validate_save_as_path(Path::new(path), &plugin_path)?;
+        }
+        let write_target = save_as.as_ref().map(PathBuf::from);
+        ensure_outside_protected_dirs(write_target.as_ref().unwrap_or(&plugin_path), protected_dirs)?;
         let timestamp = chrono::Local::now().format("%Y_%m_%d_%H_%M_%S").to_string();
+        let mut backup_path = None;
         let target_path = if let Some(ref path) = save_as {
             PathBuf::from(path)
         } else {
             // 备份原文件
-            let backup_path = format!("{}.{}.bak", plugin_path.to_string_lossy(), timestamp);
-            fs::copy(&plugin_path, &backup_path).map_err(|e| format!("备份文件失败: {}", e))?;
-            println!("✓ 已备份原文件: {}", backup_path);
+            let mut backup = plugin_path.clone().into_os_string();
+            backup.push(format!(".{}.bak", timestamp));
+            let backup = PathBuf::from(backup);
+            fs::copy(&plugin_path, &backup).map_err(|e| format!("备份文件失败: {}", e))?;
+            println!("✓ 已备份原文件: {}", backup.display());
+            backup_path = Some(backup.to_string_lossy().to_string());
             plugin_path.clone()
         };
 
         // 当覆盖原文件时，先写入临时文件，写入完成后再替换，避免被内存映射锁住
         let temp_output_path = if save_as.is_none() {
-            let mut temp = target_path.clone();
-            // e.g. plugin.esp -> plugin.esp.<timestamp>.tmp
-            let tmp_ext = format!("{}.tmp", timestamp);
-            temp.set_extension(tmp_ext);
-            Some(temp)
+            if let Some(dir) = temp_dir.as_deref() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("创建临时目录失败 ({}): {}", dir.display(), e))?;
+                let mut file_name = target_path
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| session.plugin_name.clone().into());
+                file_name.push(format!(".{}.tmp", timestamp));
+                Some(dir.join(file_name))
+            } else {
+                let mut temp = target_path.clone();
+                // e.g. plugin.esp -> plugin.esp.<timestamp>.tmp
+                let tmp_ext = format!("{}.tmp", timestamp);
+                temp.set_extension(tmp_ext);
+                Some(temp)
+            }
         } else {
             None
         };
 
         println!("⏳ 正在应用翻译到: {:?}", target_path);
 
-        // 转换为 ExtractedString (并行处理)
-        let extracted_strings: Vec<ExtractedString> = translations
-            .par_iter()
-            .map(|r| ExtractedString {
-                form_id: r.form_id.clone(),
-                editor_id: r.editor_id.clone(),
-                text: r.translated_text.clone(), // 使用翻译后的文本
-                record_type: r.record_type.clone(),
-                subrecord_type: r.subrecord_type.clone(),
-                index: r.index as i32,
-            })
-            .collect();
+        let extracted_strings = to_extracted_strings(&translations, *threads)?;
 
         // 获取 LoadedPlugin (优先使用缓存，否则重新加载)
         let loaded = if let Some(loaded) = session.loaded_plugin.take() {
@@ -316,22 +1873,124 @@ impl PluginSessionManager {
         // 如果写入临时文件，写入完后再替换原文件
         if let Some(temp_path) = temp_output_path {
             if target_path.exists() {
-                fs::remove_file(&target_path)
+                retry_file_op(|| fs::remove_file(&target_path))
                     .map_err(|e| format!("替换原文件失败: {}", e))?;
             }
-            fs::rename(&temp_path, &target_path)
+            retry_file_op(|| move_file(&temp_path, &target_path))
                 .map_err(|e| format!("写入翻译文件失败: {}", e))?;
         }
 
-        Ok(target_path.to_string_lossy().to_string())
+        // 回读校验：确认译文确实写入（备份始终保留，便于不一致时回滚）
+        let mut mismatched_keys = Vec::new();
+        let mismatch_count = if *verify {
+            let mismatched = verify_written_plugin(&target_path, &translations)?;
+            if !mismatched.is_empty() {
+                eprintln!(
+                    "⚠️ 回读校验发现 {} 条记录与预期译文不一致，原文件备份: {}",
+                    mismatched.len(),
+                    backup_path.as_deref().unwrap_or("无")
+                );
+            } else {
+                println!("✓ 回读校验通过");
+            }
+            let count = mismatched.len();
+            mismatched_keys = mismatched
+                .into_iter()
+                .take(VERIFY_MISMATCH_SAMPLE_LIMIT)
+                .collect();
+            Some(count)
+        } else {
+            None
+        };
+
+        Ok(ApplyResult {
+            saved_path: target_path.to_string_lossy().to_string(),
+            backup_path,
+            mismatch_count,
+            mismatched_keys,
+            applied_count: translations.len(),
+        })
+    }
+
+    /// 撤销最近一次写回：用最新的备份覆盖插件文件，并移除缓存的 Session 以便重新加载
+    ///
+    /// 备份文件会保留，返回被恢复的插件路径；插件位于 `protected_dirs`（只读模式）内时拒绝
+    pub fn undo_last_apply(
+        &mut self,
+        session_id: &str,
+        protected_dirs: &[PathBuf],
+    ) -> Result<String, SessionError> {
+        let plugin_path = self.session_plugin_path(session_id)?;
+        ensure_outside_protected_dirs(&plugin_path, protected_dirs)?;
+
+        let backup = find_latest_backup(&plugin_path)?
+            .ok_or_else(|| format!("未找到 {} 的备份文件", session_id))?;
+
+        self.restore_from(session_id, &plugin_path, &backup)
+    }
+
+    /// 列出 Session 对应插件的全部备份（按时间从新到旧）
+    pub fn list_backups(&self, session_id: &str) -> Result<Vec<PluginBackup>, SessionError> {
+        let plugin_path = self.session_plugin_path(session_id)?;
+        Ok(find_backups(&plugin_path)?
+            .iter()
+            .map(|backup| PluginBackup::from_path(&plugin_path, backup))
+            .collect())
+    }
+
+    /// 用指定的备份覆盖插件文件，并移除缓存的 Session 以便重新加载
+    ///
+    /// `backup_path` 必须是 [`Self::list_backups`] 返回的备份之一；
+    /// 其余规则同 [`Self::undo_last_apply`]
+    pub fn restore_backup(
+        &mut self,
+        session_id: &str,
+        backup_path: &Path,
+        protected_dirs: &[PathBuf],
+    ) -> Result<String, SessionError> {
+        let plugin_path = self.session_plugin_path(session_id)?;
+        ensure_outside_protected_dirs(&plugin_path, protected_dirs)?;
+
+        let backup = find_backups(&plugin_path)?
+            .into_iter()
+            .find(|b| b == backup_path)
+            .ok_or_else(|| format!("{} 不是 {} 的备份文件", backup_path.display(), session_id))?;
+
+        self.restore_from(session_id, &plugin_path, &backup)
+    }
+
+    fn session_plugin_path(&self, session_id: &str) -> Result<PathBuf, SessionError> {
+        self.sessions
+            .get(session_id)
+            .map(|session| session.plugin_path.clone())
+            .ok_or_else(|| SessionError::session_not_found(session_id))
+    }
+
+    fn restore_from(
+        &mut self,
+        session_id: &str,
+        plugin_path: &Path,
+        backup: &Path,
+    ) -> Result<String, SessionError> {
+        // 先释放缓存的 LoadedPlugin，避免原文件仍被占用；恢复失败时保留 Session
+        if let Some(session) = self.sessions.get_mut(session_id) {
+            session.loaded_plugin = None;
+        }
+
+        fs::copy(backup, plugin_path).map_err(|e| format!("恢复备份失败: {}", e))?;
+        self.sessions.remove(session_id);
+        println!("✓ 已从备份恢复: {} -> {}", backup.display(), plugin_path.display());
+
+        Ok(plugin_path.to_string_lossy().to_string())
     }
 
-    /// DSD 覆盖应用到 Session 记录
+    /// DSD 覆盖应用到 Session 记录，返回套用统计
     fn apply_dsd_overrides_to_records(
         records: &mut [StringRecord],
         overrides: &HashMap<String, String>,
-    ) -> usize {
+    ) -> DsdOverrideSummary {
         let mut applied = 0;
+        let mut matched_keys = HashSet::new();
         for record in records.iter_mut() {
             let key = make_record_key(&record.form_id, &record.record_type, &record.subrecord_type);
             if let Some(new_value) = overrides.get(&key) {
@@ -340,9 +1999,14 @@ impl PluginSessionManager {
                     record.translation_status = "manual".to_string();
                     applied += 1;
                 }
+                matched_keys.insert(key);
             }
         }
-        applied
+        DsdOverrideSummary {
+            total: overrides.len(),
+            applied,
+            unmatched: overrides.len() - matched_keys.len(),
+        }
     }
 
     /// 导出 DSD (Dynamic String Distributor) 格式的 JSON 文件
@@ -350,45 +2014,752 @@ impl PluginSessionManager {
     /// # 参数
     /// * `session_id` - Session ID
     /// * `records` - 要导出的翻译记录（来自前端）
-    /// * `output_base_dir` - 自定义输出基础目录（可选，如果为 None 则使用源文件所在目录）
+    /// * `layout` - 导出基础目录与路径模板（未设置导出目录时使用源文件所在目录）
+    /// * `merge` - 是否与已有的 DSD 文件合并（按 form_id + type，新值优先）
+    /// * `write_metadata` - 是否同时写入元数据文件
+    /// * `record_type_filter` - 仅导出匹配的记录（格式同提取时的过滤条件，如 `["INFO"]`）
     ///
     /// # 返回
-    /// * `Ok(String)` - 生成的文件路径
-    /// * `Err(String)` - 错误信息
+    /// * `Ok(DsdExportResult)` - 生成的文件路径、新增/更新数量与被过滤的记录数
+    /// * `Err(SessionError)` - 错误信息
     pub fn export_dsd(
         &self,
         session_id: &str,
         records: Vec<StringRecord>,
-        output_base_dir: Option<String>,
-    ) -> Result<String, String> {
+        layout: &DsdLayout,
+        merge: bool,
+        write_metadata: bool,
+        record_type_filter: Option<&[String]>,
+    ) -> Result<DsdExportResult, SessionError> {
         let session = self
             .sessions
             .get(session_id)
-            .ok_or_else(|| format!("Session {} 不存在", session_id))?;
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
 
         let plugin_path = &session.plugin_path;
+        let total_records = records.len();
+        let records = filter_records_by_type(records, record_type_filter);
+        let filtered_out = total_records - records.len();
+        let dsd_entries = records_to_dsd_entries(records);
+
+        let mut result =
+            export_dsd_entries(plugin_path, dsd_entries, layout, merge, write_metadata)?;
+        result.filtered_out = filtered_out;
+
+        println!(
+            "✓ DSD 导出成功: {} (新增 {}，更新 {}，跳过 {}，过滤 {})",
+            result.path, result.added, result.updated, result.skipped, result.filtered_out
+        );
+
+        Ok(result)
+    }
+
+    /// 预览 DSD 导出内容（路径、JSON 与记录类型过滤同 `export_dsd`，不写入文件）
+    pub fn preview_dsd(
+        &self,
+        session_id: &str,
+        records: Vec<StringRecord>,
+        layout: &DsdLayout,
+        merge: bool,
+        record_type_filter: Option<&[String]>,
+    ) -> Result<DsdPreview, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let records = filter_records_by_type(records, record_type_filter);
+        preview_dsd_entries(&session.plugin_path, records_to_dsd_entries(records), layout, merge)
+            .map_err(SessionError::from)
+    }
+
+    /// 检查 Session 插件是否已有 DSD 导出文件（遵循设置中的导出目录与路径模板）
+    pub fn dsd_status(
+        &self,
+        session_id: &str,
+        layout: &DsdLayout,
+    ) -> Result<DsdStatus, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        dsd_status(&session.plugin_path, layout).map_err(SessionError::from)
+    }
+
+    /// 导出为松散 STRINGS 文件（STRINGS/ILSTRINGS/DLSTRINGS），写入插件旁的 Strings 目录
+    ///
+    /// 仅适用于本地化插件，插件本身不做修改。
+    ///
+    /// # 参数
+    /// * `session_id` - Session ID
+    /// * `records` - 要导出的翻译记录（来自前端）
+    /// * `language` - 目标语言（用于文件名，如 "chinese"）
+    ///
+    /// # 返回
+    /// * `Ok(Vec<String>)` - 写入的文件路径
+    /// * `Err(SessionError)` - 错误信息
+    pub fn export_strings_files(
+        &self,
+        session_id: &str,
+        records: Vec<StringRecord>,
+        language: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::session_not_found(session_id))?;
+
+        let plugin_path = session.plugin_path.clone();
+        let plugin_dir = plugin_path
+            .parent()
+            .ok_or_else(|| "无法获取插件所在目录".to_string())?;
+
+        // 以英文 STRINGS 为模板（提供 StringID 映射），写出目标语言文件
+        log_bsa_presence(&plugin_path, Some("english"));
+        let loaded = LoadedPlugin::load_auto(plugin_path.clone(), Some("english"))
+            .map_err(|e| format!("加载插件失败: {}", e))?;
+        let context = match loaded {
+            LoadedPlugin::Localized(context) => context,
+            LoadedPlugin::Standard(plugin) if plugin.is_localized() => {
+                return Err("缺少英文 STRINGS 文件，无法导出".into());
+            }
+            LoadedPlugin::Standard(_) => {
+                return Err("插件未本地化，无法导出 STRINGS 文件".into());
+            }
+        };
 
-        // 转换为 DSD 格式
-        let dsd_entries: Vec<DsdEntry> = records
+        let (mut plugin, mut string_files, _) = context.into_parts();
+        string_files.language = language.to_string();
+        let file_types: Vec<StringFileType> = string_files.files.keys().copied().collect();
+        let file_stem = string_files.plugin_name.clone();
+        plugin.set_string_files(string_files);
+
+        let extracted_strings: Vec<ExtractedString> = records
             .into_iter()
-            .map(|r| DsdEntry {
+            .map(|r| ExtractedString {
                 form_id: r.form_id,
-                entry_type: format!("{} {}", r.record_type, r.subrecord_type),
-                string: r.translated_text,
+                editor_id: r.editor_id,
+                text: r.translated_text,
+                record_type: r.record_type,
+                subrecord_type: r.subrecord_type,
+                index: r.index as i32,
             })
             .collect();
 
-        let base_dir_override = output_base_dir
-            .as_ref()
-            .map(PathBuf::from);
-        let output_file = export_dsd_entries(
-            plugin_path,
-            &dsd_entries,
-            base_dir_override.as_deref(),
-        )?;
+        // 写入 plugin_dir/strings/{插件名}_{语言}.{扩展名}
+        plugin
+            .apply_translations_unified(extracted_strings, Some(plugin_dir))
+            .map_err(|e| format!("写入 STRINGS 文件失败: {}", e))?;
+
+        let strings_dir = plugin_dir.join("strings");
+        let written: Vec<String> = file_types
+            .into_iter()
+            .map(|file_type| {
+                strings_dir
+                    .join(format!("{}_{}.{}", file_stem, language, file_type.to_extension()))
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+
+        println!("✓ STRINGS 导出成功: {:?}", written);
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsd::DEFAULT_DSD_PATH_TEMPLATE;
+    use crate::utils::test_dir::TempDir;
+
+    /// 写出仅含 TES4 头的最小插件
+    fn write_empty_plugin(path: &Path) {
+        write_plugin_with_masters(path, &[]);
+    }
+
+    /// 写出仅含 TES4 头（含 MAST 列表）的最小插件
+    fn write_plugin_with_masters(path: &Path, masters: &[&str]) {
+        let mut hedr = Vec::new();
+        hedr.extend_from_slice(b"HEDR");
+        hedr.extend_from_slice(&12u16.to_le_bytes());
+        hedr.extend_from_slice(&1.7f32.to_le_bytes());
+        hedr.extend_from_slice(&0i32.to_le_bytes());
+        hedr.extend_from_slice(&0x800u32.to_le_bytes());
+        for master in masters {
+            hedr.extend_from_slice(b"MAST");
+            hedr.extend_from_slice(&(master.len() as u16 + 1).to_le_bytes());
+            hedr.extend_from_slice(master.as_bytes());
+            hedr.push(0);
+            hedr.extend_from_slice(b"DATA");
+            hedr.extend_from_slice(&8u16.to_le_bytes());
+            hedr.extend_from_slice(&0u64.to_le_bytes());
+        }
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"TES4");
+        data.extend_from_slice(&(hedr.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0u8; 8]); // flags + form id
+        data.extend_from_slice(&[0u8; 4]); // version control
+        data.extend_from_slice(&44u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&hedr);
+        fs::write(path, data).unwrap();
+    }
+
+    fn record(record_type: &str, text: &str) -> StringRecord {
+        StringRecord {
+            form_id: format!("{:08X}|Test.esp", text.len()),
+            editor_id: None,
+            record_type: record_type.to_string(),
+            subrecord_type: "NAM1".to_string(),
+            index: 0,
+            original_text: text.to_string(),
+            translated_text: text.to_string(),
+            translation_status: "untranslated".to_string(),
+            strings_table: StringsTable::classify(record_type, "NAM1"),
+            source_plugin: None,
+        }
+    }
+
+    /// 仅含内存记录的 Session（ID 与插件名均为 Test.esp）
+    fn manager_with_records(records: Vec<StringRecord>) -> PluginSessionManager {
+        let mut manager = PluginSessionManager::new();
+        manager.sessions.insert(
+            "Test.esp".to_string(),
+            PluginSession {
+                plugin_name: "Test.esp".to_string(),
+                plugin_path: PathBuf::from("Test.esp"),
+                strings: Arc::new(records),
+                loaded_at: Instant::now(),
+                has_dsd_overrides: false,
+                dsd_overrides: None,
+                duplicate_keys: Vec::new(),
+                dirty: false,
+                bsa_warning: None,
+                metadata: PluginMetadata::default(),
+                dialogue_groups: Vec::new(),
+                stat_token: None,
+                loaded_plugin: None,
+            },
+        );
+        manager
+    }
+
+    #[test]
+    fn length_violations_use_most_specific_limit_in_bytes() {
+        let limits = vec![
+            LengthLimit {
+                record_type: "*".to_string(),
+                subrecord_type: Some("FULL".to_string()),
+                max_bytes: 12,
+            },
+            LengthLimit {
+                record_type: "WEAP".to_string(),
+                subrecord_type: Some("FULL".to_string()),
+                max_bytes: 9,
+            },
+        ];
+        let named = |record_type: &str, text: &str| StringRecord {
+            subrecord_type: "FULL".to_string(),
+            translated_text: text.to_string(),
+            ..record(record_type, "Name")
+        };
+        // "精钢长剑" 为 4 个字、12 字节
+        let records = vec![
+            named("WEAP", "精钢长剑"),
+            named("ARMO", "精钢长剑"),
+            named("ARMO", "精钢长剑甲"),
+            record("BOOK", &"x".repeat(20)),
+        ];
+
+        let violations = length_violations(&records, &limits);
+        assert_eq!(violations.len(), 2);
+        assert_eq!((violations[0].byte_len, violations[0].max_bytes), (12, 9));
+        assert_eq!((violations[1].byte_len, violations[1].max_bytes), (15, 12));
+    }
+
+    #[test]
+    fn bulk_copy_and_clear_update_session_records() {
+        let translated = StringRecord {
+            translated_text: "铁剑".to_string(),
+            translation_status: "manual".to_string(),
+            ..record("WEAP", "Iron Sword")
+        };
+        let pending = record("NPC_", "Ulfric");
+        let keys = records_to_form_identifiers(&[translated.clone(), pending.clone()]);
+
+        let mut manager = manager_with_records(vec![translated, pending]);
+
+        // 已翻译的记录不会被原文覆盖
+        let copied = manager.copy_source_to_target("Test.esp", &keys).unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].translated_text, "Ulfric");
+        assert_eq!(copied[0].translation_status, "manual");
+
+        let cleared = manager.clear_target("Test.esp", &keys).unwrap();
+        assert_eq!(cleared.len(), 2);
+        let strings = manager.get_strings("Test.esp").unwrap();
+        assert!(strings.iter().all(|r| {
+            r.translated_text == r.original_text && r.translation_status == "untranslated"
+        }));
+        // 清除后写回（仅写入变更）不会写入任何记录
+        assert!(filter_changed_records(&strings, cleared).is_empty());
+    }
+
+    #[test]
+    fn set_records_status_updates_selected_records_only() {
+        let records = vec![record("WEAP", "Sword"), record("ARMO", "Helm")];
+        let keys = records_to_form_identifiers(&records[..1]);
+        let mut manager = manager_with_records(records);
+
+        let updated = manager.set_records_status("Test.esp", &keys, "manual").unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].record_type, "WEAP");
+        let statuses: Vec<_> = manager
+            .get_strings("Test.esp")
+            .unwrap()
+            .iter()
+            .map(|r| r.translation_status.clone())
+            .collect();
+        assert_eq!(statuses, vec!["manual", "untranslated"]);
+
+        assert!(manager.set_records_status("Test.esp", &keys, "done").is_err());
+        assert!(matches!(
+            manager.set_records_status("Missing.esp", &keys, "manual"),
+            Err(SessionError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn filter_changed_records_keeps_only_real_changes() {
+        let originals = vec![record("WEAP", "Iron Sword"), record("ARMO", "Helm"), record("BOOK", "Tome")];
+        let unchanged = originals[0].clone();
+        let changed = StringRecord {
+            translated_text: "头盔".to_string(),
+            translation_status: "manual".to_string(),
+            ..originals[1].clone()
+        };
+        let emptied = StringRecord {
+            translated_text: String::new(),
+            translation_status: "manual".to_string(),
+            ..originals[2].clone()
+        };
+        // 状态仍为 untranslated，但译文已被修改
+        let edited_untranslated = StringRecord {
+            translated_text: "铁剑".to_string(),
+            ..originals[0].clone()
+        };
+
+        let kept = filter_changed_records(&originals, vec![unchanged, changed, emptied]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].translated_text, "头盔");
+
+        let kept = filter_changed_records(&originals, vec![edited_untranslated]);
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn record_context_stays_within_dialogue_group() {
+        let records = vec![
+            record("DIAL", "Rumors"),
+            record("INFO", "a"),
+            record("INFO", "bb"),
+            record("INFO", "ccc"),
+            record("WEAP", "Sword"),
+            record("INFO", "dddd"),
+        ];
+        let groups = group_dialogue_records(&records);
+        assert_eq!(groups, vec![1..4, 5..6]);
+
+        let context = record_context(&records, &groups, 2, 5).unwrap();
+        assert_eq!(context.topic.as_deref(), Some("Rumors"));
+        assert_eq!((context.group_size, context.position), (3, 1));
+        assert_eq!(context.previous.len(), 1);
+        assert_eq!(context.next[0].original_text, "ccc");
+
+        let context = record_context(&records, &groups, 5, 5).unwrap();
+        assert!(context.topic.is_none() && context.previous.is_empty());
+        assert!(record_context(&records, &groups, 4, 5).is_none());
+    }
+
+    #[test]
+    fn session_progress_counts_tm_hits_and_untouched_records() {
+        let mut records = vec![
+            record("WEAP", "Sword"),
+            record("ARMO", "Helm"),
+            record("BOOK", "Tome"),
+        ];
+        records[0].translated_text = "剑".to_string();
+        // 仅多了尾随空格，视为未翻译
+        records[2].translated_text = "Tome ".to_string();
+        let hit = Translation {
+            form_id: records[1].form_id.clone(),
+            record_type: "ARMO".to_string(),
+            subrecord_type: "NAM1".to_string(),
+            index: 0,
+            editor_id: None,
+            original_text: "Helm".to_string(),
+            translated_text: "头盔".to_string(),
+            plugin_name: None,
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+
+        let whitespace_only = Translation {
+            form_id: records[2].form_id.clone(),
+            record_type: "BOOK".to_string(),
+            original_text: "Tome".to_string(),
+            translated_text: " Tome\u{3000}".to_string(),
+            ..hit.clone()
+        };
+        let existing = vec![hit, whitespace_only];
+
+        let progress = session_progress(&records, &existing);
+
+        assert_eq!((progress.total, progress.tm_hits, progress.same_as_source), (3, 1, 2));
+        let found = whitespace_only_translations(existing);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].record_type, "BOOK");
+    }
+
+    #[test]
+    fn diff_against_tm_classifies_new_unchanged_and_conflict() {
+        let mut records = vec![
+            record("WEAP", "Sword"),
+            record("ARMO", "Helm"),
+            record("BOOK", "Tome"),
+        ];
+        records[1].translated_text = "头盔".to_string();
+        records[2].translated_text = "典籍".to_string();
+        let tm = |r: &StringRecord, translated: &str| Translation {
+            form_id: r.form_id.clone(),
+            record_type: r.record_type.clone(),
+            subrecord_type: r.subrecord_type.clone(),
+            index: r.index,
+            editor_id: None,
+            original_text: r.original_text.clone(),
+            translated_text: translated.to_string(),
+            plugin_name: None,
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+        let existing = vec![tm(&records[1], "头盔"), tm(&records[2], "书")];
+
+        let diff = diff_records_against_tm(&records, existing);
+
+        let statuses: Vec<_> = diff.iter().map(|d| (d.status, d.tm_text.as_deref())).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (TmDiffStatus::New, None),
+                (TmDiffStatus::Unchanged, None),
+                (TmDiffStatus::Conflict, Some("书")),
+            ]
+        );
+        assert_eq!(diff[2].translated_text, "典籍");
+    }
+
+    #[test]
+    fn orphaned_translations_are_remapped_by_editor_id() {
+        let with_editor_id = |form_id: &str, editor_id: &str, text: &str| StringRecord {
+            form_id: form_id.to_string(),
+            editor_id: Some(editor_id.to_string()),
+            ..record("WEAP", text)
+        };
+        // 插件更新后 FormID 重新编号；两条 Stub 记录的 EditorID 与原文相同，无法确定
+        let records = vec![
+            with_editor_id("00000901|Test.esp", "MySword", "Sword"),
+            with_editor_id("00000902|Test.esp", "Stub", "Stub"),
+            with_editor_id("00000903|Test.esp", "Stub", "Stub"),
+        ];
+        let tm = |form_id: &str, editor_id: Option<&str>, text: &str| Translation {
+            form_id: form_id.to_string(),
+            record_type: "WEAP".to_string(),
+            subrecord_type: "NAM1".to_string(),
+            index: 0,
+            editor_id: editor_id.map(str::to_string),
+            original_text: text.to_string(),
+            translated_text: format!("{}（译）", text),
+            plugin_name: Some("Test.esp".to_string()),
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+        let tm_rows = vec![
+            tm("00000801|Test.esp", Some("MySword"), "Sword"),
+            tm("00000802|Test.esp", Some("Stub"), "Stub"),
+            tm("00000803|Test.esp", None, "Shield"),
+        ];
+
+        let report = match_orphans_by_editor_id(&records, tm_rows);
+
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.matched[0].old_form_id, "00000801|Test.esp");
+        assert_eq!(report.matched[0].new_form_id, "00000901|Test.esp");
+        assert_eq!(report.ambiguous, vec!["00000802|Test.esp|WEAP|NAM1|0"]);
+        assert_eq!(report.unmatched, vec!["00000803|Test.esp|WEAP|NAM1|0"]);
+    }
+
+    #[test]
+    fn text_fill_candidates_follow_source_priority() {
+        let records = vec![record("WEAP", "Iron Sword"), record("ARMO", "Helm")];
+        let tm = |plugin: &str, translated: &str, updated_at: i64| Translation {
+            form_id: format!("00000001|{}", plugin),
+            record_type: "WEAP".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+            editor_id: None,
+            original_text: "Iron Sword".to_string(),
+            translated_text: translated.to_string(),
+            plugin_name: Some(plugin.to_string()),
+            created_at: 0,
+            updated_at,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+        let rows = || {
+            vec![
+                tm("OtherMod.esp", "铁剑（新）", 30),
+                tm("OtherMod.esp", "铁剑（旧）", 10),
+                tm("Skyrim.esm", "铁剑", 20),
+            ]
+        };
+
+        let candidates =
+            text_fill_candidates("Test.esp", &records, rows(), &default_source_priority());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].translated_text, "铁剑");
+        assert_eq!(candidates[0].source, TranslationSource::BaseDictionary);
+
+        let candidates =
+            text_fill_candidates("Test.esp", &records, rows(), &[TranslationSource::Any]);
+        assert_eq!(candidates[0].translated_text, "铁剑（新）");
+
+        let candidates =
+            text_fill_candidates("Test.esp", &records, rows(), &[TranslationSource::SamePlugin]);
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn coverage_fill_candidates_skip_own_and_identical_text() {
+        let records = vec![record("WEAP", "Sword"), record("ARMO", "Helm"), record("BOOK", "Tome")];
+        let entry = |r: &StringRecord, text: &str, source_mod: &str| CoverageEntry {
+            form_id: r.form_id.clone(),
+            record_type: r.record_type.clone(),
+            subrecord_type: r.subrecord_type.clone(),
+            index: r.index,
+            text: text.to_string(),
+            source_mod: source_mod.to_string(),
+            load_order_pos: 3,
+            extracted_at: 0,
+            load_order_plugin: None,
+        };
+        let entries = vec![
+            entry(&records[0], "剑", "Patch.esp"),
+            entry(&records[1], "头盔", "test.esp"),
+            entry(&records[2], "Tome", "Patch.esp"),
+        ];
+
+        let candidates = coverage_fill_candidates("Test.esp", &records, entries);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].coverage_text, "剑");
+        assert_eq!(candidates[0].source_mod, "Patch.esp");
+    }
+
+    #[test]
+    fn retry_file_op_retries_transient_errors_only() {
+        let mut calls = 0;
+        let result = retry_file_op(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: std::io::Result<()> = retry_file_op(|| {
+            calls += 1;
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn dsd_override_summary_counts_unmatched_entries() {
+        let mut records = vec![record("WEAP", "Sword"), record("ARMO", "Helm")];
+        let mut overrides = HashMap::new();
+        for (rec, value) in [(&records[0], "剑"), (&records[1], "Helm")] {
+            let key = make_record_key(&rec.form_id, &rec.record_type, &rec.subrecord_type);
+            overrides.insert(key, value.to_string());
+        }
+        overrides.insert(make_record_key("00000999|Test.esp", "BOOK", "FULL"), "书".to_string());
+
+        let summary = PluginSessionManager::apply_dsd_overrides_to_records(&mut records, &overrides);
+
+        assert_eq!(summary, DsdOverrideSummary { total: 3, applied: 1, unmatched: 1 });
+        assert_eq!(records[0].translated_text, "剑");
+    }
+
+    #[test]
+    fn same_named_plugins_get_separate_sessions() {
+        let tmp = TempDir::new("plugin_session_ids");
+        let root = tmp.path();
+        let first = root.join("a").join("Same.esp");
+        let second = root.join("b").join("Same.esp");
+        for path in [&first, &second] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            write_empty_plugin(path);
+        }
+
+        let mut manager = PluginSessionManager::new();
+        let loaded_first = manager.get_or_load(first.clone(), DEFAULT_DSD_PATH_TEMPLATE);
+        let loaded_second = manager.get_or_load(second.clone(), DEFAULT_DSD_PATH_TEMPLATE);
+        let reloaded = manager.get_or_load(first.clone(), DEFAULT_DSD_PATH_TEMPLATE);
+
+        let (loaded_first, loaded_second) = (loaded_first.unwrap(), loaded_second.unwrap());
+        assert_eq!(loaded_first.plugin_name, "Same.esp");
+        assert_eq!(loaded_second.plugin_name, "Same.esp");
+        assert_ne!(loaded_first.session_id, loaded_second.session_id);
+        assert_eq!(reloaded.unwrap().session_id, loaded_first.session_id);
+        assert_eq!(manager.list_sessions().len(), 2);
+
+        manager.close(&loaded_first.session_id).unwrap();
+        assert!(!manager.contains(&loaded_first.session_id));
+        assert!(manager.contains(&loaded_second.session_id));
+    }
+
+    #[test]
+    fn combined_session_routes_records_by_source_plugin() {
+        let tmp = TempDir::new("plugin_session_combined");
+        let root = tmp.path();
+        let main = root.join("Mod.esp");
+        let patch = root.join("Mod - Patch.esp");
+        let clash = root.join("other").join("Mod.esp");
+        for path in [&main, &patch, &clash] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            write_empty_plugin(path);
+        }
+
+        let mut manager = PluginSessionManager::new();
+        let combined = manager.load_combined(vec![main.clone(), patch], DEFAULT_DSD_PATH_TEMPLATE);
+        let same_name = manager.load_combined(vec![main, clash], DEFAULT_DSD_PATH_TEMPLATE);
+
+        let combined = combined.unwrap();
+        assert!(combined.session_id.starts_with(COMBINED_SESSION_PREFIX));
+        assert_eq!(combined.members.len(), 2);
+        assert!(same_name.unwrap_err().to_string().contains("同名插件"));
+        // 成员仍是独立 Session
+        assert!(manager.contains(&combined.members[1].session_id));
+
+        let members = vec![
+            ("Mod.esp".to_string(), "a".to_string()),
+            ("Mod - Patch.esp".to_string(), "b".to_string()),
+        ];
+        let tagged = |source: Option<&str>| StringRecord {
+            source_plugin: source.map(str::to_string),
+            ..record("WEAP", "Sword")
+        };
+        let groups = partition_by_source_plugin(
+            &members,
+            vec![tagged(Some("Mod - Patch.esp")), tagged(Some("Mod - Patch.esp"))],
+        )
+        .unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!((groups[0].1.as_str(), groups[0].2.len()), ("b", 2));
+        assert!(partition_by_source_plugin(&members, vec![tagged(None)]).is_err());
+        assert!(partition_by_source_plugin(&members, vec![tagged(Some("Other.esp"))]).is_err());
+
+        manager.close(&combined.session_id).unwrap();
+        assert!(manager.contains(&combined.members[0].session_id));
+    }
+
+    #[test]
+    fn masters_are_read_in_declared_order() {
+        let tmp = TempDir::new("plugin_session_masters");
+        let dir = tmp.path();
+        let path = dir.join("Patch.esp");
+        write_plugin_with_masters(&path, &["Skyrim.esm", "Update.esm", "Mod.esp"]);
+
+        let mut manager = PluginSessionManager::new();
+        let unopened = manager.get_masters(&path);
+        let response = manager.get_or_load(path.clone(), DEFAULT_DSD_PATH_TEMPLATE).unwrap();
+        let cached = manager.get_masters(&path);
+        fs::remove_dir_all(dir).unwrap();
+
+        let expected = vec!["Skyrim.esm", "Update.esm", "Mod.esp"];
+        assert_eq!(unopened.unwrap(), expected);
+        // 文件已删除，仍能从 Session 缓存读取
+        assert_eq!(cached.unwrap(), expected);
+        assert_eq!(response.metadata.masters, expected);
+        assert_eq!(response.metadata.record_count, 0);
+        assert!(!response.metadata.is_master && !response.metadata.is_light);
+    }
+
+    #[test]
+    fn backups_are_listed_newest_first_and_restorable() {
+        let tmp = TempDir::new("plugin_session_backups");
+        let dir = tmp.path();
+        let path = dir.join("Patch.esp");
+        write_plugin_with_masters(&path, &["Skyrim.esm"]);
+        let older = dir.join("Patch.esp.2024_01_01_08_00_00.bak");
+        let newer = dir.join("Patch.esp.2024_03_01_08_00_00.bak");
+        fs::write(&older, b"older").unwrap();
+        fs::write(&newer, b"newer!").unwrap();
+        fs::write(dir.join("Other.esp.2024_05_01_08_00_00.bak"), b"other").unwrap();
+
+        let mut manager = PluginSessionManager::new();
+        let session_id = manager
+            .get_or_load(path.clone(), DEFAULT_DSD_PATH_TEMPLATE)
+            .unwrap()
+            .session_id;
+        let backups = manager.list_backups(&session_id).unwrap();
+        let foreign_backup = dir.join("Other.esp.2024_05_01_08_00_00.bak");
+        let foreign = manager.restore_backup(&session_id, &foreign_backup, &[]);
+        let restored = manager.restore_backup(&session_id, &older, &[]);
+        let content = fs::read(&path).unwrap();
+
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].timestamp, "2024_03_01_08_00_00");
+        assert_eq!((backups[0].size_bytes, backups[1].size_bytes), (6, 5));
+        assert!(foreign.is_err());
+        assert!(restored.is_ok());
+        assert_eq!(content, b"older");
+        // 恢复后 Session 已移除，需重新加载
+        assert!(manager.get_strings(&session_id).is_none());
+    }
+
+    #[test]
+    fn read_only_mode_blocks_writes_inside_protected_dirs() {
+        let tmp = TempDir::new("plugin_session_read_only");
+        let root = tmp.path();
+        let game = root.join("Skyrim");
+        let outside = root.join("Output");
+        fs::create_dir_all(game.join("Data")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(game.join("Data").join("Mod.esp"), b"").unwrap();
 
-        println!("✓ DSD 导出成功: {:?}", output_file);
+        let protected = vec![game.clone()];
+        let existing = ensure_outside_protected_dirs(&game.join("Data").join("Mod.esp"), &protected);
+        let new_inside = ensure_outside_protected_dirs(&game.join("Data").join("New.esp"), &protected);
+        let new_outside = ensure_outside_protected_dirs(&outside.join("Mod.esp"), &protected);
+        let disabled = ensure_outside_protected_dirs(&game.join("Data").join("Mod.esp"), &[]);
 
-        Ok(output_file.to_string_lossy().to_string())
+        assert!(existing.unwrap_err().contains("只读模式"));
+        assert!(new_inside.is_err());
+        assert!(new_outside.is_ok());
+        assert!(disabled.is_ok());
     }
 }
@@ -1,7 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::fs;
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
 /// 插件信息结构
@@ -9,8 +10,24 @@ use walkdir::WalkDir;
 pub struct PluginInfo {
     /// 插件文件名（不含路径）
     pub name: String,
-    /// 插件完整路径
+    /// 插件完整路径（显示用，非 UTF-8 字符会被替换）
     pub path: String,
+    /// 插件原始路径（文件操作使用，不做有损转换）
+    #[serde(skip)]
+    pub fs_path: PathBuf,
+}
+
+/// 插件列表排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginOrderMode {
+    /// 按 loadorder.txt 顺序，未列出的插件按字母顺序追加在末尾
+    #[default]
+    LoadOrder,
+    /// 严格按文件名字母顺序（忽略大小写）
+    Alphabetical,
+    /// 按文件修改时间从旧到新
+    Mtime,
 }
 
 /// 验证路径是否有效（支持文件夹或单个插件文件）
@@ -65,15 +82,42 @@ fn detect_skyrim_version(game_path: &Path) -> Option<&'static str> {
     None
 }
 
-/// 获取 loadorder.txt 文件路径
-fn get_loadorder_path(version: &str) -> Option<PathBuf> {
+/// 加载顺序文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadOrderFile {
+    /// loadorder.txt：包含全部插件（含未启用的）
+    LoadOrder,
+    /// plugins.txt：仅包含已启用的插件，游戏本体主文件不列出
+    Plugins,
+}
+
+impl LoadOrderFile {
+    fn file_name(self) -> &'static str {
+        match self {
+            LoadOrderFile::LoadOrder => "loadorder.txt",
+            LoadOrderFile::Plugins => "plugins.txt",
+        }
+    }
+}
+
+/// 游戏隐式加载、不会写入 plugins.txt 的主文件（按引擎加载顺序）
+const IMPLICIT_MASTERS: &[&str] = &[
+    "Skyrim.esm",
+    "Update.esm",
+    "Dawnguard.esm",
+    "HearthFires.esm",
+    "Dragonborn.esm",
+];
+
+/// 获取加载顺序文件路径（%LOCALAPPDATA%/<版本>/<文件名>）
+fn get_loadorder_path(version: &str, file: LoadOrderFile) -> Option<PathBuf> {
     // 获取 %LOCALAPPDATA% 环境变量
     let local_appdata = std::env::var("LOCALAPPDATA").ok()?;
     let mut path = PathBuf::from(local_appdata);
 
     // 根据版本选择文件夹
     path.push(version);
-    path.push("loadorder.txt");
+    path.push(file.file_name());
 
     if path.exists() {
         Some(path)
@@ -82,24 +126,65 @@ fn get_loadorder_path(version: &str) -> Option<PathBuf> {
     }
 }
 
-/// 读取并解析 loadorder.txt
+/// 读取并解析加载顺序文件
 /// 返回插件名称列表（按加载顺序）
-fn parse_loadorder(loadorder_path: &Path) -> Result<Vec<String>, String> {
+fn parse_loadorder(loadorder_path: &Path, file: LoadOrderFile) -> Result<Vec<String>, String> {
     let content = fs::read_to_string(loadorder_path)
-        .map_err(|e| format!("读取 loadorder.txt 失败: {}", e))?;
+        .map_err(|e| format!("读取 {} 失败: {}", file.file_name(), e))?;
+
+    Ok(match file {
+        LoadOrderFile::LoadOrder => parse_loadorder_content(&content),
+        LoadOrderFile::Plugins => parse_plugins_content(&content),
+    })
+}
 
-    let plugins: Vec<String> = content
+/// 解析 loadorder.txt 内容
+///
+/// 去除 UTF-8 BOM（否则第一个插件名带有不可见前缀而永远匹配不上）与首尾空白（含 CRLF 的 `\r`），
+/// 跳过注释和空行
+fn parse_loadorder_content(content: &str) -> Vec<String> {
+    content
+        .strip_prefix('\u{feff}')
+        .unwrap_or(content)
         .lines()
         .map(|line| line.trim())
-        .filter(|line| !line.is_empty() && !line.starts_with('#')) // 跳过注释和空行
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
         .map(|line| line.to_string())
+        .collect()
+}
+
+/// 解析 plugins.txt 内容
+///
+/// 格式同 loadorder.txt，行首的 `*` 表示已启用（旧格式无前缀，列出即启用）；
+/// 游戏本体主文件不会写入 plugins.txt，补在最前面
+fn parse_plugins_content(content: &str) -> Vec<String> {
+    let listed: Vec<String> = parse_loadorder_content(content)
+        .into_iter()
+        .map(|line| line.trim_start_matches('*').trim().to_string())
+        .filter(|name| !name.is_empty())
         .collect();
 
-    Ok(plugins)
+    let mut order: Vec<String> = IMPLICIT_MASTERS
+        .iter()
+        .filter(|master| !listed.iter().any(|name| name.eq_ignore_ascii_case(master)))
+        .map(|master| master.to_string())
+        .collect();
+    order.extend(listed);
+    order
 }
 
 /// 读取 loadorder.txt（如果存在）并返回插件名称列表
+///
+/// 未找到 loadorder.txt 时回退到 plugins.txt（未启用的插件不在其中，会按字母顺序追加在末尾）
 pub fn read_loadorder(game_path: &Path, log: bool) -> Option<Vec<String>> {
+    read_loadorder_with_source(game_path, log).map(|(_, order)| order)
+}
+
+/// 同 [`read_loadorder`]，同时返回实际读取的文件
+pub fn read_loadorder_with_source(
+    game_path: &Path,
+    log: bool,
+) -> Option<(LoadOrderFile, Vec<String>)> {
     if !game_path.is_dir() {
         return None;
     }
@@ -107,27 +192,28 @@ pub fn read_loadorder(game_path: &Path, log: bool) -> Option<Vec<String>> {
     let version = detect_skyrim_version(game_path);
 
     if let Some(version) = version {
-        if let Some(loadorder_path) = get_loadorder_path(version) {
-            match parse_loadorder(&loadorder_path) {
+        for file in [LoadOrderFile::LoadOrder, LoadOrderFile::Plugins] {
+            let Some(loadorder_path) = get_loadorder_path(version, file) else {
+                continue;
+            };
+            match parse_loadorder(&loadorder_path, file) {
                 Ok(order) => {
                     if log {
-                        println!("✓ 成功读取 loadorder.txt ({} 个插件)", order.len());
+                        println!("✓ 成功读取 {} ({} 个插件)", file.file_name(), order.len());
                     }
-                    Some(order)
+                    return Some((file, order));
                 }
                 Err(e) => {
                     if log {
-                        println!("⚠ 读取 loadorder.txt 失败: {}", e);
+                        println!("⚠ {}", e);
                     }
-                    None
                 }
             }
-        } else {
-            if log {
-                println!("⚠ 未找到 loadorder.txt ({})", version);
-            }
-            None
         }
+        if log {
+            println!("⚠ 未找到可用的 loadorder.txt 或 plugins.txt ({})", version);
+        }
+        None
     } else {
         if log {
             println!("⚠ 无法检测 Skyrim 版本，使用字母顺序");
@@ -136,11 +222,37 @@ pub fn read_loadorder(game_path: &Path, log: bool) -> Option<Vec<String>> {
     }
 }
 
+/// 校验手动指定的 Data 目录（必须包含 Skyrim.esm）
+pub fn validate_data_dir(data_dir: &Path) -> Result<(), String> {
+    if !data_dir.is_dir() {
+        return Err(format!("Data 目录不存在: {}", data_dir.display()));
+    }
+    if find_file_case_insensitive(data_dir, "Skyrim.esm").is_none() {
+        return Err(format!("Data 目录中未找到 Skyrim.esm: {}", data_dir.display()));
+    }
+    Ok(())
+}
+
+/// 解析实际使用的 Data 目录：优先使用手动指定的目录，否则从游戏目录中检测
+pub fn resolve_data_dir(game_path: &Path, data_dir_override: Option<&Path>) -> Result<PathBuf, String> {
+    if let Some(data_dir) = data_dir_override {
+        validate_data_dir(data_dir)?;
+        return Ok(data_dir.to_path_buf());
+    }
+
+    find_data_dir(game_path)?.ok_or_else(|| "未找到 Data 目录".to_string())
+}
+
 /// 扫描插件文件（支持文件夹或单个文件）
 ///
 /// - 如果是单个插件文件：直接返回该文件
-/// - 如果是游戏目录：返回所有 .esp, .esm, .esl 文件，按照 loadorder.txt 的顺序排列
-pub fn scan_plugins(game_path: &str) -> Result<Vec<PluginInfo>, String> {
+/// - 如果是游戏目录：返回所有 .esp, .esm, .esl 文件，按照 `order_mode` 排列
+/// - `data_dir_override` 存在时使用该 Data 目录，而不是从游戏目录中检测
+pub fn scan_plugins(
+    game_path: &str,
+    data_dir_override: Option<&Path>,
+    order_mode: PluginOrderMode,
+) -> Result<Vec<PluginInfo>, String> {
     let game_path = PathBuf::from(game_path);
 
     // 情况1：路径是单个插件文件
@@ -164,6 +276,7 @@ pub fn scan_plugins(game_path: &str) -> Result<Vec<PluginInfo>, String> {
                 return Ok(vec![PluginInfo {
                     name,
                     path: full_path,
+                    fs_path: game_path.clone(),
                 }]);
             }
         }
@@ -172,8 +285,7 @@ pub fn scan_plugins(game_path: &str) -> Result<Vec<PluginInfo>, String> {
 
     // 情况2：路径是游戏目录，扫描所有插件
     // 查找 Data 目录
-    let data_dir = find_data_dir(&game_path)?
-        .ok_or_else(|| "未找到 Data 目录".to_string())?;
+    let data_dir = resolve_data_dir(&game_path, data_dir_override)?;
 
     // 1. 收集所有插件文件
     let mut all_plugins = HashMap::new();
@@ -203,39 +315,70 @@ pub fn scan_plugins(game_path: &str) -> Result<Vec<PluginInfo>, String> {
                 all_plugins.insert(name.clone(), PluginInfo {
                     name,
                     path: full_path,
+                    fs_path: path.to_path_buf(),
                 });
             }
         }
     }
 
+    match order_mode {
+        PluginOrderMode::Alphabetical => {
+            let mut result: Vec<_> = all_plugins.into_values().collect();
+            result.sort_by_key(|a| a.name.to_lowercase());
+            return Ok(result);
+        }
+        PluginOrderMode::Mtime => {
+            // 无法读取修改时间的插件排在最前，同一时间按字母顺序
+            let mut result: Vec<_> = all_plugins
+                .into_values()
+                .map(|info| {
+                    let modified = fs::metadata(&info.fs_path)
+                        .and_then(|m| m.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    (modified, info.name.to_lowercase(), info)
+                })
+                .collect();
+            result.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+            return Ok(result.into_iter().map(|(_, _, info)| info).collect());
+        }
+        PluginOrderMode::LoadOrder => {}
+    }
+
     // 2. 检测 Skyrim 版本并读取 loadorder.txt
     let loadorder = read_loadorder(&game_path, true);
 
     // 3. 按照 loadorder.txt 的顺序排列插件
+    Ok(order_by_loadorder(all_plugins, loadorder.as_deref()))
+}
+
+/// 按 loadorder 顺序排列插件，未列出的插件按字母顺序追加在末尾
+///
+/// loadorder 与磁盘文件名按忽略大小写匹配（文件系统大小写与 loadorder.txt 不一致很常见），
+/// 结果中保留磁盘上的实际文件名
+fn order_by_loadorder(
+    all_plugins: HashMap<String, PluginInfo>,
+    loadorder: Option<&[String]>,
+) -> Vec<PluginInfo> {
+    let mut by_lower: HashMap<String, PluginInfo> = all_plugins
+        .into_values()
+        .map(|info| (info.name.to_lowercase(), info))
+        .collect();
+
     let mut result = Vec::new();
-    let mut used_plugins = std::collections::HashSet::new();
-
-    if let Some(loadorder) = loadorder {
-        // 先按 loadorder.txt 的顺序添加插件
-        for plugin_name in loadorder {
-            if let Some(plugin_info) = all_plugins.get(&plugin_name) {
-                result.push(plugin_info.clone());
-                used_plugins.insert(plugin_name);
-            }
+
+    // 先按 loadorder.txt 的顺序添加插件（取出后不会重复添加）
+    for plugin_name in loadorder.unwrap_or_default() {
+        if let Some(plugin_info) = by_lower.remove(&plugin_name.to_lowercase()) {
+            result.push(plugin_info);
         }
     }
 
     // 添加不在 loadorder.txt 中的插件（按字母顺序）
-    let mut remaining: Vec<_> = all_plugins
-        .into_iter()
-        .filter(|(name, _)| !used_plugins.contains(name))
-        .map(|(_, info)| info)
-        .collect();
-
-    remaining.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    let mut remaining: Vec<_> = by_lower.into_values().collect();
+    remaining.sort_by_key(|a| a.name.to_lowercase());
     result.extend(remaining);
 
-    Ok(result)
+    result
 }
 
 /// 查找 Data 目录（大小写不敏感）
@@ -285,15 +428,128 @@ fn find_file_case_insensitive(dir: &Path, file_name: &str) -> Option<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::test_dir::TempDir;
 
     #[test]
     fn test_plugin_info_creation() {
         let plugin = PluginInfo {
             name: "TestPlugin.esp".to_string(),
             path: "C:/Games/Skyrim/Data/TestPlugin.esp".to_string(),
+            fs_path: PathBuf::from("C:/Games/Skyrim/Data/TestPlugin.esp"),
         };
 
         assert_eq!(plugin.name, "TestPlugin.esp");
         assert!(plugin.path.contains("TestPlugin.esp"));
     }
+
+    #[test]
+    fn scan_plugins_keeps_non_ascii_paths() {
+        let tmp = TempDir::new("scanner_测试_Données");
+        let game_dir = tmp.path();
+        let data_dir = game_dir.join("Data");
+        fs::create_dir_all(&data_dir).unwrap();
+        let plugin_path = data_dir.join("汉化补丁 Élan.esp");
+        fs::write(&plugin_path, b"TES4").unwrap();
+
+        let plugins =
+            scan_plugins(game_dir.to_str().unwrap(), None, PluginOrderMode::LoadOrder).unwrap();
+        let single =
+            scan_plugins(plugin_path.to_str().unwrap(), None, PluginOrderMode::LoadOrder).unwrap();
+
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].name, "汉化补丁 Élan.esp");
+        assert_eq!(plugins[0].fs_path, plugin_path);
+        assert_eq!(PathBuf::from(&plugins[0].path), plugin_path);
+        assert_eq!(single[0].fs_path, plugin_path);
+    }
+
+    #[test]
+    fn scan_plugins_honors_order_mode() {
+        let tmp = TempDir::new("scanner_order_mode");
+        let game_dir = tmp.path();
+        let data_dir = game_dir.join("Data");
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        for (i, name) in ["c.esp", "A.esp", "b.esm"].iter().enumerate() {
+            let file = fs::File::create(data_dir.join(name)).unwrap();
+            file.set_modified(base + std::time::Duration::from_secs(i as u64 * 60))
+                .unwrap();
+        }
+
+        let names = |mode| {
+            scan_plugins(game_dir.to_str().unwrap(), None, mode)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>()
+        };
+        let alphabetical = names(PluginOrderMode::Alphabetical);
+        let mtime = names(PluginOrderMode::Mtime);
+
+        assert_eq!(alphabetical, vec!["A.esp", "b.esm", "c.esp"]);
+        assert_eq!(mtime, vec!["c.esp", "A.esp", "b.esm"]);
+    }
+
+    #[test]
+    fn loadorder_matches_plugins_ignoring_case() {
+        let plugins: HashMap<String, PluginInfo> = ["skyrim.esm", "MyMod.ESP", "Extra.esp"]
+            .iter()
+            .map(|name| {
+                let info = PluginInfo {
+                    name: name.to_string(),
+                    path: name.to_string(),
+                    fs_path: PathBuf::from(name),
+                };
+                (name.to_string(), info)
+            })
+            .collect();
+        let loadorder = vec!["Skyrim.esm".to_string(), "mymod.esp".to_string()];
+
+        let names: Vec<_> = order_by_loadorder(plugins, Some(&loadorder))
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+
+        assert_eq!(names, vec!["skyrim.esm", "MyMod.ESP", "Extra.esp"]);
+    }
+
+    #[test]
+    fn parse_loadorder_strips_bom_and_comments() {
+        let content = "\u{feff}Skyrim.esm\r\n# 由 LOOT 生成\r\nUpdate.esm  \r\n\r\n\tMyMod.esp\n";
+        assert_eq!(
+            parse_loadorder_content(content),
+            vec!["Skyrim.esm", "Update.esm", "MyMod.esp"]
+        );
+    }
+
+    #[test]
+    fn parse_plugins_strips_active_marker_and_adds_implicit_masters() {
+        let content = "# plugins.txt\r\n*Dawnguard.esm\r\nunofficial patch.esp\r\n*MyMod.esp\r\n";
+        assert_eq!(
+            parse_plugins_content(content),
+            vec![
+                "Skyrim.esm",
+                "Update.esm",
+                "HearthFires.esm",
+                "Dragonborn.esm",
+                "Dawnguard.esm",
+                "unofficial patch.esp",
+                "MyMod.esp",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_data_dir_validates_override() {
+        let tmp = TempDir::new("scanner_data_override");
+        let data_dir = tmp.path();
+
+        let missing = resolve_data_dir(Path::new("unused"), Some(data_dir));
+        fs::write(data_dir.join("skyrim.esm"), b"TES4").unwrap();
+        let resolved = resolve_data_dir(Path::new("unused"), Some(data_dir));
+
+        assert!(missing.is_err());
+        assert_eq!(resolved.unwrap(), data_dir);
+    }
 }
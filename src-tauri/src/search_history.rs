@@ -1,5 +1,8 @@
+use crate::utils::schema::{run_migrations, Migration};
+use crate::utils::sql::{configure_busy_timeout, retry_on_busy};
 use rusqlite::{params, Connection, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -13,6 +16,9 @@ pub struct SearchHistoryEntry {
     pub updated_at: i64,
 }
 
+/// schema 迁移（新增列/索引时在末尾追加，版本号递增）
+const MIGRATIONS: &[Migration] = &[];
+
 /// 搜索历史数据库管理器
 pub struct SearchHistoryDB {
     conn: Arc<Mutex<Connection>>,
@@ -20,8 +26,9 @@ pub struct SearchHistoryDB {
 
 impl SearchHistoryDB {
     /// 初始化数据库连接
-    pub fn new(db_path: &str) -> SqliteResult<Self> {
+    pub fn new<P: AsRef<Path>>(db_path: P) -> SqliteResult<Self> {
         let conn = Connection::open(db_path)?;
+        configure_busy_timeout(&conn)?;
 
         // 启用WAL模式以支持并发
         conn.execute_batch(
@@ -50,10 +57,12 @@ impl SearchHistoryDB {
             [],
         )?;
 
+        run_migrations(&conn, "search_history_meta", MIGRATIONS)?;
+
         Ok(())
     }
 
-    /// 批量保存搜索历史（UPSERT）
+    /// 批量保存搜索历史（UPSERT，单个事务内写入）
     pub fn batch_upsert(&self, entries: Vec<SearchHistoryEntry>) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         let now = SystemTime::now()
@@ -61,21 +70,23 @@ impl SearchHistoryDB {
             .unwrap()
             .as_secs() as i64;
 
-        for entry in entries {
-            let candidates_json = serde_json::to_string(&entry.candidates)
-                .unwrap_or_else(|_| "[]".to_string());
-
-            conn.execute(
-                "INSERT INTO search_history (term, candidates, updated_at)
-                 VALUES (?1, ?2, ?3)
-                 ON CONFLICT(term) DO UPDATE SET
-                    candidates = excluded.candidates,
-                    updated_at = excluded.updated_at",
-                params![entry.term, candidates_json, now],
-            )?;
-        }
-
-        Ok(())
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            for entry in &entries {
+                let candidates_json = serde_json::to_string(&entry.candidates)
+                    .unwrap_or_else(|_| "[]".to_string());
+
+                tx.execute(
+                    "INSERT INTO search_history (term, candidates, updated_at)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(term) DO UPDATE SET
+                        candidates = excluded.candidates,
+                        updated_at = excluded.updated_at",
+                    params![entry.term, candidates_json, now],
+                )?;
+            }
+            tx.commit()
+        })
     }
 
     /// 获取所有搜索历史
@@ -104,10 +115,12 @@ impl SearchHistoryDB {
     /// 删除单条搜索历史记录
     pub fn delete_entry(&self, term: &str) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM search_history WHERE term = ?1",
-            params![term],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                "DELETE FROM search_history WHERE term = ?1",
+                params![term],
+            )
+        })?;
         Ok(())
     }
 }
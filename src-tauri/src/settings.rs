@@ -1,3 +1,6 @@
+use crate::atomic_db::AtomMatcherOptions;
+use crate::plugin_session::{LengthLimit, TranslationSource};
+use crate::utils::load_order::CoverageFilter;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -10,6 +13,65 @@ pub struct Settings {
     /// DSD 导出目录（可选，如果未设置则使用源文件所在目录）
     #[serde(default)]
     pub dsd_output_dir: Option<String>,
+    /// DSD 文件路径模板（可选，占位符 {base}/{plugin}/{stem}，未设置时使用 DSD 默认目录约定）
+    #[serde(default)]
+    pub dsd_path_template: Option<String>,
+    /// Data 目录（可选，如果未设置则从游戏路径中检测）
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// 写回插件时的临时文件目录（可选，如果未设置则写在目标文件旁）
+    #[serde(default)]
+    pub temp_dir: Option<String>,
+    /// 写回时转换记录的线程数（可选，如果未设置则使用全局线程池）
+    #[serde(default)]
+    pub apply_threads: Option<usize>,
+    /// 只读模式：禁止写回游戏目录与 Data 目录内的文件（只能另存为到外部或导出 DSD）
+    #[serde(default)]
+    pub read_only_data: bool,
+    /// 原子词匹配器配置（匹配语义与大小写敏感）
+    #[serde(default)]
+    pub atom_matcher: AtomMatcherOptions,
+    /// 原子词使用计数写入遇到数据库锁定时的重试次数（可选，如果未设置则使用默认值）
+    #[serde(default)]
+    pub usage_write_retries: Option<u32>,
+    /// userdata 目录（可选，如果未设置则使用程序目录下的 userdata；
+    /// 环境变量 SSE_TRANSLATOR_DATA_DIR 优先，修改后重启生效）
+    #[serde(default)]
+    pub userdata_dir: Option<String>,
+    /// 译文字节长度上限（可选，如果未设置则使用内置的引擎硬限制）
+    #[serde(default)]
+    pub length_limits: Option<Vec<LengthLimit>>,
+    /// 覆盖提取的插件过滤（排除列表或仅包含列表，按插件名通配符匹配）
+    #[serde(default)]
+    pub coverage_filter: CoverageFilter,
+    /// 按原文自动填充时的译文来源优先级（可选，如果未设置则为 同插件 > 基础字典 > 任意插件）
+    #[serde(default)]
+    pub translation_source_priority: Option<Vec<TranslationSource>>,
+}
+
+impl Settings {
+    /// 手动指定的 Data 目录
+    pub fn data_dir_override(&self) -> Option<PathBuf> {
+        self.data_dir.as_ref().map(PathBuf::from)
+    }
+
+    /// 只读模式下禁止写入的目录（未开启只读模式时为空）
+    ///
+    /// 游戏路径指向可执行文件时保护其所在目录
+    pub fn protected_dirs(&self) -> Vec<PathBuf> {
+        if !self.read_only_data {
+            return Vec::new();
+        }
+        self.game
+            .iter()
+            .chain(self.data_dir.iter())
+            .map(PathBuf::from)
+            .map(|path| match path.parent() {
+                Some(parent) if path.is_file() => parent.to_path_buf(),
+                _ => path,
+            })
+            .collect()
+    }
 }
 
 impl Default for Settings {
@@ -17,6 +79,17 @@ impl Default for Settings {
         Self {
             game: None,
             dsd_output_dir: None,
+            dsd_path_template: None,
+            data_dir: None,
+            temp_dir: None,
+            apply_threads: None,
+            read_only_data: false,
+            atom_matcher: AtomMatcherOptions::default(),
+            usage_write_retries: None,
+            userdata_dir: None,
+            length_limits: None,
+            coverage_filter: CoverageFilter::default(),
+            translation_source_priority: None,
         }
     }
 }
@@ -24,7 +97,7 @@ impl Default for Settings {
 /// 获取settings.json文件路径
 /// 开发模式：项目根目录
 /// 生产模式：可执行文件同级目录
-fn get_settings_path() -> Result<PathBuf, String> {
+pub fn get_settings_path() -> Result<PathBuf, String> {
     // 获取当前可执行文件路径
     let exe_path = std::env::current_exe()
         .map_err(|e| format!("无法获取可执行文件路径: {}", e))?;
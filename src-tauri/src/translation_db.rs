@@ -1,3 +1,8 @@
+use crate::utils::schema::{add_column_if_missing, run_migrations, Migration};
+use crate::utils::sql::{configure_busy_timeout, escape_like, retry_on_busy};
+use crate::utils::strings_table::{StringsTable, STRINGS_TABLE_SQL};
+use regex::Regex;
+use rusqlite::limits::Limit;
 use rusqlite::{params, Connection, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -16,20 +21,147 @@ pub struct Translation {
     pub plugin_name: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// 译者备注（如 "性别不明确"、"需确认任务上下文"）
+    #[serde(default)]
+    pub note: Option<String>,
+    /// 所在的 STRINGS 表（由 record_type/subrecord_type 推导，不入库）
+    #[serde(default)]
+    pub strings_table: StringsTable,
 }
 
 /// 翻译统计信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationStats {
     pub total_count: i64,
+    /// 去除首尾空白后译文仍与原文相同的记录数（视为未翻译）
+    #[serde(default)]
+    pub untranslated_count: i64,
     pub plugin_counts: Vec<PluginCount>,
     pub last_updated: i64,
 }
 
+/// 比较译文与原文时忽略的首尾空白字符（含全角空格，与 [`TRIM_CHARS_SQL`] 一致）
+const TRIM_CHARS: &[char] = &[' ', '\t', '\n', '\r', '\u{3000}'];
+
+/// SQL `trim(X, Y)` 使用的字符集（同 [`TRIM_CHARS`]）
+const TRIM_CHARS_SQL: &str = "char(32, 9, 10, 13, 12288)";
+
+/// 去除首尾空白后译文是否仍与原文相同（仅多了空格等的"译文"不计为已翻译）
+pub fn is_untranslated_text(original: &str, translated: &str) -> bool {
+    original.trim_matches(TRIM_CHARS) == translated.trim_matches(TRIM_CHARS)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginCount {
     pub plugin_name: String,
     pub count: i64,
+    /// 该插件最后更新时间
+    #[serde(default)]
+    pub last_updated: i64,
+}
+
+/// 记录类型/子记录类型组合及其条目数（用于动态生成过滤选项）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeCount {
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub count: i64,
+}
+
+/// 统计报告导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatisticsFormat {
+    Json,
+    Markdown,
+}
+
+/// 统计报告（导出用）
+#[derive(Debug, Clone, Serialize)]
+pub struct StatisticsReport {
+    /// 报告生成时间戳
+    pub generated_at: i64,
+    #[serde(flatten)]
+    pub stats: TranslationStats,
+}
+
+impl StatisticsReport {
+    /// 渲染为 Markdown 表格
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# 翻译统计报告\n\n");
+        out.push_str(&format!("- 生成时间: {}\n", format_timestamp(self.generated_at)));
+        out.push_str(&format!("- 翻译总数: {}\n", self.stats.total_count));
+        out.push_str(&format!(
+            "- 译文与原文相同（未翻译）: {}\n",
+            self.stats.untranslated_count
+        ));
+        out.push_str(&format!(
+            "- 最后更新: {}\n\n",
+            format_timestamp(self.stats.last_updated)
+        ));
+        out.push_str("| 插件 | 翻译数量 | 最后更新 |\n");
+        out.push_str("| --- | ---: | --- |\n");
+        for plugin in &self.stats.plugin_counts {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                plugin.plugin_name.replace('|', "\\|"),
+                plugin.count,
+                format_timestamp(plugin.last_updated)
+            ));
+        }
+        out
+    }
+}
+
+/// 将秒级时间戳格式化为本地时间
+fn format_timestamp(timestamp: i64) -> String {
+    use chrono::TimeZone;
+
+    if timestamp <= 0 {
+        return "-".to_string();
+    }
+    chrono::Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// 全局搜索的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Original,
+    Translated,
+}
+
+/// 全局搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub translation: Translation,
+    /// 命中的字段（原文与译文同时命中时取 `fields` 中靠前的一个）
+    pub matched_field: SearchField,
+    /// 命中位置附近的上下文片段
+    pub snippet: String,
+    /// 命中文本在 `snippet` 中的字符区间 `[start, end)`（用于高亮）
+    pub highlight: (usize, usize),
+}
+
+/// 搜索片段在命中文本两侧保留的字符数
+const SNIPPET_RADIUS: usize = 30;
+
+/// 编辑器参考查询的最大字符数（更长的输入会退化为全表 LIKE 扫描）
+pub const MAX_LOOKUP_CHARS: usize = 64;
+
+/// 编辑器参考查询结果
+#[derive(Debug, Clone, Serialize)]
+pub struct WordLookupResult {
+    pub translations: Vec<Translation>,
+    /// 实际用于查询的文本（输入过长时为从中选出的单词）
+    pub query: String,
+    /// 输入是否超过 [`MAX_LOOKUP_CHARS`] 而被缩短
+    pub truncated: bool,
 }
 
 /// Form标识符，用于批量查询
@@ -41,6 +173,113 @@ pub struct FormIdentifier {
     pub index: u32,
 }
 
+/// 批量保存时每个事务包含的最大记录数
+const BATCH_SAVE_CHUNK_SIZE: usize = 5000;
+
+/// 批量查询时每条 SQL 默认包含的 key 数量
+pub const DEFAULT_QUERY_CHUNK_SIZE: usize = 900;
+
+/// 每个 key 在 IN 子句中占用的参数数量 (form_id, record_type, subrecord_type, index)
+const QUERY_PARAMS_PER_FORM: usize = 4;
+
+/// 保存翻译的 UPSERT 语句（?12 为 force_update_original）
+///
+/// ⚠️ 注意：默认不更新 original_text，防止已汉化源文件污染英文原文；未携带备注时保留原备注。
+/// 内容未变化的已有记录不会更新（影响行数为 0，也不刷新 updated_at）
+const UPSERT_TRANSLATION_SQL: &str = "INSERT INTO translations
+        (form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
+         translated_text, plugin_name, created_at, updated_at, note)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+     ON CONFLICT(form_id, record_type, subrecord_type, \"index\")
+     DO UPDATE SET
+        original_text = CASE WHEN ?12 THEN excluded.original_text ELSE translations.original_text END,
+        translated_text = excluded.translated_text,
+        updated_at = excluded.updated_at,
+        note = COALESCE(excluded.note, translations.note)
+     WHERE excluded.translated_text IS NOT translations.translated_text
+        OR (excluded.note IS NOT NULL AND excluded.note IS NOT translations.note)
+        OR (?12 AND excluded.original_text IS NOT translations.original_text)";
+
+/// schema 迁移（新增列/索引时在末尾追加，版本号递增）
+const MIGRATIONS: &[Migration] = &[
+    // v1：为旧版本数据库补充备注列
+    Migration {
+        version: 1,
+        up: |conn| add_column_if_missing(conn, "translations", "note", "TEXT"),
+    },
+    // v2：按原文精确查询（按文本自动填充）使用的索引
+    Migration {
+        version: 2,
+        up: |conn| {
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_original_text ON translations(original_text)",
+                [],
+            )?;
+            Ok(())
+        },
+    },
+];
+
+/// 重命名插件的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRenameResult {
+    /// 更新了 plugin_name 的记录数
+    pub renamed: usize,
+    /// form_id 中插件部分被改写的记录数
+    pub form_ids_rewritten: usize,
+    /// 改写后与已有记录冲突而保留原 form_id 的记录数
+    pub form_id_conflicts: usize,
+}
+
+/// 译文查找替换中命中的单条记录（替换前后的译文）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationReplacement {
+    pub form_id: String,
+    pub record_type: String,
+    pub subrecord_type: String,
+    pub index: u32,
+    pub plugin_name: Option<String>,
+    pub before: String,
+    pub after: String,
+    /// 该记录中的匹配次数
+    pub matches: usize,
+}
+
+/// 译文查找替换结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationReplaceResult {
+    /// 受影响的记录（预览与实际写入时相同）
+    pub rows: Vec<TranslationReplacement>,
+    /// 所有记录的匹配总数
+    pub total_matches: usize,
+    /// 是否已写入数据库（预览时为 false）
+    pub applied: bool,
+}
+
+/// 分块批量保存结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSaveOutcome {
+    /// 已提交的记录数（含内容未变化的记录）
+    pub committed: usize,
+    /// 实际新增或更新的记录数（内容未变化的记录不计入）
+    pub affected: usize,
+}
+
+/// 分块批量保存失败信息
+#[derive(Debug)]
+pub struct BatchSaveError {
+    /// 失败前已提交的记录数
+    pub committed: usize,
+    /// 导致失败的数据库错误
+    pub source: rusqlite::Error,
+}
+
+impl std::fmt::Display for BatchSaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}（已提交 {} 条）", self.source, self.committed)
+    }
+}
+
 /// 翻译数据库管理器
 pub struct TranslationDB {
     conn: Arc<Mutex<Connection>>,
@@ -50,6 +289,7 @@ impl TranslationDB {
     /// 初始化数据库连接
     pub fn new(db_path: PathBuf) -> Result<Self> {
         let conn = Connection::open(db_path)?;
+        configure_busy_timeout(&conn)?;
 
         // 启用WAL模式以支持并发（使用 execute_batch 避免返回结果的问题）
         conn.execute_batch(
@@ -98,58 +338,109 @@ impl TranslationDB {
             [],
         )?;
 
+        // 创建标签表（独立存储，一条记录可有多个标签）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS translation_tags (
+                form_id TEXT NOT NULL,
+                record_type TEXT NOT NULL,
+                subrecord_type TEXT NOT NULL,
+                \"index\" INTEGER NOT NULL DEFAULT 0,
+                tag TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (form_id, record_type, subrecord_type, \"index\", tag)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_translation_tags_tag ON translation_tags(tag)",
+            [],
+        )?;
+
+        run_migrations(&conn, "translation_meta", MIGRATIONS)?;
+
         Ok(())
     }
 
     /// 保存单条翻译（UPSERT）
-    pub fn save_translation(&self, translation: Translation) -> Result<()> {
+    ///
+    /// 默认不更新已有记录的 original_text，防止已汉化的源文件污染英文原文（基础词典尤其依赖这一点）。
+    /// `force_update_original` 为 true 时同时覆盖 original_text，仅用于修正确实提取错误的原文；
+    /// 误用会让后续按原文的查询与覆盖判断基于错误文本
+    pub fn save_translation(&self, translation: Translation, force_update_original: bool) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
-        conn.execute(
-            "INSERT INTO translations
-                (form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
-                 translated_text, plugin_name, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-             ON CONFLICT(form_id, record_type, subrecord_type, \"index\")
-             DO UPDATE SET
-                translated_text = excluded.translated_text,
-                updated_at = excluded.updated_at
-                -- ⚠️ 注意：original_text 不被更新，防止已汉化源文件污染英文原文",
-            params![
-                translation.form_id,
-                translation.record_type,
-                translation.subrecord_type,
-                translation.index,
-                translation.editor_id,
-                translation.original_text,
-                translation.translated_text,
-                translation.plugin_name,
-                translation.created_at,
-                translation.updated_at,
-            ],
-        )?;
+        retry_on_busy(|| {
+            conn.execute(
+                UPSERT_TRANSLATION_SQL,
+                params![
+                    translation.form_id,
+                    translation.record_type,
+                    translation.subrecord_type,
+                    translation.index,
+                    translation.editor_id,
+                    translation.original_text,
+                    translation.translated_text,
+                    translation.plugin_name,
+                    translation.created_at,
+                    translation.updated_at,
+                    translation.note,
+                    force_update_original,
+                ],
+            )
+        })?;
 
         Ok(())
     }
 
-    /// 批量保存翻译（使用事务）
-    pub fn batch_save_translations(&self, translations: Vec<Translation>) -> Result<()> {
+    /// 批量保存翻译（按 `BATCH_SAVE_CHUNK_SIZE` 分块提交事务）
+    ///
+    /// `force_update_original` 的含义与风险同 [`Self::save_translation`]
+    ///
+    /// # 返回
+    /// * `Ok(BatchSaveOutcome)` - 已提交的记录数与实际新增或更新的记录数
+    /// * `Err(BatchSaveError)` - 失败信息，包含失败前已提交的记录数
+    pub fn batch_save_translations(
+        &self,
+        translations: Vec<Translation>,
+        force_update_original: bool,
+    ) -> std::result::Result<BatchSaveOutcome, BatchSaveError> {
+        self.batch_save_in_chunks(translations, BATCH_SAVE_CHUNK_SIZE, force_update_original)
+    }
+
+    /// 分块批量保存：每块独立事务，后续分块失败时已提交的分块仍会保留
+    fn batch_save_in_chunks(
+        &self,
+        translations: Vec<Translation>,
+        chunk_size: usize,
+        force_update_original: bool,
+    ) -> std::result::Result<BatchSaveOutcome, BatchSaveError> {
         let conn = self.conn.lock().unwrap();
+        let mut outcome = BatchSaveOutcome::default();
+
+        for chunk in translations.chunks(chunk_size.max(1)) {
+            outcome.affected +=
+                retry_on_busy(|| Self::save_chunk(&conn, chunk, force_update_original))
+                    .map_err(|source| BatchSaveError { committed: outcome.committed, source })?;
+            outcome.committed += chunk.len();
+        }
 
+        Ok(outcome)
+    }
+
+    /// 在单个事务中写入一个分块（失败时整块回滚），返回影响的行数
+    fn save_chunk(
+        conn: &Connection,
+        chunk: &[Translation],
+        force_update_original: bool,
+    ) -> Result<usize> {
         let tx = conn.unchecked_transaction()?;
+        let mut affected = 0;
 
-        for translation in translations {
-            tx.execute(
-                "INSERT INTO translations
-                    (form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
-                     translated_text, plugin_name, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-                 ON CONFLICT(form_id, record_type, subrecord_type, \"index\")
-                 DO UPDATE SET
-                    translated_text = excluded.translated_text,
-                    updated_at = excluded.updated_at
-                    -- ⚠️ 注意：original_text 不被更新，防止已汉化源文件污染英文原文",
-                params![
+        {
+            let mut stmt = tx.prepare_cached(UPSERT_TRANSLATION_SQL)?;
+            for translation in chunk {
+                affected += stmt.execute(params![
                     translation.form_id,
                     translation.record_type,
                     translation.subrecord_type,
@@ -160,12 +451,69 @@ impl TranslationDB {
                     translation.plugin_name,
                     translation.created_at,
                     translation.updated_at,
-                ],
-            )?;
+                    translation.note,
+                    force_update_original,
+                ])?;
+            }
         }
 
         tx.commit()?;
-        Ok(())
+        Ok(affected)
+    }
+
+    /// 逐行遍历翻译（可按插件与更新时间过滤），不将结果集整体载入内存，返回遍历的记录数
+    ///
+    /// `since` 存在时仅遍历 `updated_at >= since` 的记录（使用 updated_at 索引）；
+    /// 回调返回错误时立即停止遍历
+    pub fn for_each_translation<F>(
+        &self,
+        plugin_name: Option<&str>,
+        since: Option<i64>,
+        mut f: F,
+    ) -> std::result::Result<usize, String>
+    where
+        F: FnMut(Translation) -> std::result::Result<(), String>,
+    {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
+                        translated_text, plugin_name, created_at, updated_at, note
+                 FROM translations
+                 WHERE (?1 IS NULL OR plugin_name = ?1) AND (?2 IS NULL OR updated_at >= ?2)
+                 ORDER BY plugin_name, form_id, record_type, subrecord_type, \"index\"",
+            )
+            .map_err(|e| format!("查询翻译失败: {}", e))?;
+
+        let rows = stmt
+            .query_map(params![plugin_name, since], |row| {
+                Ok(Translation {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    editor_id: row.get(4)?,
+                    original_text: row.get(5)?,
+                    translated_text: row.get(6)?,
+                    plugin_name: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
+                })
+            })
+            .map_err(|e| format!("查询翻译失败: {}", e))?;
+
+        let mut count = 0;
+        for row in rows {
+            f(row.map_err(|e| format!("读取翻译失败: {}", e))?)?;
+            count += 1;
+        }
+
+        Ok(count)
     }
 
     /// 查询单条翻译
@@ -180,7 +528,7 @@ impl TranslationDB {
 
         let mut stmt = conn.prepare(
             "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
-                    translated_text, plugin_name, created_at, updated_at
+                    translated_text, plugin_name, created_at, updated_at, note
              FROM translations
              WHERE form_id = ?1 AND record_type = ?2 AND subrecord_type = ?3 AND \"index\" = ?4",
         )?;
@@ -199,6 +547,11 @@ impl TranslationDB {
                     plugin_name: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
                 })
             },
         );
@@ -210,23 +563,131 @@ impl TranslationDB {
         }
     }
 
+    /// 设置翻译备注（传入 None 或空字符串表示清除备注）
+    ///
+    /// # 返回
+    /// * `Ok(true)` - 已更新
+    /// * `Ok(false)` - 翻译数据库中不存在该记录
+    pub fn set_translation_note(&self, key: &FormIdentifier, note: Option<&str>) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let note = note.map(str::trim).filter(|n| !n.is_empty());
+
+        let updated = conn.execute(
+            "UPDATE translations SET note = ?5
+             WHERE form_id = ?1 AND record_type = ?2 AND subrecord_type = ?3 AND \"index\" = ?4",
+            params![key.form_id, key.record_type, key.subrecord_type, key.index, note],
+        )?;
+
+        Ok(updated > 0)
+    }
+
+    /// 为翻译记录添加标签，返回是否新增（已存在时返回 false）
+    pub fn add_translation_tag(&self, key: &FormIdentifier, tag: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO translation_tags
+                (form_id, record_type, subrecord_type, \"index\", tag, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![key.form_id, key.record_type, key.subrecord_type, key.index, tag, now],
+        )?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 移除翻译记录的标签，返回是否删除
+    pub fn remove_translation_tag(&self, key: &FormIdentifier, tag: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM translation_tags
+             WHERE form_id = ?1 AND record_type = ?2 AND subrecord_type = ?3 AND \"index\" = ?4 AND tag = ?5",
+            params![key.form_id, key.record_type, key.subrecord_type, key.index, tag],
+        )?;
+
+        Ok(removed > 0)
+    }
+
+    /// 列出翻译记录（按更新时间倒序）
+    ///
+    /// # 参数
+    /// - `plugin_name`: 仅返回指定插件的记录（可选）
+    /// - `tag`: 仅返回带有该标签的记录（可选）
+    /// - `strings_table`: 仅返回位于该 STRINGS 表的记录（可选）
+    /// - `limit`: 返回结果数量限制
+    pub fn list_translations(
+        &self,
+        plugin_name: Option<&str>,
+        tag: Option<&str>,
+        strings_table: Option<StringsTable>,
+        limit: usize,
+    ) -> Result<Vec<Translation>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
+                    translated_text, plugin_name, created_at, updated_at, note
+             FROM translations t
+             WHERE (?1 IS NULL OR t.plugin_name = ?1)
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM translation_tags g
+                    WHERE g.form_id = t.form_id AND g.record_type = t.record_type
+                      AND g.subrecord_type = t.subrecord_type AND g.\"index\" = t.\"index\"
+                      AND g.tag = ?2))
+               AND (?4 IS NULL OR {} = ?4)
+             ORDER BY t.updated_at DESC
+             LIMIT ?3",
+            STRINGS_TABLE_SQL
+        ))?;
+
+        let table = strings_table.map(|t| t.as_str());
+        let rows = stmt
+            .query_map(params![plugin_name, tag, limit as i64, table], |row| {
+                Ok(Translation {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    editor_id: row.get(4)?,
+                    original_text: row.get(5)?,
+                    translated_text: row.get(6)?,
+                    plugin_name: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
     /// 批量查询翻译
     pub fn batch_query_translations(&self, forms: Vec<FormIdentifier>) -> Result<Vec<Translation>> {
-        self.batch_query_translations_with_progress(forms, |_, _| {})
+        self.batch_query_translations_with_progress(forms, DEFAULT_QUERY_CHUNK_SIZE, |_, _, _| {})
     }
 
     /// 批量查询翻译（带进度回调）
     ///
     /// # 参数
     /// - `forms`: Form标识符列表
-    /// - `progress_callback`: 进度回调函数 (当前数量, 总数量)
+    /// - `chunk_size`: 每条 SQL 查询的 key 数量（会被限制在 SQLite 参数上限以内）
+    /// - `progress_callback`: 进度回调函数 (当前数量, 总数量, 已匹配数量)
     pub fn batch_query_translations_with_progress<F>(
         &self,
         forms: Vec<FormIdentifier>,
+        chunk_size: usize,
         mut progress_callback: F,
     ) -> Result<Vec<Translation>>
     where
-        F: FnMut(usize, usize),
+        F: FnMut(usize, usize, usize),
     {
         if forms.is_empty() {
             return Ok(Vec::new());
@@ -237,15 +698,19 @@ impl TranslationDB {
         let total = forms.len();
         let mut processed = 0;
 
-        // 分批查询（每批最多1000条）
-        for chunk in forms.chunks(1000) {
+        // 每批的参数总数不能超过 SQLITE_LIMIT_VARIABLE_NUMBER
+        let max_variables = conn.limit(Limit::SQLITE_LIMIT_VARIABLE_NUMBER).max(0) as usize;
+        let chunk_size = chunk_size.clamp(1, (max_variables / QUERY_PARAMS_PER_FORM).max(1));
+
+        // 分批查询
+        for chunk in forms.chunks(chunk_size) {
             let placeholders: Vec<String> =
                 chunk.iter().map(|_| "(?, ?, ?, ?)".to_string()).collect();
             let placeholders_str = placeholders.join(", ");
 
             let query = format!(
                 "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
-                        translated_text, plugin_name, created_at, updated_at
+                        translated_text, plugin_name, created_at, updated_at, note
                  FROM translations
                  WHERE (form_id, record_type, subrecord_type, \"index\") IN ({})",
                 placeholders_str
@@ -277,6 +742,11 @@ impl TranslationDB {
                     plugin_name: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
                 })
             })?;
 
@@ -286,12 +756,79 @@ impl TranslationDB {
 
             // 更新进度
             processed += chunk.len();
-            progress_callback(processed, total);
+            progress_callback(processed, total, translations.len());
         }
 
         Ok(translations)
     }
 
+    /// 查询编辑器参考翻译（输入过长时只查询其中最长的单词，见 [`lookup_term`]）
+    pub fn lookup_word_translations(&self, text: &str, limit: usize) -> Result<WordLookupResult> {
+        let (query, truncated) = lookup_term(text);
+        let translations = self.query_by_text(&query, limit)?;
+        Ok(WordLookupResult {
+            translations,
+            query,
+            truncated,
+        })
+    }
+
+    /// 按原文精确查询翻译（用于按文本自动填充，同一原文可能返回多个插件的译文）
+    pub fn query_by_original_texts(&self, texts: &[String]) -> Result<Vec<Translation>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let max_variables = conn.limit(Limit::SQLITE_LIMIT_VARIABLE_NUMBER).max(0) as usize;
+        let chunk_size = DEFAULT_QUERY_CHUNK_SIZE.clamp(1, max_variables.max(1));
+        let mut translations = Vec::new();
+
+        for chunk in texts.chunks(chunk_size) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let query = format!(
+                "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
+                        translated_text, plugin_name, created_at, updated_at, note
+                 FROM translations
+                 WHERE original_text IN ({})",
+                placeholders
+            );
+
+            let mut stmt = conn.prepare(&query)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+                Ok(Translation {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    editor_id: row.get(4)?,
+                    original_text: row.get(5)?,
+                    translated_text: row.get(6)?,
+                    plugin_name: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
+                })
+            })?;
+
+            for row in rows {
+                translations.push(row?);
+            }
+        }
+
+        Ok(translations)
+    }
+
+    /// 获取翻译数据库中出现的全部记录类型/子记录类型组合（按条目数降序）
+    pub fn get_distinct_types(&self) -> Result<Vec<TypeCount>> {
+        let conn = self.conn.lock().unwrap();
+        query_type_counts(&conn, "translations")
+    }
+
     /// 获取统计信息
     pub fn get_statistics(&self) -> Result<TranslationStats> {
         let conn = self.conn.lock().unwrap();
@@ -299,10 +836,19 @@ impl TranslationDB {
         // 获取总数
         let total_count: i64 =
             conn.query_row("SELECT COUNT(*) FROM translations", [], |row| row.get(0))?;
+        let untranslated_count: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM translations
+                 WHERE trim(translated_text, {0}) = trim(original_text, {0})",
+                TRIM_CHARS_SQL
+            ),
+            [],
+            |row| row.get(0),
+        )?;
 
         // 获取按plugin分组的统计
         let mut stmt = conn.prepare(
-            "SELECT plugin_name, COUNT(*) as count
+            "SELECT plugin_name, COUNT(*) as count, MAX(updated_at)
              FROM translations
              GROUP BY plugin_name
              ORDER BY count DESC",
@@ -315,6 +861,7 @@ impl TranslationDB {
                         .get::<_, Option<String>>(0)?
                         .unwrap_or_else(|| "Unknown".to_string()),
                     count: row.get(1)?,
+                    last_updated: row.get::<_, Option<i64>>(2)?.unwrap_or(0),
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -328,6 +875,7 @@ impl TranslationDB {
 
         Ok(TranslationStats {
             total_count,
+            untranslated_count,
             plugin_counts,
             last_updated,
         })
@@ -343,10 +891,195 @@ impl TranslationDB {
         Ok(count)
     }
 
-    /// 删除所有翻译（慎用）
+    /// 将翻译记录的 plugin_name 从 `old_name` 改为 `new_name`（单个事务）
+    ///
+    /// `rewrite_form_ids` 为 true 时，同时把所有引用该插件的记录（form_id 形如 `XXXXXXXX|old_name`，
+    /// 包括其他插件对它的覆盖）的插件部分改写为 `new_name`，标签随之迁移。
+    /// 改写后与已有记录主键冲突的行保留原 form_id
+    pub fn rename_plugin(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        rewrite_form_ids: bool,
+    ) -> Result<PluginRenameResult> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let (form_ids_rewritten, form_id_conflicts) = if rewrite_form_ids {
+            let old_suffix = format!("%|{}", escape_like(old_name));
+            let candidates: usize = tx.query_row(
+                "SELECT COUNT(*) FROM translations WHERE form_id LIKE ?1 ESCAPE '\\'",
+                params![old_suffix],
+                |row| row.get(0),
+            )?;
+
+            let rewrite_form_id = "substr(form_id, 1, length(form_id) - length(?1)) || ?2";
+            let rewritten = tx.execute(
+                &format!(
+                    "UPDATE OR IGNORE translations SET form_id = {}
+                     WHERE form_id LIKE ?3 ESCAPE '\\'",
+                    rewrite_form_id
+                ),
+                params![old_name, new_name, old_suffix],
+            )?;
+            tx.execute(
+                &format!(
+                    "UPDATE OR IGNORE translation_tags SET form_id = {}
+                     WHERE form_id LIKE ?3 ESCAPE '\\'
+                       AND NOT EXISTS (
+                           SELECT 1 FROM translations t
+                           WHERE t.form_id = translation_tags.form_id
+                             AND t.record_type = translation_tags.record_type
+                             AND t.subrecord_type = translation_tags.subrecord_type
+                             AND t.\"index\" = translation_tags.\"index\"
+                       )",
+                    rewrite_form_id
+                ),
+                params![old_name, new_name, old_suffix],
+            )?;
+
+            (rewritten, candidates - rewritten)
+        } else {
+            (0, 0)
+        };
+
+        let renamed = tx.execute(
+            "UPDATE translations SET plugin_name = ?2 WHERE plugin_name = ?1",
+            params![old_name, new_name],
+        )?;
+
+        tx.commit()?;
+
+        Ok(PluginRenameResult {
+            renamed,
+            form_ids_rewritten,
+            form_id_conflicts,
+        })
+    }
+
+    /// 在译文中查找并替换 `pattern`（字面量需由调用方转义），`plugin_name` 限定只处理该插件的记录
+    ///
+    /// `dry_run` 为 true 时仅返回受影响记录的替换前后内容，不写入；否则在单个事务中更新译文与 updated_at
+    pub fn replace_in_translations(
+        &self,
+        pattern: &Regex,
+        replacement: &str,
+        plugin_name: Option<&str>,
+        dry_run: bool,
+    ) -> Result<TranslationReplaceResult> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut rows = Vec::new();
+        {
+            let mut stmt = conn.prepare(
+                "SELECT form_id, record_type, subrecord_type, \"index\", plugin_name, translated_text
+                 FROM translations
+                 WHERE (?1 IS NULL OR plugin_name = ?1)
+                 ORDER BY plugin_name, form_id, record_type, subrecord_type, \"index\"",
+            )?;
+            let mut query = stmt.query(params![plugin_name])?;
+            while let Some(row) = query.next()? {
+                let before: String = row.get(5)?;
+                let matches = pattern.find_iter(&before).count();
+                if matches == 0 {
+                    continue;
+                }
+                let after = pattern.replace_all(&before, replacement).into_owned();
+                if after == before {
+                    continue;
+                }
+                rows.push(TranslationReplacement {
+                    form_id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    subrecord_type: row.get(2)?,
+                    index: row.get(3)?,
+                    plugin_name: row.get(4)?,
+                    before,
+                    after,
+                    matches,
+                });
+            }
+        }
+
+        if !dry_run && !rows.is_empty() {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            retry_on_busy(|| {
+                let tx = conn.unchecked_transaction()?;
+                {
+                    let mut stmt = tx.prepare(
+                        "UPDATE translations SET translated_text = ?1, updated_at = ?2
+                         WHERE form_id = ?3 AND record_type = ?4 AND subrecord_type = ?5
+                           AND \"index\" = ?6",
+                    )?;
+                    for r in &rows {
+                        stmt.execute(params![
+                            r.after,
+                            now,
+                            r.form_id,
+                            r.record_type,
+                            r.subrecord_type,
+                            r.index
+                        ])?;
+                    }
+                }
+                tx.commit()
+            })?;
+        }
+
+        Ok(TranslationReplaceResult {
+            total_matches: rows.iter().map(|r| r.matches).sum(),
+            rows,
+            applied: !dry_run,
+        })
+    }
+
+    /// 将翻译记录改写为新的 form_id（单个事务，标签随之迁移），返回改写的记录数
+    ///
+    /// 新 key 已存在翻译时保留原记录不改写（UPDATE OR IGNORE）
+    pub fn remap_form_ids(&self, remaps: &[(FormIdentifier, String)]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        retry_on_busy(|| {
+            let tx = conn.unchecked_transaction()?;
+            let mut rewritten = 0;
+            {
+                let mut update = tx.prepare(
+                    "UPDATE OR IGNORE translations SET form_id = ?1
+                     WHERE form_id = ?2 AND record_type = ?3 AND subrecord_type = ?4
+                       AND \"index\" = ?5",
+                )?;
+                let mut update_tags = tx.prepare(
+                    "UPDATE OR IGNORE translation_tags SET form_id = ?1
+                     WHERE form_id = ?2 AND record_type = ?3 AND subrecord_type = ?4
+                       AND \"index\" = ?5",
+                )?;
+                for (old, new_form_id) in remaps {
+                    let params = params![
+                        new_form_id,
+                        old.form_id,
+                        old.record_type,
+                        old.subrecord_type,
+                        old.index
+                    ];
+                    let changed = update.execute(params)?;
+                    if changed > 0 {
+                        update_tags.execute(params)?;
+                        rewritten += changed;
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok(rewritten)
+        })
+    }
+
+    /// 删除所有翻译及其标签（慎用）
     pub fn clear_all_translations(&self) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
         let count = conn.execute("DELETE FROM translations", [])?;
+        conn.execute("DELETE FROM translation_tags", [])?;
         Ok(count)
     }
 
@@ -414,13 +1147,13 @@ impl TranslationDB {
         };
 
         let conn = self.conn.lock().unwrap();
-        let search_pattern = format!("%{}%", lowered_keyword);
+        let search_pattern = format!("%{}%", escape_like(&lowered_keyword));
 
         let mut stmt = conn.prepare(
             "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
-                    translated_text, plugin_name, created_at, updated_at
+                    translated_text, plugin_name, created_at, updated_at, note
              FROM translations
-             WHERE LOWER(original_text) LIKE ?1
+             WHERE LOWER(original_text) LIKE ?1 ESCAPE '\\'
              ORDER BY LENGTH(original_text) ASC
              LIMIT ?2",
         )?;
@@ -438,6 +1171,11 @@ impl TranslationDB {
                     plugin_name: row.get(7)?,
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
+                    note: row.get(10)?,
+                    strings_table: StringsTable::classify(
+                        &row.get::<_, String>(1)?,
+                        &row.get::<_, String>(2)?,
+                    ),
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -458,6 +1196,159 @@ impl TranslationDB {
 
         Ok(filtered)
     }
+
+    /// 在原文与译文中全局搜索（用于术语查证），返回命中字段与上下文片段
+    ///
+    /// 翻译库未建立 FTS 索引，使用 `LIKE` 子串匹配（忽略 ASCII 大小写，`%`/`_` 按字面匹配）；
+    /// `fields` 为空时同时搜索原文与译文，结果按原文长度从短到长排序
+    pub fn search_all(
+        &self,
+        query: &str,
+        fields: &[SearchField],
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let keyword = query.trim().to_ascii_lowercase();
+        if keyword.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let fields = if fields.is_empty() {
+            &[SearchField::Original, SearchField::Translated][..]
+        } else {
+            fields
+        };
+        let search_original = fields.contains(&SearchField::Original);
+        let search_translated = fields.contains(&SearchField::Translated);
+
+        let conn = self.conn.lock().unwrap();
+        let search_pattern = format!("%{}%", escape_like(&keyword));
+
+        let mut stmt = conn.prepare(
+            "SELECT form_id, record_type, subrecord_type, \"index\", editor_id, original_text,
+                    translated_text, plugin_name, created_at, updated_at, note
+             FROM translations
+             WHERE (?2 AND LOWER(original_text) LIKE ?1 ESCAPE '\\')
+                OR (?3 AND LOWER(translated_text) LIKE ?1 ESCAPE '\\')
+             ORDER BY LENGTH(original_text) ASC
+             LIMIT ?4",
+        )?;
+
+        let rows = stmt
+            .query_map(
+                params![search_pattern, search_original, search_translated, limit as i64],
+                |row| {
+                    Ok(Translation {
+                        form_id: row.get(0)?,
+                        record_type: row.get(1)?,
+                        subrecord_type: row.get(2)?,
+                        index: row.get(3)?,
+                        editor_id: row.get(4)?,
+                        original_text: row.get(5)?,
+                        translated_text: row.get(6)?,
+                        plugin_name: row.get(7)?,
+                        created_at: row.get(8)?,
+                        updated_at: row.get(9)?,
+                        note: row.get(10)?,
+                        strings_table: StringsTable::classify(
+                            &row.get::<_, String>(1)?,
+                            &row.get::<_, String>(2)?,
+                        ),
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let hits = rows
+            .into_iter()
+            .filter_map(|translation| {
+                let (matched_field, (snippet, highlight)) = fields.iter().find_map(|field| {
+                    let text = match field {
+                        SearchField::Original => &translation.original_text,
+                        SearchField::Translated => &translation.translated_text,
+                    };
+                    build_snippet(text, &keyword).map(|snippet| (*field, snippet))
+                })?;
+                Some(SearchHit {
+                    translation,
+                    matched_field,
+                    snippet,
+                    highlight,
+                })
+            })
+            .collect();
+
+        Ok(hits)
+    }
+}
+
+/// 选出编辑器参考查询使用的文本，返回 (查询文本, 是否被缩短)
+///
+/// 不超过 [`MAX_LOOKUP_CHARS`] 个字符时原样使用；否则按非字母数字字符切分，
+/// 取最长的单词（长度相同取靠前的），仍然过长（如无空格的整段文本）时截断
+pub fn lookup_term(text: &str) -> (String, bool) {
+    let trimmed = text.trim();
+    if trimmed.chars().count() <= MAX_LOOKUP_CHARS {
+        return (trimmed.to_string(), false);
+    }
+
+    let longest = trimmed
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|term| term.trim_matches('\''))
+        .fold("", |best, term| {
+            if term.chars().count() > best.chars().count() {
+                term
+            } else {
+                best
+            }
+        });
+    (longest.chars().take(MAX_LOOKUP_CHARS).collect(), true)
+}
+
+/// 截取命中位置附近的片段，返回 (片段, 高亮字符区间)
+///
+/// 匹配规则与 SQLite 的 `LOWER` 一致（仅 ASCII 忽略大小写），因此字节偏移在大小写转换后不变
+fn build_snippet(text: &str, keyword_lower: &str) -> Option<(String, (usize, usize))> {
+    let byte_start = text.to_ascii_lowercase().find(keyword_lower)?;
+    let byte_end = byte_start + keyword_lower.len();
+
+    let chars: Vec<char> = text.chars().collect();
+    let match_start = text[..byte_start].chars().count();
+    let match_end = match_start + text[byte_start..byte_end].chars().count();
+
+    let from = match_start.saturating_sub(SNIPPET_RADIUS);
+    let to = (match_end + SNIPPET_RADIUS).min(chars.len());
+
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    let offset = snippet.chars().count();
+    snippet.extend(&chars[from..to]);
+    if to < chars.len() {
+        snippet.push('…');
+    }
+
+    let start = offset + match_start - from;
+    Some((snippet, (start, start + match_end - match_start)))
+}
+
+/// 按 record_type + subrecord_type 分组统计指定表的条目数（`table` 仅限内部常量）
+pub(crate) fn query_type_counts(conn: &Connection, table: &str) -> Result<Vec<TypeCount>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT record_type, subrecord_type, COUNT(*) AS count
+         FROM {}
+         GROUP BY record_type, subrecord_type
+         ORDER BY count DESC, record_type, subrecord_type",
+        table
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(TypeCount {
+            record_type: row.get(0)?,
+            subrecord_type: row.get(1)?,
+            count: row.get(2)?,
+        })
+    })?;
+    rows.collect()
 }
 
 fn contains_ascii_token(original: &str, keyword_lower: &str) -> bool {
@@ -467,10 +1358,33 @@ fn contains_ascii_token(original: &str, keyword_lower: &str) -> bool {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::time::SystemTime;
 
+    /// 构造测试用翻译记录（`MISC FULL`，其余字段取默认值，按需用结构体更新语法覆盖）
+    pub(crate) fn translation(
+        form_id: &str,
+        plugin: &str,
+        original: &str,
+        translated: &str,
+    ) -> Translation {
+        Translation {
+            form_id: form_id.to_string(),
+            record_type: "MISC".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+            editor_id: None,
+            original_text: original.to_string(),
+            translated_text: translated.to_string(),
+            plugin_name: Some(plugin.to_string()),
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        }
+    }
+
     #[test]
     fn test_translation_db() -> Result<()> {
         // 使用内存数据库进行测试
@@ -493,9 +1407,11 @@ mod tests {
             plugin_name: Some("Skyrim.esm".to_string()),
             created_at: now,
             updated_at: now,
+            note: None,
+            strings_table: StringsTable::Strings,
         };
 
-        db.save_translation(translation.clone())?;
+        db.save_translation(translation.clone(), false)?;
 
         // 测试查询翻译
         let result = db.get_translation("00012BB7|Skyrim.esm", "WEAP", "FULL", 0)?;
@@ -508,7 +1424,7 @@ mod tests {
             updated_at: now + 1,
             ..translation
         };
-        db.save_translation(updated_translation)?;
+        db.save_translation(updated_translation, false)?;
 
         let result = db.get_translation("00012BB7|Skyrim.esm", "WEAP", "FULL", 0)?;
         assert_eq!(result.unwrap().translated_text, "钢剑");
@@ -517,6 +1433,198 @@ mod tests {
         let stats = db.get_statistics()?;
         assert_eq!(stats.total_count, 1);
 
+        let types = db.get_distinct_types()?;
+        assert_eq!(
+            types,
+            vec![TypeCount {
+                record_type: "WEAP".to_string(),
+                subrecord_type: "FULL".to_string(),
+                count: 1,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_by_original_texts_uses_index() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "EXPLAIN QUERY PLAN SELECT * FROM translations WHERE original_text IN (?1, ?2)",
+        )?;
+        let plan: Vec<String> = stmt
+            .query_map(["Iron Sword", "Steel Sword"], |row| row.get(3))?
+            .collect::<Result<_>>()?;
+        assert!(plan.iter().any(|detail| detail.contains("idx_original_text")), "{:?}", plan);
+        Ok(())
+    }
+
+    #[test]
+    fn force_update_original_controls_original_text() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let translation = Translation {
+            form_id: "00000001|Test.esp".to_string(),
+            record_type: "BOOK".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+            editor_id: None,
+            original_text: "Iron Swrod".to_string(),
+            translated_text: "铁剑".to_string(),
+            plugin_name: Some("Test.esp".to_string()),
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+        db.save_translation(translation.clone(), false)?;
+
+        let corrected = Translation {
+            original_text: "Iron Sword".to_string(),
+            ..translation
+        };
+        // 默认保护原文
+        db.save_translation(corrected.clone(), false)?;
+        let saved = db.get_translation("00000001|Test.esp", "BOOK", "FULL", 0)?.unwrap();
+        assert_eq!(saved.original_text, "Iron Swrod");
+
+        db.batch_save_translations(vec![corrected.clone()], false).unwrap();
+        let saved = db.get_translation("00000001|Test.esp", "BOOK", "FULL", 0)?.unwrap();
+        assert_eq!(saved.original_text, "Iron Swrod");
+
+        // 显式要求时覆盖原文（单条与批量）
+        db.save_translation(corrected.clone(), true)?;
+        let saved = db.get_translation("00000001|Test.esp", "BOOK", "FULL", 0)?.unwrap();
+        assert_eq!(saved.original_text, "Iron Sword");
+
+        db.batch_save_translations(
+            vec![Translation {
+                original_text: "Iron Sword!".to_string(),
+                ..corrected
+            }],
+            true,
+        )
+        .unwrap();
+        let saved = db.get_translation("00000001|Test.esp", "BOOK", "FULL", 0)?.unwrap();
+        assert_eq!(saved.original_text, "Iron Sword!");
+
+        Ok(())
+    }
+
+    #[test]
+    fn note_survives_upsert() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let translation = Translation {
+            form_id: "00012BB7|Skyrim.esm".to_string(),
+            record_type: "NPC_".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+            editor_id: None,
+            original_text: "Guard".to_string(),
+            translated_text: "守卫".to_string(),
+            plugin_name: Some("Skyrim.esm".to_string()),
+            created_at: 0,
+            updated_at: 0,
+            note: None,
+            strings_table: StringsTable::Strings,
+        };
+        db.save_translation(translation.clone(), false)?;
+
+        let key = FormIdentifier {
+            form_id: translation.form_id.clone(),
+            record_type: translation.record_type.clone(),
+            subrecord_type: translation.subrecord_type.clone(),
+            index: 0,
+        };
+        assert!(db.set_translation_note(&key, Some("性别不明确"))?);
+
+        // 不携带备注的 UPSERT 不会清除备注
+        let updated = Translation {
+            translated_text: "卫兵".to_string(),
+            updated_at: 1,
+            ..translation
+        };
+        db.save_translation(updated, false)?;
+        let saved = db.get_translation("00012BB7|Skyrim.esm", "NPC_", "FULL", 0)?.unwrap();
+        assert_eq!(saved.translated_text, "卫兵");
+        assert_eq!(saved.note.as_deref(), Some("性别不明确"));
+
+        assert!(db.set_translation_note(&key, Some("  "))?);
+        let saved = db.get_translation("00012BB7|Skyrim.esm", "NPC_", "FULL", 0)?.unwrap();
+        assert_eq!(saved.note, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_translations_filters_by_tag() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let make = |form_id: &str| Translation {
+            record_type: "BOOK".to_string(),
+            subrecord_type: "DESC".to_string(),
+            ..translation(form_id, "Test.esp", "Text", "文本")
+        };
+        db.save_translation(make("00000001|Test.esp"), false)?;
+        db.save_translation(make("00000002|Test.esp"), false)?;
+
+        let key = |form_id: &str| FormIdentifier {
+            form_id: form_id.to_string(),
+            record_type: "BOOK".to_string(),
+            subrecord_type: "DESC".to_string(),
+            index: 0,
+        };
+        assert!(db.add_translation_tag(&key("00000001|Test.esp"), "needs-review")?);
+        assert!(db.add_translation_tag(&key("00000001|Test.esp"), "lore-heavy")?);
+        assert!(!db.add_translation_tag(&key("00000001|Test.esp"), "needs-review")?);
+        assert!(db.add_translation_tag(&key("00000002|Test.esp"), "lore-heavy")?);
+
+        let tagged = db.list_translations(None, Some("needs-review"), None, 10)?;
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].form_id, "00000001|Test.esp");
+        assert_eq!(db.list_translations(None, Some("lore-heavy"), None, 10)?.len(), 2);
+        assert_eq!(db.list_translations(Some("Test.esp"), None, None, 10)?.len(), 2);
+        assert_eq!(tagged[0].strings_table, StringsTable::Dlstrings);
+        assert_eq!(db.list_translations(None, None, Some(StringsTable::Dlstrings), 10)?.len(), 2);
+        assert!(db.list_translations(None, None, Some(StringsTable::Strings), 10)?.is_empty());
+
+        assert!(db.remove_translation_tag(&key("00000001|Test.esp"), "needs-review")?);
+        assert!(db.list_translations(None, Some("needs-review"), None, 10)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rename_plugin_rewrites_form_id_suffix() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let make = |form_id: &str, plugin: &str| translation(form_id, plugin, "Text", "文本");
+        db.save_translation(make("00000001|Old.esp", "Old.esp"), false)?;
+        db.save_translation(make("00012345|Skyrim.esm", "Old.esp"), false)?;
+        db.save_translation(make("00000002|Old.esp", "Patch.esp"), false)?;
+        let key = FormIdentifier {
+            form_id: "00000001|Old.esp".to_string(),
+            record_type: "MISC".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index: 0,
+        };
+        db.add_translation_tag(&key, "needs-review")?;
+
+        let result = db.rename_plugin("Old.esp", "New.esp", true)?;
+        assert_eq!(result.renamed, 2);
+        assert_eq!(result.form_ids_rewritten, 2);
+        assert_eq!(result.form_id_conflicts, 0);
+
+        let renamed = db.list_translations(Some("New.esp"), None, None, 10)?;
+        let mut form_ids: Vec<_> = renamed.iter().map(|t| t.form_id.as_str()).collect();
+        form_ids.sort();
+        assert_eq!(form_ids, vec!["00000001|New.esp", "00012345|Skyrim.esm"]);
+        // 其他插件对该插件记录的覆盖也使用新后缀
+        assert_eq!(
+            db.list_translations(Some("Patch.esp"), None, None, 10)?[0].form_id,
+            "00000002|New.esp"
+        );
+        let tagged = db.list_translations(None, Some("needs-review"), None, 10)?;
+        assert_eq!(tagged[0].form_id, "00000001|New.esp");
+
         Ok(())
     }
 
@@ -540,6 +1648,8 @@ mod tests {
             plugin_name: Some("Test.esm".to_string()),
             created_at: now,
             updated_at: now,
+            note: None,
+            strings_table: StringsTable::Strings,
         };
 
         let float = Translation {
@@ -553,6 +1663,8 @@ mod tests {
             plugin_name: Some("Test.esm".to_string()),
             created_at: now,
             updated_at: now,
+            note: None,
+            strings_table: StringsTable::Strings,
         };
 
         let korean_name = Translation {
@@ -566,11 +1678,13 @@ mod tests {
             plugin_name: Some("Test.esm".to_string()),
             created_at: now,
             updated_at: now,
+            note: None,
+            strings_table: StringsTable::Strings,
         };
 
-        db.save_translation(loa)?;
-        db.save_translation(float)?;
-        db.save_translation(korean_name)?;
+        db.save_translation(loa, false)?;
+        db.save_translation(float, false)?;
+        db.save_translation(korean_name, false)?;
 
         let results = db.query_by_text("LoA", 5)?;
         assert_eq!(results.len(), 1);
@@ -584,6 +1698,213 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn search_all_reports_matched_field_and_snippet() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let make = |form_id: &str, original: &str, translated: &str| {
+            translation(form_id, "Test.esm", original, translated)
+        };
+
+        let long_text = format!("{}the Dwemer ruins{}", "a".repeat(40), "b".repeat(40));
+        db.save_translation(make("00000001|Test.esm", &long_text, "矮人遗迹"), false)?;
+        db.save_translation(make("00000002|Test.esm", "Falmer", "雪精灵（Dwemer 的奴仆）"), false)?;
+
+        let hits = db.search_all("dwemer", &[], 10)?;
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].matched_field, SearchField::Translated);
+        let (start, end) = hits[0].highlight;
+        let chars: Vec<char> = hits[0].snippet.chars().collect();
+        assert_eq!(chars[start..end].iter().collect::<String>(), "Dwemer");
+
+        assert_eq!(hits[1].matched_field, SearchField::Original);
+        assert!(hits[1].snippet.starts_with('…') && hits[1].snippet.ends_with('…'));
+
+        let original_only = db.search_all("dwemer", &[SearchField::Original], 10)?;
+        assert_eq!(original_only.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replace_in_translations_is_scoped_to_plugin_and_previews_first() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let make = |form_id: &str, plugin: &str| {
+            translation(form_id, plugin, "Dwarven Centurion", "矮人百夫长（矮人）")
+        };
+        db.save_translation(make("00000001|A.esp", "A.esp"), false)?;
+        db.save_translation(make("00000002|B.esp", "B.esp"), false)?;
+
+        let pattern = Regex::new("矮人").unwrap();
+        let preview = db.replace_in_translations(&pattern, "德维", Some("A.esp"), true)?;
+        assert_eq!((preview.rows.len(), preview.total_matches, preview.applied), (1, 2, false));
+        assert_eq!(preview.rows[0].after, "德维百夫长（德维）");
+        let unchanged = db.get_translation("00000001|A.esp", "MISC", "FULL", 0)?.unwrap();
+        assert_eq!(unchanged.translated_text, "矮人百夫长（矮人）");
+
+        db.replace_in_translations(&pattern, "德维", Some("A.esp"), false)?;
+        let a = db.get_translation("00000001|A.esp", "MISC", "FULL", 0)?.unwrap();
+        let b = db.get_translation("00000002|B.esp", "MISC", "FULL", 0)?.unwrap();
+        assert_eq!(a.translated_text, "德维百夫长（德维）");
+        assert_eq!(b.translated_text, "矮人百夫长（矮人）");
+
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_term_picks_longest_word_from_long_input() {
+        assert_eq!(lookup_term("  Iron Sword "), ("Iron Sword".to_string(), false));
+
+        let paragraph = "The Dragonborn's journey begins at Helgen, where an execution is \
+                         interrupted by a dragon attack.";
+        assert_eq!(lookup_term(paragraph), ("Dragonborn's".to_string(), true));
+
+        let unbroken = "龙".repeat(MAX_LOOKUP_CHARS + 10);
+        let (query, truncated) = lookup_term(&unbroken);
+        assert!(truncated);
+        assert_eq!(query.chars().count(), MAX_LOOKUP_CHARS);
+    }
+
+    #[test]
+    fn query_by_text_treats_percent_literally() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+
+        let make = |form_id: &str, original: &str| {
+            translation(form_id, "Test.esm", original, original)
+        };
+
+        db.save_translation(make("00000001|Test.esm", "Bows do 50% more damage."), false)?;
+        db.save_translation(make("00000002|Test.esm", "Bows do 500 more damage."), false)?;
+        db.save_translation(make("00000003|Test.esm", "Bows_do more damage."), false)?;
+
+        let results = db.query_by_text("50%", 5)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].original_text, "Bows do 50% more damage.");
+
+        let results = db.query_by_text("s_d", 5)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].original_text, "Bows_do more damage.");
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_save_keeps_committed_chunks_on_failure() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+
+        // 模拟第 3 块中出现坏数据
+        db.conn.lock().unwrap().execute_batch(
+            "CREATE TRIGGER reject_bad_row BEFORE INSERT ON translations
+             WHEN NEW.form_id = 'BAD'
+             BEGIN SELECT RAISE(ABORT, 'bad row'); END;",
+        )?;
+
+        let make = |form_id: &str| translation(form_id, "Test.esm", "Text", "文本");
+
+        let mut translations: Vec<Translation> =
+            (0..25).map(|i| make(&format!("{:08X}|Test.esm", i))).collect();
+        translations.insert(22, make("BAD"));
+
+        let err = db.batch_save_in_chunks(translations.clone(), 10, false).unwrap_err();
+        assert_eq!(err.committed, 20);
+        assert_eq!(db.get_statistics()?.total_count, 20);
+
+        // 重新保存时前两块内容均未变化，仍计入已提交数
+        let err = db.batch_save_in_chunks(translations, 10, false).unwrap_err();
+        assert_eq!(err.committed, 20);
+
+        // 内容未变化的记录不计入影响行数
+        let unchanged = make("00000000|Test.esm");
+        let changed = Translation {
+            translated_text: "新文本".to_string(),
+            ..make("00000001|Test.esm")
+        };
+        let outcome = db.batch_save_translations(vec![unchanged, changed], false).unwrap();
+        assert_eq!(outcome, BatchSaveOutcome { committed: 2, affected: 1 });
+
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_report_lists_plugins_with_last_updated() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+        let make = |form_id: &str, plugin: &str, updated_at: i64| Translation {
+            updated_at,
+            ..translation(form_id, plugin, "Text", "文本")
+        };
+        db.batch_save_translations(
+            vec![
+                make("00000001|A.esp", "A.esp", 100),
+                make("00000002|A.esp", "A.esp", 300),
+                make("00000001|B|C.esp", "B|C.esp", 200),
+            ],
+            false,
+        )
+        .unwrap();
+
+        let report = StatisticsReport {
+            generated_at: 400,
+            stats: db.get_statistics()?,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["generated_at"], 400);
+        assert_eq!(json["total_count"], 3);
+        assert_eq!(json["plugin_counts"][0]["plugin_name"], "A.esp");
+        assert_eq!(json["plugin_counts"][0]["last_updated"], 300);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("- 翻译总数: 3\n"));
+        assert!(markdown.contains("| A.esp | 2 | "));
+        // 插件名中的 | 被转义，不破坏表格
+        assert!(markdown.contains("| B\\|C.esp | 1 | "));
+        Ok(())
+    }
+
+    #[test]
+    fn batch_query_spans_multiple_chunks_and_clamps_size() -> Result<()> {
+        let db = TranslationDB::new(":memory:".into())?;
+
+        let translations: Vec<Translation> = (0..2500)
+            .map(|i| Translation {
+                form_id: format!("{:08X}|Test.esm", i),
+                record_type: "MISC".to_string(),
+                subrecord_type: "FULL".to_string(),
+                index: 0,
+                editor_id: None,
+                original_text: format!("Text {}", i),
+                translated_text: format!("文本 {}", i),
+                plugin_name: Some("Test.esm".to_string()),
+                created_at: 0,
+                updated_at: 0,
+                note: None,
+                strings_table: StringsTable::Strings,
+            })
+            .collect();
+        db.batch_save_translations(translations, false).unwrap();
+
+        // 查询 3000 个 key，其中 500 个不存在
+        let forms: Vec<FormIdentifier> = (0..3000)
+            .map(|i| FormIdentifier {
+                form_id: format!("{:08X}|Test.esm", i),
+                record_type: "MISC".to_string(),
+                subrecord_type: "FULL".to_string(),
+                index: 0,
+            })
+            .collect();
+
+        let mut batches = 0;
+        let found = db.batch_query_translations_with_progress(forms.clone(), 1000, |_, _, _| {
+            batches += 1
+        })?;
+        assert_eq!(found.len(), 2500);
+        assert_eq!(batches, 3);
+
+        // 超出 SQLite 参数上限的块大小会被限制，而不是报错
+        let found = db.batch_query_translations_with_progress(forms, usize::MAX, |_, _, _| {})?;
+        assert_eq!(found.len(), 2500);
+
+        Ok(())
+    }
+
     #[test]
     fn contains_ascii_token_matches_exact_word() {
         assert!(contains_ascii_token("LoA", "loa"));
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 计算内容哈希时每次读取的字节数
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 插件文件的校验信息
+///
+/// `stat_token` 由文件大小与修改时间组成，可快速判断文件是否变化；
+/// `content_hash` 为整个文件的 FNV-1a 哈希（仅在请求时计算，大文件较慢）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginChecksum {
+    pub size: u64,
+    /// 最后修改时间（Unix 毫秒）
+    pub modified_ms: i64,
+    pub stat_token: String,
+    pub content_hash: Option<String>,
+}
+
+/// 以 FNV-1a 累加字节（`hash` 传入上一段的结果以便流式计算）
+fn fnv1a_update(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// 字符串的 FNV-1a 哈希（用于生成 Session ID 等稳定标识）
+pub fn fnv1a_hash(text: &str) -> u64 {
+    fnv1a_update(FNV_OFFSET_BASIS, text.as_bytes())
+}
+
+/// 由文件大小与修改时间生成的快速校验值（格式 `<size>-<mtime_ms>`）
+pub fn stat_token(path: &Path) -> io::Result<String> {
+    let (size, modified_ms) = stat(path)?;
+    Ok(format!("{}-{}", size, modified_ms))
+}
+
+/// 整个文件内容的 FNV-1a 哈希（16 位十六进制）
+pub fn content_hash(path: &Path) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut hash = FNV_OFFSET_BASIS;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hash = fnv1a_update(hash, &buffer[..read]);
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// 计算插件校验信息（`full_hash` 为 true 时额外计算内容哈希）
+pub fn compute_plugin_checksum(path: &Path, full_hash: bool) -> io::Result<PluginChecksum> {
+    let (size, modified_ms) = stat(path)?;
+    let content_hash = if full_hash {
+        Some(content_hash(path)?)
+    } else {
+        None
+    };
+    Ok(PluginChecksum {
+        size,
+        modified_ms,
+        stat_token: format!("{}-{}", size, modified_ms),
+        content_hash,
+    })
+}
+
+fn stat(path: &Path) -> io::Result<(u64, i64)> {
+    let metadata = fs::metadata(path)?;
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    Ok((metadata.len(), modified_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    #[test]
+    fn checksum_tracks_size_and_content() {
+        let tmp = TempDir::new("checksum");
+        let path = tmp.join("checksum.esp");
+
+        fs::write(&path, b"TES4 header").unwrap();
+        let first = compute_plugin_checksum(&path, true).unwrap();
+        assert_eq!(first.size, 11);
+        assert_eq!(first.stat_token, stat_token(&path).unwrap());
+        assert_eq!(first.content_hash, Some(format!("{:016x}", fnv1a_hash("TES4 header"))));
+
+        fs::write(&path, b"TES4 header, edited").unwrap();
+        let second = compute_plugin_checksum(&path, false).unwrap();
+        assert_ne!(second.stat_token, first.stat_token);
+        assert_eq!(second.content_hash, None);
+
+        fs::remove_file(&path).unwrap();
+        assert!(compute_plugin_checksum(&path, false).is_err());
+    }
+}
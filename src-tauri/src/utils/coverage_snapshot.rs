@@ -0,0 +1,131 @@
+use crate::coverage_db::{CoverageDB, CoverageEntry, LoadOrderEntry};
+use crate::utils::schema::SCHEMA_VERSION_KEY;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 快照文件格式版本（结构不兼容时递增）
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// 快照头（快照文件第一行，用于在解析数据前校验版本）
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotHeader {
+    format_version: u32,
+    exported_at: i64,
+}
+
+/// 快照数据（头之后的 JSON）
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotBody {
+    meta: HashMap<String, String>,
+    load_order: Vec<LoadOrderEntry>,
+    entries: Vec<CoverageEntry>,
+}
+
+/// 快照导入/导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageSnapshotSummary {
+    pub path: String,
+    pub format_version: u32,
+    pub exported_at: i64,
+    pub plugin_count: usize,
+    pub entry_count: usize,
+}
+
+/// 导出覆盖数据库（覆盖记录 + load order + meta）到快照文件
+///
+/// 文件为 gzip 压缩的文本：第一行为 JSON 快照头，其后为 JSON 数据
+pub fn export_snapshot(db: &CoverageDB, path: &Path) -> Result<CoverageSnapshotSummary, String> {
+    let header = SnapshotHeader {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64,
+    };
+    let body = SnapshotBody {
+        meta: db.get_meta().map_err(|e| format!("读取覆盖数据库元数据失败: {}", e))?,
+        load_order: db
+            .get_load_order_snapshot()
+            .map_err(|e| format!("读取覆盖数据库快照失败: {}", e))?,
+        entries: db
+            .get_all_entries()
+            .map_err(|e| format!("读取覆盖记录失败: {}", e))?,
+    };
+
+    let file = File::create(path).map_err(|e| format!("创建快照文件失败: {}", e))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    serde_json::to_writer(&mut encoder, &header)
+        .map_err(|e| format!("写入快照文件失败: {}", e))?;
+    encoder
+        .write_all(b"\n")
+        .map_err(|e| format!("写入快照文件失败: {}", e))?;
+    serde_json::to_writer(&mut encoder, &body)
+        .map_err(|e| format!("写入快照文件失败: {}", e))?;
+    encoder
+        .finish()
+        .and_then(|mut writer| writer.flush())
+        .map_err(|e| format!("写入快照文件失败: {}", e))?;
+
+    Ok(summary(path, &header, &body))
+}
+
+/// 从快照文件恢复覆盖数据库（替换现有数据），版本不兼容时拒绝导入
+pub fn import_snapshot(db: &CoverageDB, path: &Path) -> Result<CoverageSnapshotSummary, String> {
+    const INVALID: &str = "文件损坏或不是覆盖数据库快照";
+
+    let file = File::open(path).map_err(|e| format!("打开快照文件失败: {}", e))?;
+    let mut reader = BufReader::new(GzDecoder::new(BufReader::new(file)));
+
+    let mut header_line = String::new();
+    reader
+        .read_line(&mut header_line)
+        .map_err(|e| format!("读取快照文件失败（{}）: {}", INVALID, e))?;
+    let header: SnapshotHeader = serde_json::from_str(&header_line)
+        .map_err(|e| format!("解析快照头失败（{}）: {}", INVALID, e))?;
+
+    if header.format_version != SNAPSHOT_FORMAT_VERSION {
+        return Err(format!(
+            "快照格式版本不兼容: 文件为 v{}，当前仅支持 v{}",
+            header.format_version, SNAPSHOT_FORMAT_VERSION
+        ));
+    }
+
+    let body: SnapshotBody = serde_json::from_reader(reader)
+        .map_err(|e| format!("解析快照数据失败（{}）: {}", INVALID, e))?;
+
+    let local_schema = db
+        .schema_version()
+        .map_err(|e| format!("读取覆盖数据库版本失败: {}", e))?;
+    let snapshot_schema = body
+        .meta
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+    if snapshot_schema > local_schema {
+        return Err(format!(
+            "快照来自更新的数据库版本 (schema v{})，当前为 v{}，请先升级程序",
+            snapshot_schema, local_schema
+        ));
+    }
+    db.restore_snapshot(&body.load_order, &body.entries, &body.meta)
+        .map_err(|e| format!("恢复覆盖数据库失败: {}", e))?;
+
+    Ok(summary(path, &header, &body))
+}
+
+fn summary(path: &Path, header: &SnapshotHeader, body: &SnapshotBody) -> CoverageSnapshotSummary {
+    CoverageSnapshotSummary {
+        path: path.to_string_lossy().to_string(),
+        format_version: header.format_version,
+        exported_at: header.exported_at,
+        plugin_count: body.load_order.len(),
+        entry_count: body.entries.len(),
+    }
+}
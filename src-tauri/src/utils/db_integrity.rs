@@ -0,0 +1,147 @@
+use crate::utils::sql::configure_busy_timeout;
+use rusqlite::{Connection, OpenFlags};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 运行 `PRAGMA integrity_check`，返回发现的问题（数据库完好时为空）
+///
+/// 使用独立的只读连接，不占用应用持有的数据库锁
+pub fn integrity_check(path: &Path) -> rusqlite::Result<Vec<String>> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    configure_busy_timeout(&conn)?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(messages.into_iter().filter(|m| m != "ok").collect())
+}
+
+/// 列出数据库的备份文件名（{数据库文件名}.{时间戳}.bak），新的在前
+pub fn list_database_backups(backup_dir: &Path, db_path: &Path) -> Vec<String> {
+    let Some(file_name) = db_path.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", file_name);
+
+    let mut names: Vec<String> = fs::read_dir(backup_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name.starts_with(&prefix) && name.ends_with(".bak"))
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    names
+}
+
+/// 使用 `VACUUM INTO` 生成数据库的一致性快照，返回备份文件路径
+pub fn backup_database(db_path: &Path, backup_dir: &Path) -> Result<PathBuf, String> {
+    let file_name = db_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("无效的数据库路径: {}", db_path.display()))?;
+    fs::create_dir_all(backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let timestamp = chrono::Local::now().format("%Y_%m_%d_%H_%M_%S");
+    let backup_path = backup_dir.join(format!("{}.{}.bak", file_name, timestamp));
+    if backup_path.exists() {
+        return Err(format!("备份文件已存在: {}", backup_path.display()));
+    }
+
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("打开数据库失败: {}", e))?;
+    configure_busy_timeout(&conn).map_err(|e| format!("打开数据库失败: {}", e))?;
+    conn.execute("VACUUM INTO ?1", [backup_path.to_string_lossy()])
+        .map_err(|e| format!("备份数据库失败: {}", e))?;
+
+    Ok(backup_path)
+}
+
+/// 待恢复文件路径（{数据库路径}.restore），应用下次启动打开数据库前替换原文件
+fn pending_restore_path(db_path: &Path) -> PathBuf {
+    let mut path: OsString = db_path.as_os_str().to_owned();
+    path.push(".restore");
+    PathBuf::from(path)
+}
+
+/// 校验备份完整性并登记为待恢复
+///
+/// 应用运行期间数据库连接一直打开，直接覆盖文件会与连接中的缓存和 WAL 冲突，
+/// 因此只复制到待恢复位置，由 [`apply_pending_restore`] 在下次启动时替换
+pub fn stage_restore(db_path: &Path, backup_path: &Path) -> Result<(), String> {
+    if !backup_path.is_file() {
+        return Err(format!("备份文件不存在: {}", backup_path.display()));
+    }
+    let problems =
+        integrity_check(backup_path).map_err(|e| format!("读取备份失败: {}", e))?;
+    if !problems.is_empty() {
+        return Err(format!("备份文件同样已损坏，请选择其他备份: {}", problems.join("; ")));
+    }
+
+    fs::copy(backup_path, pending_restore_path(db_path))
+        .map_err(|e| format!("复制备份失败: {}", e))?;
+    Ok(())
+}
+
+/// 若存在待恢复文件则替换数据库（须在打开数据库连接之前调用），返回是否执行了恢复
+///
+/// 同时删除旧的 -wal/-shm 文件，避免其中的页被回放到恢复后的数据库上
+pub fn apply_pending_restore(db_path: &Path) -> Result<bool, String> {
+    let pending = pending_restore_path(db_path);
+    if !pending.is_file() {
+        return Ok(false);
+    }
+
+    for suffix in ["-wal", "-shm"] {
+        let mut side: OsString = db_path.as_os_str().to_owned();
+        side.push(suffix);
+        let side = PathBuf::from(side);
+        if side.exists() {
+            fs::remove_file(&side).map_err(|e| format!("删除 {} 失败: {}", side.display(), e))?;
+        }
+    }
+    fs::rename(&pending, db_path).map_err(|e| format!("恢复数据库失败: {}", e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    #[test]
+    fn backup_restores_on_next_open() {
+        let tmp = TempDir::new("db_integrity");
+        let dir = tmp.path();
+        let db_path = dir.join("items.db");
+        let backup_dir = dir.join("backups");
+
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE items (name TEXT); INSERT INTO items VALUES ('a');")
+            .unwrap();
+        assert!(integrity_check(&db_path).unwrap().is_empty());
+
+        let backup = backup_database(&db_path, &backup_dir).unwrap();
+        let name = backup.file_name().unwrap().to_str().unwrap().to_string();
+        assert_eq!(list_database_backups(&backup_dir, &db_path), vec![name]);
+
+        conn.execute("DELETE FROM items", []).unwrap();
+        drop(conn);
+        stage_restore(&db_path, &backup).unwrap();
+        assert!(apply_pending_restore(&db_path).unwrap());
+        assert!(!apply_pending_restore(&db_path).unwrap());
+
+        let conn = Connection::open(&db_path).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM items", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+        drop(conn);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::utils::sql::configure_busy_timeout;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 单个数据库文件的诊断信息
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseDiagnostics {
+    /// 数据库名称（显示用）
+    pub name: String,
+    pub path: String,
+    /// 主文件大小（字节，文件不存在时为 None）
+    pub size_bytes: Option<u64>,
+    /// WAL 文件大小（字节，无 WAL 文件时为 None）
+    pub wal_size_bytes: Option<u64>,
+    /// 主表行数（读取失败时为 None，原因见 `error`）
+    pub row_count: Option<i64>,
+    /// 最后修改时间（Unix 秒）
+    pub modified_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// 收集数据库文件的大小、修改时间与主表行数
+///
+/// 使用独立的只读连接统计行数，不占用应用持有的数据库锁
+pub fn database_diagnostics(name: &str, path: &Path, main_table: &str) -> DatabaseDiagnostics {
+    let metadata = fs::metadata(path).ok();
+    let size_bytes = metadata.as_ref().map(|m| m.len());
+    let modified_at = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    let mut wal_path = path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    let wal_size_bytes = fs::metadata(PathBuf::from(wal_path)).ok().map(|m| m.len());
+
+    let (row_count, error) = if metadata.is_none() {
+        (None, Some("数据库文件不存在".to_string()))
+    } else {
+        match count_rows(path, main_table) {
+            Ok(count) => (Some(count), None),
+            Err(e) => (None, Some(format!("统计行数失败: {}", e))),
+        }
+    };
+
+    DatabaseDiagnostics {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        wal_size_bytes,
+        row_count,
+        modified_at,
+        error,
+    }
+}
+
+fn count_rows(path: &Path, table: &str) -> rusqlite::Result<i64> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    configure_busy_timeout(&conn)?;
+    conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    #[test]
+    fn database_diagnostics_reports_rows_and_missing_files() {
+        let tmp = TempDir::new("diagnostics");
+        let path = tmp.join("diagnostics.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE items (name TEXT);
+                 INSERT INTO items VALUES ('a'), ('b');",
+            )
+            .unwrap();
+        }
+
+        let report = database_diagnostics("测试", &path, "items");
+        let missing_table = database_diagnostics("测试", &path, "absent");
+        fs::remove_file(&path).unwrap();
+        let missing_file = database_diagnostics("测试", &path, "items");
+
+        assert_eq!(report.row_count, Some(2));
+        assert!(report.size_bytes.unwrap() > 0);
+        assert!(report.modified_at.is_some());
+        assert!(report.error.is_none());
+        assert!(missing_table.row_count.is_none() && missing_table.error.is_some());
+        assert!(missing_file.size_bytes.is_none() && missing_file.error.is_some());
+    }
+}
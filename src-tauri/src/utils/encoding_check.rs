@@ -0,0 +1,58 @@
+/// 判断中文译文是否疑似编码错误（乱码）
+///
+/// 启发式规则（任一命中即视为可疑）：
+/// - 含 Unicode 替换字符 U+FFFD
+/// - 含 C1 控制字符（U+0080..U+009F），通常是字节被按 Latin-1 解码
+/// - 不含任何 CJK 字符，但 Latin-1 扩展字母（À..ÿ）占字母的比例过高，
+///   常见于 UTF-8 / GBK 中文被按 Latin-1 / Windows-1252 解码（如 "ä¸­æ–‡"、"ÖÐÎÄ"）
+pub fn looks_mis_encoded(text: &str) -> bool {
+    let mut letters = 0usize;
+    let mut latin1_letters = 0usize;
+    let mut has_cjk = false;
+
+    for c in text.chars() {
+        match c {
+            '\u{FFFD}' | '\u{0080}'..='\u{009F}' => return true,
+            c if is_cjk(c) => has_cjk = true,
+            '\u{00C0}'..='\u{00FF}' => {
+                letters += 1;
+                latin1_letters += 1;
+            }
+            c if c.is_alphabetic() => letters += 1,
+            _ => {}
+        }
+    }
+
+    !has_cjk && latin1_letters >= MIN_LATIN1_LETTERS && latin1_letters * 10 >= letters * 3
+}
+
+/// 判定为乱码所需的最少 Latin-1 扩展字母数（避免少量法/德语字母误报）
+const MIN_LATIN1_LETTERS: usize = 3;
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3000}'..='\u{303F}'   // CJK 标点
+            | '\u{3400}'..='\u{4DBF}' // 扩展 A
+            | '\u{4E00}'..='\u{9FFF}' // 基本汉字
+            | '\u{F900}'..='\u{FAFF}' // 兼容汉字
+            | '\u{FF00}'..='\u{FFEF}' // 全角字符
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_mojibake_patterns() {
+        assert!(!looks_mis_encoded("铁剑"));
+        assert!(!looks_mis_encoded("Iron Sword"));
+        assert!(!looks_mis_encoded("Café Élan"));
+        assert!(!looks_mis_encoded("钢剑（Café）"));
+
+        assert!(looks_mis_encoded("é\u{0093}\u{0081}å\u{0089}\u{0091}")); // UTF-8 → Latin-1
+        assert!(looks_mis_encoded("ÌúœÄ")); // GBK → Windows-1252
+        assert!(looks_mis_encoded("铁\u{FFFD}"));
+    }
+}
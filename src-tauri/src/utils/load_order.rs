@@ -1,20 +1,35 @@
-use crate::coverage_db::{CoverageDB, CoverageEntry, LoadOrderEntry};
+use crate::coverage_db::{
+    CoverageDB, CoverageEntry, LoadOrderEntry, PluginEntriesReplaced, UPSERT_CHUNK_SIZE,
+};
 use crate::dsd::{load_dsd_overrides, make_record_key};
-use crate::esp_service::extract_plugin_strings;
+use crate::esp_service::{extract_plugin_strings_with, PluginExtractionError, RecordTypeFilter};
 use crate::scanner::PluginInfo;
 use crate::translation_db::Translation;
+use crate::utils::checksum::stat_token;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// 单个插件的提取结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginExtractionResult {
+    pub plugin_name: String,
+    pub record_count: usize,
+    pub duration_ms: u64,
+    pub error: Option<PluginExtractionError>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoverageExtractionStats {
     pub total_plugins: usize,
     pub processed_plugins: usize,
     pub failed_plugins: usize,
+    /// 因插件过滤设置而跳过的插件数
+    pub skipped_plugins: usize,
     pub total_records: usize,
     pub errors: Vec<String>,
+    pub plugin_results: Vec<PluginExtractionResult>,
 }
 
 impl CoverageExtractionStats {
@@ -23,10 +38,87 @@ impl CoverageExtractionStats {
             total_plugins,
             processed_plugins: 0,
             failed_plugins: 0,
+            skipped_plugins: 0,
             total_records: 0,
             errors: Vec::new(),
+            plugin_results: Vec::new(),
+        }
+    }
+}
+
+/// 覆盖提取的插件过滤方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFilterMode {
+    /// 跳过匹配的插件（默认）
+    #[default]
+    Exclude,
+    /// 仅提取匹配的插件
+    IncludeOnly,
+}
+
+/// 覆盖提取的插件过滤设置
+///
+/// `patterns` 为插件名通配符（`*` 匹配任意字符，`?` 匹配单个字符，忽略大小写），
+/// 如 `"*Patch*.esp"`；排除模式下 `patterns` 为空则提取全部插件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageFilter {
+    #[serde(default)]
+    pub mode: CoverageFilterMode,
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl CoverageFilter {
+    /// 插件是否需要提取
+    pub fn should_extract(&self, plugin_name: &str) -> bool {
+        let matched = self
+            .patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern.trim(), plugin_name));
+        match self.mode {
+            CoverageFilterMode::Exclude => !matched,
+            CoverageFilterMode::IncludeOnly => matched,
+        }
+    }
+}
+
+/// 通配符匹配（`*` 任意长度，`?` 单个字符，忽略 ASCII 大小写）
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 * 的位置及其当前匹配到的文本位置（用于回溯）
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
         }
     }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 单插件增量更新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginCoverageUpdate {
+    pub plugin_name: String,
+    /// 该插件在上次完整提取时的 load order 位置
+    pub load_order_pos: i64,
+    pub record_count: usize,
+    #[serde(flatten)]
+    pub replaced: PluginEntriesReplaced,
+    pub duration_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,43 +135,55 @@ fn now_ts() -> i64 {
         .as_secs() as i64
 }
 
-fn apply_dsd_overrides_to_translations(
-    translations: &mut [Translation],
-    overrides: &HashMap<String, String>,
-) -> usize {
-    let mut applied = 0;
-    for translation in translations.iter_mut() {
-        let key = make_record_key(
-            &translation.form_id,
-            &translation.record_type,
-            &translation.subrecord_type,
-        );
-        if let Some(new_value) = overrides.get(&key) {
-            if translation.translated_text != *new_value {
-                translation.translated_text = new_value.clone();
-                applied += 1;
-            }
+/// 套用 DSD 覆盖到单条翻译，返回是否发生变化
+fn apply_dsd_override(translation: &mut Translation, overrides: &HashMap<String, String>) -> bool {
+    let key = make_record_key(
+        &translation.form_id,
+        &translation.record_type,
+        &translation.subrecord_type,
+    );
+    match overrides.get(&key) {
+        Some(new_value) if translation.translated_text != *new_value => {
+            translation.translated_text = new_value.clone();
+            true
         }
+        _ => false,
     }
-    applied
 }
 
 /// 按 load order 依次解包并写入覆盖关系数据库，并在提取后套用 DSD JSON 覆盖
+///
+/// `record_type_filter` 存在时仅重新提取匹配的记录类型（只删除这些类型的旧记录，其余类型保留），
+/// `dsd_template` 为 DSD 路径模板；
+/// 被 `plugin_filter` 排除的插件不提取，但仍以 `skipped` 标记写入 load order 快照
 pub fn extract_and_store<F>(
     coverage_db: &CoverageDB,
     plugins: &[PluginInfo],
+    record_type_filter: Option<&[String]>,
+    plugin_filter: &CoverageFilter,
+    dsd_template: &str,
     mut progress_callback: F,
 ) -> Result<CoverageExtractionStats, String>
 where
     F: FnMut(CoverageProgressUpdate),
 {
-    coverage_db
-        .clear_entries()
-        .map_err(|e| format!("清空覆盖数据库失败: {}", e))?;
+    match RecordTypeFilter::parse(record_type_filter) {
+        Some(filter) => coverage_db
+            .delete_entries_by_types(filter.entries())
+            .map(|_| ())
+            .map_err(|e| format!("删除旧覆盖记录失败: {}", e))?,
+        None => coverage_db
+            .clear_entries()
+            .map_err(|e| format!("清空覆盖数据库失败: {}", e))?,
+    }
 
     let mut stats = CoverageExtractionStats::new(plugins.len());
     let snapshot_ts = now_ts();
     let total_plugins = plugins.len();
+    let skipped: Vec<bool> = plugins
+        .iter()
+        .map(|plugin| !plugin_filter.should_extract(&plugin.name))
+        .collect();
 
     for (idx, plugin) in plugins.iter().enumerate() {
         progress_callback(CoverageProgressUpdate {
@@ -87,49 +191,84 @@ where
             current_progress: idx + 1,
             total: total_plugins,
         });
-        let path = Path::new(&plugin.path);
-        match extract_plugin_strings(path) {
-            Ok(mut translations) => {
-                if let Some(overrides) = load_dsd_overrides(path)? {
-                    let applied =
-                        apply_dsd_overrides_to_translations(&mut translations, &overrides);
-                    if applied > 0 {
-                        println!("✓ {} 套用 {} 条 DSD 覆盖", plugin.name, applied);
-                    }
+        if skipped[idx] {
+            stats.skipped_plugins += 1;
+            continue;
+        }
+        let path = plugin.fs_path.as_path();
+        let started = Instant::now();
+        let overrides = load_dsd_overrides(path, dsd_template)?;
+        let load_order_pos = idx as i64;
+        let mut buffer: Vec<CoverageEntry> = Vec::with_capacity(UPSERT_CHUNK_SIZE);
+        let mut written = 0;
+        let mut applied = 0;
+        let mut write_error: Option<String> = None;
+
+        let mut flush = |buffer: &mut Vec<CoverageEntry>, written: &mut usize| {
+            if write_error.is_some() || buffer.is_empty() {
+                buffer.clear();
+                return;
+            }
+            match coverage_db.batch_upsert_entries(buffer.drain(..)) {
+                Ok(count) => *written += count,
+                Err(e) => write_error = Some(format!("写入覆盖数据库失败: {}", e)),
+            }
+        };
+
+        let result = extract_plugin_strings_with(path, record_type_filter, |mut t| {
+            if let Some(overrides) = &overrides {
+                if apply_dsd_override(&mut t, overrides) {
+                    applied += 1;
                 }
+            }
+            buffer.push(CoverageEntry {
+                form_id: t.form_id,
+                record_type: t.record_type,
+                subrecord_type: t.subrecord_type,
+                index: t.index,
+                text: t.translated_text,
+                source_mod: t.plugin_name.unwrap_or_else(|| plugin.name.clone()),
+                load_order_pos,
+                extracted_at: snapshot_ts,
+                load_order_plugin: None,
+            });
+            if buffer.len() >= UPSERT_CHUNK_SIZE {
+                flush(&mut buffer, &mut written);
+            }
+        });
+        flush(&mut buffer, &mut written);
 
-                let load_order_pos = idx as i64;
-                let entries: Vec<CoverageEntry> = translations
-                    .into_iter()
-                    .map(|t| CoverageEntry {
-                        form_id: t.form_id,
-                        record_type: t.record_type,
-                        subrecord_type: t.subrecord_type,
-                        index: t.index,
-                        text: t.translated_text,
-                        source_mod: t
-                            .plugin_name
-                            .clone()
-                            .unwrap_or_else(|| plugin.name.clone()),
-                        load_order_pos,
-                        extracted_at: snapshot_ts,
-                    })
-                    .collect();
-
-                let entry_count = entries.len();
-
-                coverage_db
-                    .batch_upsert_entries(entries)
-                    .map_err(|e| format!("写入覆盖数据库失败: {}", e))?;
+        if let Some(err) = write_error {
+            return Err(err);
+        }
+
+        match result {
+            Ok(summary) => {
+                debug_assert_eq!(summary.record_count, written);
+                if applied > 0 {
+                    println!("✓ {} 套用 {} 条 DSD 覆盖", plugin.name, applied);
+                }
 
                 stats.processed_plugins += 1;
-                stats.total_records += entry_count;
+                stats.total_records += written;
+                stats.plugin_results.push(PluginExtractionResult {
+                    plugin_name: plugin.name.clone(),
+                    record_count: written,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    error: None,
+                });
             }
             Err(err) => {
                 stats.failed_plugins += 1;
                 stats
                     .errors
                     .push(format!("{}: {}", plugin.name, err));
+                stats.plugin_results.push(PluginExtractionResult {
+                    plugin_name: plugin.name.clone(),
+                    record_count: 0,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    error: Some(err),
+                });
             }
         }
     }
@@ -141,8 +280,9 @@ where
             position: idx as i64,
             plugin_name: plugin.name.clone(),
             plugin_path: Some(plugin.path.clone()),
-            checksum: None,
+            checksum: stat_token(Path::new(&plugin.path)).ok(),
             extracted_at: snapshot_ts,
+            skipped: skipped[idx],
         })
         .collect();
 
@@ -152,3 +292,94 @@ where
 
     Ok(stats)
 }
+
+/// 仅重新提取单个插件并替换其覆盖记录（其余插件的记录与快照保持不变）
+///
+/// load order 位置按插件名称（忽略大小写）从上次完整提取的快照中查找，
+/// 避免插件增删导致的位置偏移；插件不在快照中时需重新完整提取
+pub fn update_plugin_coverage(
+    coverage_db: &CoverageDB,
+    plugin: &PluginInfo,
+    record_type_filter: Option<&[String]>,
+    dsd_template: &str,
+) -> Result<PluginCoverageUpdate, String> {
+    let started = Instant::now();
+    let snapshot = coverage_db
+        .get_load_order_snapshot()
+        .map_err(|e| format!("读取LoadOrder快照失败: {}", e))?;
+    let snapshot_entry = snapshot
+        .iter()
+        .find(|entry| entry.plugin_name.eq_ignore_ascii_case(&plugin.name))
+        .ok_or_else(|| {
+            format!("{} 不在上次覆盖提取的 load order 中，请重新完整提取", plugin.name)
+        })?;
+    let load_order_pos = snapshot_entry.position;
+    let extracted_at = now_ts();
+
+    let path = plugin.fs_path.as_path();
+    let overrides = load_dsd_overrides(path, dsd_template)?;
+    let mut entries = Vec::new();
+    extract_plugin_strings_with(path, record_type_filter, |mut t| {
+        if let Some(overrides) = &overrides {
+            apply_dsd_override(&mut t, overrides);
+        }
+        entries.push(CoverageEntry {
+            form_id: t.form_id,
+            record_type: t.record_type,
+            subrecord_type: t.subrecord_type,
+            index: t.index,
+            text: t.translated_text,
+            source_mod: snapshot_entry.plugin_name.clone(),
+            load_order_pos,
+            extracted_at,
+            load_order_plugin: None,
+        });
+    })
+    .map_err(|e| format!("{}: {}", plugin.name, e))?;
+
+    let replaced = coverage_db
+        .replace_plugin_entries(&snapshot_entry.plugin_name, load_order_pos, &entries)
+        .map_err(|e| format!("写入覆盖数据库失败: {}", e))?;
+    let checksum = stat_token(Path::new(&plugin.path)).ok();
+    coverage_db
+        .touch_load_order_entry(
+            &snapshot_entry.plugin_name,
+            Some(&plugin.path),
+            checksum.as_deref(),
+            extracted_at,
+        )
+        .map_err(|e| format!("更新LoadOrder快照失败: {}", e))?;
+
+    Ok(PluginCoverageUpdate {
+        plugin_name: snapshot_entry.plugin_name.clone(),
+        load_order_pos,
+        record_count: entries.len(),
+        replaced,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_filter_matches_wildcards_case_insensitively() {
+        let exclude = CoverageFilter {
+            mode: CoverageFilterMode::Exclude,
+            patterns: vec!["*patch*.esp".to_string(), "Unofficial Skyrim ?pecial*".to_string()],
+        };
+        assert!(!exclude.should_extract("USSEP Patch - Armor.esp"));
+        assert!(!exclude.should_extract("Unofficial Skyrim Special Edition Patch.esp"));
+        assert!(exclude.should_extract("Patch.esm"));
+        assert!(exclude.should_extract("Immersive Armors.esp"));
+        assert!(CoverageFilter::default().should_extract("Skyrim.esm"));
+
+        let include_only = CoverageFilter {
+            mode: CoverageFilterMode::IncludeOnly,
+            patterns: vec!["*.esm".to_string()],
+        };
+        assert!(include_only.should_extract("Skyrim.ESM"));
+        assert!(!include_only.should_extract("Mod.esp"));
+    }
+}
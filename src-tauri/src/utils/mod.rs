@@ -1,2 +1,15 @@
+pub mod checksum;
+pub mod coverage_snapshot;
+pub mod db_integrity;
+pub mod diagnostics;
+pub mod encoding_check;
 pub mod load_order;
+pub mod pairing;
 pub mod paths;
+pub mod schema;
+pub mod sql;
+pub mod string_exchange;
+pub mod strings_table;
+#[cfg(test)]
+pub mod test_dir;
+pub mod translation_ndjson;
@@ -0,0 +1,133 @@
+use crate::coverage_db::CoverageEntry;
+use crate::plugin_session::StringRecord;
+use crate::translation_db::{FormIdentifier, Translation};
+use esp_extractor::ExtractedString;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// 可由 form_id|record_type|subrecord_type|index 唯一定位的字符串记录
+pub trait RecordKey {
+    /// 生成记录的唯一 key（form_id|record_type|subrecord_type|index）
+    fn record_key(&self) -> String;
+}
+
+fn format_key(
+    form_id: &str,
+    record_type: &str,
+    subrecord_type: &str,
+    index: impl Display,
+) -> String {
+    format!("{}|{}|{}|{}", form_id, record_type, subrecord_type, index)
+}
+
+impl RecordKey for ExtractedString {
+    fn record_key(&self) -> String {
+        format_key(&self.form_id, &self.record_type, &self.subrecord_type, self.index)
+    }
+}
+
+impl RecordKey for StringRecord {
+    fn record_key(&self) -> String {
+        format_key(&self.form_id, &self.record_type, &self.subrecord_type, self.index)
+    }
+}
+
+impl RecordKey for Translation {
+    fn record_key(&self) -> String {
+        format_key(&self.form_id, &self.record_type, &self.subrecord_type, self.index)
+    }
+}
+
+impl RecordKey for FormIdentifier {
+    fn record_key(&self) -> String {
+        format_key(&self.form_id, &self.record_type, &self.subrecord_type, self.index)
+    }
+}
+
+impl RecordKey for CoverageEntry {
+    fn record_key(&self) -> String {
+        format_key(&self.form_id, &self.record_type, &self.subrecord_type, self.index)
+    }
+}
+
+/// 按 key 对齐后的中英文记录
+#[derive(Debug, Clone)]
+pub struct PairedString {
+    /// 英文记录（原文）
+    pub english: ExtractedString,
+    /// 对应的中文文本（中文版中不存在该 key 时为 None）
+    pub chinese: Option<String>,
+}
+
+impl PairedString {
+    /// 是否存在实际翻译：中文存在、原文非空且与原文不同
+    pub fn is_translated(&self) -> bool {
+        match &self.chinese {
+            Some(text) => !self.english.text.is_empty() && *text != self.english.text,
+            None => false,
+        }
+    }
+}
+
+/// 以英文记录为准对齐中文文本
+///
+/// 结果保持英文记录的顺序；中文中重复出现的 key 以最后一条为准，
+/// 只存在于中文版的记录会被丢弃。
+pub fn pair_english_chinese(
+    english: Vec<ExtractedString>,
+    chinese: Vec<ExtractedString>,
+) -> Vec<PairedString> {
+    let mut chinese_map: HashMap<String, String> = HashMap::with_capacity(chinese.len());
+    for s in chinese {
+        chinese_map.insert(s.record_key(), s.text);
+    }
+
+    english
+        .into_iter()
+        .map(|s| {
+            let chinese = chinese_map.get(&s.record_key()).cloned();
+            PairedString { english: s, chinese }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(form_id: &str, index: i32, text: &str) -> ExtractedString {
+        ExtractedString {
+            editor_id: None,
+            form_id: form_id.to_string(),
+            text: text.to_string(),
+            record_type: "WEAP".to_string(),
+            subrecord_type: "FULL".to_string(),
+            index,
+        }
+    }
+
+    #[test]
+    fn pair_english_chinese_aligns_by_full_key() {
+        let english = vec![
+            record("00000001|Test.esp", 0, "Iron Sword"),
+            record("00000001|Test.esp", 1, "Steel Sword"),
+            record("00000002|Test.esp", 0, ""),
+            record("00000003|Test.esp", 0, "Gold"),
+        ];
+        let chinese = vec![
+            record("00000001|Test.esp", 1, "钢剑"),
+            record("00000001|Test.esp", 0, "铁剑"),
+            record("00000002|Test.esp", 0, "空"),
+            record("00000003|Test.esp", 0, "Gold"),
+            record("00000009|Test.esp", 0, "仅中文"),
+        ];
+
+        let paired = pair_english_chinese(english, chinese);
+
+        assert_eq!(paired.len(), 4);
+        assert_eq!(paired[0].chinese.as_deref(), Some("铁剑"));
+        assert_eq!(paired[1].chinese.as_deref(), Some("钢剑"));
+        let translated: Vec<bool> = paired.iter().map(PairedString::is_translated).collect();
+        assert_eq!(translated, vec![true, true, false, false]);
+    }
+}
@@ -1,8 +1,27 @@
-use std::path::PathBuf;
+use crate::settings::read_settings;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-/// 获取 userdata 目录路径
-pub fn get_userdata_dir() -> PathBuf {
-    let userdata_dir = if cfg!(debug_assertions) {
+/// 指定 userdata 目录的环境变量（优先于设置中的 `userdata_dir`）
+pub const DATA_DIR_ENV: &str = "SSE_TRANSLATOR_DATA_DIR";
+
+/// 本次运行解析出的 userdata 目录（首次访问时确定，修改设置后需重启生效）
+static USERDATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 解析 userdata 目录：环境变量 > 设置 > 程序目录下的 userdata
+fn resolve_userdata_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    if let Some(dir) = read_settings().ok().and_then(|s| s.userdata_dir) {
+        return PathBuf::from(dir);
+    }
+    default_userdata_dir()
+}
+
+/// 默认 userdata 目录
+fn default_userdata_dir() -> PathBuf {
+    if cfg!(debug_assertions) {
         // 开发模式：项目根目录
         std::env::current_dir()
             .expect("无法获取当前目录")
@@ -14,16 +33,49 @@ pub fn get_userdata_dir() -> PathBuf {
             .parent()
             .expect("无法获取父目录")
             .join("userdata")
-    };
+    }
+}
+
+/// 确保目录存在且可写（通过写入并删除探测文件验证）
+pub fn ensure_dir_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("无法创建目录 {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(".write_test");
+    std::fs::write(&probe, b"")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| format!("目录 {} 不可写: {}", dir.display(), e))
+}
+
+/// 启动时校验 userdata 目录可用，返回解析出的目录
+pub fn init_userdata_dir() -> Result<PathBuf, String> {
+    let dir = get_userdata_dir();
+    ensure_dir_writable(&dir).map_err(|e| {
+        format!(
+            "userdata 目录不可用，请检查环境变量 {} 或设置中的 userdata_dir: {}",
+            DATA_DIR_ENV, e
+        )
+    })?;
+    Ok(dir)
+}
 
-    // 确保userdata目录存在
+/// 获取 userdata 目录路径
+pub fn get_userdata_dir() -> PathBuf {
+    let userdata_dir = USERDATA_DIR.get_or_init(resolve_userdata_dir).clone();
+
+    // 确保userdata目录存在（启动时已由 init_userdata_dir 校验）
     if !userdata_dir.exists() {
-        std::fs::create_dir_all(&userdata_dir).expect("无法创建userdata目录");
+        let _ = std::fs::create_dir_all(&userdata_dir);
     }
 
     userdata_dir
 }
 
+/// 获取数据库备份目录路径
+pub fn get_db_backup_dir() -> PathBuf {
+    get_userdata_dir().join("backups")
+}
+
 /// 获取翻译数据库文件路径
 pub fn get_db_path() -> PathBuf {
     get_userdata_dir().join("translations.db")
@@ -48,3 +100,32 @@ pub fn get_search_history_db_path() -> PathBuf {
 pub fn get_coverage_db_path() -> PathBuf {
     get_userdata_dir().join("coverage.db")
 }
+
+/// 获取启动日志文件路径（main.rs 在当前工作目录下写入 startup.log）
+pub fn get_startup_log_path() -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_default()
+        .join("startup.log")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    #[test]
+    fn ensure_dir_writable_creates_missing_dir() {
+        let tmp = TempDir::new("paths_writable");
+        let dir = tmp.join("nested");
+
+        ensure_dir_writable(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert!(!dir.join(".write_test").exists());
+
+        // 路径被普通文件占用时无法创建目录
+        let file = dir.join("occupied");
+        std::fs::write(&file, b"").unwrap();
+        assert!(ensure_dir_writable(&file).is_err());
+
+    }
+}
@@ -0,0 +1,126 @@
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// 元数据表中记录 schema 版本的键
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// 单个 schema 迁移步骤
+///
+/// `version` 从 1 开始递增；未记录版本的旧数据库视为版本 0，
+/// 因此迁移必须对已通过旧的临时迁移补充过列/索引的数据库保持幂等
+pub struct Migration {
+    pub version: u32,
+    pub up: fn(&Connection) -> Result<()>,
+}
+
+/// 执行尚未应用的迁移，返回迁移后的 schema 版本
+///
+/// 版本号存储在 key-value 元数据表 `meta_table` 中（不存在时自动创建），
+/// 每个迁移在独立事务内执行并同时写入新版本号
+pub fn run_migrations(conn: &Connection, meta_table: &str, migrations: &[Migration]) -> Result<u32> {
+    conn.execute(
+        &format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            meta_table
+        ),
+        [],
+    )?;
+
+    let mut version = read_schema_version(conn, meta_table)?;
+    let latest = migrations.iter().map(|m| m.version).max().unwrap_or(0);
+
+    if version > latest {
+        println!(
+            "⚠ 数据库 schema 版本 (v{}) 高于当前程序支持的版本 (v{})，可能由更新版本创建",
+            version, latest
+        );
+        return Ok(version);
+    }
+
+    let applied = version;
+    for migration in migrations.iter().filter(|m| m.version > applied) {
+        let tx = conn.unchecked_transaction()?;
+        (migration.up)(&tx)?;
+        tx.execute(
+            &format!(
+                "INSERT INTO {} (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                meta_table
+            ),
+            params![SCHEMA_VERSION_KEY, migration.version.to_string()],
+        )?;
+        tx.commit()?;
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+/// 读取元数据表中的 schema 版本（未记录时为 0）
+pub fn read_schema_version(conn: &Connection, meta_table: &str) -> Result<u32> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!("SELECT value FROM {} WHERE key = ?1", meta_table),
+            params![SCHEMA_VERSION_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
+}
+
+/// 若表缺少指定列则追加
+pub fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_migrations_applies_pending_steps_once() -> Result<()> {
+        const MIGRATIONS: &[Migration] = &[
+            Migration {
+                version: 1,
+                up: |conn| conn.execute_batch("CREATE TABLE items (name TEXT NOT NULL)"),
+            },
+            Migration {
+                version: 2,
+                up: |conn| add_column_if_missing(conn, "items", "note", "TEXT"),
+            },
+        ];
+
+        let conn = Connection::open_in_memory()?;
+        assert_eq!(run_migrations(&conn, "meta", &MIGRATIONS[..1])?, 1);
+        assert_eq!(run_migrations(&conn, "meta", MIGRATIONS)?, 2);
+        // 重复执行不会再次运行已应用的迁移（否则 CREATE TABLE 会失败）
+        assert_eq!(run_migrations(&conn, "meta", MIGRATIONS)?, 2);
+        conn.execute("INSERT INTO items (name, note) VALUES ('a', 'b')", [])?;
+
+        // 数据库版本高于程序已知版本时保持不变
+        assert_eq!(run_migrations(&conn, "meta", &MIGRATIONS[..1])?, 2);
+
+        Ok(())
+    }
+}
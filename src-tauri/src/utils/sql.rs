@@ -0,0 +1,112 @@
+use rusqlite::{Connection, ErrorCode, Result};
+use std::time::Duration;
+
+/// 连接等待其他写入者释放锁的最长时间
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 写操作遇到 `SQLITE_BUSY`/`SQLITE_LOCKED` 时的最大重试次数（不含首次执行）
+pub const BUSY_RETRY_ATTEMPTS: u32 = 3;
+
+/// 重试的基础退避时间（第 n 次重试等待 n 倍）
+const BUSY_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// 转义 LIKE 模式中的通配符（`%`、`_`）与转义符本身（`\`）
+///
+/// 需配合 `ESCAPE '\'` 子句使用
+pub fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 为连接设置 busy_timeout（各数据库构造时调用）
+pub fn configure_busy_timeout(conn: &Connection) -> Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)
+}
+
+/// 是否为数据库被锁定的瞬时错误
+pub fn is_busy_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// 执行写操作，遇到锁定错误时按递增退避重试
+///
+/// `op` 可能被执行多次，其中的事务必须在失败时整体回滚（未提交的事务在 drop 时自动回滚）
+pub fn retry_on_busy<T>(op: impl FnMut() -> Result<T>) -> Result<T> {
+    retry_on_busy_with(BUSY_RETRY_ATTEMPTS, |_| {}, op)
+}
+
+/// 同 [`retry_on_busy`]，但可指定最大重试次数，并在每次重试前回调 `on_retry`（用于统计）
+pub fn retry_on_busy_with<T>(
+    max_retries: u32,
+    mut on_retry: impl FnMut(&rusqlite::Error),
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if is_busy_error(&e) && attempt < max_retries => {
+                attempt += 1;
+                eprintln!("⚠ 数据库被锁定，第 {} 次重试: {}", attempt, e);
+                on_retry(&e);
+                std::thread::sleep(BUSY_RETRY_BACKOFF * attempt);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy() -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None)
+    }
+
+    #[test]
+    fn retry_on_busy_retries_only_lock_errors() {
+        let mut calls = 0;
+        let result = retry_on_busy(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(busy())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: Result<()> = retry_on_busy(|| {
+            calls += 1;
+            Err(busy())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, BUSY_RETRY_ATTEMPTS + 1);
+
+        let mut calls = 0;
+        let result: Result<()> = retry_on_busy(|| {
+            calls += 1;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        let (mut calls, mut retries) = (0, 0);
+        let result: Result<()> = retry_on_busy_with(1, |_| retries += 1, || {
+            calls += 1;
+            Err(busy())
+        });
+        assert!(result.is_err());
+        assert_eq!((calls, retries), (2, 1));
+    }
+}
@@ -0,0 +1,314 @@
+use crate::plugin_session::StringRecord;
+use crate::utils::pairing::RecordKey;
+use encoding_rs::GBK;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// 离线翻译交换文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeFormat {
+    /// CSV：key, editor_id, type, original, translation（导入时读取 key 与 translation 列）
+    #[default]
+    Csv,
+    /// 两列制表符分隔：key 与文本（导出原文，导入时第二列视为译文；\t \n \r \\ 转义）
+    Tsv,
+}
+
+/// 交换文件的文本编码（部分旧版本地化工具只能读取 GBK 编码的中文 CSV）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ExchangeEncoding {
+    #[serde(rename = "utf8")]
+    Utf8,
+    /// 带 BOM 的 UTF-8（Excel 据此识别编码）
+    #[serde(rename = "utf8-bom")]
+    Utf8Bom,
+    #[serde(rename = "gbk")]
+    Gbk,
+}
+
+impl ExchangeEncoding {
+    /// 未指定编码时的默认值：CSV 使用带 BOM 的 UTF-8，TSV 使用 UTF-8
+    pub fn default_for(format: ExchangeFormat) -> Self {
+        match format {
+            ExchangeFormat::Csv => ExchangeEncoding::Utf8Bom,
+            ExchangeFormat::Tsv => ExchangeEncoding::Utf8,
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+const CSV_HEADER: [&str; 5] = ["key", "editor_id", "type", "original", "translation"];
+
+
+/// 写出交换文件，返回写出的记录数
+///
+/// 内容无法用 `encoding` 表示（如 GBK 之外的字符）时返回错误，不写出文件
+pub fn write_exchange_file(
+    path: &Path,
+    records: &[&StringRecord],
+    format: ExchangeFormat,
+    encoding: ExchangeEncoding,
+) -> Result<usize, String> {
+    let mut content = String::new();
+    match format {
+        ExchangeFormat::Csv => {
+            push_csv_row(&mut content, &CSV_HEADER);
+            for r in records {
+                let key = r.record_key();
+                let entry_type = format!("{} {}", r.record_type, r.subrecord_type);
+                let editor_id = r.editor_id.as_deref().unwrap_or("");
+                push_csv_row(&mut content, &[&key, editor_id, &entry_type, &r.original_text, ""]);
+            }
+        }
+        ExchangeFormat::Tsv => {
+            for r in records {
+                content.push_str(&escape_tsv(&r.record_key()));
+                content.push('\t');
+                content.push_str(&escape_tsv(&r.original_text));
+                content.push('\n');
+            }
+        }
+    }
+
+    let bytes = encode_content(&content, encoding)?;
+    fs::write(path, bytes).map_err(|e| format!("写入导出文件失败: {}", e))?;
+    Ok(records.len())
+}
+
+/// 读取交换文件中的 (key, 译文)，跳过译文为空的行
+///
+/// 文件带 UTF-8 BOM 时始终按 UTF-8 读取；未指定 `encoding` 时先尝试 UTF-8，失败再按 GBK 解码
+pub fn read_exchange_file(
+    path: &Path,
+    format: ExchangeFormat,
+    encoding: Option<ExchangeEncoding>,
+) -> Result<Vec<(String, String)>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取导入文件失败: {}", e))?;
+    let content = decode_content(&bytes, encoding)?;
+    let content = content.as_str();
+
+    let pairs = match format {
+        ExchangeFormat::Csv => {
+            let mut rows = parse_csv(content)?.into_iter();
+            let header = rows.next().ok_or_else(|| "导入文件为空".to_string())?;
+            let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+            let key_col = column("key").ok_or_else(|| "CSV 缺少 key 列".to_string())?;
+            let text_col = column("translation").ok_or_else(|| "CSV 缺少 translation 列".to_string())?;
+
+            rows.filter_map(|row| {
+                let key = row.get(key_col)?.trim().to_string();
+                let text = row.get(text_col)?.clone();
+                (!key.is_empty() && !text.is_empty()).then_some((key, text))
+            })
+            .collect()
+        }
+        ExchangeFormat::Tsv => content
+            .lines()
+            .filter_map(|line| {
+                let (key, text) = line.trim_end_matches('\r').split_once('\t')?;
+                let text = unescape_tsv(text);
+                (!key.trim().is_empty() && !text.is_empty())
+                    .then(|| (unescape_tsv(key.trim()), text))
+            })
+            .collect(),
+    };
+
+    Ok(pairs)
+}
+
+/// 按指定编码转码；存在无法表示的字符时报告第一个字符及其所在行
+fn encode_content(content: &str, encoding: ExchangeEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        ExchangeEncoding::Utf8 => Ok(content.as_bytes().to_vec()),
+        ExchangeEncoding::Utf8Bom => Ok([UTF8_BOM, content.as_bytes()].concat()),
+        ExchangeEncoding::Gbk => {
+            let (bytes, _, had_errors) = GBK.encode(content);
+            if !had_errors {
+                return Ok(bytes.into_owned());
+            }
+            let unmappable = content.lines().enumerate().find_map(|(line, text)| {
+                text.chars()
+                    .find(|c| GBK.encode(c.encode_utf8(&mut [0; 4])).2)
+                    .map(|c| (line + 1, c))
+            });
+            Err(match unmappable {
+                Some((line, c)) => format!(
+                    "字符 '{}' (U+{:04X}) 无法用 GBK 编码（第 {} 行），请改用 UTF-8",
+                    c, c as u32, line
+                ),
+                None => "内容包含无法用 GBK 编码的字符，请改用 UTF-8".to_string(),
+            })
+        }
+    }
+}
+
+/// 按指定编码解码（BOM 优先）
+fn decode_content(bytes: &[u8], encoding: Option<ExchangeEncoding>) -> Result<String, String> {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return String::from_utf8(rest.to_vec()).map_err(|_| "文件不是有效的 UTF-8 编码".to_string());
+    }
+    let decode_gbk = || {
+        GBK.decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| text.into_owned())
+    };
+    match encoding {
+        Some(ExchangeEncoding::Utf8 | ExchangeEncoding::Utf8Bom) => {
+            String::from_utf8(bytes.to_vec()).map_err(|_| "文件不是有效的 UTF-8 编码".to_string())
+        }
+        Some(ExchangeEncoding::Gbk) => decode_gbk().ok_or_else(|| "文件不是有效的 GBK 编码".to_string()),
+        None => String::from_utf8(bytes.to_vec())
+            .ok()
+            .or_else(decode_gbk)
+            .ok_or_else(|| "无法识别文件编码（既不是 UTF-8 也不是 GBK）".to_string()),
+    }
+}
+
+fn push_csv_row(out: &mut String, fields: &[&str]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// 解析 CSV（RFC 4180：双引号包裹的字段可包含逗号、换行与 "" 转义）
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("CSV 格式错误：引号未闭合".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    Ok(rows)
+}
+
+fn escape_tsv(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape_tsv(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TempDir;
+
+    fn record(form_id: &str, text: &str) -> StringRecord {
+        StringRecord {
+            form_id: form_id.to_string(),
+            editor_id: None,
+            record_type: "BOOK".to_string(),
+            subrecord_type: "DESC".to_string(),
+            index: 0,
+            original_text: text.to_string(),
+            translated_text: text.to_string(),
+            translation_status: "untranslated".to_string(),
+            strings_table: Default::default(),
+            source_plugin: None,
+        }
+    }
+
+    #[test]
+    fn exchange_files_round_trip_special_characters() {
+        let tmp = TempDir::new("string_exchange");
+        let dir = tmp.path();
+        let text = "He said, \"Run!\"\n\tC:\\Path";
+        let a = record("00000001|Test.esp", text);
+
+        let csv = dir.join("out.csv");
+        write_exchange_file(&csv, &[&a], ExchangeFormat::Csv, ExchangeEncoding::Utf8Bom).unwrap();
+        let written = fs::read(&csv).unwrap();
+        assert!(written.starts_with(UTF8_BOM));
+        let rows = parse_csv(&decode_content(&written, None).unwrap()).unwrap();
+        assert_eq!(rows[1][3], text);
+
+        // 外部工具填写 translation 列后导入（列顺序可变，未填写的行被跳过）
+        fs::write(
+            &csv,
+            "\u{feff}key,translation\r\n00000001|Test.esp|BOOK|DESC|0,\"他说，\"\"快跑！\"\"\"\r\n00000002|Test.esp|BOOK|DESC|0,\r\n",
+        )
+        .unwrap();
+        let pairs = read_exchange_file(&csv, ExchangeFormat::Csv, None).unwrap();
+        assert_eq!(pairs, vec![(a.record_key(), "他说，\"快跑！\"".to_string())]);
+
+        let tsv = dir.join("out.tsv");
+        write_exchange_file(&tsv, &[&a], ExchangeFormat::Tsv, ExchangeEncoding::Utf8).unwrap();
+        let pairs = read_exchange_file(&tsv, ExchangeFormat::Tsv, None).unwrap();
+        assert_eq!(pairs, vec![(a.record_key(), text.to_string())]);
+    }
+
+    #[test]
+    fn gbk_encoding_round_trips_and_rejects_unmappable_characters() {
+        let bytes = encode_content("key,translation\r\na,铁剑\r\n", ExchangeEncoding::Gbk).unwrap();
+        assert!(String::from_utf8(bytes.clone()).is_err());
+        // 未指定编码时 UTF-8 解码失败后按 GBK 读取
+        assert_eq!(decode_content(&bytes, None).unwrap(), "key,translation\r\na,铁剑\r\n");
+        assert!(decode_content(&bytes, Some(ExchangeEncoding::Utf8)).is_err());
+
+        let err = encode_content("key\r\n剑 🗡️\r\n", ExchangeEncoding::Gbk).unwrap_err();
+        assert!(err.contains("U+1F5E1") && err.contains("第 2 行"), "{}", err);
+    }
+}
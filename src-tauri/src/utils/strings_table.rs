@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// 本地化插件中字符串所在的 STRINGS 表
+///
+/// 划分规则与 esp_extractor 写回时一致：INFO 记录存于 ILSTRINGS，
+/// DESC/CNAM 子记录存于 DLSTRINGS，其余存于 STRINGS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StringsTable {
+    #[default]
+    Strings,
+    /// 对话文本
+    Ilstrings,
+    /// 描述、书籍等长文本
+    Dlstrings,
+}
+
+/// 按 `record_type`/`subrecord_type` 列计算 STRINGS 表的 SQL 表达式（与 [`StringsTable::classify`] 一致）
+pub const STRINGS_TABLE_SQL: &str = "CASE WHEN record_type = 'INFO' THEN 'ILSTRINGS' \
+     WHEN subrecord_type IN ('DESC', 'CNAM') THEN 'DLSTRINGS' ELSE 'STRINGS' END";
+
+impl StringsTable {
+    /// 根据记录类型与子记录类型判断所在的 STRINGS 表
+    pub fn classify(record_type: &str, subrecord_type: &str) -> Self {
+        if record_type == "INFO" {
+            StringsTable::Ilstrings
+        } else if matches!(subrecord_type, "DESC" | "CNAM") {
+            StringsTable::Dlstrings
+        } else {
+            StringsTable::Strings
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StringsTable::Strings => "STRINGS",
+            StringsTable::Ilstrings => "ILSTRINGS",
+            StringsTable::Dlstrings => "DLSTRINGS",
+        }
+    }
+}
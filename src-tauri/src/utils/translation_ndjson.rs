@@ -0,0 +1,195 @@
+use crate::translation_db::{Translation, TranslationDB};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// 流式导入时每批写入的记录数
+const IMPORT_BATCH_SIZE: usize = 5000;
+
+/// 翻译导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationExportFormat {
+    /// 每行一条翻译（可直接用 [`import_ndjson`] 导入）
+    #[default]
+    Ndjson,
+    /// 单个 JSON 数组
+    Json,
+}
+
+/// 翻译导出结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationExportSummary {
+    /// 导出条数
+    pub exported: usize,
+    /// 导出记录中最大的 updated_at（无记录时为 None），可作为下次增量导出的 `since`
+    pub max_updated_at: Option<i64>,
+}
+
+/// 将翻译数据库逐行导出为 NDJSON（每行一条翻译），返回导出条数
+pub fn export_ndjson(
+    db: &TranslationDB,
+    path: &Path,
+    plugin_name: Option<&str>,
+) -> Result<usize, String> {
+    export_translations(db, path, TranslationExportFormat::Ndjson, plugin_name, None)
+        .map(|summary| summary.exported)
+}
+
+/// 流式导出翻译（可按插件过滤，`since` 存在时仅导出 `updated_at >= since` 的记录，用于增量备份）
+pub fn export_translations(
+    db: &TranslationDB,
+    path: &Path,
+    format: TranslationExportFormat,
+    plugin_name: Option<&str>,
+    since: Option<i64>,
+) -> Result<TranslationExportSummary, String> {
+    let file = File::create(path).map_err(|e| format!("创建导出文件失败: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let write_err = |e: std::io::Error| format!("写入导出文件失败: {}", e);
+
+    let mut max_updated_at: Option<i64> = None;
+    let exported = db.for_each_translation(plugin_name, since, |translation| {
+        // JSON 数组的元素之间以逗号分隔，NDJSON 每条独占一行
+        let prefix: &[u8] = match (format, max_updated_at) {
+            (TranslationExportFormat::Json, None) => b"[\n",
+            (TranslationExportFormat::Json, Some(_)) => b",\n",
+            (TranslationExportFormat::Ndjson, _) => b"",
+        };
+        max_updated_at = max_updated_at.max(Some(translation.updated_at));
+
+        writer.write_all(prefix).map_err(write_err)?;
+        serde_json::to_writer(&mut writer, &translation)
+            .map_err(|e| format!("写入导出文件失败: {}", e))?;
+        if format == TranslationExportFormat::Ndjson {
+            writer.write_all(b"\n").map_err(write_err)?;
+        }
+        Ok(())
+    })?;
+
+    if format == TranslationExportFormat::Json {
+        let suffix: &[u8] = if exported == 0 { b"[]\n" } else { b"\n]\n" };
+        writer.write_all(suffix).map_err(write_err)?;
+    }
+    writer.flush().map_err(write_err)?;
+
+    Ok(TranslationExportSummary {
+        exported,
+        max_updated_at,
+    })
+}
+
+/// 从 NDJSON 文件流式导入翻译（UPSERT，按批提交），返回导入条数
+///
+/// 空行会被跳过；某行解析失败时中止，此前已提交的批次会保留
+pub fn import_ndjson(db: &TranslationDB, path: &Path) -> Result<usize, String> {
+    let file = File::open(path).map_err(|e| format!("打开导入文件失败: {}", e))?;
+    let reader = BufReader::new(file);
+
+    let mut batch: Vec<Translation> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut imported = 0;
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("读取导入文件失败: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let translation: Translation = serde_json::from_str(&line).map_err(|e| {
+            format!(
+                "解析第 {} 行失败（已导入 {} 条）: {}",
+                line_no + 1,
+                imported,
+                e
+            )
+        })?;
+        batch.push(translation);
+
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            imported += save_batch(db, std::mem::take(&mut batch), imported)?;
+        }
+    }
+
+    if !batch.is_empty() {
+        imported += save_batch(db, batch, imported)?;
+    }
+
+    Ok(imported)
+}
+
+fn save_batch(db: &TranslationDB, batch: Vec<Translation>, imported: usize) -> Result<usize, String> {
+    db.batch_save_translations(batch, false)
+        .map(|outcome| outcome.committed)
+        .map_err(|e| format!("导入翻译失败（已导入 {} 条）: {}", imported + e.committed, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation_db::tests::translation;
+    use crate::utils::test_dir::TempDir;
+
+    #[test]
+    fn ndjson_roundtrip_filters_by_plugin() {
+        let source = TranslationDB::new(":memory:".into()).unwrap();
+        let make = |form_id: &str, plugin: &str, text: &str| Translation {
+            note: Some("备注\n第二行".to_string()),
+            ..translation(form_id, plugin, "Iron Sword", text)
+        };
+        source
+            .batch_save_translations(
+                vec![
+                    make("00000001|A.esp", "A.esp", "铁剑"),
+                    make("00000002|A.esp", "A.esp", "钢剑"),
+                    make("00000003|B.esp", "B.esp", "精灵剑"),
+                ],
+                false,
+            )
+            .unwrap();
+
+        let tmp = TempDir::new("translations");
+        let path = tmp.join("translations.ndjson");
+
+        let exported = export_ndjson(&source, &path, Some("A.esp")).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        let target = TranslationDB::new(":memory:".into()).unwrap();
+        let imported = import_ndjson(&target, &path).unwrap();
+
+        assert_eq!(exported, 2);
+        assert_eq!(content.lines().count(), 2);
+        assert_eq!(imported, 2);
+        let restored = target
+            .get_translation("00000002|A.esp", "MISC", "FULL", 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.translated_text, "钢剑");
+        assert_eq!(restored.note.as_deref(), Some("备注\n第二行"));
+    }
+
+    #[test]
+    fn json_export_since_returns_next_cursor() {
+        let db = TranslationDB::new(":memory:".into()).unwrap();
+        let newer = Translation {
+            updated_at: 5,
+            ..translation("00000002|A.esp", "A.esp", "Steel Sword", "钢剑")
+        };
+        let older = Translation {
+            updated_at: 1,
+            ..translation("00000001|A.esp", "A.esp", "Iron Sword", "铁剑")
+        };
+        db.batch_save_translations(vec![older, newer], false).unwrap();
+
+        let tmp = TempDir::new("translations_delta");
+        let path = tmp.join("translations_delta.json");
+
+        let summary =
+            export_translations(&db, &path, TranslationExportFormat::Json, None, Some(2)).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!((summary.exported, summary.max_updated_at), (1, Some(5)));
+        let parsed: Vec<Translation> = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed[0].translated_text, "钢剑");
+    }
+}
@@ -23,6 +23,7 @@ import AddIcon from '@mui/icons-material/Add';
 import VisibilityIcon from '@mui/icons-material/Visibility';
 import VisibilityOffIcon from '@mui/icons-material/VisibilityOff';
 import { useApiConfigStore, type ApiConfig } from '../stores/apiConfigStore';
+import { DEFAULT_USER_PROMPT_TEMPLATE } from '../utils/aiPrompts';
 
 /**
  * API配置面板组件
@@ -50,6 +51,10 @@ export default function ApiConfigPanel() {
     apiKey: '',
     modelName: '',
     maxTokens: 2000,
+    systemPrompt: '',
+    userPromptTemplate: '',
+    promptPrice: 0,
+    completionPrice: 0,
   });
 
   // 显示API Key
@@ -80,6 +85,10 @@ export default function ApiConfigPanel() {
         apiKey: firstConfig.apiKey || '',
         modelName: firstConfig.modelName || '',
         maxTokens: firstConfig.maxTokens || 2000,
+        promptPrice: firstConfig.promptPrice || 0,
+        completionPrice: firstConfig.completionPrice || 0,
+        systemPrompt: firstConfig.systemPrompt || '',
+        userPromptTemplate: firstConfig.userPromptTemplate || '',
       });
       setShowApiKey(false);
       return;
@@ -95,6 +104,10 @@ export default function ApiConfigPanel() {
           apiKey: currentConfig.apiKey || '',
           modelName: currentConfig.modelName || '',
           maxTokens: currentConfig.maxTokens || 2000,
+          promptPrice: currentConfig.promptPrice || 0,
+          completionPrice: currentConfig.completionPrice || 0,
+          systemPrompt: currentConfig.systemPrompt || '',
+          userPromptTemplate: currentConfig.userPromptTemplate || '',
         });
       }
     }
@@ -188,6 +201,10 @@ export default function ApiConfigPanel() {
           apiKey: '',
           modelName: '',
           maxTokens: 2000,
+          systemPrompt: '',
+          userPromptTemplate: '',
+          promptPrice: 0,
+          completionPrice: 0,
         });
       }
     } catch (err) {
@@ -351,6 +368,33 @@ export default function ApiConfigPanel() {
                 helperText="支持200k+上下文的模型（如GPT-4、Claude等）"
               />
 
+              {/* Token 单价（用于估算费用） */}
+              <Box sx={{ display: 'flex', gap: 2 }}>
+                <TextField
+                  label="输入单价（每百万 token）"
+                  size="small"
+                  fullWidth
+                  type="number"
+                  value={formData.promptPrice}
+                  onChange={(e) => handleFieldChange('promptPrice', parseFloat(e.target.value) || 0)}
+                  onBlur={() => handleFieldBlur('promptPrice')}
+                  disabled={isLoading}
+                  inputProps={{ min: 0, step: 0.01 }}
+                />
+                <TextField
+                  label="输出单价（每百万 token）"
+                  size="small"
+                  fullWidth
+                  type="number"
+                  value={formData.completionPrice}
+                  onChange={(e) => handleFieldChange('completionPrice', parseFloat(e.target.value) || 0)}
+                  onBlur={() => handleFieldBlur('completionPrice')}
+                  disabled={isLoading}
+                  inputProps={{ min: 0, step: 0.01 }}
+                  helperText="用于估算 API 费用，0 表示不计费"
+                />
+              </Box>
+
               {/* Temperature（只读显示） */}
               <TextField
                 label="Temperature"
@@ -361,6 +405,38 @@ export default function ApiConfigPanel() {
                 helperText="翻译任务使用固定的低温度值以确保确定性"
               />
 
+              {/* System Prompt 模板 */}
+              <TextField
+                label="System Prompt"
+                size="small"
+                fullWidth
+                multiline
+                minRows={3}
+                maxRows={10}
+                placeholder="留空使用内置默认 Prompt"
+                value={formData.systemPrompt}
+                onChange={(e) => handleFieldChange('systemPrompt', e.target.value)}
+                onBlur={() => handleFieldBlur('systemPrompt')}
+                disabled={isLoading}
+                helperText="支持 {text}（待翻译列表）与 {glossary}（术语缓存）占位符"
+              />
+
+              {/* User Prompt 模板 */}
+              <TextField
+                label="User Prompt 模板"
+                size="small"
+                fullWidth
+                multiline
+                minRows={3}
+                maxRows={10}
+                placeholder={DEFAULT_USER_PROMPT_TEMPLATE}
+                value={formData.userPromptTemplate}
+                onChange={(e) => handleFieldChange('userPromptTemplate', e.target.value)}
+                onBlur={() => handleFieldBlur('userPromptTemplate')}
+                disabled={isLoading}
+                helperText="留空使用内置默认模板；进度与历史记录会自动追加在末尾"
+              />
+
               {/* 说明文本 */}
               <Box sx={{ bgcolor: 'info.lighter', p: 2, borderRadius: 1, mt: 1 }}>
                 <Typography variant="body2" color="text.secondary" gutterBottom>
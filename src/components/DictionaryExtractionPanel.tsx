@@ -12,9 +12,12 @@ import {
   ListItemText,
 } from '@mui/material';
 import FolderOpenIcon from '@mui/icons-material/FolderOpen';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { open as openDialog } from '@tauri-apps/plugin-dialog';
 import { useTranslationStore } from '../stores/translationStore';
-import type { ExtractionStats } from '../types';
+import type { ExtractionProgress, ExtractionStats, Settings } from '../types';
+import { getErrorMessage } from '../utils/commandError';
 
 /**
  * 字典提取面板组件
@@ -26,14 +29,42 @@ export default function DictionaryExtractionPanel() {
   const [extractionError, setExtractionError] = useState<string | null>(null);
   const [deletedCount, setDeletedCount] = useState<number | null>(null);
   const [basePlugins, setBasePlugins] = useState<string[]>([]);
+  const [recordTypeFilter, setRecordTypeFilter] = useState('');
+  const [progress, setProgress] = useState<ExtractionProgress | null>(null);
 
-  const { extractDictionary, getBasePluginsList, clearBaseDictionary } = useTranslationStore();
+  const {
+    extractDictionary,
+    cancelDictionaryExtraction,
+    getBasePluginsList,
+    clearBaseDictionary,
+  } = useTranslationStore();
+
+  // 监听逐插件提取进度
+  useEffect(() => {
+    const unlisten = listen<ExtractionProgress>('dictionary_extraction_progress', (event) => {
+      setProgress(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   // 组件挂载时加载插件列表
   useEffect(() => {
     handleLoadPluginsList();
   }, []);
 
+  // 若设置中指定了 Data 目录，默认使用该目录
+  useEffect(() => {
+    invoke<Settings>('get_settings')
+      .then((settings) => {
+        if (settings.data_dir) {
+          setDataDir((current) => current || settings.data_dir!);
+        }
+      })
+      .catch((error) => console.error('读取设置失败:', error));
+  }, []);
+
   // 加载基础插件列表
   const handleLoadPluginsList = async () => {
     try {
@@ -84,25 +115,35 @@ export default function DictionaryExtractionPanel() {
     setExtractionError(null);
     setExtractionResult(null);
     setDeletedCount(null);
+    setProgress(null);
+
+    // 逗号分隔，每项为记录类型或 "记录类型 子记录类型"
+    const filter = recordTypeFilter
+      .split(',')
+      .map((item) => item.trim())
+      .filter((item) => item.length > 0);
 
     try {
-      // 第一步：清除基础词典
-      console.log('🗑️ 正在清除旧的基础词典数据...');
-      const deleted = await clearBaseDictionary();
-      setDeletedCount(deleted);
-      console.log(`✅ 已清除 ${deleted} 条旧记录`);
+      // 第一步：清除基础词典（仅提取部分记录类型时保留现有数据，避免丢失其他类型）
+      if (filter.length === 0) {
+        console.log('🗑️ 正在清除旧的基础词典数据...');
+        const deleted = await clearBaseDictionary();
+        setDeletedCount(deleted);
+        console.log(`✅ 已清除 ${deleted} 条旧记录`);
+      }
 
       // 第二步：重新提取
       console.log('📖 正在重新提取基础词典...');
-      const stats = await extractDictionary(dataDir);
+      const stats = await extractDictionary(dataDir, filter.length > 0 ? filter : undefined);
       setExtractionResult(stats);
       console.log('✅ 提取完成');
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       setExtractionError(errorMsg);
       console.error('❌ 提取失败:', errorMsg);
     } finally {
       setIsExtracting(false);
+      setProgress(null);
     }
   };
 
@@ -133,6 +174,22 @@ export default function DictionaryExtractionPanel() {
         </Box>
       </Box>
 
+      {/* 记录类型过滤 */}
+      <Box>
+        <Typography variant="subtitle2" gutterBottom>
+          记录类型过滤（可选）
+        </Typography>
+        <TextField
+          fullWidth
+          size="small"
+          value={recordTypeFilter}
+          onChange={(e) => setRecordTypeFilter(e.target.value)}
+          placeholder="如 INFO NAM1, BOOK（逗号分隔，留空提取全部）"
+          helperText="指定后仅提取匹配的记录，且不会清除已有的基础词典"
+          disabled={isExtracting}
+        />
+      </Box>
+
       {/* 插件列表 */}
       {basePlugins.length > 0 && (
         <Box>
@@ -166,9 +223,14 @@ export default function DictionaryExtractionPanel() {
       {isExtracting && (
         <Box>
           <Typography variant="body2" gutterBottom>
-            正在提取字典...
+            {progress
+              ? `正在提取 ${progress.current_plugin}（${progress.current_progress}/${progress.total}）...`
+              : '正在提取字典...'}
           </Typography>
-          <LinearProgress />
+          <LinearProgress
+            variant={progress ? 'determinate' : 'indeterminate'}
+            value={progress ? ((progress.current_progress - 1) / progress.total) * 100 : 0}
+          />
         </Box>
       )}
 
@@ -183,7 +245,7 @@ export default function DictionaryExtractionPanel() {
       {extractionResult && (
         <Alert severity="success">
           <Typography variant="subtitle2" gutterBottom>
-            ✅ 提取完成！
+            {extractionResult.cancelled ? '⏹ 提取已取消（已保存完成的插件）' : '✅ 提取完成！'}
           </Typography>
           <Box sx={{ mt: 1 }}>
             {deletedCount !== null && deletedCount > 0 && (
@@ -202,6 +264,18 @@ export default function DictionaryExtractionPanel() {
                 • 跳过文件：{extractionResult.skipped_files.join(', ')}
               </Typography>
             )}
+            {extractionResult.english_only_files?.length > 0 && (
+              <Typography variant="body2" color="warning.main">
+                • 仅英文（未找到中文版）：{extractionResult.english_only_files.join(', ')}
+              </Typography>
+            )}
+            {extractionResult.suspicious_encoding_count > 0 && (
+              <Typography variant="body2" color="warning.main">
+                • 疑似乱码（中文 STRINGS 编码可能有误）：
+                {extractionResult.suspicious_encoding_count.toLocaleString()} 条
+                （{extractionResult.encoding_warnings.join(', ')}）
+              </Typography>
+            )}
             {extractionResult.errors.length > 0 && (
               <Box sx={{ mt: 1 }}>
                 <Typography variant="body2" color="error">
@@ -238,7 +312,12 @@ export default function DictionaryExtractionPanel() {
       </Box>
 
       {/* 操作按钮 */}
-      <Box sx={{ display: 'flex', justifyContent: 'flex-end', mt: 2 }}>
+      <Box sx={{ display: 'flex', justifyContent: 'flex-end', gap: 1, mt: 2 }}>
+        {isExtracting && (
+          <Button onClick={() => cancelDictionaryExtraction()} color="warning">
+            取消
+          </Button>
+        )}
         <Button
           onClick={handleExtract}
           variant="contained"
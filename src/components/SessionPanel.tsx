@@ -32,6 +32,7 @@ import UndoIcon from "@mui/icons-material/Undo";
 import CompareArrowsIcon from "@mui/icons-material/CompareArrows";
 import PublishIcon from "@mui/icons-material/Publish";
 import FileDownloadIcon from "@mui/icons-material/FileDownload";
+import RestoreIcon from "@mui/icons-material/Restore";
 import { open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 import type { PluginStringsResponse } from "../types";
@@ -43,6 +44,7 @@ import { useHistoryStore, type HistoryCommand, type HistoryRecord } from "../sto
 import {
   showSuccess,
   showError,
+  showWarning,
   showInfo as showInfoNotification,
 } from "../stores/notificationStore";
 import { useState, useMemo, useRef, useEffect, useCallback } from "react";
@@ -54,6 +56,7 @@ import {
   type AiStatusUpdate,
 } from "../utils/aiTranslation";
 import type { SearchResult } from "../utils/aiPrompts";
+import { getErrorMessage } from "../utils/commandError";
 
 // Thinking 动画组件（Claude/ChatGPT 风格 shimmer 效果）
 const ThinkingText = styled(Typography)(({ theme }) => ({
@@ -218,6 +221,13 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
   // API配置
   const currentApi = useApiConfigStore((state) => state.currentApi);
 
+  // 字符串缺失或数量可疑时提示 BSA 诊断信息（每个 Session 提示一次）
+  useEffect(() => {
+    if (sessionData.bsa_warning) {
+      showWarning(`${sessionData.plugin_name}: ${sessionData.bsa_warning}`);
+    }
+  }, [sessionData.session_id, sessionData.bsa_warning, sessionData.plugin_name]);
+
   const progress = translationProgress.get(sessionData.session_id);
   const [showInfo, setShowInfo] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
@@ -364,7 +374,8 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
   }, [isAiTranslating]);
 
   // 是否正在加载翻译
-  const isLoadingTranslations = progress !== undefined && progress < 100;
+  const isLoadingTranslations =
+    progress !== undefined && progress.percentage < 100;
 
   // 获取当前 session 的未保存数量
   const pendingCount = getSessionPendingCount
@@ -437,7 +448,7 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
         showSuccess("没有需要保存的翻译");
       }
     } catch (error) {
-      showError("保存翻译失败: " + String(error));
+      showError("保存翻译失败: " + getErrorMessage(error));
     } finally {
       setIsSaving(false);
     }
@@ -715,7 +726,7 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
         }
       }
     } catch (error) {
-      showError("AI翻译失败: " + String(error));
+      showError("AI翻译失败: " + getErrorMessage(error));
     } finally {
       resetAiContext();
     }
@@ -825,13 +836,47 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
             )}
           </Typography>
 
-          <Chip
-            size="small"
-            label={sessionData.has_dsd_overrides ? "esp/dsd" : "esp"}
-            color={sessionData.has_dsd_overrides ? "secondary" : "default"}
-            variant={sessionData.has_dsd_overrides ? "filled" : "outlined"}
-            sx={{ mx: 1 }}
-          />
+          <Tooltip
+            title={
+              sessionData.dsd_overrides
+                ? `DSD 条目 ${sessionData.dsd_overrides.total} 条：覆盖 ${sessionData.dsd_overrides.applied} 条，未匹配 ${sessionData.dsd_overrides.unmatched} 条`
+                : "未检测到 DSD 文件"
+            }
+          >
+            <Chip
+              size="small"
+              label={sessionData.has_dsd_overrides ? "esp/dsd" : "esp"}
+              color={
+                sessionData.dsd_overrides?.unmatched
+                  ? "warning"
+                  : sessionData.has_dsd_overrides
+                    ? "secondary"
+                    : "default"
+              }
+              variant={sessionData.has_dsd_overrides ? "filled" : "outlined"}
+              sx={{ mx: 1 }}
+            />
+          </Tooltip>
+
+          {sessionData.metadata && (
+            <Tooltip
+              title={`记录 ${sessionData.metadata.record_count} 条 · 主文件: ${
+                sessionData.metadata.masters.join(", ") || "无"
+              }`}
+            >
+              <Chip
+                size="small"
+                variant="outlined"
+                label={[
+                  sessionData.metadata.is_master ? "ESM" : "ESP",
+                  sessionData.metadata.is_light && "ESL",
+                  sessionData.metadata.is_localized && "本地化",
+                ]
+                  .filter(Boolean)
+                  .join(" · ")}
+              />
+            </Tooltip>
+          )}
 
           {/* 筛选Chips */}
           <Box sx={{ display: "flex", gap: 0.5, ml: 2 }}>
@@ -964,12 +1009,25 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
                   if (useSessionStore.getState().applyTranslations) {
                     try {
                       setIsSaving(true);
-                      await useSessionStore.getState().applyTranslations!(
-                        sessionData.session_id,
-                      );
-                      showSuccess("成功应用翻译到插件文件");
+                      const result = await useSessionStore
+                        .getState()
+                        .applyTranslations!(
+                          sessionData.session_id,
+                          undefined,
+                          true,
+                          true,
+                        );
+                      if (result.mismatch_count) {
+                        showWarning(
+                          `已写入插件，但回读校验发现 ${result.mismatch_count} 条记录不一致，原文件备份: ${result.backup_path ?? "无"}`,
+                        );
+                      } else {
+                        showSuccess(
+                          `成功应用翻译到插件文件（写入 ${result.applied_count} 条）`,
+                        );
+                      }
                     } catch (error) {
-                      showError("应用翻译失败: " + String(error));
+                      showError("应用翻译失败: " + getErrorMessage(error));
                     } finally {
                       setIsSaving(false);
                     }
@@ -982,6 +1040,35 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
             </span>
           </Tooltip>
 
+          {/* 撤销最近一次写回（从备份恢复） */}
+          <Tooltip title="从最近的备份恢复插件文件">
+            <span>
+              <IconButton
+                size="small"
+                color="warning"
+                sx={{ ml: 0.5 }}
+                onClick={async () => {
+                  if (useSessionStore.getState().undoLastApply) {
+                    try {
+                      setIsSaving(true);
+                      const restoredPath = await useSessionStore
+                        .getState()
+                        .undoLastApply!(sessionData.session_id);
+                      showSuccess(`已从备份恢复: ${restoredPath}`);
+                    } catch (error) {
+                      showError("撤销写回失败: " + getErrorMessage(error));
+                    } finally {
+                      setIsSaving(false);
+                    }
+                  }
+                }}
+                disabled={isSaving}
+              >
+                <RestoreIcon fontSize="small" />
+              </IconButton>
+            </span>
+          </Tooltip>
+
           {/* 导出 DSD 格式 */}
           <Tooltip title="导出为 DSD 格式">
             <span>
@@ -992,13 +1079,38 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
                 onClick={async () => {
                   if (useSessionStore.getState().exportDsd) {
                     try {
+                      const status =
+                        await useSessionStore.getState().getDsdStatus?.(
+                          sessionData.session_id,
+                        );
+                      let merge = false;
+                      if (status?.exists) {
+                        merge = window.confirm(
+                          `已存在 DSD 文件（${status.entry_count ?? "未知"} 条）:\n${status.path}\n\n确定：合并（同一记录使用新译文）\n取消：选择是否覆盖`,
+                        );
+                        if (!merge && !window.confirm("是否覆盖现有 DSD 文件？")) {
+                          return;
+                        }
+                      }
+
                       setIsSaving(true);
-                      const savedPath = await useSessionStore.getState().exportDsd!(
+                      const result = await useSessionStore.getState().exportDsd!(
                         sessionData.session_id,
+                        merge,
+                      );
+                      showSuccess(
+                        merge
+                          ? `DSD 已合并到: ${result.path}（新增 ${result.added}，更新 ${result.updated}）`
+                          : `DSD 已导出到: ${result.path}`,
                       );
-                      showSuccess(`DSD 已导出到: ${savedPath}`);
+                      if (result.skipped > 0) {
+                        console.warn("DSD 跳过的条目:", result.skipped_reasons);
+                        showWarning(
+                          `${result.skipped} 条记录未写入 DSD（如 ${result.skipped_reasons[0].form_id}: ${result.skipped_reasons[0].reason}）`,
+                        );
+                      }
                     } catch (error) {
-                      showError("导出 DSD 失败: " + String(error));
+                      showError("导出 DSD 失败: " + getErrorMessage(error));
                     } finally {
                       setIsSaving(false);
                     }
@@ -1037,12 +1149,13 @@ export default function SessionPanel({ sessionData }: SessionPanelProps) {
                 📥 获取数据库翻译
               </Typography>
               <Typography variant="caption" color="text.secondary">
-                {progress?.toFixed(1)}%
+                {progress &&
+                  `匹配中 ${progress.current.toLocaleString()}/${progress.total.toLocaleString()}（已找到 ${progress.matched.toLocaleString()}） ${progress.percentage.toFixed(1)}%`}
               </Typography>
             </Box>
             <LinearProgress
               variant="determinate"
-              value={progress || 0}
+              value={progress?.percentage || 0}
               sx={{ height: 4, borderRadius: 2 }}
             />
           </Box>
@@ -7,6 +7,7 @@ import type { StringRecord } from '../types';
 import { showError } from '../stores/notificationStore';
 import { useSessionStore } from '../stores/sessionStore';
 import type { GridPaginationModel } from '@mui/x-data-grid';
+import { getErrorMessage } from '../utils/commandError';
 
 interface StringTableProps {
   /** 字符串记录列表 */
@@ -86,6 +87,14 @@ const StringTable = memo(function StringTable({ rows, sessionId, paginationModel
         id: 'type',
         size: 150,
       },
+      {
+        header: 'Table',
+        accessorFn: (row) => row.strings_table ?? 'STRINGS',
+        id: 'strings_table',
+        filterVariant: 'select',
+        filterSelectOptions: ['STRINGS', 'ILSTRINGS', 'DLSTRINGS'],
+        size: 110,
+      },
       {
         header: 'Original Text',
         accessorKey: 'original_text',
@@ -208,7 +217,7 @@ const StringTable = memo(function StringTable({ rows, sessionId, paginationModel
     // 这样即使后端阻塞也不会影响主窗口
     invoke('open_editor_window', { record }).catch((error) => {
       console.error('打开编辑窗口失败:', error);
-      showError('打开编辑窗口失败: ' + String(error));
+      showError('打开编辑窗口失败: ' + getErrorMessage(error));
     });
 
     console.log('→ 调用已发出，不等待返回');
@@ -24,6 +24,7 @@ import AddIcon from '@mui/icons-material/Add';
 import DeleteIcon from '@mui/icons-material/Delete';
 import EditIcon from '@mui/icons-material/Edit';
 import { invoke } from '@tauri-apps/api/core';
+import { getErrorMessage } from '../../utils/commandError';
 
 interface AtomTranslation {
   id: number;
@@ -42,6 +43,8 @@ export default function AtomDbTermsPanel() {
   const [newOriginal, setNewOriginal] = useState('');
   const [newTranslated, setNewTranslated] = useState('');
   const [rowSelection, setRowSelection] = useState<RowSelectionState>({});
+  // 仅显示从未使用过的术语（便于清理）
+  const [staleOnly, setStaleOnly] = useState(false);
   const [snackbar, setSnackbar] = useState<{ open: boolean; message: string; severity: 'success' | 'error' }>({
     open: false,
     message: '',
@@ -64,18 +67,25 @@ export default function AtomDbTermsPanel() {
   const loadAtoms = async () => {
     try {
       setLoading(true);
-      const data = await invoke<AtomTranslation[]>('get_all_atoms');
+      // 先写入累积的使用计数，保证按使用次数排序是最新的
+      await invoke<number>('flush_atom_usage').catch((error) =>
+        console.warn('写入原子词使用计数失败:', error),
+      );
+      const data = staleOnly
+        ? await invoke<AtomTranslation[]>('list_stale_atoms', { maxUsage: 0 })
+        : await invoke<AtomTranslation[]>('get_all_atoms');
       setAtoms(data);
     } catch (error) {
-      showSnackbar('加载原子数据失败: ' + String(error), 'error');
+      showSnackbar('加载原子数据失败: ' + getErrorMessage(error), 'error');
     } finally {
       setLoading(false);
     }
   };
 
   useEffect(() => {
+    setRowSelection({});
     loadAtoms();
-  }, []);
+  }, [staleOnly]);
 
   // 显示提示消息
   const showSnackbar = (message: string, severity: 'success' | 'error') => {
@@ -107,7 +117,7 @@ export default function AtomDbTermsPanel() {
       setNewTranslated('');
       loadAtoms(); // 重新加载数据
     } catch (error) {
-      showSnackbar('添加失败: ' + String(error), 'error');
+      showSnackbar('添加失败: ' + getErrorMessage(error), 'error');
     }
   };
 
@@ -119,21 +129,14 @@ export default function AtomDbTermsPanel() {
     }
 
     try {
-      // 批量删除
-      for (const id of selectedIds) {
-        const atom = atoms.find((a) => a.id === Number(id));
-        if (atom) {
-          await invoke('delete_atom_translation', {
-            original: atom.original,
-          });
-        }
-      }
+      // 批量删除（单次调用，后端只重建一次匹配器）
+      const deleted = await invoke<number>('delete_atoms', { ids: selectedIds });
 
-      showSnackbar(`成功删除 ${selectedCount} 条记录`, 'success');
+      showSnackbar(`成功删除 ${deleted} 条记录`, 'success');
       setRowSelection({});
       loadAtoms(); // 重新加载数据
     } catch (error) {
-      showSnackbar('删除失败: ' + String(error), 'error');
+      showSnackbar('删除失败: ' + getErrorMessage(error), 'error');
     }
   };
 
@@ -165,7 +168,7 @@ export default function AtomDbTermsPanel() {
       setEditingAtom(null);
       loadAtoms(); // 重新加载数据
     } catch (error) {
-      showSnackbar('修改失败: ' + String(error), 'error');
+      showSnackbar('修改失败: ' + getErrorMessage(error), 'error');
     }
   };
 
@@ -275,6 +278,13 @@ export default function AtomDbTermsPanel() {
         >
           删除选中 ({selectedCount})
         </Button>
+        <Button
+          variant={staleOnly ? 'contained' : 'outlined'}
+          color="warning"
+          onClick={() => setStaleOnly((value) => !value)}
+        >
+          {staleOnly ? '显示全部' : '仅显示未使用'}
+        </Button>
       </Box>
 
       {/* 术语表格 */}
@@ -2,6 +2,7 @@ import { useCallback, useEffect, useMemo, useState } from 'react';
 import { MaterialReactTable, type MRT_ColumnDef } from 'material-react-table';
 import { invoke } from '@tauri-apps/api/core';
 import { Box, Typography, Chip, Alert, Snackbar } from '@mui/material';
+import { getErrorMessage } from '../../utils/commandError';
 
 interface SearchHistoryEntry {
   term: string;
@@ -27,7 +28,7 @@ export default function SearchHistoryPanel() {
       setHistory(data);
     } catch (err) {
       console.error('加载搜索历史失败:', err);
-      setError('加载搜索历史失败: ' + String(err));
+      setError('加载搜索历史失败: ' + getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -58,7 +59,7 @@ export default function SearchHistoryPanel() {
       setToastOpen(true);
     } catch (error) {
       console.error('操作失败:', error);
-      setToastMessage(`操作失败: ${String(error)}`);
+      setToastMessage(`操作失败: ${getErrorMessage(error)}`);
       setToastSeverity('error');
       setToastOpen(true);
     }
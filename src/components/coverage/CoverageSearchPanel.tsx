@@ -75,7 +75,11 @@ export default function CoverageSearchPanel() {
     {
       header: "加载顺序",
       accessorKey: "load_order_pos",
-      size: 90,
+      size: 200,
+      Cell: ({ row }) =>
+        row.original.load_order_plugin
+          ? `${row.original.load_order_pos} (${row.original.load_order_plugin})`
+          : row.original.load_order_pos,
     },
   ], []);
 
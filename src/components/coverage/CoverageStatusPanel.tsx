@@ -18,6 +18,23 @@ import CheckCircleIcon from "@mui/icons-material/CheckCircle";
 import WarningIcon from "@mui/icons-material/Warning";
 import { useCoverageStore } from "../../stores/coverageStore";
 import ExtractionProgress from "./ExtractionProgress";
+import type { PluginExtractionError } from "../../types";
+
+/**
+ * 将插件提取失败原因转换为可读文本
+ */
+function describeExtractionError(error: PluginExtractionError | null): string {
+  switch (error?.kind) {
+    case "file_missing":
+      return "插件文件不存在";
+    case "bsa_missing":
+      return "缺少 STRINGS 文件（未找到 BSA 或松散文件）";
+    case "parse_error":
+      return error.message;
+    default:
+      return "未知错误";
+  }
+}
 
 /**
  * 覆盖状态检测面板
@@ -88,6 +105,19 @@ export default function CoverageStatusPanel() {
           {lastExtractionStats.total_records} 条记录
           {lastExtractionStats.failed_plugins > 0 &&
             ` (${lastExtractionStats.failed_plugins} 个失败)`}
+          {lastExtractionStats.skipped_plugins > 0 &&
+            ` (按过滤设置跳过 ${lastExtractionStats.skipped_plugins} 个)`}
+          {lastExtractionStats.plugin_results
+            ?.filter((result) => result.error)
+            .map((result) => (
+              <Typography
+                key={result.plugin_name}
+                variant="caption"
+                component="div"
+              >
+                • {result.plugin_name}: {describeExtractionError(result.error)}
+              </Typography>
+            ))}
         </Alert>
       )}
 
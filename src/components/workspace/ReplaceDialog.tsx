@@ -25,6 +25,7 @@ import {
 } from "@mui/icons-material";
 import { useState, useEffect, useRef } from "react";
 import type { StringRecord } from "../../types";
+import { getErrorMessage } from "../../utils/commandError";
 
 interface ReplaceDialogProps {
   /** 是否打开对话框 */
@@ -147,7 +148,7 @@ export default function ReplaceDialog({
         setError("未找到匹配项");
       }
     } catch (err) {
-      setError(`正则表达式错误: ${err instanceof Error ? err.message : String(err)}`);
+      setError(`正则表达式错误: ${getErrorMessage(err)}`);
       setMatches([]);
     }
   };
@@ -195,7 +196,7 @@ export default function ReplaceDialog({
       // 临时提示
       setError("AI辅助功能即将推出，敬请期待！");
     } catch (err) {
-      setError(`AI辅助失败: ${err instanceof Error ? err.message : String(err)}`);
+      setError(`AI辅助失败: ${getErrorMessage(err)}`);
     } finally {
       setAiLoading(false);
     }
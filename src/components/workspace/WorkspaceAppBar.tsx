@@ -4,6 +4,8 @@ import SettingsIcon from "@mui/icons-material/Settings";
 import StorageIcon from "@mui/icons-material/Storage";
 import FolderOpenIcon from "@mui/icons-material/FolderOpen";
 import ClearIcon from "@mui/icons-material/Clear";
+import LockIcon from "@mui/icons-material/Lock";
+import LockOpenIcon from "@mui/icons-material/LockOpen";
 
 interface WorkspaceAppBarProps {
   onToggleDrawer: () => void;
@@ -14,6 +16,9 @@ interface WorkspaceAppBarProps {
   onResetWorkspace?: () => void;
   onSetDsdOutputDir?: () => void;
   onClearDsdOutputDir?: () => void;
+  /** 只读模式（禁止写回游戏目录） */
+  readOnlyData?: boolean;
+  onToggleReadOnlyData?: () => void;
 }
 
 /**
@@ -36,6 +41,8 @@ export default function WorkspaceAppBar({
   onResetWorkspace,
   onSetDsdOutputDir,
   onClearDsdOutputDir,
+  readOnlyData,
+  onToggleReadOnlyData,
 }: WorkspaceAppBarProps) {
   return (
     <AppBar
@@ -145,6 +152,18 @@ export default function WorkspaceAppBar({
           )}
         </Box>
 
+        <Tooltip
+          title={
+            readOnlyData
+              ? "只读模式：禁止写回游戏目录内的插件（点击关闭）"
+              : "点击开启只读模式（只能另存为或导出 DSD）"
+          }
+        >
+          <IconButton color="inherit" onClick={onToggleReadOnlyData}>
+            {readOnlyData ? <LockIcon /> : <LockOpenIcon />}
+          </IconButton>
+        </Tooltip>
+
         <IconButton color="inherit" onClick={onOpenAtomicDb} title="原子数据库">
           <StorageIcon />
         </IconButton>
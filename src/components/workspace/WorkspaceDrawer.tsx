@@ -14,11 +14,13 @@ import {
   Typography,
   IconButton,
   Tooltip,
+  Select,
+  MenuItem,
 } from "@mui/material";
 import ArchiveOutlinedIcon from "@mui/icons-material/ArchiveOutlined";
 import { invoke } from "@tauri-apps/api/core";
 import { useAppStore } from "../../stores/appStore";
-import type { PluginInfo } from "../../types";
+import type { PluginInfo, PluginOrderMode } from "../../types";
 
 export const DRAWER_WIDTH = 300;
 
@@ -52,7 +54,8 @@ export default function WorkspaceDrawer({
   open,
   onPluginClick,
 }: WorkspaceDrawerProps) {
-  const { plugins, isLoading, error } = useAppStore();
+  const { plugins, isLoading, error, pluginOrderMode, setPluginOrderMode } =
+    useAppStore();
   const [searchQuery, setSearchQuery] = useState("");
 
   // 过滤插件列表
@@ -93,6 +96,19 @@ export default function WorkspaceDrawer({
           value={searchQuery}
           onChange={(e) => setSearchQuery(e.target.value)}
         />
+        <Select
+          fullWidth
+          size="small"
+          value={pluginOrderMode}
+          onChange={(e) =>
+            setPluginOrderMode(e.target.value as PluginOrderMode)
+          }
+          sx={{ mt: 1 }}
+        >
+          <MenuItem value="load_order">按加载顺序</MenuItem>
+          <MenuItem value="alphabetical">按字母顺序</MenuItem>
+          <MenuItem value="mtime">按修改时间</MenuItem>
+        </Select>
       </Box>
 
       <Divider />
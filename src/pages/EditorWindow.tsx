@@ -11,12 +11,13 @@ import {
 import { Save, Close } from "@mui/icons-material";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import type { StringRecord, Translation } from "../types";
+import type { StringRecord, Translation, WordLookupResult } from "../types";
 import TranslationReferencePanel from "../components/TranslationReferencePanel";
 import { showSuccess, showError } from "../stores/notificationStore";
 import CodeMirror from "@uiw/react-codemirror";
 import { EditorView } from "@codemirror/view";
 import { bookDescExtensions, editorFontFamily } from "../utils/customSyntax";
+import { getErrorMessage } from "../utils/commandError";
 
 /**
  * 编辑窗口页面
@@ -29,6 +30,8 @@ export default function EditorWindow() {
   const [selectedText, setSelectedText] = useState("");
   const [references, setReferences] = useState<Translation[]>([]);
   const [loadingReferences, setLoadingReferences] = useState(false);
+  // 选中文本过长时实际查询的单词
+  const [lookupQuery, setLookupQuery] = useState<string | null>(null);
 
   // ✅ 组件加载后主动拉取数据
   useEffect(() => {
@@ -60,7 +63,7 @@ export default function EditorWindow() {
         setTranslatedText(data.translated_text);
       } catch (error) {
         console.error("❌ 加载编辑数据失败:", error);
-        showError("加载编辑数据失败: " + String(error));
+        showError("加载编辑数据失败: " + getErrorMessage(error));
 
         // 如果加载失败，显示错误信息而不是一直显示"加载中..."
         setRecord({
@@ -69,7 +72,7 @@ export default function EditorWindow() {
           record_type: "ERROR",
           subrecord_type: "ERROR",
           index: 0,
-          original_text: "加载失败: " + String(error),
+          original_text: "加载失败: " + getErrorMessage(error),
           translated_text: "",
           translation_status: "untranslated",
         });
@@ -97,6 +100,7 @@ export default function EditorWindow() {
     } else {
       setSelectedText("");
       setReferences([]);
+      setLookupQuery(null);
     }
   }, []);
 
@@ -104,14 +108,16 @@ export default function EditorWindow() {
   const queryReferences = async (text: string) => {
     try {
       setLoadingReferences(true);
-      const results = await invoke<Translation[]>("query_word_translations", {
+      const result = await invoke<WordLookupResult>("query_word_translations", {
         text,
         limit: 3,
       });
-      setReferences(results);
+      setReferences(result.translations);
+      setLookupQuery(result.truncated ? result.query : null);
     } catch (error) {
       console.error("查询参考翻译失败:", error);
       setReferences([]);
+      setLookupQuery(null);
     } finally {
       setLoadingReferences(false);
     }
@@ -145,7 +151,7 @@ export default function EditorWindow() {
       await getCurrentWebviewWindow().close();
     } catch (error) {
       console.error("应用翻译失败:", error);
-      showError("应用翻译失败: " + String(error));
+      showError("应用翻译失败: " + getErrorMessage(error));
     }
   };
 
@@ -283,6 +289,11 @@ export default function EditorWindow() {
                 <Typography variant="caption" color="text.secondary">
                   已选中: {selectedText}
                 </Typography>
+                {lookupQuery !== null && (
+                  <Typography variant="caption" color="warning.main" display="block">
+                    选中文本过长，仅按 “{lookupQuery}” 查询参考翻译
+                  </Typography>
+                )}
               </Box>
             )}
 
@@ -14,6 +14,7 @@ import {
 import FolderOpenIcon from "@mui/icons-material/FolderOpen";
 import { useAppStore } from "../stores/appStore";
 import { WORKSPACE_STORAGE_KEY } from "../constants/storageKeys";
+import { getErrorMessage } from "../utils/commandError";
 
 interface StoredWorkspace {
   path: string;
@@ -69,7 +70,7 @@ export default function GamePathSelector() {
         navigate("/workspace");
       } catch (err) {
         console.error("路径选择失败:", err);
-        setError(err instanceof Error ? err.message : String(err));
+        setError(getErrorMessage(err));
       } finally {
         setIsValidating(false);
       }
@@ -119,7 +120,7 @@ export default function GamePathSelector() {
       await handlePathSelection(selectedPath, "directory");
     } catch (err) {
       console.error("选择目录失败:", err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     }
   };
 
@@ -146,7 +147,7 @@ export default function GamePathSelector() {
       await handlePathSelection(selectedPath, "file");
     } catch (err) {
       console.error("选择文件失败:", err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     }
   };
 
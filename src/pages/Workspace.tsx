@@ -43,7 +43,8 @@ const openAtomicDbWindow = async () => {
 export default function Workspace() {
   const navigate = useNavigate();
 
-  const { gamePath, dsdOutputDir, loadSettings } = useAppStore();
+  const { gamePath, dsdOutputDir, readOnlyData, loadSettings } = useAppStore();
+  const setReadOnlyData = useAppStore((state) => state.setReadOnlyData);
   const clearGamePath = useAppStore((state) => state.clearGamePath);
   const setDsdOutputDir = useAppStore((state) => state.setDsdOutputDir);
   const clearDsdOutputDir = useAppStore((state) => state.clearDsdOutputDir);
@@ -51,8 +52,8 @@ export default function Workspace() {
   // ✅ 只获取需要的 action 函数（zustand actions 引用稳定）
   const openSession = useSessionStore((state) => state.openSession);
   const switchSession = useSessionStore((state) => state.switchSession);
-  const checkSessionExists = useSessionStore(
-    (state) => state.checkSessionExists,
+  const findSessionIdByPath = useSessionStore(
+    (state) => state.findSessionIdByPath,
   );
 
   // API配置加载函数
@@ -155,17 +156,17 @@ export default function Workspace() {
   // 处理插件点击：检查是否已打开，已打开则切换，未打开则新建
   const handlePluginClick = useCallback(
     (plugin: PluginInfo) => {
-      const pluginName = plugin.name;
+      const sessionId = findSessionIdByPath(plugin.path);
 
-      if (checkSessionExists(pluginName)) {
+      if (sessionId) {
         // 已打开，切换到该 Session
-        switchSession(pluginName);
+        switchSession(sessionId);
       } else {
         // 未打开，创建新 Session
         openSession(plugin.path);
       }
     },
-    [checkSessionExists, switchSession, openSession],
+    [findSessionIdByPath, switchSession, openSession],
   );
 
   return (
@@ -186,6 +187,8 @@ export default function Workspace() {
         onResetWorkspace={handleResetWorkspace}
         onSetDsdOutputDir={handleSetDsdOutputDir}
         onClearDsdOutputDir={clearDsdOutputDir}
+        readOnlyData={readOnlyData}
+        onToggleReadOnlyData={() => setReadOnlyData(!readOnlyData)}
       />
 
       {/* 左侧插件列表 */}
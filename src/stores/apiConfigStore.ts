@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { getErrorMessage } from '../utils/commandError';
 
 /**
  * API配置数据结构
@@ -11,11 +12,33 @@ export interface ApiConfig {
   apiKey: string;
   modelName: string;
   maxTokens: number;
+  /** System Prompt 模板（为空时使用内置默认 Prompt） */
+  systemPrompt: string;
+  /** User Prompt 模板，支持 {text} 与 {glossary} 占位符（为空时使用内置默认模板） */
+  userPromptTemplate: string;
+  /** 输入 token 单价（每百万 token，0 表示不计费） */
+  promptPrice: number;
+  /** 输出 token 单价（每百万 token） */
+  completionPrice: number;
   isActive: boolean;
   createdAt: number;
   updatedAt: number;
 }
 
+/**
+ * 单个配置的 API 用量汇总
+ */
+export interface ApiUsageSummary {
+  configId: number;
+  /** 配置名称（配置已删除时为 null） */
+  configName: string | null;
+  requestCount: number;
+  promptTokens: number;
+  completionTokens: number;
+  /** 按记录时的单价估算的费用 */
+  approxCost: number;
+}
+
 /**
  * API配置状态管理
  */
@@ -31,8 +54,13 @@ interface ApiConfigState {
   createConfig: (name: string) => Promise<number>;
   updateConfig: (id: number, config: Partial<ApiConfig>) => Promise<void>;
   deleteConfig: (id: number) => Promise<void>;
-  activateConfig: (id: number) => Promise<void>;
+  /** 激活配置，返回此前激活的配置ID（便于撤销） */
+  activateConfig: (id: number) => Promise<number | null>;
+  /** 取消所有配置的激活状态（禁用 AI 功能），返回此前激活的配置ID */
+  deactivateAll: () => Promise<number | null>;
   refreshCurrentApi: () => Promise<void>;
+  /** 按配置汇总 API 用量（since/until 为 Unix 秒） */
+  getApiUsage: (since?: number, until?: number) => Promise<ApiUsageSummary[]>;
   setError: (error: string | null) => void;
 }
 
@@ -63,7 +91,7 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
     } catch (error) {
       console.error('加载API配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
       });
     }
@@ -85,7 +113,7 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
     } catch (error) {
       console.error('创建API配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
       });
       throw error;
@@ -113,7 +141,7 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
     } catch (error) {
       console.error('更新API配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
       });
       throw error;
     }
@@ -133,7 +161,7 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
     } catch (error) {
       console.error('删除API配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
       });
       throw error;
@@ -145,16 +173,39 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
     try {
       set({ isLoading: true, error: null });
 
-      await invoke('activate_api_config', { id });
+      const previousId = await invoke<number | null>('activate_api_config', { id });
 
       // 刷新配置列表和当前配置
       await get().loadConfigs();
 
       set({ isLoading: false });
+      return previousId;
     } catch (error) {
       console.error('激活API配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
+        isLoading: false,
+      });
+      throw error;
+    }
+  },
+
+  // 取消所有配置的激活状态
+  deactivateAll: async () => {
+    try {
+      set({ isLoading: true, error: null });
+
+      const previousId = await invoke<number | null>('deactivate_all_api_configs');
+
+      // 刷新配置列表和当前配置
+      await get().loadConfigs();
+
+      set({ isLoading: false });
+      return previousId;
+    } catch (error) {
+      console.error('取消激活API配置失败:', error);
+      set({
+        error: getErrorMessage(error),
         isLoading: false,
       });
       throw error;
@@ -171,8 +222,21 @@ export const useApiConfigStore = create<ApiConfigState>((set, get) => ({
       console.error('获取当前API配置失败:', error);
       set({
         currentApi: null,
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 获取 API 用量汇总
+  getApiUsage: async (since?: number, until?: number) => {
+    try {
+      return await invoke<ApiUsageSummary[]>('get_api_usage', { since, until });
+    } catch (error) {
+      console.error('获取API用量失败:', error);
+      set({
+        error: getErrorMessage(error),
       });
+      throw error;
     }
   },
 
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { AppState, Settings, PluginInfo } from '../types';
+import type { AppState, Settings, PluginInfo, PluginOrderMode, Diagnostics, IntegrityReport, LengthLimit, CoverageFilter, TranslationSource } from '../types';
+import { getErrorMessage } from '../utils/commandError';
 
 /**
  * 应用全局状态管理
@@ -9,7 +10,10 @@ export const useAppStore = create<AppState>((set, get) => ({
   // 状态
   gamePath: null,
   dsdOutputDir: null,
+  applyThreads: null,
+  readOnlyData: false,
   plugins: [],
+  pluginOrderMode: 'load_order',
   isLoading: false,
   error: null,
 
@@ -23,6 +27,8 @@ export const useAppStore = create<AppState>((set, get) => ({
       set({
         gamePath: settings.game,
         dsdOutputDir: settings.dsd_output_dir ?? null,
+        applyThreads: settings.apply_threads ?? null,
+        readOnlyData: settings.read_only_data ?? false,
         isLoading: false,
       });
 
@@ -33,12 +39,32 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('加载配置失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
       });
     }
   },
 
+  // 重新读取 settings.json 并应用到运行时状态（手动编辑设置文件后使用）
+  reloadSettings: async () => {
+    try {
+      const settings = await invoke<Settings>('reload_settings');
+
+      set({
+        gamePath: settings.game,
+        dsdOutputDir: settings.dsd_output_dir ?? null,
+        applyThreads: settings.apply_threads ?? null,
+        readOnlyData: settings.read_only_data ?? false,
+        error: null,
+      });
+    } catch (error) {
+      console.error('重新加载配置失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
   // 设置游戏路径
   setGamePath: async (path: string) => {
     try {
@@ -57,7 +83,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('设置游戏路径失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
       });
     }
@@ -78,7 +104,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('清除游戏路径失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
         gamePath: null,
         plugins: [],
@@ -91,7 +117,9 @@ export const useAppStore = create<AppState>((set, get) => ({
     try {
       set({ isLoading: true, error: null });
 
-      const plugins = await invoke<PluginInfo[]>('get_plugin_list');
+      const plugins = await invoke<PluginInfo[]>('get_plugin_list', {
+        orderMode: get().pluginOrderMode,
+      });
 
       set({
         plugins,
@@ -100,13 +128,112 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('加载插件列表失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoading: false,
         plugins: [],
       });
     }
   },
 
+  // 切换插件列表排序方式
+  setPluginOrderMode: async (mode: PluginOrderMode) => {
+    set({ pluginOrderMode: mode });
+    if (get().gamePath) {
+      await get().loadPlugins();
+    }
+  },
+
+  // 获取诊断信息
+  getDiagnostics: async () => {
+    try {
+      return await invoke<Diagnostics>('get_diagnostics');
+    } catch (error) {
+      console.error('获取诊断信息失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 检查数据库完整性
+  checkIntegrity: async () => {
+    try {
+      return await invoke<IntegrityReport[]>('check_integrity');
+    } catch (error) {
+      console.error('检查数据库完整性失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 备份所有数据库
+  backupDatabases: async () => {
+    try {
+      return await invoke<string[]>('backup_databases');
+    } catch (error) {
+      console.error('备份数据库失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 从备份恢复数据库
+  rebuildFromBackup: async (database: string, backupName: string) => {
+    try {
+      await invoke('rebuild_from_backup', { database, backupName });
+    } catch (error) {
+      console.error('恢复数据库失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 在文件管理器中打开 userdata 目录
+  revealUserdataDir: async () => {
+    try {
+      await invoke('reveal_userdata_dir');
+    } catch (error) {
+      console.error('打开 userdata 目录失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 在文件管理器中定位配置文件
+  revealSettingsFile: async () => {
+    try {
+      await invoke('reveal_settings_file');
+    } catch (error) {
+      console.error('打开配置文件位置失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 在文件管理器中定位启动日志
+  revealLogFile: async () => {
+    try {
+      await invoke('reveal_log_file');
+    } catch (error) {
+      console.error('打开日志文件位置失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
   // 设置 DSD 导出目录
   setDsdOutputDir: async (path: string) => {
     try {
@@ -115,7 +242,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('设置 DSD 导出目录失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
       });
     }
   },
@@ -128,8 +255,136 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch (error) {
       console.error('清除 DSD 导出目录失败:', error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 开启或关闭只读模式
+  setReadOnlyData: async (enabled: boolean) => {
+    try {
+      await invoke('set_read_only_data', { enabled });
+      set({ readOnlyData: enabled });
+    } catch (error) {
+      console.error('设置只读模式失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置写回转换线程数
+  setApplyThreads: async (threads: number | null) => {
+    try {
+      if (threads === null) {
+        await invoke('clear_apply_threads');
+      } else {
+        await invoke('set_apply_threads', { threads });
+      }
+      set({ applyThreads: threads });
+    } catch (error) {
+      console.error('设置写回线程数失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置覆盖提取的插件过滤（null 时恢复提取全部插件）
+  setCoverageFilter: async (filter: CoverageFilter | null) => {
+    try {
+      if (filter === null) {
+        await invoke('clear_coverage_filter');
+      } else {
+        await invoke('set_coverage_filter', { filter });
+      }
+    } catch (error) {
+      console.error('设置覆盖提取插件过滤失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置按原文自动填充的译文来源优先级（null 时恢复默认顺序）
+  setTranslationSourcePriority: async (priority: TranslationSource[] | null) => {
+    try {
+      if (priority === null) {
+        await invoke('clear_translation_source_priority');
+      } else {
+        await invoke('set_translation_source_priority', { priority });
+      }
+    } catch (error) {
+      console.error('设置译文来源优先级失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置译文字节长度上限（null 时恢复内置默认值）
+  setLengthLimits: async (limits: LengthLimit[] | null) => {
+    try {
+      if (limits === null) {
+        await invoke('clear_length_limits');
+      } else {
+        await invoke('set_length_limits', { limits });
+      }
+    } catch (error) {
+      console.error('设置译文长度上限失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置使用计数写入重试次数（null 时恢复默认值）
+  setUsageWriteRetries: async (retries: number | null) => {
+    try {
+      if (retries === null) {
+        await invoke('clear_usage_write_retries');
+      } else {
+        await invoke('set_usage_write_retries', { retries });
+      }
+    } catch (error) {
+      console.error('设置使用计数写入重试次数失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+    }
+  },
+
+  // 设置 DSD 路径模板（null 时恢复默认约定）
+  setDsdPathTemplate: async (template: string | null) => {
+    try {
+      if (template === null) {
+        await invoke('clear_dsd_path_template');
+      } else {
+        await invoke('set_dsd_path_template', { template });
+      }
+    } catch (error) {
+      console.error('设置 DSD 路径模板失败:', error);
+      set({
+        error: getErrorMessage(error),
+      });
+      throw error;
+    }
+  },
+
+  // 设置 userdata 目录（重启后生效）
+  setUserdataDir: async (path: string | null) => {
+    try {
+      if (path === null) {
+        await invoke('clear_userdata_dir');
+      } else {
+        await invoke('set_userdata_dir', { path });
+      }
+    } catch (error) {
+      console.error('设置 userdata 目录失败:', error);
+      set({
+        error: getErrorMessage(error),
       });
+      throw error;
     }
   },
 
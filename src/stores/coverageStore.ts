@@ -5,7 +5,11 @@ import type {
   CoverageEntry,
   CoverageExtractionStats,
   CoverageProgressPayload,
+  CoverageSnapshotSummary,
+  PluginCoverageUpdate,
+  TypeCount,
 } from "../types";
+import { getErrorMessage } from "../utils/commandError";
 
 /**
  * Coverage DB 状态管理
@@ -31,12 +35,22 @@ interface CoverageState {
 
   // Actions
   fetchStatus: () => Promise<void>;
-  startExtraction: () => Promise<void>;
+  /** recordTypeFilter 仅提取匹配的记录（如 ["INFO NAM1"]），未指定时提取全部 */
+  startExtraction: (recordTypeFilter?: string[]) => Promise<void>;
+  /** 仅重新提取单个插件（需已完成一次完整提取，recordTypeFilter 应与完整提取一致） */
+  updatePlugin: (
+    pluginPath: string,
+    recordTypeFilter?: string[]
+  ) => Promise<PluginCoverageUpdate | null>;
   searchEntries: (
     formIdQuery?: string,
     textQuery?: string,
     limit?: number
   ) => Promise<void>;
+  /** 覆盖数据库中出现的记录类型/子记录类型组合（按条目数降序） */
+  getDistinctTypes: () => Promise<TypeCount[]>;
+  exportSnapshot: (path: string) => Promise<CoverageSnapshotSummary | null>;
+  importSnapshot: (path: string) => Promise<CoverageSnapshotSummary | null>;
 
   // 事件驱动的状态更新 (由组件调用)
   setExtractionProgress: (progress: CoverageProgressPayload) => void;
@@ -75,14 +89,14 @@ export const useCoverageStore = create<CoverageState>((set, get) => ({
     } catch (error) {
       console.error("获取覆盖状态失败:", error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isLoadingStatus: false,
       });
     }
   },
 
   // 启动提取 (命令立即返回，进度通过事件更新)
-  startExtraction: async () => {
+  startExtraction: async (recordTypeFilter?: string[]) => {
     try {
       set({
         isExtracting: true,
@@ -92,20 +106,37 @@ export const useCoverageStore = create<CoverageState>((set, get) => ({
       });
 
       // 命令立即返回，不等待提取完成
-      await invoke("run_coverage_extraction");
+      await invoke("run_coverage_extraction", { recordTypeFilter });
 
       // 注意：isExtracting 将在收到 coverage_complete 事件后由 setExtractionComplete 设为 false
     } catch (error) {
       // 这里只会捕获预检查阶段的错误（如没有游戏路径）
       console.error("启动覆盖提取失败:", error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isExtracting: false,
         extractionProgress: null,
       });
     }
   },
 
+  // 增量更新单个插件的覆盖记录
+  updatePlugin: async (pluginPath: string, recordTypeFilter?: string[]) => {
+    try {
+      set({ error: null });
+      const result = await invoke<PluginCoverageUpdate>(
+        "update_coverage_for_plugin",
+        { pluginPath, recordTypeFilter }
+      );
+      await get().fetchStatus();
+      return result;
+    } catch (error) {
+      console.error("增量更新覆盖记录失败:", error);
+      set({ error: getErrorMessage(error) });
+      return null;
+    }
+  },
+
   // 搜索覆盖记录
   searchEntries: async (
     formIdQuery?: string,
@@ -128,13 +159,55 @@ export const useCoverageStore = create<CoverageState>((set, get) => ({
     } catch (error) {
       console.error("搜索覆盖记录失败:", error);
       set({
-        error: error instanceof Error ? error.message : String(error),
+        error: getErrorMessage(error),
         isSearching: false,
         searchResults: [],
       });
     }
   },
 
+  // 获取覆盖数据库中的记录类型组合
+  getDistinctTypes: async () => {
+    try {
+      return await invoke<TypeCount[]>("get_coverage_distinct_types");
+    } catch (error) {
+      console.error("获取覆盖记录类型失败:", error);
+      set({ error: getErrorMessage(error) });
+      return [];
+    }
+  },
+
+  // 导出覆盖数据库快照
+  exportSnapshot: async (path: string) => {
+    try {
+      set({ error: null });
+      return await invoke<CoverageSnapshotSummary>("export_coverage_snapshot", {
+        path,
+      });
+    } catch (error) {
+      console.error("导出覆盖快照失败:", error);
+      set({ error: getErrorMessage(error) });
+      return null;
+    }
+  },
+
+  // 导入覆盖数据库快照（替换现有数据）
+  importSnapshot: async (path: string) => {
+    try {
+      set({ error: null });
+      const summary = await invoke<CoverageSnapshotSummary>(
+        "import_coverage_snapshot",
+        { path }
+      );
+      await get().fetchStatus();
+      return summary;
+    } catch (error) {
+      console.error("导入覆盖快照失败:", error);
+      set({ error: getErrorMessage(error) });
+      return null;
+    }
+  },
+
   // 事件驱动：更新提取进度
   setExtractionProgress: (progress: CoverageProgressPayload) => {
     set({ extractionProgress: progress });
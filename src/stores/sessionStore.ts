@@ -8,12 +8,31 @@ import type {
   FormIdentifier,
   Translation,
   TranslationProgressPayload,
+  ApplyResult,
+  DsdExportResult,
+  DsdPreview,
+  ExchangeFormat,
+  ExchangeEncoding,
+  ExchangeImportResult,
+  DsdStatus,
+  RecordContext,
+  SessionProgress,
+  CoverageFillCandidate,
+  TextFillCandidate,
+  PluginChecksum,
+  PluginBackup,
+  EditorIdRemapReport,
+  LengthViolation,
+  CombinedStringsResponse,
+  CombinedApplyEntry,
+  StringRecord,
 } from "../types";
 import {
   useHistoryStore,
   type HistoryCommand,
   type HistoryRecord,
 } from "./historyStore";
+import { getErrorMessage } from "../utils/commandError";
 
 /**
  * 翻译更新事件 Payload
@@ -56,15 +75,13 @@ export const useSessionStore = create<SessionState>((set, get) => ({
    * @param pluginPath - 插件文件的完整路径
    */
   openSession: async (pluginPath: string) => {
-    const { checkSessionExists, refreshTranslations } = get();
+    const { findSessionIdByPath, refreshTranslations } = get();
 
-    // 提取插件名称
-    const pluginName = pluginPath.split(/[/\\]/).pop() || "";
-
-    // 检查是否已打开
-    if (checkSessionExists(pluginName)) {
-      console.log(`Session 已存在，切换到: ${pluginName}`);
-      set({ activeSessionId: pluginName });
+    // 检查是否已打开（同名插件可能来自不同目录，按完整路径判断）
+    const existingId = findSessionIdByPath(pluginPath);
+    if (existingId) {
+      console.log(`Session 已存在，切换到: ${existingId}`);
+      set({ activeSessionId: existingId });
       return;
     }
 
@@ -100,7 +117,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
         console.error("自动刷新翻译失败:", err);
       });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("加载 Session 失败:", errorMsg);
       set({ error: errorMsg, isLoading: false });
     }
@@ -109,7 +126,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
   /**
    * 关闭插件 Session
    *
-   * @param sessionId - Session ID（即插件名称）
+   * @param sessionId - Session ID（由后端根据插件完整路径生成）
    */
   closeSession: async (sessionId: string) => {
     set({ isLoading: true, error: null });
@@ -182,12 +199,57 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       // 🗑️ 清空历史记录
       useHistoryStore.getState().clearSession(sessionId);
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("关闭 Session 失败:", errorMsg);
       set({ error: errorMsg, isLoading: false });
     }
   },
 
+  /**
+   * 关闭所有 Session
+   *
+   * @param saveDirty - 关闭前是否先保存存在未保存修改的 Session
+   * @returns 关闭的 Session 数量
+   */
+  closeAllSessions: async (saveDirty?: boolean): Promise<number> => {
+    set({ isLoading: true, error: null });
+
+    try {
+      if (saveDirty) {
+        const { pendingChanges, saveSessionTranslations } = get();
+        for (const [sessionId, changes] of pendingChanges ?? new Map()) {
+          if (changes.size > 0) {
+            await saveSessionTranslations!(sessionId);
+          }
+        }
+      }
+
+      const closedCount = await invoke<number>("close_all_sessions");
+      console.log(`✓ 已关闭全部 ${closedCount} 个 Session`);
+
+      set({
+        openedSessions: new Map(),
+        translationProgress: new Map(),
+        filterStatus: new Map(),
+        selectedRows: new Map(),
+        pendingChanges: new Map(),
+        espReferenceLoading: new Map(),
+        activeSessionId: null,
+        isLoading: false,
+      });
+
+      // 🗑️ 清空历史记录
+      useHistoryStore.getState().clearAll();
+
+      return closedCount;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("关闭全部 Session 失败:", errorMsg);
+      set({ error: errorMsg, isLoading: false });
+      throw new Error(errorMsg);
+    }
+  },
+
   /**
    * 切换激活的 Session
    *
@@ -207,12 +269,27 @@ export const useSessionStore = create<SessionState>((set, get) => ({
   /**
    * 检查 Session 是否已存在
    *
-   * @param pluginName - 插件名称
+   * @param pluginPath - 插件完整路径
    * @returns true 如果 Session 已打开
    */
-  checkSessionExists: (pluginName: string): boolean => {
+  checkSessionExists: (pluginPath: string): boolean => {
+    return get().findSessionIdByPath(pluginPath) !== null;
+  },
+
+  /**
+   * 查找插件路径对应的 Session ID
+   *
+   * @param pluginPath - 插件完整路径
+   * @returns Session ID，未打开时为 null
+   */
+  findSessionIdByPath: (pluginPath: string): string | null => {
     const { openedSessions } = get();
-    return openedSessions.has(pluginName);
+    for (const [sessionId, session] of openedSessions) {
+      if (session.plugin_path === pluginPath) {
+        return sessionId;
+      }
+    }
+    return null;
   },
 
   /**
@@ -295,7 +372,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
 
       console.log(`✓ 刷新翻译完成: 应用了 ${translations.length} 条翻译`);
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("刷新翻译失败:", errorMsg);
       set({ error: errorMsg });
 
@@ -323,11 +400,15 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     const unlisten = await listen<TranslationProgressPayload>(
       "translation_progress",
       (event) => {
-        const { session_id, percentage } = event.payload;
+        const { session_id } = event.payload;
 
         useSessionStore.setState((state) => {
+          // 忽略已关闭 Session 的迟到事件
+          if (!state.openedSessions.has(session_id)) {
+            return {};
+          }
           const newProgress = new Map(state.translationProgress);
-          newProgress.set(session_id, percentage);
+          newProgress.set(session_id, event.payload);
           return { translationProgress: newProgress };
         });
       },
@@ -535,7 +616,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
 
       return translationsToSave.length;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("批量保存翻译失败:", errorMsg);
       throw new Error(errorMsg);
     }
@@ -662,7 +743,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
 
       return translationsToSave.length;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error(`Session ${sessionId}: 保存翻译失败:`, errorMsg);
       throw new Error(errorMsg);
     }
@@ -673,12 +754,16 @@ export const useSessionStore = create<SessionState>((set, get) => ({
    *
    * @param sessionId - Session ID
    * @param saveAs - 另存为路径（可选）
-   * @returns 保存的文件路径
+   * @param verify - 写入后是否回读校验（可选）
+   * @param onlyChanged - 仅写入已翻译或译文有变化的记录（可选）
+   * @returns 保存路径、写入数量与校验结果
    */
   applyTranslations: async (
     sessionId: string,
     saveAs?: string,
-  ): Promise<string> => {
+    verify?: boolean,
+    onlyChanged?: boolean,
+  ): Promise<ApplyResult> => {
     const { openedSessions } = get();
     const session = openedSessions.get(sessionId);
 
@@ -690,28 +775,118 @@ export const useSessionStore = create<SessionState>((set, get) => ({
 
     try {
       // 调用后端命令
-      const savedPath = await invoke<string>("apply_translations", {
+      const result = await invoke<ApplyResult>("apply_translations", {
         sessionId,
         translations: session.strings,
         saveAs,
+        verify,
+        onlyChanged,
       });
 
-      console.log(`✓ 翻译已应用到文件: ${savedPath}`);
-      return savedPath;
+      console.log(
+        `✓ 翻译已应用到文件: ${result.saved_path}（写入 ${result.applied_count} 条）`,
+      );
+      return result;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("应用翻译失败:", errorMsg);
       throw new Error(errorMsg);
     }
   },
 
+  /**
+   * 撤销最近一次写回
+   *
+   * 后端从最新的 .bak 恢复插件文件并移除缓存，这里重新加载 Session 并清空未保存修改
+   *
+   * @param sessionId - Session ID
+   * @returns 恢复的插件路径
+   */
+  undoLastApply: async (sessionId: string): Promise<string> => {
+    const session = get().openedSessions.get(sessionId);
+
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      const restoredPath = await invoke<string>("undo_last_apply", {
+        sessionId,
+      });
+      console.log(`✓ 已从备份恢复: ${restoredPath}`);
+
+      await reloadRestoredSession(sessionId, session.plugin_path);
+      return restoredPath;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("撤销写回失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 列出插件写回时生成的全部备份
+   *
+   * @param sessionId - Session ID
+   * @returns 备份列表（按时间从新到旧）
+   */
+  listBackups: async (sessionId: string): Promise<PluginBackup[]> => {
+    try {
+      return await invoke<PluginBackup[]>("list_backups", { sessionId });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("获取备份列表失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 从指定备份恢复插件文件
+   *
+   * 与 undoLastApply 相同，恢复后重新加载 Session 并清空未保存修改
+   *
+   * @param sessionId - Session ID
+   * @param backupPath - 备份文件路径（来自 listBackups）
+   * @returns 恢复的插件路径
+   */
+  restoreBackup: async (sessionId: string, backupPath: string): Promise<string> => {
+    const session = get().openedSessions.get(sessionId);
+
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      const restoredPath = await invoke<string>("restore_backup", {
+        sessionId,
+        backupPath,
+      });
+      console.log(`✓ 已从备份恢复: ${backupPath} -> ${restoredPath}`);
+
+      await reloadRestoredSession(sessionId, session.plugin_path);
+      return restoredPath;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("恢复备份失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
   /**
    * 导出 DSD (Dynamic String Distributor) 格式
    *
    * @param sessionId - Session ID
-   * @returns 生成的文件路径
+   * @param merge - 是否与已有文件合并（按 form_id + type，新值优先）
+   * @param writeMetadata - 是否同时写入元数据文件
+   * @param recordTypeFilter - 仅导出匹配的记录（如 ["INFO"]），未指定时导出全部
+   * @returns 生成的文件路径、新增/更新数量与被过滤的记录数
    */
-  exportDsd: async (sessionId: string): Promise<string> => {
+  exportDsd: async (
+    sessionId: string,
+    merge?: boolean,
+    writeMetadata?: boolean,
+    recordTypeFilter?: string[],
+  ): Promise<DsdExportResult> => {
     const { openedSessions } = get();
     const session = openedSessions.get(sessionId);
 
@@ -722,20 +897,514 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     console.log(`开始导出 DSD 格式: ${sessionId}`);
 
     try {
-      const savedPath = await invoke<string>("export_dsd", {
+      const result = await invoke<DsdExportResult>("export_dsd", {
         sessionId,
         records: session.strings,
+        merge,
+        writeMetadata,
+        recordTypeFilter,
       });
 
-      console.log(`✓ DSD 已导出到: ${savedPath}`);
-      return savedPath;
+      console.log(`✓ DSD 已导出到: ${result.path}`);
+      return result;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       console.error("导出 DSD 失败:", errorMsg);
       throw new Error(errorMsg);
     }
   },
 
+  /**
+   * 预览 DSD 导出内容（不写入文件）
+   *
+   * @param sessionId - Session ID
+   * @param merge - 是否按导出时的合并规则与已有文件合并
+   * @param recordTypeFilter - 仅预览匹配的记录（同导出时的过滤条件）
+   * @returns 目标路径、将写入的 JSON 与现有文件内容
+   */
+  previewDsd: async (
+    sessionId: string,
+    merge?: boolean,
+    recordTypeFilter?: string[],
+  ): Promise<DsdPreview> => {
+    const { openedSessions } = get();
+    const session = openedSessions.get(sessionId);
+
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      return await invoke<DsdPreview>("preview_dsd", {
+        sessionId,
+        records: session.strings,
+        merge,
+        recordTypeFilter,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("预览 DSD 失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 查询是否已有 DSD 导出文件（用于导出前提示合并或覆盖）
+   *
+   * @param sessionId - Session ID
+   * @returns 文件路径、是否存在、大小与条目数
+   */
+  getDsdStatus: async (sessionId: string): Promise<DsdStatus> => {
+    try {
+      return await invoke<DsdStatus>("dsd_status", { sessionId });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("查询 DSD 状态失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 获取对话记录在同一话题下的前后记录
+   *
+   * @param sessionId - Session ID
+   * @param key - 目标记录
+   * @param radius - 前后各返回的记录数（默认 3）
+   * @returns 非对话记录返回 null
+   */
+  getRecordContext: async (
+    sessionId: string,
+    key: FormIdentifier,
+    radius?: number,
+  ): Promise<RecordContext | null> => {
+    try {
+      return await invoke<RecordContext | null>("get_record_context", {
+        sessionId,
+        key,
+        radius,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("获取对话上下文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 获取单个 Session 的翻译进度（按当前编辑中的记录统计）
+   *
+   * @param sessionId - Session ID
+   * @returns 记录总数、翻译数据库命中数与仍与原文相同的记录数
+   */
+  getSessionTranslationProgress: async (sessionId: string): Promise<SessionProgress> => {
+    const session = get().openedSessions.get(sessionId);
+
+    try {
+      return await invoke<SessionProgress>("get_session_translation_progress", {
+        sessionId,
+        records: session?.strings,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("获取 Session 翻译进度失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 检查当前编辑中的译文是否超出字节长度上限
+   *
+   * @param sessionId - Session ID
+   * @returns 超限的记录（含字节数与对应上限）
+   */
+  validateLengths: async (sessionId: string): Promise<LengthViolation[]> => {
+    const session = get().openedSessions.get(sessionId);
+
+    try {
+      return await invoke<LengthViolation[]>("validate_lengths", {
+        sessionId,
+        records: session?.strings,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("检查译文长度失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 查找 Session 记录在翻译数据库中仅首尾空白与原文不同的译文
+   *
+   * @param sessionId - Session ID
+   * @returns 进度统计中视为未翻译的译文记录
+   */
+  findWhitespaceOnlyTranslations: async (sessionId: string): Promise<Translation[]> => {
+    try {
+      return await invoke<Translation[]>("find_whitespace_only_translations", { sessionId });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("查找仅空白差异的译文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 插件更新导致 FormID 重新编号时，按 EditorID + 原文 重新匹配翻译数据库中的孤立译文
+   *
+   * @param sessionId - Session ID
+   * @param apply - 为 true 时改写唯一匹配的译文 form_id，否则仅预览
+   * @returns 匹配/无法确定/未匹配的译文
+   */
+  remapByEditorId: async (sessionId: string, apply?: boolean): Promise<EditorIdRemapReport> => {
+    try {
+      return await invoke<EditorIdRemapReport>("remap_by_editor_id", { sessionId, apply });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("按 EditorID 重新匹配译文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 将多个插件加载为一个合并 Session
+   *
+   * @param pluginPaths - 插件完整路径（插件名须互不相同）
+   * @returns 按插件顺序拼接的记录，每条带 source_plugin
+   */
+  loadCombinedSession: async (pluginPaths: string[]): Promise<CombinedStringsResponse> => {
+    try {
+      return await invoke<CombinedStringsResponse>("load_combined_session", { pluginPaths });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("加载合并 Session 失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 将合并 Session 的翻译按来源插件分别写回
+   *
+   * @param sessionId - 合并 Session ID
+   * @param translations - 带 source_plugin 的记录
+   * @returns 每个写回插件的结果
+   */
+  applyCombinedTranslations: async (
+    sessionId: string,
+    translations: StringRecord[],
+    verify?: boolean,
+    onlyChanged?: boolean,
+  ): Promise<CombinedApplyEntry[]> => {
+    try {
+      return await invoke<CombinedApplyEntry[]>("apply_combined_translations", {
+        sessionId,
+        translations,
+        verify,
+        onlyChanged,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("写回合并 Session 失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 按覆盖数据库中每条记录的最终文本生成填充候选
+   *
+   * @param sessionId - Session ID
+   * @returns 最终文本来自其他插件且与当前译文不同的记录
+   */
+  getCoverageFillCandidates: async (sessionId: string): Promise<CoverageFillCandidate[]> => {
+    try {
+      return await invoke<CoverageFillCandidate[]>("get_coverage_fill_candidates", { sessionId });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("获取覆盖填充候选失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 按原文从翻译数据库填充未翻译的记录
+   *
+   * 同一原文有多个插件的译文时按设置中的来源优先级选择
+   *
+   * @param sessionId - Session ID
+   * @returns 实际填充的记录数
+   */
+  autoFillByText: async (sessionId: string): Promise<number> => {
+    const session = get().openedSessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      const candidates = await invoke<TextFillCandidate[]>("get_text_fill_candidates", {
+        sessionId,
+      });
+
+      // 只填充前端中仍未翻译的记录（后端 Session 可能不含尚未保存的编辑）
+      const untranslated = new Set(
+        session.strings
+          .filter((r) => (r.translation_status ?? "untranslated") === "untranslated")
+          .map((r) => `${r.form_id}|${r.record_type}|${r.subrecord_type}|${r.index}`),
+      );
+      const updates = candidates
+        .filter((c) =>
+          untranslated.has(`${c.form_id}|${c.record_type}|${c.subrecord_type}|${c.index}`),
+        )
+        .map((c) => ({
+          formId: c.form_id,
+          recordType: c.record_type,
+          subrecordType: c.subrecord_type,
+          index: c.index,
+          translatedText: c.translated_text,
+          translationStatus: "manual",
+        }));
+
+      if (updates.length > 0) {
+        get().batchUpdateStringRecords(sessionId, updates, `Auto-fill by text (${updates.length})`);
+      }
+      return updates.length;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("按原文自动填充失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 获取插件依赖的主文件列表
+   *
+   * @param pluginPath - 插件完整路径
+   * @returns TES4 MAST 中声明的主文件名（按声明顺序）
+   */
+  getPluginMasters: async (pluginPath: string): Promise<string[]> => {
+    try {
+      return await invoke<string[]>("get_plugin_masters", { pluginPath });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("读取主文件列表失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 计算插件文件的校验信息（用于判断 Session 是否因插件更新而过期）
+   *
+   * @param pluginPath - 插件完整路径
+   * @param fullHash - 是否额外计算整个文件的内容哈希（大文件较慢）
+   */
+  computePluginChecksum: async (
+    pluginPath: string,
+    fullHash?: boolean,
+  ): Promise<PluginChecksum> => {
+    try {
+      return await invoke<PluginChecksum>("compute_plugin_checksum", { pluginPath, fullHash });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("计算插件校验值失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 导出为松散 STRINGS 文件（STRINGS/ILSTRINGS/DLSTRINGS）
+   *
+   * @param sessionId - Session ID
+   * @param language - 目标语言（默认 chinese）
+   * @returns 写入的文件路径列表
+   */
+  exportStringsFiles: async (
+    sessionId: string,
+    language?: string,
+  ): Promise<string[]> => {
+    const { openedSessions } = get();
+    const session = openedSessions.get(sessionId);
+
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      const paths = await invoke<string[]>("export_strings_files", {
+        sessionId,
+        records: session.strings,
+        language,
+      });
+
+      console.log(`✓ STRINGS 已导出: ${paths.join(", ")}`);
+      return paths;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("导出 STRINGS 失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 导出仍需翻译的记录（未翻译或译文与原文相同）供外部翻译
+   *
+   * @param sessionId - Session ID
+   * @param path - 导出文件路径
+   * @param format - 文件格式（默认 csv）
+   * @param encoding - 文件编码（默认 CSV 为 utf8-bom，TSV 为 utf8）
+   * @returns 导出条数
+   */
+  exportUntranslated: async (
+    sessionId: string,
+    path: string,
+    format?: ExchangeFormat,
+    encoding?: ExchangeEncoding,
+  ): Promise<number> => {
+    const { openedSessions } = get();
+    const session = openedSessions.get(sessionId);
+
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    try {
+      return await invoke<number>("export_untranslated", {
+        sessionId,
+        records: session.strings,
+        path,
+        format,
+        encoding,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("导出未翻译记录失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 将选中的未翻译记录的译文设为原文（记入历史，可撤销）
+   *
+   * 以前端当前状态为准，只提交仍为 untranslated 的记录，避免覆盖尚未写回后端的译文
+   *
+   * @param sessionId - Session ID
+   * @param keys - 选中记录的 key
+   * @returns 修改条数
+   */
+  copySourceToTarget: async (sessionId: string, keys: FormIdentifier[]): Promise<number> => {
+    const session = get().openedSessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session 不存在: ${sessionId}`);
+    }
+
+    const untranslated = new Set(
+      session.strings
+        .filter((r) => (r.translation_status ?? "untranslated") === "untranslated")
+        .map((r) => `${r.form_id}|${r.record_type}|${r.subrecord_type}|${r.index}`),
+    );
+    const pendingKeys = keys.filter((k) =>
+      untranslated.has(`${k.form_id}|${k.record_type}|${k.subrecord_type}|${k.index}`),
+    );
+
+    try {
+      const records = await invoke<StringRecord[]>("session_copy_source_to_target", {
+        sessionId,
+        keys: pendingKeys,
+      });
+      get().batchUpdateStringRecords(
+        sessionId,
+        records.map((r) => ({
+          formId: r.form_id,
+          recordType: r.record_type,
+          subrecordType: r.subrecord_type,
+          index: r.index,
+          translatedText: r.translated_text,
+          translationStatus: r.translation_status,
+        })),
+        `Copy source to target (${records.length})`,
+      );
+      return records.length;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("复制原文到译文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 清除选中记录的译文，恢复为原文（记入历史，可撤销）
+   *
+   * @param sessionId - Session ID
+   * @param keys - 选中记录的 key
+   * @returns 修改条数
+   */
+  clearTarget: async (sessionId: string, keys: FormIdentifier[]): Promise<number> => {
+    try {
+      const records = await invoke<StringRecord[]>("session_clear_target", { sessionId, keys });
+      get().batchUpdateStringRecords(
+        sessionId,
+        records.map((r) => ({
+          formId: r.form_id,
+          recordType: r.record_type,
+          subrecordType: r.subrecord_type,
+          index: r.index,
+          translatedText: r.translated_text,
+          translationStatus: r.translation_status,
+        })),
+        `Clear target (${records.length})`,
+      );
+      return records.length;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("清空译文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
+  /**
+   * 导入外部翻译文件，按 key 填回译文（记入历史，可撤销）
+   *
+   * @param sessionId - Session ID
+   * @param path - 导入文件路径
+   * @param format - 文件格式（默认 csv）
+   * @param encoding - 文件编码（默认自动识别）
+   * @returns 填入译文的记录与未匹配的 key
+   */
+  importUntranslated: async (
+    sessionId: string,
+    path: string,
+    format?: ExchangeFormat,
+    encoding?: ExchangeEncoding,
+  ): Promise<ExchangeImportResult> => {
+    try {
+      const result = await invoke<ExchangeImportResult>("import_untranslated", {
+        sessionId,
+        path,
+        format,
+        encoding,
+      });
+
+      get().batchUpdateStringRecords(
+        sessionId,
+        result.records.map((r) => ({
+          formId: r.form_id,
+          recordType: r.record_type,
+          subrecordType: r.subrecord_type,
+          index: r.index,
+          translatedText: r.translated_text,
+          translationStatus: r.translation_status,
+        })),
+        `Import ${result.records.length} translations`,
+      );
+
+      if (result.unmatched_keys.length > 0) {
+        console.warn(`⚠ ${result.unmatched_keys.length} 条译文未匹配到记录`);
+      }
+      return result;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      console.error("导入外部译文失败:", errorMsg);
+      throw new Error(errorMsg);
+    }
+  },
+
   /**
    * 设置筛选状态
    *
@@ -1033,3 +1702,55 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     });
   },
 }));
+
+// 将各 Session 的未保存状态同步到后端（用于退出前提示未保存的 Session）
+useSessionStore.subscribe((state, prevState) => {
+  if (state.pendingChanges === prevState.pendingChanges) return;
+
+  const current = state.pendingChanges ?? new Map<string, Set<string>>();
+  const previous = prevState.pendingChanges ?? new Map<string, Set<string>>();
+  const sessionIds = new Set([...current.keys(), ...previous.keys()]);
+  for (const sessionId of sessionIds) {
+    const dirty = (current.get(sessionId)?.size ?? 0) > 0;
+    const wasDirty = (previous.get(sessionId)?.size ?? 0) > 0;
+    if (dirty !== wasDirty && state.openedSessions.has(sessionId)) {
+      invoke("mark_session_dirty", { sessionId, dirty }).catch((error) => {
+        console.error("同步未保存状态失败:", error);
+      });
+    }
+  }
+});
+
+/**
+ * 插件从备份恢复后重新加载 Session（后端已移除缓存），并清空未保存修改与撤销历史
+ */
+async function reloadRestoredSession(sessionId: string, pluginPath: string): Promise<void> {
+  const response = await invoke<PluginStringsResponse>("load_plugin_session", {
+    pluginPath,
+  });
+
+  useSessionStore.setState((state) => {
+    const newSessions = new Map(state.openedSessions);
+    newSessions.set(response.session_id, response);
+
+    const newPendingChanges = new Map(state.pendingChanges);
+    newPendingChanges.delete(sessionId);
+
+    const newSelectedRows = new Map(state.selectedRows);
+    newSelectedRows.delete(sessionId);
+
+    return {
+      openedSessions: newSessions,
+      pendingChanges: newPendingChanges,
+      selectedRows: newSelectedRows,
+    };
+  });
+  useHistoryStore.getState().clearSession(sessionId);
+
+  useSessionStore
+    .getState()
+    .refreshTranslations(response.session_id)
+    .catch((err) => {
+      console.error("自动刷新翻译失败:", err);
+    });
+}
@@ -5,7 +5,19 @@ import type {
   FormIdentifier,
   TranslationStats,
   ExtractionStats,
+  StatisticsFormat,
+  CoverageEstimate,
+  PluginRenameResult,
+  StringsTable,
+  SearchField,
+  SearchHit,
+  TypeCount,
+  TranslationReplaceOptions,
+  TranslationReplaceResult,
+  TranslationExportFormat,
+  TranslationExportSummary,
 } from '../types';
+import { getErrorMessage } from '../utils/commandError';
 
 /**
  * 翻译状态管理
@@ -22,13 +34,18 @@ interface TranslationState {
 
   /**
    * 保存单条翻译
+   *
+   * forceUpdateOriginal 为 true 时同时覆盖已有记录的原文（仅用于修正错误提取）
    */
-  saveTranslation: (translation: Translation) => Promise<void>;
+  saveTranslation: (translation: Translation, forceUpdateOriginal?: boolean) => Promise<void>;
 
   /**
    * 批量保存翻译
    */
-  batchSaveTranslations: (translations: Translation[]) => Promise<void>;
+  batchSaveTranslations: (
+    translations: Translation[],
+    forceUpdateOriginal?: boolean
+  ) => Promise<void>;
 
   /**
    * 查询单条翻译
@@ -50,11 +67,95 @@ interface TranslationState {
    */
   loadStatistics: () => Promise<void>;
 
+  /**
+   * 获取翻译数据库中出现的记录类型/子记录类型组合（按条目数降序）
+   */
+  getDistinctTypes: () => Promise<TypeCount[]>;
+
+  /**
+   * 导出统计报告（JSON 或 Markdown）
+   */
+  exportStatistics: (path: string, format: StatisticsFormat) => Promise<string>;
+
+  /**
+   * 设置翻译备注（传入 null 或空字符串清除），返回记录是否存在
+   */
+  setTranslationNote: (key: FormIdentifier, note: string | null) => Promise<boolean>;
+
+  /**
+   * 为翻译记录添加标签，返回是否新增
+   */
+  addTranslationTag: (key: FormIdentifier, tag: string) => Promise<boolean>;
+
+  /**
+   * 移除翻译记录的标签，返回是否删除
+   */
+  removeTranslationTag: (key: FormIdentifier, tag: string) => Promise<boolean>;
+
+  /**
+   * 列出翻译记录（可按插件、标签与 STRINGS 表筛选）
+   */
+  listTranslations: (
+    limit: number,
+    pluginName?: string,
+    tag?: string,
+    stringsTable?: StringsTable,
+  ) => Promise<Translation[]>;
+
+  /**
+   * 在原文与译文中全局搜索（返回命中字段与上下文片段）
+   */
+  searchAll: (query: string, limit: number, fields?: SearchField[]) => Promise<SearchHit[]>;
+
+  /**
+   * 将翻译数据库导出为 NDJSON（每行一条，可按插件过滤），返回导出条数
+   */
+  exportTranslationsNdjson: (path: string, pluginName?: string) => Promise<number>;
+
+  /**
+   * 导出翻译（sinceTs 存在时仅导出 updated_at >= sinceTs 的记录，用于增量备份）
+   */
+  exportTranslations: (
+    path: string,
+    format?: TranslationExportFormat,
+    sinceTs?: number,
+    pluginName?: string
+  ) => Promise<TranslationExportSummary>;
+
+  /**
+   * 从 NDJSON 文件导入翻译（UPSERT），返回导入条数
+   */
+  importTranslationsNdjson: (path: string) => Promise<number>;
+
+  /**
+   * 估算磁盘上插件的翻译完成度（不创建 Session）
+   */
+  estimateCoverage: (pluginPath: string) => Promise<CoverageEstimate>;
+
   /**
    * 清除指定插件的翻译
    */
   clearPluginTranslations: (pluginName: string) => Promise<void>;
 
+  /**
+   * 重命名插件的所有翻译记录（可选同时改写 form_id 中的插件部分）
+   */
+  renamePluginTranslations: (
+    oldName: string,
+    newName: string,
+    rewriteFormIds?: boolean
+  ) => Promise<PluginRenameResult>;
+
+  /**
+   * 在译文中查找替换（dryRun 为 true 时仅返回替换前后内容，不写入）
+   */
+  replaceInTranslations: (
+    pattern: string,
+    replacement: string,
+    dryRun: boolean,
+    options?: TranslationReplaceOptions
+  ) => Promise<TranslationReplaceResult>;
+
   /**
    * 清除所有翻译（慎用）
    */
@@ -73,7 +174,12 @@ interface TranslationState {
   /**
    * 从游戏 Data 目录提取基础字典
    */
-  extractDictionary: (dataDir: string) => Promise<ExtractionStats>;
+  extractDictionary: (dataDir: string, recordTypeFilter?: string[]) => Promise<ExtractionStats>;
+
+  /**
+   * 取消正在进行的基础字典提取（当前插件处理完后停止）
+   */
+  cancelDictionaryExtraction: () => Promise<void>;
 
   /**
    * 设置错误信息
@@ -91,14 +197,17 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
 
   // ==================== Actions ====================
 
-  saveTranslation: async (translation: Translation) => {
+  saveTranslation: async (translation: Translation, forceUpdateOriginal?: boolean) => {
     set({ isLoading: true, error: null });
     try {
-      await invoke('save_translation', { translation });
+      await invoke('save_translation', {
+        translation,
+        forceUpdateOriginal: forceUpdateOriginal ?? false,
+      });
       // 保存后刷新统计
       await get().loadStatistics();
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -106,14 +215,17 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
     }
   },
 
-  batchSaveTranslations: async (translations: Translation[]) => {
+  batchSaveTranslations: async (translations: Translation[], forceUpdateOriginal?: boolean) => {
     set({ isLoading: true, error: null });
     try {
-      await invoke('batch_save_translations', { translations });
+      await invoke('batch_save_translations', {
+        translations,
+        forceUpdateOriginal: forceUpdateOriginal ?? false,
+      });
       // 保存后刷新统计
       await get().loadStatistics();
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -132,7 +244,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       });
       return result;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -148,7 +260,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       });
       return translations;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -162,7 +274,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       const stats = await invoke<TranslationStats>('get_translation_statistics');
       set({ stats });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -170,6 +282,138 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
     }
   },
 
+  getDistinctTypes: async () => {
+    try {
+      return await invoke<TypeCount[]>('get_distinct_types');
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  exportStatistics: async (path: string, format: StatisticsFormat) => {
+    try {
+      return await invoke<string>('export_statistics', { path, format });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  setTranslationNote: async (key: FormIdentifier, note: string | null) => {
+    try {
+      return await invoke<boolean>('set_translation_note', { key, note });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  addTranslationTag: async (key: FormIdentifier, tag: string) => {
+    try {
+      return await invoke<boolean>('add_translation_tag', { key, tag });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  removeTranslationTag: async (key: FormIdentifier, tag: string) => {
+    try {
+      return await invoke<boolean>('remove_translation_tag', { key, tag });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  listTranslations: async (
+    limit: number,
+    pluginName?: string,
+    tag?: string,
+    stringsTable?: StringsTable,
+  ) => {
+    try {
+      return await invoke<Translation[]>('list_translations', {
+        limit,
+        pluginName,
+        tag,
+        stringsTable,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  searchAll: async (query: string, limit: number, fields?: SearchField[]) => {
+    try {
+      return await invoke<SearchHit[]>('search_all', { query, fields, limit });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  exportTranslationsNdjson: async (path: string, pluginName?: string) => {
+    try {
+      return await invoke<number>('export_translations_ndjson', { path, pluginName });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  exportTranslations: async (
+    path: string,
+    format?: TranslationExportFormat,
+    sinceTs?: number,
+    pluginName?: string
+  ) => {
+    try {
+      return await invoke<TranslationExportSummary>('export_translations', {
+        path,
+        format,
+        sinceTs,
+        pluginName,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  importTranslationsNdjson: async (path: string) => {
+    try {
+      const imported = await invoke<number>('import_translations_ndjson', { path });
+      await get().loadStatistics();
+      return imported;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
+  estimateCoverage: async (pluginPath: string) => {
+    try {
+      return await invoke<CoverageEstimate>('estimate_coverage', { pluginPath });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
   clearPluginTranslations: async (pluginName: string) => {
     set({ isLoading: true, error: null });
     try {
@@ -177,7 +421,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       // 清除后刷新统计
       await get().loadStatistics();
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -185,6 +429,48 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
     }
   },
 
+  renamePluginTranslations: async (oldName: string, newName: string, rewriteFormIds?: boolean) => {
+    set({ isLoading: true, error: null });
+    try {
+      const result = await invoke<PluginRenameResult>('rename_plugin_translations', {
+        oldName,
+        newName,
+        rewriteFormIds: rewriteFormIds ?? false,
+      });
+      // 重命名后刷新统计
+      await get().loadStatistics();
+      return result;
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    } finally {
+      set({ isLoading: false });
+    }
+  },
+
+  replaceInTranslations: async (
+    pattern: string,
+    replacement: string,
+    dryRun: boolean,
+    options?: TranslationReplaceOptions
+  ) => {
+    try {
+      return await invoke<TranslationReplaceResult>('replace_in_translations', {
+        pattern,
+        replacement,
+        dryRun,
+        useRegex: options?.useRegex,
+        caseSensitive: options?.caseSensitive,
+        pluginName: options?.pluginName,
+      });
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+      throw error;
+    }
+  },
+
   clearAllTranslations: async () => {
     set({ isLoading: true, error: null });
     try {
@@ -192,7 +478,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       // 清除后刷新统计
       set({ stats: null });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -208,7 +494,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       set({ stats: null });
       return deletedCount;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -222,7 +508,7 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
       const plugins = await invoke<string[]>('get_base_plugins_list');
       return plugins;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -230,17 +516,18 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
     }
   },
 
-  extractDictionary: async (dataDir: string) => {
+  extractDictionary: async (dataDir: string, recordTypeFilter?: string[]) => {
     set({ isLoading: true, error: null });
     try {
       const stats = await invoke<ExtractionStats>('extract_dictionary', {
         dataDir,
+        recordTypeFilter,
       });
       // 提取后刷新翻译统计
       await get().loadStatistics();
       return stats;
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = getErrorMessage(error);
       set({ error: errorMsg });
       throw error;
     } finally {
@@ -248,6 +535,15 @@ export const useTranslationStore = create<TranslationState>((set, get) => ({
     }
   },
 
+  cancelDictionaryExtraction: async () => {
+    try {
+      await invoke('cancel_dictionary_extraction');
+    } catch (error) {
+      const errorMsg = getErrorMessage(error);
+      set({ error: errorMsg });
+    }
+  },
+
   setError: (error: string | null) => {
     set({ error });
   },
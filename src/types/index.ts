@@ -1,3 +1,5 @@
+import type { AtomSourceType } from "../utils/aiTools";
+
 /**
  * 应用配置
  */
@@ -6,6 +8,219 @@ export interface Settings {
   game: string | null;
   /** DSD 导出目录（可选，如果未设置则使用源文件所在目录） */
   dsd_output_dir?: string | null;
+  /** DSD 文件路径模板（占位符 {base}/{plugin}/{stem}，未设置时使用 DSD 默认目录约定） */
+  dsd_path_template?: string | null;
+  /** Data 目录（可选，如果未设置则从游戏路径中检测） */
+  data_dir?: string | null;
+  /** 写回插件时的临时文件目录（可选，如果未设置则写在目标文件旁） */
+  temp_dir?: string | null;
+  /** 写回时转换记录的线程数（可选，如果未设置则使用全局线程池） */
+  apply_threads?: number | null;
+  /** 只读模式：禁止写回游戏目录与 Data 目录内的文件 */
+  read_only_data?: boolean;
+  /** 原子词匹配器配置 */
+  atom_matcher?: AtomMatcherOptions;
+  /** 原子词使用计数写入遇到数据库锁定时的重试次数（可选，如果未设置则使用默认值） */
+  usage_write_retries?: number | null;
+  /** userdata 目录（可选，环境变量 SSE_TRANSLATOR_DATA_DIR 优先，重启后生效） */
+  userdata_dir?: string | null;
+  /** 译文字节长度上限（可选，如果未设置则使用内置的引擎硬限制） */
+  length_limits?: LengthLimit[] | null;
+  /** 覆盖提取的插件过滤 */
+  coverage_filter?: CoverageFilter;
+  /** 按原文自动填充时的译文来源优先级（可选，如果未设置则为 同插件 > 基础字典 > 任意插件） */
+  translation_source_priority?: TranslationSource[] | null;
+}
+
+/**
+ * 按原文自动填充时的译文来源
+ * - same_plugin：与当前 Session 同一插件的译文
+ * - base_dictionary：基础字典（官方主文件与 CC 内容）
+ * - any：任意插件的译文
+ */
+export type TranslationSource = 'same_plugin' | 'base_dictionary' | 'any';
+
+/**
+ * 覆盖提取的插件过滤
+ * - exclude：跳过匹配的插件（默认）
+ * - include_only：仅提取匹配的插件
+ *
+ * patterns 为插件名通配符（* 任意字符，? 单个字符，忽略大小写），如 "*Patch*.esp"
+ */
+export interface CoverageFilter {
+  mode: 'exclude' | 'include_only';
+  patterns: string[];
+}
+
+/**
+ * 译文字节长度上限（按 UTF-8 字节计算，中文每字 3 字节）
+ */
+export interface LengthLimit {
+  /** 记录类型，"*" 匹配所有类型 */
+  record_type: string;
+  /** 子记录类型，null 匹配所有子记录 */
+  subrecord_type?: string | null;
+  max_bytes: number;
+}
+
+/**
+ * 后端命令错误类别（稳定代码，可用于分支处理与本地化）
+ */
+export type CommandErrorCode =
+  | "not_configured"
+  | "not_found"
+  | "invalid_input"
+  | "busy"
+  | "db_error"
+  | "io_error"
+  | "parse"
+  | "internal";
+
+/**
+ * 后端命令返回的结构化错误
+ */
+export interface CommandError {
+  code: CommandErrorCode;
+  /** 可直接展示的中文说明 */
+  message: string;
+}
+
+/**
+ * 原子词匹配语义
+ * - leftmost_longest：同一起点优先最长词（默认）
+ * - standard：较短的词先结束即命中
+ */
+export type AtomMatchKind = "leftmost_longest" | "standard";
+
+/**
+ * 原子词自动机实现（内存/速度权衡）
+ * - auto：按词表大小自动选择（默认）
+ * - noncontiguous_nfa：内存最小，匹配最慢
+ * - contiguous_nfa：内存与速度居中
+ * - dfa：匹配最快，大词表下内存可达 NFA 的数十倍
+ */
+export type AtomAutomatonKind = "auto" | "noncontiguous_nfa" | "contiguous_nfa" | "dfa";
+
+/**
+ * 原子词匹配器配置
+ */
+export interface AtomMatcherOptions {
+  match_kind: AtomMatchKind;
+  /** 大小写敏感（原子词以小写存储，开启后仅匹配小写形式） */
+  case_sensitive: boolean;
+  /** 自动机实现，缺省为 auto */
+  automaton_kind?: AtomAutomatonKind;
+  /** 是否启用前置过滤，缺省为 true */
+  prefilter?: boolean;
+}
+
+/**
+ * 待导入的原子词条目
+ */
+export interface AtomImportEntry {
+  original: string;
+  translated: string;
+}
+
+/**
+ * 原子词导入冲突（库中已有且译文不同）
+ */
+export interface AtomImportConflict {
+  /** 原文（小写形式） */
+  original: string;
+  existing: string;
+  incoming: string;
+  existing_source: AtomSourceType;
+}
+
+/**
+ * 导入冲突处理策略：保留原译文 / 覆盖 / 跳过
+ */
+export type AtomConflictStrategy = "keep" | "overwrite" | "skip";
+
+/**
+ * 原子词导入结果统计
+ */
+export interface AtomImportResult {
+  inserted: number;
+  overwritten: number;
+  kept: number;
+  skipped: number;
+  /** 与库中译文相同、无需写入的条目数 */
+  unchanged: number;
+  /** 导入文件内原文重复的条目（仅最后一条参与导入） */
+  duplicates: AtomBatchDuplicate[];
+}
+
+/**
+ * 同一批次内原文重复的条目（最后一条生效）
+ */
+export interface AtomBatchDuplicate {
+  /** 原文（小写形式） */
+  original: string;
+  /** 最终写入的译文 */
+  kept: string;
+  /** 被后续条目覆盖的译文（按出现顺序） */
+  discarded: string[];
+}
+
+/**
+ * 单个数据库文件的诊断信息
+ */
+export interface DatabaseDiagnostics {
+  /** 数据库名称 */
+  name: string;
+  path: string;
+  /** 主文件大小（字节，文件不存在时为 null） */
+  size_bytes: number | null;
+  /** WAL 文件大小（字节，无 WAL 文件时为 null） */
+  wal_size_bytes: number | null;
+  /** 主表行数（读取失败时为 null） */
+  row_count: number | null;
+  /** 最后修改时间（Unix 秒） */
+  modified_at: number | null;
+  error: string | null;
+}
+
+/**
+ * 安装状态诊断汇总
+ */
+export interface Diagnostics {
+  userdata_dir: string;
+  databases: DatabaseDiagnostics[];
+  /** 原子词使用计数写入统计 */
+  atom_usage_writes: UsageWriteStats;
+}
+
+/**
+ * 原子词使用计数写入统计（进程启动以来）
+ */
+export interface UsageWriteStats {
+  /** 成功写入的次数 */
+  flushes: number;
+  /** 因数据库锁定而重试的次数 */
+  retries: number;
+  /** 重试耗尽后仍失败的次数（计数放回队列等待下次写入） */
+  failures: number;
+  /** 最近一次失败的错误信息 */
+  last_error: string | null;
+}
+
+/**
+ * 单个数据库的完整性检查结果
+ */
+export interface IntegrityReport {
+  /** 数据库标识（用于 rebuild_from_backup） */
+  database: string;
+  name: string;
+  path: string;
+  ok: boolean;
+  /** PRAGMA integrity_check 发现的问题 */
+  problems: string[];
+  /** 可用备份文件名（新的在前） */
+  backups: string[];
+  /** 损坏时的处理建议 */
+  suggestion: string | null;
 }
 
 /**
@@ -18,6 +233,41 @@ export interface PluginInfo {
   path: string;
 }
 
+/**
+ * 插件列表排序方式
+ * - load_order：按 loadorder.txt 顺序（默认）
+ * - alphabetical：严格按文件名字母顺序
+ * - mtime：按文件修改时间从旧到新
+ */
+export type PluginOrderMode = "load_order" | "alphabetical" | "mtime";
+
+/**
+ * 加载顺序来源
+ * - load_order_file：来自 loadorder.txt
+ * - plugins_file：未找到 loadorder.txt，来自 plugins.txt（仅含已启用的插件）
+ * - alphabetical：两者均未找到，按字母顺序排列
+ */
+export type LoadOrderSource = "load_order_file" | "plugins_file" | "alphabetical";
+
+/**
+ * 已解析加载顺序中的单个插件
+ */
+export interface ResolvedPlugin {
+  position: number;
+  name: string;
+  path: string;
+  /** 是否列在加载顺序文件（loadorder.txt 或 plugins.txt）中 */
+  in_load_order_file: boolean;
+}
+
+/**
+ * 当前解析出的加载顺序
+ */
+export interface ResolvedLoadOrder {
+  source: LoadOrderSource;
+  plugins: ResolvedPlugin[];
+}
+
 /**
  * 翻译记录
  */
@@ -42,6 +292,33 @@ export interface Translation {
   created_at: number;
   /** 更新时间戳 */
   updated_at: number;
+  /** 译者备注 */
+  note?: string | null;
+  /** 所在的 STRINGS 表（由记录类型推导） */
+  strings_table?: StringsTable;
+}
+
+/**
+ * 字符串所在的 STRINGS 表（ILSTRINGS: 对话；DLSTRINGS: 描述、书籍等长文本）
+ */
+export type StringsTable = 'STRINGS' | 'ILSTRINGS' | 'DLSTRINGS';
+
+/**
+ * 全局搜索的字段
+ */
+export type SearchField = 'original' | 'translated';
+
+/**
+ * 全局搜索结果
+ */
+export interface SearchHit {
+  translation: Translation;
+  /** 命中的字段 */
+  matched_field: SearchField;
+  /** 命中位置附近的上下文片段 */
+  snippet: string;
+  /** 命中文本在 snippet 中的字符区间 [start, end) */
+  highlight: [number, number];
 }
 
 /**
@@ -66,6 +343,17 @@ export interface PluginCount {
   plugin_name: string;
   /** 翻译数量 */
   count: number;
+  /** 该插件最后更新时间戳 */
+  last_updated: number;
+}
+
+/**
+ * 记录类型/子记录类型组合及其条目数（用于动态生成过滤选项）
+ */
+export interface TypeCount {
+  record_type: string;
+  subrecord_type: string;
+  count: number;
 }
 
 /**
@@ -74,12 +362,98 @@ export interface PluginCount {
 export interface TranslationStats {
   /** 总翻译数量 */
   total_count: number;
+  /** 去除首尾空白后译文仍与原文相同的记录数（视为未翻译） */
+  untranslated_count: number;
   /** 按插件分组的统计 */
   plugin_counts: PluginCount[];
   /** 最后更新时间戳 */
   last_updated: number;
 }
 
+/**
+ * 翻译导出格式
+ */
+export type TranslationExportFormat = "ndjson" | "json";
+
+/**
+ * 翻译导出结果
+ */
+export interface TranslationExportSummary {
+  /** 导出条数 */
+  exported: number;
+  /** 导出记录中最大的 updated_at（无记录时为 null），可作为下次增量导出的 sinceTs */
+  max_updated_at: number | null;
+}
+
+/**
+ * 插件重命名结果
+ */
+export interface PluginRenameResult {
+  /** 更新了 plugin_name 的记录数 */
+  renamed: number;
+  /** 改写了 form_id 插件部分的记录数 */
+  form_ids_rewritten: number;
+  /** 因与已有记录冲突而保留原 form_id 的记录数 */
+  form_id_conflicts: number;
+}
+
+/**
+ * 译文查找替换选项
+ */
+export interface TranslationReplaceOptions {
+  /** 按正则表达式匹配（替换文本可用 $1 引用捕获组），默认按字面量匹配 */
+  useRegex?: boolean;
+  /** 区分大小写（默认 true） */
+  caseSensitive?: boolean;
+  /** 仅处理该插件的记录 */
+  pluginName?: string;
+}
+
+/**
+ * 译文查找替换中命中的单条记录
+ */
+export interface TranslationReplacement {
+  form_id: string;
+  record_type: string;
+  subrecord_type: string;
+  index: number;
+  plugin_name: string | null;
+  before: string;
+  after: string;
+  /** 该记录中的匹配次数 */
+  matches: number;
+}
+
+/**
+ * 译文查找替换结果
+ */
+export interface TranslationReplaceResult {
+  /** 受影响的记录 */
+  rows: TranslationReplacement[];
+  /** 匹配总数 */
+  total_matches: number;
+  /** 是否已写入数据库（预览时为 false） */
+  applied: boolean;
+}
+
+/**
+ * 统计报告导出格式
+ */
+export type StatisticsFormat = "json" | "markdown";
+
+/**
+ * 插件翻译完成度估算结果
+ */
+export interface CoverageEstimate {
+  plugin_path: string;
+  /** 插件中的字符串记录数（按 key 去重） */
+  total: number;
+  /** 翻译数据库中已有译文的记录数 */
+  matched: number;
+  /** 完成度百分比（0-100） */
+  percentage: number;
+}
+
 /**
  * ESP 字典提取统计信息
  */
@@ -96,6 +470,24 @@ export interface ExtractionStats {
   skipped_files: string[];
   /** 错误信息列表 */
   errors: string[];
+  /** 中文版加载失败、仅提取英文的文件列表 */
+  english_only_files: string[];
+  /** 疑似编码错误（乱码）的记录总数 */
+  suspicious_encoding_count: number;
+  /** 含疑似乱码记录的文件及数量 */
+  encoding_warnings: string[];
+  /** 用户中途取消（已完成插件的记录仍会保存） */
+  cancelled: boolean;
+}
+
+/**
+ * 基础字典提取进度（dictionary_extraction_progress 事件）
+ */
+export interface ExtractionProgress {
+  current_plugin: string;
+  /** 从 1 开始的插件序号 */
+  current_progress: number;
+  total: number;
 }
 
 /**
@@ -123,6 +515,10 @@ export interface StringRecord {
   translated_text: string;
   /** 翻译状态 (用于标记来源和决定行颜色) */
   translation_status?: TranslationStatus;
+  /** 所在的 STRINGS 表（由记录类型推导） */
+  strings_table?: StringsTable;
+  /** 记录来源插件（仅合并 Session 中存在，写回时据此路由） */
+  source_plugin?: string;
 }
 
 /**
@@ -137,6 +533,230 @@ export interface DsdEntry {
   string: string;
 }
 
+/**
+ * 应用翻译到插件文件的结果
+ */
+export interface ApplyResult {
+  /** 保存的路径 */
+  saved_path: string;
+  /** 原文件备份路径（另存为时为 null） */
+  backup_path: string | null;
+  /** 回读校验的不一致记录数（未启用校验时为 null） */
+  mismatch_count: number | null;
+  /** 不一致记录的 key 示例 */
+  mismatched_keys: string[];
+  /** 实际写入的记录数 */
+  applied_count: number;
+}
+
+/**
+ * DSD 导出结果
+ */
+export interface DsdExportResult {
+  /** 写入的 DSD JSON 文件路径 */
+  path: string;
+  /** 新增的条目数（覆盖模式下为全部条目） */
+  added: number;
+  /** 合并模式下更新的已有条目数 */
+  updated: number;
+  /** 文件中的总条目数 */
+  total: number;
+  /** 本次写入的条目数（新增 + 更新） */
+  written: number;
+  /** 因校验失败被跳过的条目数 */
+  skipped: number;
+  /** 被跳过的条目及原因 */
+  skipped_reasons: DsdSkippedEntry[];
+  /** 同时写入的元数据文件路径（未开启时为 null） */
+  metadata_path: string | null;
+  /** 参与导出的记录数（记录类型过滤后） */
+  included: number;
+  /** 被记录类型过滤排除的记录数 */
+  filtered_out: number;
+}
+
+/**
+ * DSD 导出元数据（`<插件名>.meta`）
+ */
+export interface DsdMetadata {
+  generator: string;
+  version: string;
+  source_plugin: string;
+  /** 导出时间（RFC 3339） */
+  exported_at: string;
+  entry_count: number;
+}
+
+/**
+ * 未写入 DSD 文件的条目及原因
+ */
+export interface DsdSkippedEntry {
+  form_id: string;
+  /** DSD 类型字段（"记录类型 子记录类型"） */
+  entry_type: string;
+  reason: string;
+}
+
+/**
+ * 离线翻译交换文件格式
+ * - csv：key, editor_id, type, original, translation（导入读取 translation 列）
+ * - tsv：两列制表符分隔的 key 与文本（导入时第二列视为译文）
+ */
+export type ExchangeFormat = 'csv' | 'tsv';
+
+/**
+ * 交换文件编码
+ * - utf8-bom：带 BOM 的 UTF-8（CSV 导出默认）
+ * - utf8：无 BOM 的 UTF-8（TSV 导出默认）
+ * - gbk：兼容只能读取 GBK 的旧版工具（含 GBK 之外的字符时导出报错）
+ *
+ * 导入时未指定编码则自动识别（BOM / UTF-8 / GBK）
+ */
+export type ExchangeEncoding = 'utf8' | 'utf8-bom' | 'gbk';
+
+/**
+ * 离线翻译文件导入结果
+ */
+export interface ExchangeImportResult {
+  /** 填入译文（状态为 manual）的记录 */
+  records: StringRecord[];
+  /** 文件中未匹配到记录的 key */
+  unmatched_keys: string[];
+}
+
+/**
+ * 对话记录上下文（同一话题下的前后记录，文本取自 Session 加载时的内容）
+ */
+export interface RecordContext {
+  /** 对话组之前紧邻的话题（DIAL 记录文本） */
+  topic: string | null;
+  /** 对话组内的记录数 */
+  group_size: number;
+  /** 目标记录在组内的位置（从 0 开始） */
+  position: number;
+  /** 之前的记录（最接近目标的在最后） */
+  previous: StringRecord[];
+  next: StringRecord[];
+}
+
+/**
+ * 单个 Session 的翻译进度（与翻译数据库比对）
+ */
+export interface SessionProgress {
+  /** Session 记录总数 */
+  total: number;
+  /** 在翻译数据库中已有译文的记录数（仅首尾空白不同的译文不计入） */
+  tm_hits: number;
+  /** 译文仍与原文相同的记录数（忽略首尾空白） */
+  same_as_source: number;
+  /** 已有译文的百分比（0~100） */
+  percentage: number;
+}
+
+/**
+ * 按 EditorID 重新匹配到的记录（旧 form_id -> 新 form_id）
+ */
+export interface FormIdRemap {
+  old_form_id: string;
+  new_form_id: string;
+  editor_id: string;
+  record_type: string;
+  subrecord_type: string;
+  index: number;
+}
+
+/**
+ * 按 EditorID 重新匹配 form_id 的报告（仅处理 key 已不在 Session 中的译文）
+ */
+export interface EditorIdRemapReport {
+  /** 唯一匹配到新记录的译文 */
+  matched: FormIdRemap[];
+  /** 无法唯一确定对应记录的译文 key */
+  ambiguous: string[];
+  /** 找不到对应记录（或没有 EditorID）的译文 key */
+  unmatched: string[];
+  /** 实际改写的记录数（仅预览时为 0） */
+  applied: number;
+}
+
+/**
+ * 超出长度上限的译文
+ */
+export interface LengthViolation {
+  form_id: string;
+  record_type: string;
+  subrecord_type: string;
+  index: number;
+  translated_text: string;
+  /** 译文的 UTF-8 字节数 */
+  byte_len: number;
+  max_bytes: number;
+}
+
+/**
+ * 覆盖数据库填充候选（该记录在整个 load order 中最终生效的文本）
+ */
+export interface CoverageFillCandidate {
+  form_id: string;
+  record_type: string;
+  subrecord_type: string;
+  index: number;
+  /** Session 中的当前译文 */
+  current_text: string;
+  /** 覆盖数据库中的最终文本 */
+  coverage_text: string;
+  /** 最终覆盖该记录的插件 */
+  source_mod: string;
+  load_order_pos: number;
+}
+
+/**
+ * 按原文从翻译数据库匹配到的填充候选
+ */
+export interface TextFillCandidate {
+  form_id: string;
+  record_type: string;
+  subrecord_type: string;
+  index: number;
+  translated_text: string;
+  /** 译文所属插件 */
+  source_plugin?: string | null;
+  /** 命中的来源规则 */
+  source: TranslationSource;
+}
+
+/**
+ * DSD 导出预览（不写入文件）
+ */
+export interface DsdPreview {
+  /** 将要写入的 DSD JSON 文件路径 */
+  path: string;
+  /** 将要写入的 JSON 内容 */
+  json: string;
+  /** 目标文件的现有内容（不存在时为 null） */
+  existing_json: string | null;
+  added: number;
+  updated: number;
+  total: number;
+  written: number;
+  skipped: number;
+  skipped_reasons: DsdSkippedEntry[];
+}
+
+/**
+ * 已存在的 DSD 导出文件状态
+ */
+export interface DsdStatus {
+  /** DSD JSON 文件路径（按当前设置推导） */
+  path: string;
+  /** 文件是否已存在 */
+  exists: boolean;
+  /** 文件大小（字节） */
+  size: number | null;
+  /** 文件中的条目数（无法解析时为 null） */
+  entry_count: number | null;
+}
+
 /**
  * 加载插件返回的完整响应
  */
@@ -153,6 +773,74 @@ export interface PluginStringsResponse {
   total_count: number;
   /** 是否检测到并应用 DSD 覆盖 */
   has_dsd_overrides: boolean;
+  /** DSD 覆盖的套用情况（未检测到 DSD 文件时为 null） */
+  dsd_overrides: DsdOverrideSummary | null;
+  /** 插件内重复出现的记录 key（form_id|record_type|subrecord_type|index） */
+  duplicate_keys: string[];
+  /** 字符串缺失或数量可疑时的 BSA 诊断警告 */
+  bsa_warning: string | null;
+  /** 插件头信息（主文件列表、ESM/ESL 标志与记录总数） */
+  metadata: PluginMetadata;
+}
+
+/**
+ * 合并 Session 的成员插件
+ */
+export interface CombinedSessionMember {
+  session_id: string;
+  plugin_name: string;
+  plugin_path: string;
+  total_count: number;
+  duplicate_keys: string[];
+  bsa_warning: string | null;
+  metadata: PluginMetadata;
+}
+
+/**
+ * 加载合并 Session 返回的响应（strings 按成员顺序拼接，每条记录带 source_plugin）
+ */
+export interface CombinedStringsResponse {
+  /** 合并 Session ID（以 "combined#" 开头） */
+  session_id: string;
+  members: CombinedSessionMember[];
+  strings: StringRecord[];
+  total_count: number;
+}
+
+/**
+ * 合并 Session 中单个成员插件的写回结果
+ */
+export interface CombinedApplyEntry {
+  plugin_name: string;
+  result: ApplyResult;
+}
+
+/**
+ * 插件头信息
+ */
+export interface PluginMetadata {
+  /** 插件依赖的主文件（按声明顺序） */
+  masters: string[];
+  /** 是否为主文件（.esm 扩展名或设置了 ESM 标志） */
+  is_master: boolean;
+  /** 是否为轻量插件（.esl 扩展名或设置了 Light 标志） */
+  is_light: boolean;
+  /** 是否为本地化插件 */
+  is_localized: boolean;
+  /** 记录总数（不限于含字符串的记录） */
+  record_count: number;
+}
+
+/**
+ * DSD 覆盖套用统计
+ */
+export interface DsdOverrideSummary {
+  /** DSD 条目总数 */
+  total: number;
+  /** 实际改写了译文的记录数 */
+  applied: number;
+  /** 找不到对应记录的 DSD 条目数 */
+  unmatched: number;
 }
 
 /**
@@ -167,6 +855,46 @@ export interface SessionInfo {
   string_count: number;
   /** 加载时间（秒） */
   loaded_at: number;
+  /** 是否存在未保存的修改 */
+  dirty: boolean;
+  /** 插件文件在加载后已被修改（或已无法读取） */
+  stale?: boolean;
+}
+
+/**
+ * 编辑器参考查询结果（query_word_translations）
+ */
+export interface WordLookupResult {
+  translations: Translation[];
+  /** 实际用于查询的文本（输入过长时为从中选出的单词） */
+  query: string;
+  /** 输入是否过长而被缩短 */
+  truncated: boolean;
+}
+
+/**
+ * 插件写回前生成的备份文件（{插件文件名}.{时间戳}.bak）
+ */
+export interface PluginBackup {
+  path: string;
+  /** 文件名中的时间戳（格式 YYYY_MM_DD_HH_MM_SS，本地时间） */
+  timestamp: string;
+  size_bytes: number;
+  /** 备份文件的修改时间（Unix 秒） */
+  modified_at?: number | null;
+}
+
+/**
+ * 插件文件的校验信息
+ */
+export interface PluginChecksum {
+  size: number;
+  /** 最后修改时间（Unix 毫秒） */
+  modified_ms: number;
+  /** 由大小与修改时间组成的快速校验值 */
+  stat_token: string;
+  /** 整个文件的内容哈希（仅在请求时计算） */
+  content_hash?: string | null;
 }
 
 /**
@@ -181,6 +909,8 @@ export interface TranslationProgressPayload {
   total: number;
   /** 百分比 (0-100) */
   percentage: number;
+  /** 已在翻译数据库中匹配到的记录数 */
+  matched: number;
 }
 
 /**
@@ -191,8 +921,8 @@ export interface SessionState {
   openedSessions: Map<string, PluginStringsResponse>;
   /** 当前激活的 Session ID */
   activeSessionId: string | null;
-  /** 翻译刷新进度 (Map: session_id -> 百分比 0-100) */
-  translationProgress: Map<string, number>;
+  /** 翻译刷新进度 (Map: session_id -> 最新进度事件) */
+  translationProgress: Map<string, TranslationProgressPayload>;
   /** 未保存的修改 (Map: session_id -> Set<form_id>) */
   pendingChanges?: Map<string, Set<string>>;
   /** 筛选状态 (Map: session_id -> filter status) */
@@ -211,10 +941,14 @@ export interface SessionState {
   openSession: (pluginPath: string) => Promise<void>;
   /** 关闭插件 Session */
   closeSession: (sessionId: string) => Promise<void>;
+  /** 关闭所有 Session（可选先保存未保存的修改），返回关闭数量 */
+  closeAllSessions?: (saveDirty?: boolean) => Promise<number>;
   /** 切换激活的 Session */
   switchSession: (sessionId: string) => void;
-  /** 检查 Session 是否已存在 */
-  checkSessionExists: (pluginName: string) => boolean;
+  /** 检查插件路径对应的 Session 是否已存在 */
+  checkSessionExists: (pluginPath: string) => boolean;
+  /** 查找插件路径对应的 Session ID（未打开时为 null） */
+  findSessionIdByPath: (pluginPath: string) => string | null;
   /** 刷新Session的翻译（从数据库批量拉取） */
   refreshTranslations: (sessionId: string) => Promise<void>;
   /** 初始化Event监听器（返回清理函数） */
@@ -241,9 +975,84 @@ export interface SessionState {
   /** 保存单个 session 的翻译到数据库 */
   saveSessionTranslations?: (sessionId: string) => Promise<number>;
   /** 应用翻译到插件文件（生成新的 ESP 文件） */
-  applyTranslations?: (sessionId: string, saveAs?: string) => Promise<string>;
+  applyTranslations?: (
+    sessionId: string,
+    saveAs?: string,
+    verify?: boolean,
+    onlyChanged?: boolean,
+  ) => Promise<ApplyResult>;
+  /** 撤销最近一次写回（从备份恢复并重新加载 Session） */
+  undoLastApply?: (sessionId: string) => Promise<string>;
+  /** 列出插件写回时生成的全部备份（按时间从新到旧） */
+  listBackups?: (sessionId: string) => Promise<PluginBackup[]>;
+  /** 从指定备份恢复插件文件（重新加载 Session） */
+  restoreBackup?: (sessionId: string, backupPath: string) => Promise<string>;
   /** 导出 DSD (Dynamic String Distributor) 格式 */
-  exportDsd?: (sessionId: string) => Promise<string>;
+  exportDsd?: (
+    sessionId: string,
+    merge?: boolean,
+    writeMetadata?: boolean,
+    recordTypeFilter?: string[],
+  ) => Promise<DsdExportResult>;
+  /** 预览 DSD 导出内容（不写入文件） */
+  previewDsd?: (
+    sessionId: string,
+    merge?: boolean,
+    recordTypeFilter?: string[],
+  ) => Promise<DsdPreview>;
+  /** 查询是否已有 DSD 导出文件 */
+  getDsdStatus?: (sessionId: string) => Promise<DsdStatus>;
+  /** 获取插件依赖的主文件列表（按声明顺序，已打开的 Session 使用缓存） */
+  getPluginMasters?: (pluginPath: string) => Promise<string[]>;
+  /** 计算插件文件的校验信息（fullHash 为 true 时额外计算内容哈希） */
+  computePluginChecksum?: (pluginPath: string, fullHash?: boolean) => Promise<PluginChecksum>;
+  /** 将选中的未翻译记录的译文设为原文（状态改为 manual，可撤销），返回修改条数 */
+  copySourceToTarget?: (sessionId: string, keys: FormIdentifier[]) => Promise<number>;
+  /** 清除选中记录的译文（恢复为原文，状态改为 untranslated，可撤销），返回修改条数 */
+  clearTarget?: (sessionId: string, keys: FormIdentifier[]) => Promise<number>;
+  /** 获取对话记录的前后记录（非对话记录返回 null） */
+  getRecordContext?: (
+    sessionId: string,
+    key: FormIdentifier,
+    radius?: number,
+  ) => Promise<RecordContext | null>;
+  /** 获取单个 Session 当前编辑中记录的翻译进度（翻译数据库命中数、仍与原文相同的记录数） */
+  getSessionTranslationProgress?: (sessionId: string) => Promise<SessionProgress>;
+  /** 检查当前译文是否超出设置中的字节长度上限（写回前发现会被截断的译文） */
+  validateLengths?: (sessionId: string) => Promise<LengthViolation[]>;
+  /** 查找翻译数据库中仅首尾空白与原文不同的译文（便于清理） */
+  findWhitespaceOnlyTranslations?: (sessionId: string) => Promise<Translation[]>;
+  /** 按 EditorID + 原文 将孤立译文匹配到新的 form_id（apply 为 true 时写入翻译数据库） */
+  remapByEditorId?: (sessionId: string, apply?: boolean) => Promise<EditorIdRemapReport>;
+  /** 将多个插件加载为一个合并 Session（成员插件仍可单独打开） */
+  loadCombinedSession?: (pluginPaths: string[]) => Promise<CombinedStringsResponse>;
+  /** 将合并 Session 的翻译按 source_plugin 分别写回各插件 */
+  applyCombinedTranslations?: (
+    sessionId: string,
+    translations: StringRecord[],
+    verify?: boolean,
+    onlyChanged?: boolean,
+  ) => Promise<CombinedApplyEntry[]>;
+  /** 按覆盖数据库的最终文本生成填充候选（需先完成覆盖提取） */
+  getCoverageFillCandidates?: (sessionId: string) => Promise<CoverageFillCandidate[]>;
+  /** 按原文从翻译数据库填充未翻译的记录（按来源优先级选择译文），返回填充条数 */
+  autoFillByText?: (sessionId: string) => Promise<number>;
+  /** 导出为松散 STRINGS 文件 */
+  exportStringsFiles?: (sessionId: string, language?: string) => Promise<string[]>;
+  /** 导出仍需翻译的记录供外部翻译，返回导出条数 */
+  exportUntranslated?: (
+    sessionId: string,
+    path: string,
+    format?: ExchangeFormat,
+    encoding?: ExchangeEncoding,
+  ) => Promise<number>;
+  /** 导入外部翻译文件并按 key 填回译文（可撤销），返回导入结果 */
+  importUntranslated?: (
+    sessionId: string,
+    path: string,
+    format?: ExchangeFormat,
+    encoding?: ExchangeEncoding,
+  ) => Promise<ExchangeImportResult>;
   /** 设置错误信息 */
   setError: (error: string | null) => void;
   /** 设置筛选状态 */
@@ -289,8 +1098,14 @@ export interface AppState {
   gamePath: string | null;
   /** DSD 导出目录 */
   dsdOutputDir: string | null;
+  /** 写回转换线程数（null 表示使用全局线程池） */
+  applyThreads: number | null;
+  /** 只读模式（禁止写回游戏目录内的文件） */
+  readOnlyData: boolean;
   /** 插件列表 */
   plugins: PluginInfo[];
+  /** 插件列表排序方式 */
+  pluginOrderMode: PluginOrderMode;
   /** 加载状态 */
   isLoading: boolean;
   /** 错误信息 */
@@ -299,6 +1114,8 @@ export interface AppState {
   // Actions
   /** 加载配置 */
   loadSettings: () => Promise<void>;
+  /** 重新读取 settings.json 并应用到运行时状态（无需重启，会发送 settings-changed 事件） */
+  reloadSettings?: () => Promise<void>;
   /** 设置游戏路径 */
   setGamePath: (path: string) => Promise<void>;
   /** 清除游戏路径（重新选择工作区） */
@@ -307,8 +1124,40 @@ export interface AppState {
   setDsdOutputDir?: (path: string) => Promise<void>;
   /** 清除 DSD 导出目录 */
   clearDsdOutputDir?: () => Promise<void>;
+  /** 设置写回转换线程数（null 恢复默认） */
+  setApplyThreads: (threads: number | null) => Promise<void>;
+  /** 设置覆盖提取的插件过滤（null 恢复提取全部插件） */
+  setCoverageFilter?: (filter: CoverageFilter | null) => Promise<void>;
+  /** 设置按原文自动填充的译文来源优先级（null 恢复默认） */
+  setTranslationSourcePriority?: (priority: TranslationSource[] | null) => Promise<void>;
+  /** 设置译文字节长度上限（null 恢复内置默认值） */
+  setLengthLimits?: (limits: LengthLimit[] | null) => Promise<void>;
+  /** 设置使用计数写入重试次数（null 恢复默认） */
+  setUsageWriteRetries?: (retries: number | null) => Promise<void>;
+  /** 开启或关闭只读模式 */
+  setReadOnlyData: (enabled: boolean) => Promise<void>;
+  /** 设置 DSD 路径模板（null 时恢复默认约定；模板无效或目录不可写时抛出错误） */
+  setDsdPathTemplate?: (template: string | null) => Promise<void>;
+  /** 设置 userdata 目录（null 恢复默认，重启后生效；目录不可写时抛出错误） */
+  setUserdataDir?: (path: string | null) => Promise<void>;
   /** 加载插件列表 */
   loadPlugins: () => Promise<void>;
+  /** 切换插件列表排序方式并重新加载 */
+  setPluginOrderMode: (mode: PluginOrderMode) => Promise<void>;
+  /** 获取各数据库的诊断信息 */
+  getDiagnostics: () => Promise<Diagnostics>;
+  /** 检查各数据库完整性 */
+  checkIntegrity: () => Promise<IntegrityReport[]>;
+  /** 备份所有数据库，返回备份文件路径 */
+  backupDatabases: () => Promise<string[]>;
+  /** 从指定备份恢复数据库（重启后生效） */
+  rebuildFromBackup: (database: string, backupName: string) => Promise<void>;
+  /** 在文件管理器中打开 userdata 目录 */
+  revealUserdataDir?: () => Promise<void>;
+  /** 在文件管理器中定位配置文件（文件不存在时打开所在目录） */
+  revealSettingsFile?: () => Promise<void>;
+  /** 在文件管理器中定位启动日志（文件不存在时报错） */
+  revealLogFile?: () => Promise<void>;
   /** 设置错误信息 */
   setError: (error: string | null) => void;
 }
@@ -337,6 +1186,8 @@ export interface CoverageEntry {
   load_order_pos: number;
   /** 提取时间戳 (Unix秒) */
   extracted_at: number;
+  /** 加载顺序位置对应的插件名称（仅搜索结果） */
+  load_order_plugin?: string | null;
 }
 
 /**
@@ -375,6 +1226,44 @@ export interface CoverageStatus {
   extra_plugins: LoadOrderDiffItem[];
 }
 
+/**
+ * 覆盖数据库快照导入/导出结果
+ */
+export interface CoverageSnapshotSummary {
+  /** 快照文件路径 */
+  path: string;
+  /** 快照格式版本 */
+  format_version: number;
+  /** 导出时间戳 (Unix秒) */
+  exported_at: number;
+  /** load order 插件数量 */
+  plugin_count: number;
+  /** 覆盖记录数量 */
+  entry_count: number;
+}
+
+/**
+ * 单个插件提取失败原因
+ */
+export type PluginExtractionError =
+  | { kind: "file_missing" }
+  | { kind: "bsa_missing" }
+  | { kind: "parse_error"; message: string };
+
+/**
+ * 单个插件的提取结果
+ */
+export interface PluginExtractionResult {
+  /** 插件名称 */
+  plugin_name: string;
+  /** 写入的记录数 */
+  record_count: number;
+  /** 耗时（毫秒） */
+  duration_ms: number;
+  /** 失败原因（成功时为 null） */
+  error: PluginExtractionError | null;
+}
+
 /**
  * 覆盖提取统计
  */
@@ -385,10 +1274,31 @@ export interface CoverageExtractionStats {
   processed_plugins: number;
   /** 失败插件数 */
   failed_plugins: number;
+  /** 因插件过滤设置而跳过的插件数 */
+  skipped_plugins: number;
   /** 提取的总记录数 */
   total_records: number;
   /** 错误信息列表 */
   errors: string[];
+  /** 每个插件的提取结果 */
+  plugin_results: PluginExtractionResult[];
+}
+
+/**
+ * 单插件增量覆盖更新结果
+ */
+export interface PluginCoverageUpdate {
+  plugin_name: string;
+  /** 该插件在上次完整提取时的 load order 位置 */
+  load_order_pos: number;
+  /** 提取的记录数 */
+  record_count: number;
+  /** 写入（该插件为最终覆盖者）的记录数 */
+  written: number;
+  /** 该插件原先覆盖、新版本中已不存在的记录数 */
+  removed: number;
+  /** 耗时（毫秒） */
+  duration_ms: number;
 }
 
 // ============================================
@@ -132,35 +132,85 @@ export function buildSystemPrompt(): string {
 }
 
 /**
- * 构建User Prompt
- * 提供当前任务状态（CSV + SEARCH缓存 + 可选错误）
+ * 默认User Prompt模板
+ * {text} 替换为CSV待翻译列表，{glossary} 替换为SEARCH缓存
+ */
+export const DEFAULT_USER_PROMPT_TEMPLATE = `当前翻译任务状态：\n\n{text}{glossary}`;
+
+/**
+ * 自定义Prompt模板（来自当前激活的API配置，为空时使用内置默认值）
+ */
+export interface PromptTemplates {
+  systemPrompt?: string;
+  userPromptTemplate?: string;
+}
+
+/**
+ * 渲染Prompt模板中的 {text} 与 {glossary} 占位符
  */
-export function buildUserPrompt(state: SessionState): string {
-  let prompt = `当前翻译任务状态：\n\n`;
+export function renderPromptTemplate(
+  template: string,
+  values: { text: string; glossary: string },
+): string {
+  return template
+    .split("{text}")
+    .join(values.text)
+    .split("{glossary}")
+    .join(values.glossary);
+}
 
-  // 1. CSV待翻译列表
-  prompt += `## CSV待翻译 (${state.csv.length}条)\n\n`;
+/**
+ * 构建CSV待翻译列表区块（对应 {text} 占位符）
+ */
+function buildTextSection(state: SessionState): string {
+  let section = `## CSV待翻译 (${state.csv.length}条)\n\n`;
   if (state.csv.length === 0) {
-    prompt += `(无待翻译内容)\n\n`;
+    section += `(无待翻译内容)\n\n`;
   } else {
-    prompt += "```csv\nindex,original_text\n";
+    section += "```csv\nindex,original_text\n";
     state.csv.forEach((row) => {
       // 转义CSV中的引号和换行
       const escapedText = row.text.replace(/"/g, '""').replace(/\n/g, "\\n");
-      prompt += `${row.index},"${escapedText}"\n`;
+      section += `${row.index},"${escapedText}"\n`;
     });
-    prompt += "```\n\n";
+    section += "```\n\n";
   }
+  return section;
+}
 
-  // 2. SEARCH缓存
-  prompt += `## SEARCH缓存\n\n`;
+/**
+ * 构建SEARCH缓存区块（对应 {glossary} 占位符）
+ */
+function buildGlossarySection(state: SessionState): string {
+  let section = `## SEARCH缓存\n\n`;
   const cacheEntries = Object.entries(state.searchCache);
   if (cacheEntries.length === 0) {
-    prompt += `(空缓存)\n\n`;
+    section += `(空缓存)\n\n`;
   } else {
-    prompt += "```json\n";
-    prompt += JSON.stringify(state.searchCache, null, 2);
-    prompt += "\n```\n\n";
+    section += "```json\n";
+    section += JSON.stringify(state.searchCache, null, 2);
+    section += "\n```\n\n";
+  }
+  return section;
+}
+
+/**
+ * 构建User Prompt
+ * 提供当前任务状态（CSV + SEARCH缓存 + 可选错误）
+ */
+export function buildUserPrompt(state: SessionState, template?: string): string {
+  const userTemplate = template?.trim() ? template : DEFAULT_USER_PROMPT_TEMPLATE;
+  const text = buildTextSection(state);
+  const glossary = buildGlossarySection(state);
+
+  // 1-2. CSV待翻译列表 + SEARCH缓存（按模板渲染）
+  let prompt = renderPromptTemplate(userTemplate, { text, glossary });
+  // 模板缺少 {text} 时仍需提供待翻译列表，否则工具调用无法进行
+  if (!userTemplate.includes("{text}")) {
+    prompt += `\n\n${text}`;
+  }
+  if (!prompt.endsWith("\n\n")) {
+    prompt += "\n\n";
   }
 
   // 3. 进度
@@ -197,15 +247,22 @@ export function buildUserPrompt(state: SessionState): string {
 /**
  * 完整消息构造（用于AI SDK）
  */
-export function buildMessages(state: SessionState) {
+export function buildMessages(state: SessionState, templates?: PromptTemplates) {
+  const customSystemPrompt = templates?.systemPrompt?.trim()
+    ? renderPromptTemplate(templates.systemPrompt, {
+        text: buildTextSection(state),
+        glossary: buildGlossarySection(state),
+      })
+    : null;
+
   return [
     {
       role: "system" as const,
-      content: buildSystemPrompt(),
+      content: customSystemPrompt ?? buildSystemPrompt(),
     },
     {
       role: "user" as const,
-      content: buildUserPrompt(state),
+      content: buildUserPrompt(state, templates?.userPromptTemplate),
     },
   ];
 }
@@ -5,6 +5,7 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import type { SessionState, SearchResult } from "./aiPrompts";
+import { getErrorMessage } from "./commandError";
 
 /**
  * Search执行结果（包含缓存命中和实际查询的统计）
@@ -191,13 +192,12 @@ export async function executeSearch(
 
       // 2.4 查询参考翻译（translations表）
       try {
-        const refs = await invoke<ReferenceTranslation[]>(
-          "query_word_translations",
-          {
-            text: term,
-            limit: 5, // 最多返回5个参考
-          },
-        );
+        const { translations: refs } = await invoke<{
+          translations: ReferenceTranslation[];
+        }>("query_word_translations", {
+          text: term,
+          limit: 5, // 最多返回5个参考
+        });
 
         // 安全检查：确保refs是数组且元素有效
         if (Array.isArray(refs)) {
@@ -375,7 +375,7 @@ export function executeApply(
     console.error("executeApply失败:", error);
     return {
       success: false,
-      error: `应用翻译失败: ${String(error)}`,
+      error: `应用翻译失败: ${getErrorMessage(error)}`,
     };
   }
 }
@@ -444,15 +444,23 @@ export function executeSkip(
   };
 }
 
+/** 原子词来源（与后端 AtomSource 一致） */
+export type AtomSourceType = "Base" | "AI" | "Manual";
+
 /**
  * 术语预处理：调用replace_with_atoms标注术语
  * @param text 原文
+ * @param allowedSources 仅使用这些来源的原子词（默认全部）
  * @returns 标注后的文本（如："The argonian(亚龙人) looks unfriendly."）
  */
-export async function preprocessTerms(text: string): Promise<string> {
+export async function preprocessTerms(
+  text: string,
+  allowedSources?: AtomSourceType[],
+): Promise<string> {
   try {
     const annotated = await invoke<string>("replace_text_with_atoms", {
       text,
+      allowedSources,
     });
     return annotated;
   } catch (error) {
@@ -462,19 +470,91 @@ export async function preprocessTerms(text: string): Promise<string> {
   }
 }
 
+/** 本次替换命中的原子词（hits 为本次命中次数，与累计使用次数无关） */
+export interface AppliedAtom {
+  original: string;
+  translated: string;
+  source: AtomSourceType;
+  hits: number;
+}
+
+/** 批量替换结果及命中的原子词汇总 */
+export interface AtomReplaceReport {
+  texts: string[];
+  /** 按命中次数降序 */
+  applied_atoms: AppliedAtom[];
+}
+
+/** 单次替换的性能指标 */
+export interface AtomReplaceMetrics {
+  /** 自动机报告的候选命中数（含被重叠或来源过滤剔除的） */
+  candidate_matches: number;
+  /** 实际替换的命中数 */
+  applied: number;
+  /** 匹配与替换耗时（微秒） */
+  elapsed_us: number;
+}
+
+/** 单条文本替换结果及性能指标 */
+export interface AtomReplaceDetailed {
+  text: string;
+  metrics: AtomReplaceMetrics;
+}
+
+/**
+ * 替换单条文本中的术语并返回性能指标（用于调优匹配器配置）
+ * @param text 原文
+ * @param allowedSources 仅使用这些来源的原子词（默认全部）
+ */
+export async function replaceWithAtomsDetailed(
+  text: string,
+  allowedSources?: AtomSourceType[],
+): Promise<AtomReplaceDetailed> {
+  return invoke<AtomReplaceDetailed>("replace_with_atoms_detailed", {
+    text,
+    allowedSources,
+  });
+}
+
+/**
+ * 统计一组原文中命中的术语（如传入整个插件的原文，得到"本插件使用的术语"报告）
+ * @param texts 原文列表
+ * @param allowedSources 仅使用这些来源的原子词（默认全部）
+ */
+export async function collectAppliedAtoms(
+  texts: string[],
+  allowedSources?: AtomSourceType[],
+): Promise<AtomReplaceReport> {
+  return invoke<AtomReplaceReport>("replace_texts_with_atoms_report", {
+    texts,
+    allowedSources,
+  });
+}
+
 /**
  * 批量术语预处理
  * 返回结构包含原始原文（rawText）和标注后文本（text）
  */
 export async function preprocessBatch(
   entries: Array<{ index: number; text: string }>,
+  allowedSources?: AtomSourceType[],
 ): Promise<Array<{ index: number; text: string; rawText: string }>> {
-  const preprocessed = await Promise.all(
-    entries.map(async (entry) => ({
-      index: entry.index,
-      text: await preprocessTerms(entry.text), // 术语标注后的文本
-      rawText: entry.text, // 原始原文
-    })),
-  );
-  return preprocessed;
+  let annotated: string[];
+  try {
+    // 单次调用批量标注，避免逐条往返
+    annotated = await invoke<string[]>("replace_texts_with_atoms", {
+      texts: entries.map((entry) => entry.text),
+      allowedSources,
+    });
+  } catch (error) {
+    console.warn("术语预处理失败:", error);
+    // 失败时返回原文
+    annotated = entries.map((entry) => entry.text);
+  }
+
+  return entries.map((entry, i) => ({
+    index: entry.index,
+    text: annotated[i] ?? entry.text, // 术语标注后的文本
+    rawText: entry.text, // 原始原文
+  }));
 }
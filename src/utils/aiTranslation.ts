@@ -4,6 +4,7 @@
  */
 
 import OpenAI from "openai";
+import { invoke } from "@tauri-apps/api/core";
 import type { ChatCompletionMessageParam } from "openai/resources/chat/completions";
 import type { ApiConfig } from "../stores/apiConfigStore";
 import type { AiHistoryEntry, SessionState, SearchResult } from "./aiPrompts";
@@ -249,7 +250,10 @@ export async function translateBatchWithAI(
       maxIterations--;
 
       // 4.1 构造消息
-      const messages = buildMessages(sessionState);
+      const messages = buildMessages(sessionState, {
+        systemPrompt: apiConfig.systemPrompt,
+        userPromptTemplate: apiConfig.userPromptTemplate,
+      });
 
       // 4.2 调用AI
       console.log(`[AI翻译] 调用AI，剩余 ${sessionState.csv.length} 条待翻译`);
@@ -302,6 +306,15 @@ export async function translateBatchWithAI(
         throw new Error(`AI API调用失败: ${error.message || String(error)}`);
       }
 
+      // 记录 token 用量（失败不影响翻译流程）
+      if (completion.usage) {
+        invoke("record_api_usage", {
+          configId: apiConfig.id,
+          promptTokens: completion.usage.prompt_tokens,
+          completionTokens: completion.usage.completion_tokens,
+        }).catch((error) => console.warn("[AI翻译] 记录API用量失败:", error));
+      }
+
       const message = completion.choices[0]?.message;
       if (!message) {
         throw new Error("AI返回空响应");
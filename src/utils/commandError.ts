@@ -0,0 +1,30 @@
+import type { CommandError, CommandErrorCode } from "../types";
+
+/**
+ * 判断是否为后端命令返回的结构化错误
+ */
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as CommandError).code === "string" &&
+    typeof (error as CommandError).message === "string"
+  );
+}
+
+/**
+ * 提取可展示的错误信息（兼容 CommandError、Error 与字符串）
+ */
+export function getErrorMessage(error: unknown): string {
+  if (isCommandError(error) || error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}
+
+/**
+ * 提取后端错误类别（非 CommandError 时返回 null）
+ */
+export function getErrorCode(error: unknown): CommandErrorCode | null {
+  return isCommandError(error) ? error.code : null;
+}